@@ -0,0 +1,48 @@
+//! Measuring the columns of a large table, on every thread and on one.
+//!
+//! Run with `cargo bench --bench column_widths`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use csvpretty::{RenderOptions, Renderer, Table, WrapMode};
+
+/// A table of `rows` rows like a log export: ids, timestamps, names, amounts and free text,
+/// some of it outside ASCII.
+fn large_table(rows: usize) -> Table<'static> {
+    let names = ["Alice", "Bob", "Zoë", "José", "李雷", "Ngāti"];
+    let records = (0..rows)
+        .map(|row| {
+            vec![
+                row.to_string().into(),
+                format!("2024-01-{:02}T{:02}:{:02}:00Z", row % 28 + 1, row % 24, row % 60).into(),
+                names[row % names.len()].into(),
+                format!("{}.{:02}", row * 37 % 100_000, row % 100).into(),
+                "lorem ipsum dolor sit amet ".repeat(row % 7 + 1).into(),
+                format!("status-{}", row % 5).into(),
+            ]
+        })
+        .collect();
+    Table {
+        headers: ["id", "timestamp", "name", "amount", "notes", "status"].into_iter().collect(),
+        records,
+    }
+}
+
+fn column_widths(c: &mut Criterion) {
+    let table = large_table(200_000);
+    let one_thread = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let mut group = c.benchmark_group("column_widths");
+    group.sample_size(10);
+    for (name, wrap_mode, terminal_width) in [("no-wrap", WrapMode::None, usize::MAX), ("word", WrapMode::Word, 120)] {
+        let options = RenderOptions { wrap_mode, terminal_width, ..RenderOptions::default() };
+        group.bench_with_input(BenchmarkId::new("all-threads", name), &options, |b, options| {
+            b.iter(|| Renderer::new(&table, options));
+        });
+        group.bench_with_input(BenchmarkId::new("one-thread", name), &options, |b, options| {
+            b.iter(|| one_thread.install(|| Renderer::new(&table, options)));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, column_widths);
+criterion_main!(benches);
@@ -0,0 +1,105 @@
+//! Parsing a large CSV input with every cell copied and with cells borrowed from the input: the
+//! heap memory each table takes on top of the input, then how long each takes.
+//!
+//! Run with `cargo bench --bench parse_memory`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use csvpretty::{CsvOptions, Table};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The system allocator, keeping count of the bytes allocated now and at most.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+impl CountingAllocator {
+    fn grow(by: usize) {
+        let allocated = ALLOCATED.fetch_add(by, Ordering::Relaxed) + by;
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::grow(layout.size());
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        match new_size.checked_sub(layout.size()) {
+            Some(grown) => Self::grow(grown),
+            None => {
+                ALLOCATED.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+            }
+        }
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// CSV text of `rows` rows like a log export: ids, timestamps, names, amounts and free text,
+/// some of it quoted.
+fn large_input(rows: usize) -> String {
+    let names = ["Alice", "Bob", "Zoë", "José", "李雷", "Ngāti"];
+    let mut input = String::from("id,timestamp,name,amount,notes,status\n");
+    for row in 0..rows {
+        input += &format!(
+            "{},2024-01-{:02}T{:02}:{:02}:00Z,{},{}.{:02},\"{}\",status-{}\n",
+            row,
+            row % 28 + 1,
+            row % 24,
+            row % 60,
+            names[row % names.len()],
+            row * 37 % 100_000,
+            row % 100,
+            "lorem ipsum, dolor sit amet ".repeat(row % 7 + 1),
+            row % 5,
+        );
+    }
+    input
+}
+
+/// Heap bytes `parse` takes at most, and keeps in the table it returns.
+fn heap_use<'a>(parse: impl FnOnce() -> Table<'a>) -> (usize, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let table = parse();
+    let kept = ALLOCATED.load(Ordering::Relaxed) - before;
+    let peak = PEAK.load(Ordering::Relaxed) - before;
+    drop(table);
+    (peak, kept)
+}
+
+fn megabytes(bytes: usize) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_000_000.0)
+}
+
+fn parse_memory(c: &mut Criterion) {
+    let input = large_input(200_000);
+    let options = CsvOptions::default();
+    let copied = || Table::from_reader_with_options(input.as_bytes(), &options).unwrap();
+    let borrowed = || Table::from_borrowed_str(&input, &options, false).unwrap().0;
+
+    println!("Heap used on top of the {} input:", megabytes(input.len()));
+    for (name, (peak, kept)) in [("copied", heap_use(copied)), ("borrowed", heap_use(borrowed))] {
+        println!("  {:<9} peak {:>9}, kept {:>9}", name, megabytes(peak), megabytes(kept));
+    }
+
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    group.bench_function("copied", |b| b.iter(copied));
+    group.bench_function("borrowed", |b| b.iter(borrowed));
+    group.finish();
+}
+
+criterion_group!(benches, parse_memory);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+//! Wrapping long cells into narrow columns, by word and by character.
+//!
+//! Run with `cargo bench --bench wrapping`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use csvpretty::{RenderOptions, Renderer, Table, WrapMode};
+
+/// The long-text fixture, its rows repeated `copies` times.
+fn long_text_table(copies: usize) -> Table<'static> {
+    let mut table = Table::from_reader(include_str!("../tests/fixtures/long_text.csv").as_bytes()).unwrap();
+    table.records = table.records.iter().cycle().take(table.records.len() * copies).cloned().collect();
+    table
+}
+
+fn wrapping(c: &mut Criterion) {
+    let table = long_text_table(5_000);
+    let mut group = c.benchmark_group("wrapping");
+    group.sample_size(20);
+    for (name, wrap_mode) in [("word", WrapMode::Word), ("char", WrapMode::Char)] {
+        let options = RenderOptions { wrap_mode, terminal_width: 60, ..RenderOptions::default() };
+        let renderer = Renderer::new(&table, &options).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &renderer, |b, renderer| {
+            b.iter(|| renderer.render_rows(&mut std::io::sink()).unwrap());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, wrapping);
+criterion_main!(benches);
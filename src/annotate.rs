@@ -0,0 +1,76 @@
+//! Problems found by `--check` as CI annotations (`--annotate`).
+//!
+//! On GitHub Actions each problem becomes an `::error` workflow command, which the pull request
+//! shows on the line of the file it's about. GitLab reads a Code Quality report instead: a JSON
+//! file the job uploads with `artifacts:reports:codequality`.
+
+use crate::check::{Problem, Report};
+use crate::ci;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{self, Write};
+
+/// Where the GitLab report is written, the name GitLab's documentation uses.
+pub const GITLAB_REPORT: &str = "gl-code-quality-report.json";
+
+/// Title of the GitHub Actions annotations.
+const TITLE: &str = "csvpretty --check";
+
+/// A CI system to annotate the checked file for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Annotations {
+    /// GitHub Actions error commands, written along with the report
+    Gha,
+    /// A GitLab Code Quality report, written to gl-code-quality-report.json
+    Gitlab,
+}
+
+/// Annotates `path` with the problems in `report`: GitHub Actions commands go to `out`, a
+/// GitLab report to [`GITLAB_REPORT`].
+pub fn annotate(annotations: Annotations, report: &Report, path: &str, out: &mut impl Write) -> io::Result<()> {
+    match annotations {
+        Annotations::Gha => {
+            for problem in &report.problems {
+                writeln!(out, "::error file={},line={},title={}::{}", escape_property(path), problem.line, escape_property(TITLE), escape_data(&message(problem)))?;
+            }
+            Ok(())
+        }
+        Annotations::Gitlab => {
+            let issues: Vec<Value> = report.problems.iter().map(|problem| code_quality_issue(problem, path)).collect();
+            let json = serde_json::to_string_pretty(&issues).map_err(io::Error::other)?;
+            fs::write(GITLAB_REPORT, json + "\n")
+        }
+    }
+}
+
+/// The annotation text for a problem. The line is given separately, so it's left out.
+fn message(problem: &Problem) -> String {
+    format!("record {}: {}", problem.record, problem.kind)
+}
+
+/// An entry of a GitLab Code Quality report. The fingerprint tells issues apart between runs,
+/// so it's made from what the issue is about rather than from its position in the report.
+fn code_quality_issue(problem: &Problem, path: &str) -> Value {
+    let description = message(problem);
+    let fingerprint = ci::hash(format!("{}\0{}\0{}", path, problem.line, description).as_bytes());
+    json!({
+        "description": description,
+        "check_name": problem.kind.name(),
+        "fingerprint": format!("{:016x}", fingerprint),
+        "severity": "major",
+        "location": {
+            "path": path,
+            "lines": { "begin": problem.line },
+        },
+    })
+}
+
+/// Escapes the message of a GitHub Actions workflow command.
+fn escape_data(value: &str) -> String {
+    value.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escapes a property value of a GitHub Actions workflow command, such as the file name.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
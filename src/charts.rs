@@ -0,0 +1,120 @@
+//! Inline charts drawn with Unicode block characters.
+
+use crate::column_types::ColumnType;
+use crate::is_numeric;
+use csvpretty::Cell;
+
+/// Default bar length, in terminal columns, when `--bar` doesn't specify one.
+pub const DEFAULT_BAR_WIDTH: usize = 20;
+
+/// Partial blocks for 1/8 through 7/8 of a cell.
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Sparkline levels, lowest to highest.
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A `--bar COLUMN[:WIDTH]` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BarSpec {
+    pub column: String,
+    pub width: usize,
+}
+
+/// Clap value parser for `--bar COLUMN[:WIDTH]`.
+pub fn parse_bar_spec(s: &str) -> Result<BarSpec, String> {
+    match s.rsplit_once(':') {
+        Some((column, width)) if !width.is_empty() && width.bytes().all(|b| b.is_ascii_digit()) => {
+            let width: usize = width.parse().map_err(|e| format!("invalid bar width '{}': {}", width, e))?;
+            if width == 0 {
+                return Err("bar width must be at least 1".to_string());
+            }
+            Ok(BarSpec { column: column.to_string(), width })
+        }
+        _ => Ok(BarSpec { column: s.to_string(), width: DEFAULT_BAR_WIDTH }),
+    }
+}
+
+/// Reads the number a bar should represent. Typed columns use their underlying value.
+fn bar_value(value: &str, column_type: Option<ColumnType>) -> Option<f64> {
+    match column_type {
+        Some(column_type) => column_type.numeric_value(value),
+        None if is_numeric(value) => value.trim().parse().ok(),
+        None => None,
+    }
+}
+
+/// Draws a bar of `(value - min) / (max - min)` of `width` columns, with 1/8-column resolution.
+fn draw_bar(value: f64, min: f64, max: f64, width: usize) -> String {
+    if max <= min || value <= min {
+        return String::new();
+    }
+    let eighths = (((value - min) / (max - min)).min(1.0) * width as f64 * 8.0).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    let partial = eighths % 8;
+    if partial > 0 {
+        bar.push(PARTIAL_BLOCKS[partial - 1]);
+    }
+    bar
+}
+
+/// The numbers in one column, with None for non-numeric and empty values.
+fn column_values(records: &[Vec<Cell>], col_idx: usize, column_type: Option<ColumnType>) -> Vec<Option<f64>> {
+    records.iter()
+        .map(|row| row.get(col_idx).and_then(|value| bar_value(value, column_type)))
+        .collect()
+}
+
+/// Computes bar cells for one column, scaled from 0 to the column's largest value, or from its
+/// smallest value with `from_min` to tell apart values close together. Non-numeric, empty and
+/// values at or below the start of the scale get an empty bar.
+pub fn bar_column(records: &[Vec<Cell>], col_idx: usize, column_type: Option<ColumnType>, width: usize, from_min: bool) -> Vec<String> {
+    let values = column_values(records, col_idx, column_type);
+    let max = values.iter().flatten().copied().fold(0.0, f64::max);
+    let min = if from_min { values.iter().flatten().copied().fold(f64::INFINITY, f64::min) } else { 0.0 };
+
+    values.into_iter()
+        .map(|value| value.map_or_else(String::new, |value| draw_bar(value, min, max, width)))
+        .collect()
+}
+
+/// A column's values in row order, drawn as a sparkline below the table with `--spark`.
+#[derive(Debug, Clone)]
+pub struct Sparkline {
+    pub column: String,
+    values: Vec<Option<f64>>,
+}
+
+impl Sparkline {
+    /// Reads the numbers in a column. Typed columns use their underlying value.
+    pub fn new(records: &[Vec<Cell>], col_idx: usize, column_type: Option<ColumnType>, column: &str) -> Sparkline {
+        Sparkline { column: column.to_string(), values: column_values(records, col_idx, column_type) }
+    }
+
+    /// The smallest and largest value, unless the column has no numbers.
+    pub fn range(&self) -> Option<(f64, f64)> {
+        let numbers = self.values.iter().flatten().copied();
+        numbers.clone().reduce(f64::min).zip(numbers.reduce(f64::max))
+    }
+
+    /// Draws the sparkline at most `width` characters long. With more rows than that, each
+    /// character stands for the mean of a run of rows. Rows without a number are spaces.
+    pub fn draw(&self, width: usize) -> String {
+        let Some((min, max)) = self.range() else {
+            return String::new();
+        };
+        let count = self.values.len();
+        let width = width.min(count).max(1);
+        (0..width)
+            .map(|index| {
+                let run = &self.values[index * count / width..(index + 1) * count / width];
+                let numbers: Vec<f64> = run.iter().flatten().copied().collect();
+                if numbers.is_empty() {
+                    return ' ';
+                }
+                let mean = numbers.iter().sum::<f64>() / numbers.len() as f64;
+                let level = if max > min { ((mean - min) / (max - min) * 7.0).round() as usize } else { 3 };
+                SPARK_LEVELS[level.min(7)]
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,178 @@
+//! Diagnostics for ragged and malformed CSV (`--check`).
+//!
+//! The csv parser is forgiving about stray quotes and, in lenient mode, about records with the
+//! wrong number of fields. A check lists every such record with its position in the input, so
+//! the export that produced it can be fixed.
+
+use csvpretty::{Cell, CsvOptions, Highlights};
+use std::fmt;
+use std::io::{self, Write};
+
+/// Color of problem rows in the table, the same red as `--rule '... => red'`.
+const PROBLEM_COLOR: (u8, u8, u8) = (220, 50, 47);
+
+/// What is wrong with a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemKind {
+    TooFewFields { fields: usize, expected: usize },
+    TooManyFields { fields: usize, expected: usize },
+    /// An odd number of quotes: a stray quote, or a quoted field that is never closed
+    UnbalancedQuotes { runs_to_end: bool },
+}
+
+/// A problem found in one record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Problem {
+    /// Position among the data records, starting at 1
+    pub record: usize,
+    pub line: u64,
+    pub byte: u64,
+    pub kind: ProblemKind,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "record {} (line {}, byte {}): {}", self.record, self.line, self.byte, self.kind)
+    }
+}
+
+impl ProblemKind {
+    /// A short name for the kind of problem, e.g. `too-many-fields`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ProblemKind::TooFewFields { .. } => "too-few-fields",
+            ProblemKind::TooManyFields { .. } => "too-many-fields",
+            ProblemKind::UnbalancedQuotes { .. } => "unbalanced-quotes",
+        }
+    }
+}
+
+impl fmt::Display for ProblemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ProblemKind::TooFewFields { fields, expected } => write!(f, "too few fields ({} instead of {})", fields, expected),
+            ProblemKind::TooManyFields { fields, expected } => write!(f, "too many fields ({} instead of {})", fields, expected),
+            ProblemKind::UnbalancedQuotes { runs_to_end: false } => write!(f, "unbalanced quotes"),
+            ProblemKind::UnbalancedQuotes { runs_to_end: true } => write!(f, "unbalanced quotes (a quoted field runs to the end of the input)"),
+        }
+    }
+}
+
+/// The outcome of checking the input.
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// Number of data records checked
+    pub records: usize,
+    /// Problems in input order
+    pub problems: Vec<Problem>,
+}
+
+impl Report {
+    /// Prints one line per problem, then a summary such as `2 problems in 40 records`.
+    pub fn print(&self, out: &mut impl Write) -> io::Result<()> {
+        for problem in &self.problems {
+            writeln!(out, "{}", problem)?;
+        }
+        let plural = |count: usize, noun: &str| format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" });
+        match self.problems.len() {
+            0 => writeln!(out, "No problems in {}", plural(self.records, "record")),
+            count => writeln!(out, "{} in {}", plural(count, "problem"), plural(self.records, "record")),
+        }
+    }
+
+    /// Colors the rows with problems. `highlights` is indexed like the rows of a table read
+    /// leniently, which leaves out records with too many fields.
+    pub fn highlight_rows(&self, highlights: &mut Highlights, records: &[Vec<Cell>]) {
+        highlights.resize_with(records.len(), Vec::new);
+        let mut skipped = 0;
+        let mut last_skipped = None;
+        for problem in &self.problems {
+            if matches!(problem.kind, ProblemKind::TooManyFields { .. }) {
+                skipped += 1;
+                last_skipped = Some(problem.record);
+            }
+            if last_skipped == Some(problem.record) {
+                continue;
+            }
+            let row = problem.record - 1 - skipped;
+            highlights[row] = vec![Some(PROBLEM_COLOR); records[row].len()];
+        }
+    }
+}
+
+/// Checks every data record of `input` for the wrong number of fields and unbalanced quotes.
+/// Problems are placed counting `options.offset`, i.e. in the input `input` was cut from.
+pub fn check(input: &str, options: &CsvOptions) -> Result<Report, csvpretty::Error> {
+    let mut reader = options.reader_builder()
+        .flexible(true)
+        .from_reader(input.as_bytes());
+    let error = |e| csvpretty::Error::from_csv(e, options.offset);
+    let expected = reader.byte_headers().map_err(error)?.len();
+
+    // (line, byte, fields) of each record; a record's raw text runs up to the next one's start
+    let mut records = Vec::new();
+    for result in reader.byte_records() {
+        let record = result.map_err(error)?;
+        let position = record.position().expect("records read from input have a position");
+        records.push((position.line(), position.byte(), record.len()));
+    }
+
+    let mut problems = Vec::new();
+    for (index, &(line, byte, fields)) in records.iter().enumerate() {
+        let problem = |kind| Problem { record: index + 1, line: line + options.offset.lines, byte: byte + options.offset.bytes, kind };
+        if fields < expected {
+            problems.push(problem(ProblemKind::TooFewFields { fields, expected }));
+        } else if fields > expected {
+            problems.push(problem(ProblemKind::TooManyFields { fields, expected }));
+        }
+
+        // Quotes inside quoted fields are doubled or escaped, so a well-formed record has an even
+        // number that aren't escaped
+        let end = records.get(index + 1).map_or(input.len(), |&(_, next, _)| next as usize);
+        if count_quotes(&input.as_bytes()[byte as usize..end], options) % 2 == 1 {
+            let runs_to_end = index + 1 == records.len() && ends_in_quoted_field(&input.as_bytes()[byte as usize..], options);
+            problems.push(problem(ProblemKind::UnbalancedQuotes { runs_to_end }));
+        }
+    }
+    Ok(Report { records: records.len(), problems })
+}
+
+/// The number of quotes in `raw` that aren't escaped.
+fn count_quotes(raw: &[u8], options: &CsvOptions) -> usize {
+    let mut quotes = 0;
+    let mut bytes = raw.iter();
+    while let Some(&b) = bytes.next() {
+        if b == options.quote {
+            quotes += 1;
+        } else if Some(b) == options.escape {
+            bytes.next();
+        }
+    }
+    quotes
+}
+
+/// Whether `raw` ends inside a quoted field, i.e. a field opened with a quote is never closed.
+fn ends_in_quoted_field(raw: &[u8], options: &CsvOptions) -> bool {
+    let quote = options.quote;
+    let mut in_quotes = false;
+    let mut field_start = true;
+    let mut bytes = raw.iter().peekable();
+    while let Some(&b) = bytes.next() {
+        match b {
+            b if in_quotes && Some(b) == options.escape && b != quote => {
+                bytes.next();
+            }
+            // A doubled quote is an escaped quote rather than the end of the field
+            b if b == quote && in_quotes && !(options.double_quote && bytes.next_if_eq(&&quote).is_some()) => in_quotes = false,
+            b if b == quote && in_quotes => {}
+            b if b == quote && field_start => in_quotes = true,
+            b'\n' | b',' if !in_quotes => {
+                field_start = true;
+                continue;
+            }
+            _ => {}
+        }
+        field_start = false;
+    }
+    in_quotes
+}
@@ -0,0 +1,101 @@
+//! Output for CI build logs (`--format gha` and `--format teamcity`).
+//!
+//! The table is wrapped in a collapsible block, and log commands are switched off around it so
+//! that cell contents can't be mistaken for workflow commands or service messages.
+
+use std::io::{self, Write};
+
+/// Table width used in CI logs unless `--width` is given, since there's no terminal to measure.
+pub const CI_WIDTH: usize = 120;
+
+/// A CI system whose log viewer understands collapsible blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiSystem {
+    GitHubActions,
+    TeamCity,
+}
+
+impl CiSystem {
+    /// Whether the build is running on this CI system. Colors are only emitted there, since the
+    /// same output viewed in a plain log file would be full of escape sequences.
+    pub fn is_running(self) -> bool {
+        match self {
+            CiSystem::GitHubActions => std::env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true"),
+            CiSystem::TeamCity => std::env::var_os("TEAMCITY_VERSION").is_some(),
+        }
+    }
+
+    /// Opens a collapsible block titled `title` and stops command processing until [`end`].
+    ///
+    /// GitHub Actions resumes commands when it sees the token passed to `stop-commands`, so the
+    /// token is derived from the block's contents; a table can't contain its own hash.
+    ///
+    /// [`end`]: CiSystem::end
+    pub fn begin(self, out: &mut impl Write, title: &str, contents: &[u8]) -> io::Result<()> {
+        match self {
+            CiSystem::GitHubActions => {
+                writeln!(out, "::group::{}", title)?;
+                writeln!(out, "::stop-commands::{}", resume_token(contents))
+            }
+            CiSystem::TeamCity => {
+                writeln!(out, "##teamcity[blockOpened name='{}']", escape_teamcity(title))?;
+                writeln!(out, "##teamcity[disableServiceMessages]")
+            }
+        }
+    }
+
+    /// Resumes command processing and closes the block opened by [`begin`](CiSystem::begin).
+    pub fn end(self, out: &mut impl Write, title: &str, contents: &[u8]) -> io::Result<()> {
+        match self {
+            CiSystem::GitHubActions => {
+                writeln!(out, "::{}::", resume_token(contents))?;
+                writeln!(out, "::endgroup::")
+            }
+            CiSystem::TeamCity => {
+                writeln!(out, "##teamcity[enableServiceMessages]")?;
+                writeln!(out, "##teamcity[blockClosed name='{}']", escape_teamcity(title))
+            }
+        }
+    }
+}
+
+/// Replaces control characters with their visible Unicode "control picture" (e.g. `\r` → `␍`),
+/// since CI log viewers interpret carriage returns, tabs and escape sequences in cells.
+pub fn escape_control_chars(text: &str) -> String {
+    text.chars()
+        .map(|ch| match ch {
+            '\u{0}'..='\u{1f}' => char::from_u32(0x2400 + ch as u32).unwrap_or(ch),
+            '\u{7f}' => '␡',
+            ch => ch,
+        })
+        .collect()
+}
+
+/// The block contents' hash, used as the `stop-commands` token.
+fn resume_token(contents: &[u8]) -> String {
+    format!("csvpretty-{:016x}", hash(contents))
+}
+
+/// A 64-bit FNV-1a hash, stable across runs and platforms.
+pub fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325_u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Escapes a value for a TeamCity service message attribute.
+fn escape_teamcity(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\'' | '|' | '[' | ']' => {
+                escaped.push('|');
+                escaped.push(ch);
+            }
+            '\n' => escaped.push_str("|n"),
+            '\r' => escaped.push_str("|r"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
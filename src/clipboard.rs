@@ -0,0 +1,106 @@
+//! Reading and writing the system clipboard (`--clipboard-in` and `--clipboard-out`).
+//!
+//! The clipboard is reached through the platform's command-line tools: pbpaste/pbcopy on
+//! macOS, PowerShell on Windows, and wl-clipboard, xclip or xsel on Linux and the BSDs,
+//! whichever is installed for the display server in use.
+
+use std::env;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// A tool's commands for pasting (printing the clipboard) and for copying (from stdin).
+struct Tool {
+    paste: &'static [&'static str],
+    copy: &'static [&'static str],
+}
+
+/// The tools that may work here, most likely first.
+fn tools() -> Vec<Tool> {
+    if cfg!(target_os = "macos") {
+        return vec![Tool { paste: &["pbpaste"], copy: &["pbcopy"] }];
+    }
+    if cfg!(windows) {
+        // PowerShell's pipes use the console's legacy code page unless told otherwise, which
+        // would mangle anything outside it; UTF8Encoding($false) also keeps a BOM out
+        return vec![Tool {
+            paste: &["powershell", "-NoProfile", "-Command", "[Console]::OutputEncoding = New-Object System.Text.UTF8Encoding $false; Get-Clipboard -Raw"],
+            copy: &["powershell", "-NoProfile", "-Command", "[Console]::InputEncoding = New-Object System.Text.UTF8Encoding $false; [Console]::In.ReadToEnd() | Set-Clipboard"],
+        }];
+    }
+    let mut tools = Vec::new();
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        tools.push(Tool { paste: &["wl-paste", "--no-newline"], copy: &["wl-copy"] });
+    }
+    if env::var_os("DISPLAY").is_some() {
+        tools.push(Tool { paste: &["xclip", "-selection", "clipboard", "-o"], copy: &["xclip", "-selection", "clipboard"] });
+        tools.push(Tool { paste: &["xsel", "--clipboard", "--output"], copy: &["xsel", "--clipboard", "--input"] });
+    }
+    tools
+}
+
+/// The error when none of the tools could be run.
+fn no_tool() -> String {
+    if cfg!(any(target_os = "macos", windows)) {
+        "the clipboard tool couldn't be run".to_string()
+    } else {
+        "no clipboard tool found; install wl-clipboard (Wayland), or xclip or xsel (X11)".to_string()
+    }
+}
+
+/// The text on the clipboard.
+pub fn paste() -> Result<String, String> {
+    for tool in tools() {
+        let output = match Command::new(tool.paste[0]).args(&tool.paste[1..]).stdin(Stdio::null()).output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("reading the clipboard with {}: {}", tool.paste[0], e)),
+        };
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("reading the clipboard with {}: {}", tool.paste[0], message.trim()));
+        }
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    Err(no_tool())
+}
+
+/// Puts `text` on the clipboard.
+pub fn copy(text: &str) -> Result<(), String> {
+    for tool in tools() {
+        let mut child = match Command::new(tool.copy[0]).args(&tool.copy[1..]).stdin(Stdio::piped()).stdout(Stdio::null()).spawn() {
+            Ok(child) => child,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("writing the clipboard with {}: {}", tool.copy[0], e)),
+        };
+        let written = child.stdin.take().expect("stdin was piped").write_all(text.as_bytes());
+        let status = child.wait().map_err(|e| format!("writing the clipboard with {}: {}", tool.copy[0], e))?;
+        return match (written, status.success()) {
+            (Ok(()), true) => Ok(()),
+            (Err(e), _) => Err(format!("writing the clipboard with {}: {}", tool.copy[0], e)),
+            (Ok(()), false) => Err(format!("writing the clipboard with {} failed", tool.copy[0])),
+        };
+    }
+    Err(no_tool())
+}
+
+/// Pasted text as CSV. A range copied from a spreadsheet is tab-separated, so text whose first
+/// line has tabs but no commas is rewritten with commas between its fields; anything else, or
+/// text that can't be read that way, is left as it is.
+pub fn tabs_to_commas(text: String) -> String {
+    let first_line = text.lines().next().unwrap_or_default();
+    if !first_line.contains('\t') || first_line.contains(',') {
+        return text;
+    }
+    let mut reader = csv::ReaderBuilder::new().delimiter(b'\t').has_headers(false).flexible(true).from_reader(text.as_bytes());
+    let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(Vec::new());
+    for record in reader.byte_records() {
+        match record.map(|record| writer.write_byte_record(&record)) {
+            Ok(Ok(())) => {}
+            _ => return text,
+        }
+    }
+    match writer.into_inner().map(String::from_utf8) {
+        Ok(Ok(csv)) => csv,
+        _ => text,
+    }
+}
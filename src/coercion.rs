@@ -0,0 +1,101 @@
+//! Cells that don't match their column's type (`--coerce-report`).
+//!
+//! A column of numbers with one `n/a` or `1,200` in it is what makes an import fail, and it's
+//! easy to miss in a long table. Each column gets the type most of its values have (or its
+//! `--column-type`), and every non-null value that can't be read as that type is listed.
+
+use crate::column_types::ColumnType;
+use crate::inference::{self, InferredType};
+use crate::is_null;
+use csvpretty::Cell;
+use std::fmt;
+use std::io::{self, Write};
+
+/// A cell that can't be read as its column's type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Position among the rows, starting at 1 like `--line-numbers`
+    pub row: usize,
+    pub column: String,
+    /// Name of the column's type
+    pub expected: &'static str,
+    pub value: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}, {}: expected {}, got '{}'", self.row, self.column, self.expected, self.value)
+    }
+}
+
+/// The outcome of checking every cell against its column's type.
+#[derive(Debug, Clone, Default)]
+pub struct CoercionReport {
+    /// Number of rows checked
+    pub rows: usize,
+    /// Mismatches by row, then by column
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl CoercionReport {
+    /// Prints one line per mismatch, then a summary such as `2 cells don't match their column's type in 40 rows`.
+    pub fn print(&self, out: &mut impl Write) -> io::Result<()> {
+        for mismatch in &self.mismatches {
+            writeln!(out, "{}", mismatch)?;
+        }
+        let rows = format!("{} row{}", self.rows, if self.rows == 1 { "" } else { "s" });
+        match self.mismatches.len() {
+            0 => writeln!(out, "All cells match their column's type in {}", rows),
+            1 => writeln!(out, "1 cell doesn't match its column's type in {}", rows),
+            count => writeln!(out, "{} cells don't match their column's type in {}", count, rows),
+        }
+    }
+}
+
+/// The type a column's values are checked against.
+#[derive(Debug, Clone, Copy)]
+enum Expected {
+    Declared(ColumnType),
+    Inferred(InferredType),
+}
+
+impl Expected {
+    fn name(self) -> &'static str {
+        match self {
+            Expected::Declared(column_type) => column_type.name(),
+            Expected::Inferred(inferred_type) => inferred_type.name(),
+        }
+    }
+
+    fn matches(self, value: &str) -> bool {
+        match self {
+            Expected::Declared(column_type) => column_type.numeric_value(value).is_some(),
+            Expected::Inferred(inferred_type) => inferred_type.matches(value),
+        }
+    }
+}
+
+/// Finds the cells that can't be read as their column's type: the `--column-type` when there
+/// is one, otherwise the type most of the column's values have. Columns where no type has a
+/// majority hold text, which every value matches.
+pub fn coercion_report(headers: &csv::StringRecord, records: &[Vec<Cell>], column_types: &[Option<ColumnType>]) -> CoercionReport {
+    let expected: Vec<Option<Expected>> = (0..headers.len())
+        .map(|col_idx| match column_types[col_idx] {
+            Some(column_type) => Some(Expected::Declared(column_type)),
+            None => inference::majority_type(records, col_idx).map(Expected::Inferred),
+        })
+        .collect();
+
+    let mut mismatches = Vec::new();
+    for (row_idx, row) in records.iter().enumerate() {
+        for (col_idx, value) in row.iter().enumerate() {
+            let Some(Some(expected)) = expected.get(col_idx) else {
+                continue;
+            };
+            if !is_null(value) && !expected.matches(value) {
+                mismatches.push(Mismatch { row: row_idx + 1, column: headers[col_idx].to_string(), expected: expected.name(), value: value.to_string() });
+            }
+        }
+    }
+    CoercionReport { rows: records.len(), mismatches }
+}
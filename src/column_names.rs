@@ -0,0 +1,48 @@
+//! Matching the column names given to flags against the headers.
+//!
+//! Spreadsheet exports often carry invisible differences in their headers: a trailing space, a
+//! non-breaking space, a zero-width character, or a typographic dash or quote that looks like
+//! the ASCII one. Names are compared with those smoothed out, while the headers are shown as
+//! they are.
+
+use std::cell::Cell;
+
+/// Characters that take up no space, e.g. a byte order mark left at the start of a header.
+const INVISIBLE_CHARS: [char; 5] = ['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+
+thread_local! {
+    static IGNORE_CASE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sets whether column names match regardless of case (`--ignore-header-case`).
+pub fn set_ignore_case(ignore_case: bool) {
+    IGNORE_CASE.with(|cell| cell.set(ignore_case));
+}
+
+/// The form of a column name that matching compares: trimmed, with runs of whitespace as one
+/// space, invisible characters dropped, dashes as `-` and quotes as `'` or `"`.
+pub fn normalize(name: &str) -> String {
+    let mut normalized = String::new();
+    for word in name.split_whitespace() {
+        if !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        normalized.extend(word.chars().filter(|c| !INVISIBLE_CHARS.contains(c)).map(|c| match c {
+            '\u{2010}'..='\u{2015}' | '\u{2212}' => '-',
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => '\'',
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' => '"',
+            c => c,
+        }));
+    }
+    if IGNORE_CASE.with(Cell::get) { normalized.to_lowercase() } else { normalized }
+}
+
+/// Position of the column `name` refers to: the column with exactly that name, or else the
+/// first whose name matches once both are normalized.
+pub fn position<'a>(headers: impl IntoIterator<Item = &'a str> + Clone, name: &str) -> Option<usize> {
+    if let Some(position) = headers.clone().into_iter().position(|header| header == name) {
+        return Some(position);
+    }
+    let name = normalize(name);
+    headers.into_iter().position(|header| normalize(header) == name)
+}
@@ -0,0 +1,297 @@
+//! Column type transforms selected with `--column-type COLUMN=TYPE`.
+//!
+//! A column type reinterprets raw cell values and renders them in a human-friendly form.
+//! Values that don't parse as the declared type are left untouched. Sorting uses the
+//! underlying value rather than the rendered text.
+
+use crate::{dates, locale, stats, Alignment};
+use chrono::DateTime;
+
+/// A display transform applied to every cell of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    /// Elapsed time, rendered like `2m 31s`. Plain numbers are read in the given unit;
+    /// ISO 8601 durations (`PT2M31S`) are recognized regardless of unit.
+    Duration(DurationUnit),
+    /// Raw byte counts, rendered with binary units like `1.5 MiB`.
+    Bytes,
+    /// Fractions rendered as percentages (`0.053` → `5.3%`) with the given number of decimals.
+    Percent(usize),
+    /// Numbers with their digits grouped (`1234567.5` → `1,234,567.5`), with the given number
+    /// of decimals or as many as written.
+    Number(Option<usize>),
+    /// Dates and times written with month names (`2024-01-31` → `31 Jan 2024`).
+    Date(DateStyle),
+}
+
+/// How much of a date is spelled out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateStyle {
+    /// The locale's numeric form, e.g. `01/31/24` or `31.01.2024`
+    Short,
+    /// An abbreviated month name, e.g. `31 Jan 2024`
+    Medium,
+    /// The full month name, e.g. `31 January 2024`
+    Long,
+}
+
+impl DateStyle {
+    /// The chrono format for dates, and for date-times with a time of day.
+    fn formats(self) -> (&'static str, &'static str) {
+        match self {
+            DateStyle::Short => ("%x", "%x %H:%M"),
+            DateStyle::Medium => ("%-d %b %Y", "%-d %b %Y %H:%M"),
+            DateStyle::Long => ("%-d %B %Y", "%-d %B %Y %H:%M"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationUnit {
+    Seconds,
+    Milliseconds,
+}
+
+impl ColumnType {
+    /// Parses a type name such as `duration` or `duration(ms)`.
+    fn parse(name: &str) -> Result<Self, String> {
+        let (base, param) = match name.split_once('(') {
+            Some((base, rest)) => {
+                let param = rest.strip_suffix(')')
+                    .ok_or_else(|| format!("missing ')' in column type '{}'", name))?;
+                (base, Some(param))
+            }
+            None => (name, None),
+        };
+
+        match base {
+            "duration" => {
+                let unit = match param {
+                    None | Some("s") => DurationUnit::Seconds,
+                    Some("ms") => DurationUnit::Milliseconds,
+                    Some(other) => return Err(format!("unknown duration unit '{}' (expected s or ms)", other)),
+                };
+                Ok(ColumnType::Duration(unit))
+            }
+            "bytes" if param.is_none() => Ok(ColumnType::Bytes),
+            "bytes" => Err(format!("column type 'bytes' takes no parameter, got '{}'", name)),
+            "percent" => {
+                let decimals = match param {
+                    None => 1,
+                    Some(digits) => digits.parse()
+                        .map_err(|_| format!("invalid percent decimals '{}' (expected a number)", digits))?,
+                };
+                Ok(ColumnType::Percent(decimals))
+            }
+            "number" => {
+                let decimals = param
+                    .map(|digits| digits.parse().map_err(|_| format!("invalid number decimals '{}' (expected a number)", digits)))
+                    .transpose()?;
+                Ok(ColumnType::Number(decimals))
+            }
+            "date" => {
+                let style = match param {
+                    Some("short") => DateStyle::Short,
+                    None | Some("medium") => DateStyle::Medium,
+                    Some("long") => DateStyle::Long,
+                    Some(other) => return Err(format!("unknown date style '{}' (expected short, medium or long)", other)),
+                };
+                Ok(ColumnType::Date(style))
+            }
+            _ => Err(format!("unknown column type '{}' (expected duration, bytes, percent, number or date)", base)),
+        }
+    }
+
+    /// The type's name as written in `--column-type`, without parameters.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColumnType::Duration(_) => "duration",
+            ColumnType::Bytes => "bytes",
+            ColumnType::Percent(_) => "percent",
+            ColumnType::Number(_) => "number",
+            ColumnType::Date(_) => "date",
+        }
+    }
+
+    /// Renders a raw cell value, or returns None if it isn't a valid value of this type.
+    /// Numbers are written the way the locale writes them.
+    pub fn format(&self, value: &str) -> Option<String> {
+        match self {
+            // Plain numbers keep the decimals they were written with
+            ColumnType::Number(None) if value.trim().bytes().all(|b| b.is_ascii_digit() || b"+-.".contains(&b)) => {
+                parse_number(value).map(|_| locale::group_digits(value.trim()))
+            }
+            _ => self.numeric_value(value).map(|quantity| self.format_quantity(quantity)),
+        }
+    }
+
+    /// Renders an underlying quantity as returned by [`numeric_value`](ColumnType::numeric_value).
+    pub fn format_quantity(&self, quantity: f64) -> String {
+        match self {
+            ColumnType::Duration(_) => locale::localize_decimal(humanize_millis(quantity)),
+            ColumnType::Bytes => locale::localize_decimal(humanize_bytes(quantity)),
+            ColumnType::Percent(decimals) => locale::localize_decimal(format!("{:.*}%", *decimals, quantity * 100.0)),
+            ColumnType::Number(Some(decimals)) => locale::group_digits(&format!("{:.*}", *decimals, quantity)),
+            ColumnType::Number(None) => locale::group_digits(&stats::format_number(quantity)),
+            ColumnType::Date(style) => match DateTime::from_timestamp(quantity.round() as i64, 0) {
+                Some(date) => {
+                    let date = date.naive_utc();
+                    let (date_format, date_time_format) = style.formats();
+                    let format = if date.time() == chrono::NaiveTime::MIN { date_format } else { date_time_format };
+                    locale::format_date(date, format)
+                }
+                None => String::new(),
+            },
+        }
+    }
+
+    /// The underlying quantity of a raw cell value (milliseconds for durations, bytes for sizes,
+    /// the fraction for percentages), used to sort and compare by magnitude rather than by the
+    /// formatted text.
+    pub fn numeric_value(&self, value: &str) -> Option<f64> {
+        match self {
+            ColumnType::Duration(unit) => parse_duration_millis(value, *unit),
+            ColumnType::Bytes => parse_number(value),
+            // Values already written as percentages ("5.3%") are converted back to fractions
+            ColumnType::Percent(_) => match value.trim().strip_suffix('%') {
+                Some(percentage) => parse_number(percentage).map(|p| p / 100.0),
+                None => parse_number(value),
+            },
+            ColumnType::Number(_) => parse_number(value),
+            // Seconds since 1970
+            ColumnType::Date(_) => dates::parse_timestamp(value).map(|date| date.and_utc().timestamp() as f64),
+        }
+    }
+
+    /// Typed columns hold quantities, so they line up on the right like numbers (dates too,
+    /// which keeps years under years).
+    pub fn alignment(&self) -> Alignment {
+        Alignment::Right
+    }
+}
+
+/// Clap value parser for `COLUMN=TYPE` entries of `--column-type`.
+pub fn parse_column_type_arg(s: &str) -> Result<(String, ColumnType), String> {
+    let (column, type_name) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected COLUMN=TYPE, got '{}'", s))?;
+    Ok((column.to_string(), ColumnType::parse(type_name.trim())?))
+}
+
+/// Parses a plain number, rejecting words like "inf" that have no digits.
+fn parse_number(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if !value.bytes().any(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// Parses a duration value into milliseconds.
+fn parse_duration_millis(value: &str, unit: DurationUnit) -> Option<f64> {
+    let value = value.trim();
+    if value.starts_with(['P', 'p', '-']) && value.contains(['P', 'p']) {
+        return parse_iso8601_duration(value);
+    }
+    let number = parse_number(value)?;
+    Some(match unit {
+        DurationUnit::Seconds => number * 1000.0,
+        DurationUnit::Milliseconds => number,
+    })
+}
+
+/// Parses an ISO 8601 duration such as `P1DT2H3M4.5S` into milliseconds.
+/// Years and months are approximated as 365 and 30 days.
+fn parse_iso8601_duration(value: &str) -> Option<f64> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let body = value.strip_prefix(['P', 'p'])?;
+
+    let mut total_seconds = 0.0;
+    let mut in_time = false;
+    let mut number = String::new();
+    let mut saw_component = false;
+    for ch in body.chars() {
+        match ch.to_ascii_uppercase() {
+            'T' if number.is_empty() && !in_time => in_time = true,
+            c if c.is_ascii_digit() || c == '.' || c == ',' => number.push(if c == ',' { '.' } else { c }),
+            designator => {
+                let amount: f64 = number.parse().ok()?;
+                number.clear();
+                let seconds_per_unit = match (designator, in_time) {
+                    ('Y', false) => 365.0 * 86400.0,
+                    ('M', false) => 30.0 * 86400.0,
+                    ('W', false) => 7.0 * 86400.0,
+                    ('D', false) => 86400.0,
+                    ('H', true) => 3600.0,
+                    ('M', true) => 60.0,
+                    ('S', true) => 1.0,
+                    _ => return None,
+                };
+                total_seconds += amount * seconds_per_unit;
+                saw_component = true;
+            }
+        }
+    }
+
+    if !number.is_empty() || !saw_component {
+        return None;
+    }
+    let millis = total_seconds * 1000.0;
+    Some(if negative { -millis } else { millis })
+}
+
+/// Formats milliseconds as a short human-readable duration.
+///
+/// Sub-second values show milliseconds (`850ms`), values under a minute show seconds with one
+/// decimal (`12.5s`), and longer values show their two most significant units (`2m 31s`, `3d 4h`).
+fn humanize_millis(millis: f64) -> String {
+    let sign = if millis < 0.0 { "-" } else { "" };
+    let millis = millis.abs();
+
+    if millis < 1000.0 {
+        return format!("{}{}ms", sign, millis.round() as u64);
+    }
+    if millis < 60_000.0 {
+        let seconds = format!("{:.1}", millis / 1000.0);
+        return format!("{}{}s", sign, seconds.trim_end_matches(".0"));
+    }
+
+    let total_seconds = (millis / 1000.0).round() as u64;
+    let units = [
+        (total_seconds / 86400, "d"),
+        (total_seconds % 86400 / 3600, "h"),
+        (total_seconds % 3600 / 60, "m"),
+        (total_seconds % 60, "s"),
+    ];
+    let first = units.iter().position(|&(amount, _)| amount > 0).unwrap_or(units.len() - 1);
+    let parts: Vec<String> = units[first..]
+        .iter()
+        .take(2)
+        .filter(|&&(amount, _)| amount > 0)
+        .map(|(amount, suffix)| format!("{}{}", amount, suffix))
+        .collect();
+    format!("{}{}", sign, parts.join(" "))
+}
+
+/// Formats a byte count using binary (1024-based) units, e.g. `512 B`, `1.5 KiB`, `20.0 GiB`.
+fn humanize_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+    let sign = if bytes < 0.0 { "-" } else { "" };
+    let mut size = bytes.abs();
+    if size < 1024.0 {
+        return format!("{}{} B", sign, size.round() as u64);
+    }
+
+    let mut unit = 0;
+    size /= 1024.0;
+    // Check the rounded value so that e.g. 1023.99 KiB becomes 1.0 MiB rather than 1024.0 KiB
+    while (size * 10.0).round() / 10.0 >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{}{:.1} {}", sign, size, UNITS[unit])
+}
@@ -0,0 +1,51 @@
+//! Showing several files as one table (several FILE arguments, or `--concat`).
+//!
+//! Exports made at different times rarely have exactly the same columns. The table gets every
+//! column of every file, in the order they first appear, and rows from files without a column
+//! leave it empty. A source column can say which file each row came from.
+
+use csvpretty::{Cell, Table};
+
+/// Stacks the rows of `sources`, each named by where it was read from, under the union of
+/// their columns. Columns are matched by name; a name repeated within a file matches the same
+/// repeat in the others. With `source_column`, a first column of that name holds each row's
+/// source.
+pub fn concat<'a>(sources: Vec<(String, Table<'a>)>, source_column: Option<&str>) -> Table<'a> {
+    let mut headers: Vec<String> = Vec::new();
+    for (_, table) in &sources {
+        for (col_idx, name) in table.headers.iter().enumerate() {
+            let repeat = repeat_count(&table.headers, col_idx);
+            if nth_position(headers.iter().map(String::as_str), name, repeat).is_none() {
+                headers.push(name.to_string());
+            }
+        }
+    }
+
+    let mut records = Vec::new();
+    for (source, table) in sources {
+        // The union column each of this file's columns goes in
+        let mut columns: Vec<Option<usize>> = vec![None; headers.len()];
+        for (col_idx, name) in table.headers.iter().enumerate() {
+            if let Some(position) = nth_position(headers.iter().map(String::as_str), name, repeat_count(&table.headers, col_idx)) {
+                columns[position] = Some(col_idx);
+            }
+        }
+        for row in table.records {
+            let cells = columns.iter().map(|column| column.and_then(|col_idx| row.get(col_idx)).cloned().unwrap_or_default());
+            records.push(source_column.map(|_| Cell::Owned(source.clone())).into_iter().chain(cells).collect());
+        }
+    }
+
+    let headers = source_column.map(String::from).into_iter().chain(headers).collect();
+    Table { headers, records }
+}
+
+/// How many columns before `col_idx` have the same name.
+fn repeat_count(headers: &csv::StringRecord, col_idx: usize) -> usize {
+    headers.iter().take(col_idx).filter(|name| *name == &headers[col_idx]).count()
+}
+
+/// Position of the `n`th column (from 0) named `name`.
+fn nth_position<'a>(headers: impl Iterator<Item = &'a str>, name: &str, n: usize) -> Option<usize> {
+    headers.enumerate().filter(|(_, header)| *header == name).nth(n).map(|(position, _)| position)
+}
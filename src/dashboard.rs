@@ -0,0 +1,333 @@
+//! Live tables of aggregates (`--dashboard`).
+//!
+//! Rows are read as they arrive, e.g. from a process writing CSV to a pipe, and grouped by a
+//! column. Each group gets a row of aggregates such as `count()` or `p95(latency)`, and on a
+//! terminal the table is drawn again in place as the numbers change, a few times a second at
+//! most. Elsewhere only the final table is printed.
+
+use crate::{column_names, stats};
+use crossterm::{cursor, queue, terminal};
+use csv::StringRecord;
+use csvpretty::{is_numeric, Cell, CsvOptions, Table};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Shortest time between two redraws.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often a followed file is checked for new data once its end is reached.
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What an aggregate computes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AggregateKind {
+    /// Rows, or non-empty values when a column is given
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+    /// The value below which this percentage of the values fall
+    Percentile(u8),
+}
+
+/// An `--agg` entry such as `p95(latency)`.
+#[derive(Debug, Clone)]
+pub struct Aggregate {
+    kind: AggregateKind,
+    column: Option<String>,
+    /// As written, for the column header
+    spec: String,
+}
+
+/// Clap value parser for `--agg`: `count()`, `count(COLUMN)`, `sum(COLUMN)`, `avg(COLUMN)`,
+/// `min(COLUMN)`, `max(COLUMN)` or a percentile such as `p95(COLUMN)`.
+pub fn parse_aggregate(s: &str) -> Result<Aggregate, String> {
+    let usage = || format!("invalid aggregate '{}' (expected count(), or count, sum, avg, min, max or pNN of a column, e.g. p95(latency))", s);
+    let (name, rest) = s.trim().split_once('(').ok_or_else(usage)?;
+    let column = rest.strip_suffix(')').ok_or_else(usage)?.trim();
+    let name = name.trim().to_ascii_lowercase();
+    let kind = match name.as_str() {
+        "count" => AggregateKind::Count,
+        "sum" => AggregateKind::Sum,
+        "avg" | "mean" => AggregateKind::Avg,
+        "min" => AggregateKind::Min,
+        "max" => AggregateKind::Max,
+        _ => match name.strip_prefix('p').map(str::parse::<u8>) {
+            Some(Ok(percent @ 1..=100)) => AggregateKind::Percentile(percent),
+            _ => return Err(usage()),
+        },
+    };
+    if column.is_empty() && kind != AggregateKind::Count {
+        return Err(format!("{}() needs a column, e.g. {}(latency)", name, name));
+    }
+    Ok(Aggregate { kind, column: Some(column.to_string()).filter(|column| !column.is_empty()), spec: s.trim().to_string() })
+}
+
+impl Aggregate {
+    /// The column the aggregate reads, which `count()` doesn't have.
+    pub fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    /// Whether the aggregate counts rows or values, rather than giving a figure in the units of
+    /// its column.
+    pub fn is_count(&self) -> bool {
+        self.kind == AggregateKind::Count
+    }
+
+    /// An empty tally of the values this aggregate reads.
+    pub fn tally(&self) -> Tally {
+        Tally { sorted: matches!(self.kind, AggregateKind::Percentile(_)).then(Vec::new), ..Tally::default() }
+    }
+
+    /// The aggregate of `rows` rows whose values in the column are in `tally`. None when there
+    /// are no numbers to aggregate.
+    pub fn evaluate(&self, rows: usize, tally: &Tally) -> Option<f64> {
+        match self.kind {
+            AggregateKind::Count if self.column.is_none() => Some(rows as f64),
+            AggregateKind::Count => Some(tally.filled as f64),
+            _ if tally.numbers == 0 => None,
+            AggregateKind::Sum => Some(tally.sum),
+            AggregateKind::Avg => Some(tally.sum / tally.numbers as f64),
+            AggregateKind::Min => Some(tally.min),
+            AggregateKind::Max => Some(tally.max),
+            AggregateKind::Percentile(percent) => {
+                // Nearest rank: the smallest value at least `percent`% of the values are at or below
+                let sorted = tally.sorted.as_deref()?;
+                let rank = (f64::from(percent) / 100.0 * sorted.len() as f64).ceil() as usize;
+                Some(sorted[rank.clamp(1, sorted.len()) - 1])
+            }
+        }
+    }
+}
+
+/// The values an aggregate has read so far, as running figures, so that aggregating doesn't
+/// go through every value again each time the table is drawn. Only percentiles keep the
+/// numbers themselves, sorted as they arrive.
+#[derive(Debug, Clone)]
+pub struct Tally {
+    /// Values that aren't empty
+    filled: usize,
+    /// Numbers among them, with their sum and range
+    numbers: usize,
+    sum: f64,
+    min: f64,
+    max: f64,
+    sorted: Option<Vec<f64>>,
+}
+
+impl Default for Tally {
+    fn default() -> Self {
+        Tally { filled: 0, numbers: 0, sum: 0.0, min: f64::INFINITY, max: f64::NEG_INFINITY, sorted: None }
+    }
+}
+
+impl Tally {
+    /// Adds a value that isn't empty, with its number if it has one.
+    pub fn add(&mut self, number: Option<f64>) {
+        self.filled += 1;
+        let Some(number) = number else {
+            return;
+        };
+        self.numbers += 1;
+        self.sum += number;
+        self.min = self.min.min(number);
+        self.max = self.max.max(number);
+        if let Some(sorted) = &mut self.sorted {
+            let position = sorted.partition_point(|&value| value.total_cmp(&number).is_le());
+            sorted.insert(position, number);
+        }
+    }
+}
+
+/// The rows of one group seen so far.
+struct GroupStats {
+    key: String,
+    rows: usize,
+    /// The values of each aggregate's column
+    tallies: Vec<Tally>,
+}
+
+/// Aggregates per group of the rows added so far.
+pub struct Dashboard {
+    headers: StringRecord,
+    group_column: usize,
+    aggregates: Vec<(Aggregate, Option<usize>)>,
+    /// In the order the groups first appeared, so rows don't jump around
+    groups: Vec<GroupStats>,
+    index: HashMap<String, usize>,
+    rows: usize,
+}
+
+impl Dashboard {
+    pub fn new(headers: StringRecord, group_by: &str, aggregates: &[Aggregate]) -> Result<Dashboard, String> {
+        let find = |name: &str, flag: &str| column_names::position(&headers, name).ok_or_else(|| format!("unknown column '{}' in {}", name, flag));
+        let group_column = find(group_by, "--group-by")?;
+        let aggregates = aggregates.iter()
+            .map(|aggregate| match &aggregate.column {
+                Some(column) => find(column, "--agg").map(|col_idx| (aggregate.clone(), Some(col_idx))),
+                None => Ok((aggregate.clone(), None)),
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Dashboard { headers, group_column, aggregates, groups: Vec::new(), index: HashMap::new(), rows: 0 })
+    }
+
+    pub fn add(&mut self, record: &StringRecord) {
+        self.rows += 1;
+        let key = record.get(self.group_column).unwrap_or_default();
+        let position = match self.index.get(key) {
+            Some(&position) => position,
+            None => {
+                self.index.insert(key.to_string(), self.groups.len());
+                self.groups.push(GroupStats {
+                    key: key.to_string(),
+                    rows: 0,
+                    tallies: self.aggregates.iter().map(|(aggregate, _)| aggregate.tally()).collect(),
+                });
+                self.groups.len() - 1
+            }
+        };
+        let group = &mut self.groups[position];
+        group.rows += 1;
+        for (agg_idx, (_, column)) in self.aggregates.iter().enumerate() {
+            let Some(value) = column.and_then(|col_idx| record.get(col_idx)).map(str::trim).filter(|value| !value.is_empty()) else {
+                continue;
+            };
+            group.tallies[agg_idx].add(is_numeric(value).then(|| value.parse().unwrap_or_default()));
+        }
+    }
+
+    /// The rows added so far.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// A row per group: its value of the --group-by column, then its aggregates.
+    pub fn table(&self) -> Table<'static> {
+        let mut headers = StringRecord::new();
+        headers.push_field(self.headers.get(self.group_column).unwrap_or_default());
+        for (aggregate, _) in &self.aggregates {
+            headers.push_field(&aggregate.spec);
+        }
+        let records = self.groups.iter()
+            .map(|group| {
+                let mut row = vec![Cell::Owned(group.key.clone())];
+                for (agg_idx, (aggregate, _)) in self.aggregates.iter().enumerate() {
+                    let value = aggregate.evaluate(group.rows, &group.tallies[agg_idx]);
+                    row.push(value.map(stats::format_number).unwrap_or_default().into());
+                }
+                row
+            })
+            .collect();
+        Table { headers, records }
+    }
+}
+
+/// A file read like `tail -f`: at its end, it waits for more to be written instead of ending.
+pub struct Follow<R>(pub R);
+
+impl<R: Read> Read for Follow<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.0.read(buf)? {
+                0 if !buf.is_empty() => std::thread::sleep(FOLLOW_INTERVAL),
+                read => return Ok(read),
+            }
+        }
+    }
+}
+
+/// What the reading thread passes on.
+enum Message {
+    Headers(StringRecord),
+    Record(StringRecord),
+    Error(String),
+}
+
+/// Reads CSV rows from `reader` until it ends, keeping the aggregates per group up to date.
+/// With `live`, `draw` is called with the table whenever it changed (at most every
+/// [`REDRAW_INTERVAL`]); it's always called once more at the end, with the final table.
+pub fn run(
+    reader: impl Read + Send + 'static,
+    options: &CsvOptions,
+    group_by: &str,
+    aggregates: &[Aggregate],
+    live: bool,
+    mut draw: impl FnMut(&Table, usize) -> io::Result<()>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Rows are read on their own thread, so a pause in the input doesn't hold up a redraw
+    let (sender, receiver) = mpsc::channel();
+    let options = *options;
+    std::thread::spawn(move || {
+        let mut reader = options.reader_builder()
+            .flexible(true)
+            .from_reader(reader);
+        let headers = match reader.headers() {
+            Ok(headers) if options.has_headers => headers.clone(),
+            Ok(first) => (1..=first.len()).map(|n| n.to_string()).collect(),
+            Err(e) => {
+                let _ = sender.send(Message::Error(e.to_string()));
+                return;
+            }
+        };
+        if sender.send(Message::Headers(headers)).is_err() {
+            return;
+        }
+        for result in reader.records() {
+            let message = result.map_or_else(|e| Message::Error(e.to_string()), Message::Record);
+            if sender.send(message).is_err() {
+                return;
+            }
+        }
+    });
+
+    let mut dashboard = match receiver.recv() {
+        Ok(Message::Headers(headers)) => Dashboard::new(headers, group_by, aggregates)?,
+        Ok(Message::Error(e)) => return Err(e.into()),
+        _ => return Err("No input provided".into()),
+    };
+    let mut changed = live;
+    let mut last_draw: Option<Instant> = None;
+    loop {
+        match receiver.recv_timeout(REDRAW_INTERVAL) {
+            Ok(Message::Record(record)) => {
+                dashboard.add(&record);
+                changed = true;
+            }
+            Ok(Message::Error(e)) => return Err(e.into()),
+            Ok(Message::Headers(_)) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if live && changed && last_draw.is_none_or(|drawn| drawn.elapsed() >= REDRAW_INTERVAL) {
+            draw(&dashboard.table(), dashboard.rows())?;
+            changed = false;
+            last_draw = Some(Instant::now());
+        }
+    }
+    draw(&dashboard.table(), dashboard.rows())?;
+    Ok(())
+}
+
+/// Draws frames over the previous one, on a terminal.
+#[derive(Default)]
+pub struct Screen {
+    /// Lines the frame on screen takes
+    lines: usize,
+}
+
+impl Screen {
+    /// Replaces the frame on screen with `frame`.
+    pub fn show(&mut self, out: &mut impl Write, frame: &[u8]) -> io::Result<()> {
+        if self.lines > 0 {
+            let lines = u16::try_from(self.lines).unwrap_or(u16::MAX);
+            queue!(out, cursor::MoveToPreviousLine(lines), terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        }
+        out.write_all(frame)?;
+        out.flush()?;
+        self.lines = frame.iter().filter(|&&byte| byte == b'\n').count();
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+//! Date and time parsing shared by the filtering flags.
+//!
+//! CSV exports use a handful of timestamp layouts; this module recognizes the common ones
+//! and normalizes them to a `NaiveDateTime` so values can be compared. Timestamps with an
+//! explicit offset are converted to UTC, everything else is taken as-is.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+/// Date-time layouts tried in order, after RFC 3339.
+const DATE_TIME_FORMATS: [&str; 6] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+];
+
+/// Date-only layouts, interpreted as midnight.
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%d/%m/%Y"];
+
+/// Parses a date or date-time value, returning None if it matches no known layout.
+pub fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.naive_utc());
+    }
+
+    DATE_TIME_FORMATS.iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            DATE_FORMATS.iter()
+                .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+}
+
+/// Clap value parser for timestamp arguments such as `--since`.
+pub fn parse_timestamp_arg(value: &str) -> Result<NaiveDateTime, String> {
+    parse_timestamp(value).ok_or_else(|| {
+        format!("unrecognized date '{}' (expected e.g. 2024-01-31 or 2024-01-31T12:00:00)", value)
+    })
+}
@@ -0,0 +1,170 @@
+//! Comparing two CSV files (`csvpretty diff`).
+//!
+//! The rows of both files are lined up, by position or by a key column, and shown as one
+//! table with a leading column marking each row as added (`+`), removed (`-`), changed (`~`)
+//! or unchanged. Changed cells show the old and the new value. Columns are matched by name,
+//! so files whose columns were reordered, added or dropped can still be compared; only the
+//! columns both files have count towards changes.
+
+use csvpretty::{Cell, Highlights, Table};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Colors of added rows, removed rows and changed cells.
+const ADDED_COLOR: (u8, u8, u8) = (40, 180, 40);
+const REMOVED_COLOR: (u8, u8, u8) = (220, 50, 47);
+const CHANGED_COLOR: (u8, u8, u8) = (230, 180, 0);
+
+/// What happened to a row between the old and the new file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+impl Change {
+    fn marker(self) -> &'static str {
+        match self {
+            Change::Added => "+",
+            Change::Removed => "-",
+            Change::Changed => "~",
+            Change::Unchanged => "",
+        }
+    }
+}
+
+/// Number of rows of each kind, printed below the table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+}
+
+impl Summary {
+    pub fn has_differences(&self) -> bool {
+        self.added + self.removed + self.changed > 0
+    }
+
+    /// Prints e.g. `1 added, 2 removed, 3 changed, 10 unchanged`.
+    pub fn print(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "{} added, {} removed, {} changed, {} unchanged", self.added, self.removed, self.changed, self.unchanged)
+    }
+
+    fn count(&mut self, change: Change) {
+        match change {
+            Change::Added => self.added += 1,
+            Change::Removed => self.removed += 1,
+            Change::Changed => self.changed += 1,
+            Change::Unchanged => self.unchanged += 1,
+        }
+    }
+}
+
+/// The combined table of a comparison, with its colors.
+pub struct Diff<'a> {
+    pub headers: csv::StringRecord,
+    pub records: Vec<Vec<Cell<'a>>>,
+    pub highlights: Highlights,
+    pub summary: Summary,
+}
+
+impl<'a> Diff<'a> {
+    /// Adds a row, with its change marker in front.
+    fn push(&mut self, change: Change, row: Vec<Cell<'a>>, colors: Vec<Option<(u8, u8, u8)>>) {
+        self.summary.count(change);
+        self.records.push(std::iter::once(Cell::Borrowed(change.marker())).chain(row).collect());
+        // The marker takes the row's color, or its first changed cell's
+        let marker_color = colors.iter().flatten().next().copied();
+        self.highlights.push(std::iter::once(marker_color).chain(colors).collect());
+    }
+}
+
+/// A row of the combined table: an old row no new row matched, or a new row.
+enum Row {
+    Removed(usize),
+    New(usize),
+}
+
+/// Compares `old` and `new`. With `key`, the positions of the key column in the old and the new
+/// file, rows are matched by their key (the first row with a key wins); otherwise row by row.
+/// Rows come in the new file's order, with removed rows where they were in the old file.
+pub fn diff<'a>(old: &Table<'a>, new: &Table<'a>, key: Option<(usize, usize)>) -> Diff<'a> {
+    // The new file's columns, then the ones only the old file has
+    let mut names: Vec<&str> = new.headers.iter().collect();
+    names.extend(old.headers.iter().filter(|name| !new.headers.iter().any(|new_name| new_name == *name)));
+    let position = |headers: &csv::StringRecord, name: &str| headers.iter().position(|header| header == name);
+    let old_columns: Vec<Option<usize>> = names.iter().map(|name| position(&old.headers, name)).collect();
+    let new_columns: Vec<Option<usize>> = names.iter().map(|name| position(&new.headers, name)).collect();
+    // Columns only one file has show that file's values, and don't count as changes
+    let shared: Vec<bool> = old_columns.iter().zip(&new_columns).map(|(old, new)| old.is_some() && new.is_some()).collect();
+    let cells = |row: &[Cell<'a>], columns: &[Option<usize>]| -> Vec<Cell<'a>> {
+        columns.iter().map(|column| column.and_then(|col_idx| row.get(col_idx)).cloned().unwrap_or_default()).collect()
+    };
+
+    // The old row each new row is compared with
+    let matches: Vec<Option<usize>> = match key {
+        Some((old_key, new_key)) => {
+            let mut old_rows: HashMap<&str, usize> = HashMap::new();
+            for (row_idx, row) in old.records.iter().enumerate() {
+                old_rows.entry(row.get(old_key).map_or("", |cell| &**cell)).or_insert(row_idx);
+            }
+            new.records.iter()
+                .map(|row| old_rows.get(row.get(new_key).map_or("", |cell| &**cell)).copied())
+                .collect()
+        }
+        None => (0..new.records.len()).map(|row_idx| (row_idx < old.records.len()).then_some(row_idx)).collect(),
+    };
+    let mut matched = vec![false; old.records.len()];
+    for &old_idx in matches.iter().flatten() {
+        matched[old_idx] = true;
+    }
+
+    // Removed rows go before the first new row matched with a later old row
+    let mut order = Vec::new();
+    let mut next_old = 0;
+    for (new_idx, old_idx) in matches.iter().enumerate() {
+        if let Some(old_idx) = *old_idx {
+            order.extend((next_old..old_idx).filter(|&row_idx| !matched[row_idx]).map(Row::Removed));
+            next_old = next_old.max(old_idx);
+        }
+        order.push(Row::New(new_idx));
+    }
+    order.extend((next_old..old.records.len()).filter(|&row_idx| !matched[row_idx]).map(Row::Removed));
+
+    let mut result = Diff { headers: csv::StringRecord::new(), records: Vec::new(), highlights: Vec::new(), summary: Summary::default() };
+    for row in order {
+        let (new_idx, old_idx) = match row {
+            Row::Removed(old_idx) => {
+                result.push(Change::Removed, cells(&old.records[old_idx], &old_columns), vec![Some(REMOVED_COLOR); names.len()]);
+                continue;
+            }
+            Row::New(new_idx) => (new_idx, matches[new_idx]),
+        };
+        let new_cells = cells(&new.records[new_idx], &new_columns);
+        let Some(old_idx) = old_idx else {
+            result.push(Change::Added, new_cells, vec![Some(ADDED_COLOR); names.len()]);
+            continue;
+        };
+        let old_cells = cells(&old.records[old_idx], &old_columns);
+        let changed: Vec<bool> = (0..names.len()).map(|column| shared[column] && old_cells[column] != new_cells[column]).collect();
+        let row = old_cells.into_iter()
+            .zip(new_cells)
+            .enumerate()
+            .map(|(column, (old, new))| match (changed[column], new_columns[column]) {
+                (true, _) => format!("{} → {}", old, new).into(),
+                (false, Some(_)) => new,
+                (false, None) => old,
+            })
+            .collect();
+        let colors: Vec<Option<(u8, u8, u8)>> = changed.iter().map(|&changed| changed.then_some(CHANGED_COLOR)).collect();
+        let change = if changed.contains(&true) { Change::Changed } else { Change::Unchanged };
+        result.push(change, row, colors);
+    }
+
+    result.headers = std::iter::once("").chain(names).collect();
+    result
+}
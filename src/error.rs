@@ -0,0 +1,96 @@
+//! Errors from reading and drawing tables.
+
+use crate::InputOffset;
+use std::io;
+
+/// What went wrong reading or drawing a table. The message is the one csvpretty prints; match
+/// on the variant to tell the kinds of failure apart, e.g. to point at the line of a
+/// [`ParseError`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input couldn't be read, e.g. a file that isn't a workbook or a missing database table.
+    #[error("{0}")]
+    Input(String),
+    /// The input was read but isn't well-formed.
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// The table can't be laid out, e.g. a row with more cells than the table has columns.
+    #[error("{0}")]
+    Layout(String),
+    /// Writing the table failed.
+    #[error(transparent)]
+    Render(#[from] io::Error),
+}
+
+/// A problem at a place in the input.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message}")]
+pub struct ParseError {
+    /// Line the problem is on, starting at 1, when known
+    pub line: Option<u64>,
+    /// Where on the line, starting at 1, when known: the field for CSV, the character for
+    /// JSON and YAML
+    pub column: Option<u64>,
+    /// The CSV record the problem is in, counting from 0 at the first record read (the header
+    /// row, if there is one)
+    pub record: Option<u64>,
+    /// Offset in bytes from the start of the parsed text, when known: the start of the record
+    /// for CSV, the problem itself for YAML
+    pub byte: Option<u64>,
+    pub kind: ParseErrorKind,
+    pub message: String,
+}
+
+/// The kinds of [`ParseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// Text that doesn't follow the format, e.g. an unclosed JSON string
+    Syntax,
+    /// Text that isn't valid UTF-8
+    Encoding,
+    /// A CSV record with a different number of fields than the header (or first record)
+    FieldCount { fields: usize, expected: usize },
+}
+
+impl Error {
+    /// The error for CSV text that starts `offset` into its input. The line and byte of a
+    /// [`ParseError`], also in its message, count from the start of the input.
+    pub fn from_csv(e: csv::Error, offset: InputOffset) -> Error {
+        let position = e.position().cloned();
+        match (Error::from(e), position) {
+            (Error::Parse(mut parse_error), Some(position)) if offset != InputOffset::default() => {
+                let (line, byte) = (position.line() + offset.lines, position.byte() + offset.bytes);
+                // csv writes the position as `(line: 3, byte: 8)`, or `(line 3, field: 1, byte: 8)`
+                parse_error.message = parse_error.message
+                    .replacen(&format!("line: {},", position.line()), &format!("line: {},", line), 1)
+                    .replacen(&format!("line {},", position.line()), &format!("line {},", line), 1)
+                    .replacen(&format!("byte: {})", position.byte()), &format!("byte: {})", byte), 1);
+                parse_error.line = Some(line);
+                parse_error.byte = Some(byte);
+                Error::Parse(parse_error)
+            }
+            (error, _) => error,
+        }
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        let message = e.to_string();
+        let position = e.position();
+        let line = position.map(|position| position.line());
+        let record = position.map(|position| position.record());
+        let byte = position.map(|position| position.byte());
+        let (kind, column) = match e.kind() {
+            csv::ErrorKind::Io(_) => return Error::Input(message),
+            csv::ErrorKind::Utf8 { err, .. } => (ParseErrorKind::Encoding, Some(err.field() as u64 + 1)),
+            csv::ErrorKind::UnequalLengths { expected_len, len, .. } => {
+                (ParseErrorKind::FieldCount { fields: *len as usize, expected: *expected_len as usize }, None)
+            }
+            _ => (ParseErrorKind::Syntax, None),
+        };
+        Error::Parse(ParseError { line, column, record, byte, kind, message })
+    }
+}
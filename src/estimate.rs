@@ -0,0 +1,106 @@
+//! Estimating how big a file is before showing it (`--estimate`).
+//!
+//! Pretty-printing a multi-gigabyte export in full takes a long time and floods the scrollback.
+//! The estimate only reads the start of the file, measures the rows there and scales them up to
+//! the file's size, then suggests flags that show part of the file instead.
+
+use crate::column_types::ColumnType;
+use csvpretty::CsvOptions;
+use std::io::{self, Write};
+
+/// Bytes read from the start of the file to measure its rows.
+pub const SAMPLE_BYTES: u64 = 1 << 20;
+
+/// Rows above which showing the whole file is discouraged.
+const LARGE_ROWS: u64 = 100_000;
+
+/// Rows suggested for `--sample` when the file is large.
+const SUGGESTED_SAMPLE: u64 = 1000;
+
+/// The size of a file, measured on its first rows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub file_size: u64,
+    /// Rows in the whole file, counted when the sample covers it all
+    pub rows: u64,
+    /// Average size of a row in bytes
+    pub row_bytes: f64,
+    /// Rows the estimate was measured on
+    pub sampled_rows: u64,
+    /// Whether the sample is the whole file, making `rows` a count rather than an estimate
+    pub exact: bool,
+}
+
+/// Measures the rows of `sample`, the start of a CSV file `file_size` bytes long, and scales
+/// them up to the whole file. The header line doesn't count as a row.
+pub fn estimate(sample: &str, file_size: u64, options: &CsvOptions) -> Result<Estimate, String> {
+    let mut reader = options.reader_builder()
+        .flexible(true)
+        .from_reader(sample.as_bytes());
+    let header_bytes = if options.has_headers {
+        reader.headers().map_err(|e| e.to_string())?;
+        reader.position().byte()
+    } else {
+        0
+    };
+    let sampled_rows = reader.records().count() as u64;
+    let exact = file_size <= SAMPLE_BYTES;
+    let row_bytes = (sample.len() as u64).saturating_sub(header_bytes) as f64 / sampled_rows.max(1) as f64;
+    let rows = match (exact, row_bytes > 0.0) {
+        (true, _) => sampled_rows,
+        (false, true) => (file_size.saturating_sub(header_bytes) as f64 / row_bytes).round() as u64,
+        (false, false) => 0,
+    };
+    Ok(Estimate { file_size, rows, row_bytes, sampled_rows, exact })
+}
+
+impl Estimate {
+    /// Prints e.g. `big.csv: 2.0 GiB, about 14,000,000 rows of 150 B each (measured on the first
+    /// 6,990 rows)`, then whether the file is small enough to show in full.
+    pub fn print(&self, out: &mut impl Write, name: &str) -> io::Result<()> {
+        let size = ColumnType::Bytes.format_quantity(self.file_size as f64);
+        let row_size = ColumnType::Bytes.format_quantity(self.row_bytes);
+        if self.exact {
+            writeln!(out, "{}: {}, {} of {} each", name, size, plural(self.rows, "row"), row_size)?;
+        } else {
+            writeln!(
+                out,
+                "{}: {}, about {} of {} each (measured on the first {})",
+                name,
+                size,
+                plural(round_estimate(self.rows), "row"),
+                row_size,
+                plural(self.sampled_rows, "row"),
+            )?;
+        }
+        if self.rows > LARGE_ROWS {
+            writeln!(
+                out,
+                "That's a lot to show in full: try --head-bytes {} to read only the start, or --sample {} for rows from throughout",
+                SAMPLE_BYTES,
+                SUGGESTED_SAMPLE,
+            )
+        } else {
+            writeln!(out, "Small enough to show in full")
+        }
+    }
+}
+
+/// Rounds an estimate to two significant digits, since it's no more precise than that.
+fn round_estimate(count: u64) -> u64 {
+    let scale = 10u64.pow((count.max(1).ilog10()).saturating_sub(1));
+    (count + scale / 2) / scale * scale
+}
+
+/// A count with its digits grouped in threes, e.g. `14,000,000 rows`.
+fn plural(count: u64, noun: &str) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{} {}{}", grouped, noun, if count == 1 { "" } else { "s" })
+}
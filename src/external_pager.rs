@@ -0,0 +1,36 @@
+//! Piping the table into a pager (`--pager`).
+//!
+//! Pagers such as less can scroll sideways, so the table is laid out without wrapping and long
+//! lines are cut at the screen edge instead of folded onto the next line.
+
+use std::env;
+use std::io;
+use std::process::{Child, Command, Stdio};
+
+/// Pager used when neither CSVPRETTY_PAGER nor PAGER is set.
+const DEFAULT_PAGER: &str = "less";
+
+/// Starts the pager named by CSVPRETTY_PAGER or PAGER (default: less), reading from a pipe.
+///
+/// less is told to pass colors through (-R) and to cut long lines rather than wrap them (-S).
+/// Unless LESS is set, it also quits straight away when the table fits on one screen and
+/// leaves it on the screen afterwards, as git does.
+pub fn spawn() -> io::Result<Child> {
+    let command = env::var("CSVPRETTY_PAGER")
+        .or_else(|_| env::var("PAGER"))
+        .ok()
+        .filter(|command| !command.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_PAGER);
+
+    let mut cmd = Command::new(program);
+    cmd.args(words).stdin(Stdio::piped());
+    if program.rsplit('/').next() == Some("less") {
+        cmd.args(["-R", "-S"]);
+        if env::var_os("LESS").is_none() {
+            cmd.env("LESS", "FX");
+        }
+    }
+    cmd.spawn().map_err(|e| io::Error::new(e.kind(), format!("starting pager '{}': {}", program, e)))
+}
@@ -0,0 +1,64 @@
+//! Regex extraction into new columns (`--extract`).
+//!
+//! A common first step with messy data is pulling one piece out of a field, such as the host
+//! of a URL or an id embedded in a description. Each capture group becomes a new column
+//! appended to the table.
+
+use regex::Regex;
+
+/// A `--extract 'COLUMN => NAME[,NAME...]:REGEX'` request.
+#[derive(Debug, Clone)]
+pub struct Extraction {
+    column: String,
+    names: Vec<String>,
+    regex: Regex,
+}
+
+/// Clap value parser for `--extract`. There must be one name per capture group; a regex
+/// without groups extracts the whole match into a single column.
+pub fn parse_extraction(spec: &str) -> Result<Extraction, String> {
+    let usage = || format!("invalid extraction '{}' (expected COLUMN => NAME:REGEX)", spec);
+    let (column, target) = spec.split_once("=>").ok_or_else(usage)?;
+    let (names, pattern) = target.split_once(':').ok_or_else(usage)?;
+
+    let column = column.trim();
+    let names: Vec<String> = names.split(',').map(|name| name.trim().to_string()).collect();
+    if column.is_empty() || names.iter().any(String::is_empty) {
+        return Err(usage());
+    }
+    let regex = Regex::new(pattern).map_err(|e| format!("invalid extraction regex '{}': {}", pattern, e))?;
+
+    let groups = (regex.captures_len() - 1).max(1);
+    if names.len() != groups {
+        return Err(format!("extraction '{}' names {} column(s) but the regex captures {}", spec, names.len(), groups));
+    }
+    Ok(Extraction { column: column.to_string(), names, regex })
+}
+
+impl Extraction {
+    /// The column the regex is applied to.
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// Names of the columns this extraction adds.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The captured values for one cell, one per new column. Cells that don't match, and
+    /// optional groups that didn't participate, give empty values.
+    pub fn extract(&self, value: &str) -> Vec<String> {
+        let captures = self.regex.captures(value);
+        let group_count = self.regex.captures_len();
+        // Group 0 is the whole match, used only when there are no explicit groups
+        let groups = if group_count == 1 { 0..1 } else { 1..group_count };
+        groups
+            .map(|group| {
+                captures.as_ref()
+                    .and_then(|captures| captures.get(group))
+                    .map_or_else(String::new, |m| m.as_str().to_string())
+            })
+            .collect()
+    }
+}
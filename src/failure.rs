@@ -0,0 +1,127 @@
+//! How csvpretty stops on an error (`--error-format`). The exit code tells scripts what kind of
+//! problem it was, and with `--error-format json` the error is written to stderr as a JSON
+//! object for tools to read.
+
+use csvpretty::ParseError;
+use serde_json::{json, Map, Value};
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// How errors are written to stderr.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ErrorFormat {
+    /// `Error: ` followed by the message
+    #[default]
+    Text,
+    /// A JSON object on one line, e.g.
+    /// `{"kind":"parse","exit_code":2,"message":"...","line":3,"record":2,"byte":24}`
+    Json,
+}
+
+static ERROR_FORMAT: OnceLock<ErrorFormat> = OnceLock::new();
+
+/// Sets how errors are written from now on.
+pub fn set_error_format(format: ErrorFormat) {
+    let _ = ERROR_FORMAT.set(format);
+}
+
+/// The error format in use. Before the flags are parsed it's looked for in the raw arguments,
+/// so that even a mistyped flag is reported as JSON.
+fn error_format() -> ErrorFormat {
+    *ERROR_FORMAT.get_or_init(|| {
+        let args: Vec<String> = std::env::args().collect();
+        let json = args.iter().any(|arg| arg == "--error-format=json")
+            || args.windows(2).any(|pair| pair[0] == "--error-format" && pair[1] == "json");
+        if json { ErrorFormat::Json } else { ErrorFormat::Text }
+    })
+}
+
+/// What kind of problem stopped csvpretty, which decides its exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// Anything not covered below, such as a file that can't be read: 1
+    Other,
+    /// Input that isn't well-formed, e.g. a CSV record with the wrong number of fields: 2
+    Parse,
+    /// No input, or nothing but blank lines: 3
+    EmptyInput,
+    /// Invalid flags, flags that don't go together, or a flag naming a column that isn't
+    /// there: 4
+    Usage,
+}
+
+impl Failure {
+    pub fn code(self) -> i32 {
+        match self {
+            Failure::Other => 1,
+            Failure::Parse => 2,
+            Failure::EmptyInput => 3,
+            Failure::Usage => 4,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Failure::Other => "other",
+            Failure::Parse => "parse",
+            Failure::EmptyInput => "empty_input",
+            Failure::Usage => "usage",
+        }
+    }
+}
+
+/// Writes `message` to stderr and exits with the code for `failure`.
+pub fn exit(failure: Failure, message: impl Display) -> ! {
+    report(failure, &message.to_string(), None)
+}
+
+/// Like [`exit`] for an error from reading a table: a parse error exits with
+/// [`Failure::Parse`], and the JSON object says where in the input it was found. `message` is
+/// the error as shown, e.g. with the file name in front.
+pub fn exit_error(e: &csvpretty::Error, message: impl Display) -> ! {
+    match e {
+        csvpretty::Error::Parse(parse_error) => report(Failure::Parse, &message.to_string(), Some(parse_error)),
+        _ => report(Failure::Other, &message.to_string(), None),
+    }
+}
+
+/// Reports a command-line error found by clap as a [`Failure::Usage`]. Help and version
+/// requests print as usual and exit successfully.
+pub fn exit_usage(e: clap::Error) -> ! {
+    if !e.use_stderr() {
+        e.exit();
+    }
+    match error_format() {
+        ErrorFormat::Text => {
+            let _ = e.print();
+            std::process::exit(Failure::Usage.code());
+        }
+        ErrorFormat::Json => {
+            let rendered = e.render().to_string();
+            let message = rendered.lines().next().unwrap_or_default();
+            report(Failure::Usage, message.strip_prefix("error: ").unwrap_or(message), None)
+        }
+    }
+}
+
+fn report(failure: Failure, message: &str, parse_error: Option<&ParseError>) -> ! {
+    match error_format() {
+        ErrorFormat::Text => eprintln!("Error: {}", message),
+        ErrorFormat::Json => {
+            let mut object = Map::new();
+            object.insert("kind".to_string(), json!(failure.name()));
+            object.insert("exit_code".to_string(), json!(failure.code()));
+            object.insert("message".to_string(), json!(message));
+            if let Some(parse_error) = parse_error {
+                let places = [("line", parse_error.line), ("column", parse_error.column), ("record", parse_error.record), ("byte", parse_error.byte)];
+                for (name, place) in places {
+                    if let Some(place) = place {
+                        object.insert(name.to_string(), json!(place));
+                    }
+                }
+            }
+            eprintln!("{}", Value::Object(object));
+        }
+    }
+    std::process::exit(failure.code())
+}
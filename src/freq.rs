@@ -0,0 +1,33 @@
+//! Value frequencies of one column (`--freq`).
+//!
+//! Instead of the rows themselves, `--freq` renders one row per distinct value of a column with
+//! how often it occurs, most common first: a quick way to see the categories in a column and
+//! spot the rare or misspelled ones.
+
+use crate::column_types::ColumnType;
+use csvpretty::Cell;
+use std::collections::HashMap;
+
+/// Shares are stored as fractions and shown as percentages with one decimal.
+pub const PERCENT_TYPE: ColumnType = ColumnType::Percent(1);
+
+/// Builds the frequency table: the column's values with their count and share of the rows,
+/// most common first. Values are compared without surrounding whitespace, and values with the
+/// same count keep the order they first appear in.
+pub fn frequencies(headers: &csv::StringRecord, records: &[Vec<Cell>], col_idx: usize) -> (csv::StringRecord, Vec<Vec<Cell<'static>>>) {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    for value in records.iter().map(|row| row.get(col_idx).map_or("", |value| value.trim())) {
+        let position = *positions.entry(value).or_insert_with(|| {
+            counts.push((value, 0));
+            counts.len() - 1
+        });
+        counts[position].1 += 1;
+    }
+    counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    let rows = counts.into_iter()
+        .map(|(value, count)| vec![value.to_string().into(), count.to_string().into(), (count as f64 / records.len() as f64).to_string().into()])
+        .collect();
+    ([&headers[col_idx], "count", "percent"].into_iter().collect(), rows)
+}
@@ -0,0 +1,75 @@
+//! Generating the example tables for the documentation (`csvpretty gen-examples`).
+//!
+//! Each fixture is rendered by running csvpretty itself once for every combination of output
+//! format, border style and wrap mode, so the examples come from the same code paths as real
+//! use rather than being kept up to date by hand. The examples aren't checked in; to spot
+//! rendering changes, generate them before and after a change and diff the two directories.
+//! A fixture that can't be rendered fails the run, so a broken fixture isn't mistaken for an
+//! unchanged one.
+
+use crate::OutputFormat;
+use clap::ValueEnum;
+use csvpretty::{TableStyle, WrapMode};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The names of every value of a flag, as given on the command line.
+fn names<T: ValueEnum>() -> Vec<String> {
+    T::value_variants().iter()
+        .filter_map(ValueEnum::to_possible_value)
+        .map(|value| value.get_name().to_string())
+        .collect()
+}
+
+/// What [`generate`] did.
+pub struct Generated {
+    /// Example files written
+    pub written: usize,
+    /// Fixtures csvpretty couldn't read, so none of their examples were written
+    pub skipped: usize,
+}
+
+/// Renders each of `fixtures`, `width` columns wide and without colors, into
+/// `<out_dir>/<fixture>/<format>-<style>-<wrap>.txt`. Fixtures csvpretty can't read are
+/// reported and skipped, and the rest are still rendered.
+pub fn generate(fixtures: &[PathBuf], out_dir: &Path, width: u16, verbose: bool) -> io::Result<Generated> {
+    let exe = std::env::current_exe()?;
+    let mut combinations = Vec::new();
+    for format in names::<OutputFormat>() {
+        for style in names::<TableStyle>() {
+            for wrap in names::<WrapMode>() {
+                combinations.push((format.clone(), style.clone(), wrap));
+            }
+        }
+    }
+
+    let mut written = 0;
+    let mut skipped = 0;
+    'fixtures: for fixture in fixtures {
+        let name = fixture.file_name().map_or_else(|| fixture.display().to_string(), |name| name.to_string_lossy().into_owned());
+        let dir = out_dir.join(&name);
+        for (format, style, wrap) in &combinations {
+            let output = Command::new(&exe)
+                .args(["--format", format, "--style", style, "--wrap", wrap, "--color", "never", "--width", &width.to_string()])
+                .arg(fixture)
+                .output()?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let reason = stderr.lines().next().unwrap_or_default().trim_start_matches("Error: ");
+                eprintln!("Warning: skipped {}: {}", fixture.display(), reason);
+                skipped += 1;
+                continue 'fixtures;
+            }
+            fs::create_dir_all(&dir)?;
+            let path = dir.join(format!("{}-{}-{}.txt", format, style, wrap));
+            fs::write(&path, &output.stdout)?;
+            if verbose {
+                eprintln!("Verbose: wrote {}", path.display());
+            }
+            written += 1;
+        }
+    }
+    Ok(Generated { written, skipped })
+}
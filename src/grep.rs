@@ -0,0 +1,61 @@
+//! Searching rows with a regex (`--grep`).
+//!
+//! By default the parsed values are searched, so a cell is found as it's shown. With `--raw`,
+//! each record's text as written in the input is searched instead, quotes and escapes
+//! included, to find e.g. literal `""` or a stray quote. A record that spans several lines
+//! because of a quoted line break is searched as a whole.
+
+use csvpretty::{Cell, CsvOptions};
+use regex::Regex;
+
+/// Keeps the rows with a cell that `regex` matches. With `verbose`, each hit is reported with
+/// the column it was found in.
+pub fn filter_parsed(records: &mut Vec<Vec<Cell>>, headers: &csv::StringRecord, regex: &Regex, verbose: bool) {
+    let mut row_number = 0;
+    records.retain(|row| {
+        row_number += 1;
+        let Some(col_idx) = row.iter().position(|cell| regex.is_match(cell)) else {
+            return false;
+        };
+        if verbose {
+            eprintln!("Verbose: --grep matched row {} in the parsed value of column '{}'", row_number, headers.get(col_idx).unwrap_or_default());
+        }
+        true
+    });
+}
+
+/// Cuts the data records whose source text `regex` doesn't match out of CSV `input`, keeping
+/// the header and everything before the first record. Also returns how many records were cut.
+/// With `verbose`, each hit is reported with the line it starts on.
+pub fn filter_raw(input: &str, regex: &Regex, options: &CsvOptions, verbose: bool) -> csv::Result<(String, usize)> {
+    let mut reader = options.reader_builder()
+        .flexible(true)
+        .from_reader(input.as_bytes());
+
+    // (line, byte) where each record starts; its text runs up to the next one's start
+    let mut starts = Vec::new();
+    for result in reader.byte_records() {
+        let record = result?;
+        let position = record.position().expect("records read from input have a position");
+        starts.push((position.line(), position.byte() as usize));
+    }
+
+    let Some(&(_, first)) = starts.first() else {
+        return Ok((input.to_string(), 0));
+    };
+    let mut kept = input[..first].to_string();
+    let mut cut = 0;
+    for (index, &(line, start)) in starts.iter().enumerate() {
+        let end = starts.get(index + 1).map_or(input.len(), |&(_, next)| next);
+        let raw = &input[start..end];
+        if regex.is_match(raw) {
+            if verbose {
+                eprintln!("Verbose: --grep matched record {} (line {}) in its raw text", index + 1, line);
+            }
+            kept.push_str(raw);
+        } else {
+            cut += 1;
+        }
+    }
+    Ok((kept, cut))
+}
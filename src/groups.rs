@@ -0,0 +1,113 @@
+//! Grouping rows by the values of a column (`--group-by`).
+//!
+//! The rows are sorted by the group column, and each run of rows sharing a value becomes a
+//! group, set apart from the others by a rule that can name the value. Subtotal rows add up the
+//! numeric columns of each group, for eyeballing categorized data without a spreadsheet.
+
+use crate::column_types::ColumnType;
+use crate::inference::InferredType;
+use crate::is_numeric;
+use csvpretty::{Cell, GroupStart, Highlights};
+
+/// Text in the group column of subtotal rows.
+const SUBTOTAL_LABEL: &str = "subtotal";
+
+/// A run of rows sharing a value in the group column.
+pub struct Group {
+    /// Index of the first row.
+    pub start: usize,
+    /// Number of rows, not counting a subtotal row.
+    pub len: usize,
+    pub value: String,
+}
+
+/// Splits rows sorted by `col_idx` into runs of the same value. Surrounding whitespace is ignored.
+pub fn find_groups(records: &[Vec<Cell>], col_idx: usize) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    for (row_idx, row) in records.iter().enumerate() {
+        let value = row.get(col_idx).map_or("", |value| value.trim());
+        match groups.last_mut() {
+            Some(group) if group.value == value => group.len += 1,
+            _ => groups.push(Group { start: row_idx, len: 1, value: value.to_string() }),
+        }
+    }
+    groups
+}
+
+/// A subtotal row for each group, holding the sum of each numeric column and `subtotal` in the
+/// group column. Typed columns are summed by their underlying quantity and shown in the type's
+/// format; percentages and dates don't add up, so they're left empty like text columns.
+pub fn subtotal_rows(records: &[Vec<Cell>], groups: &[Group], col_idx: usize, column_types: &[Option<ColumnType>], inferred_types: &[InferredType]) -> Vec<Vec<Cell<'static>>> {
+    let column_count = column_types.len();
+    groups.iter()
+        .map(|group| {
+            let rows = &records[group.start..group.start + group.len];
+            (0..column_count)
+                .map(|column| {
+                    if column == col_idx {
+                        return Cell::Borrowed(SUBTOTAL_LABEL);
+                    }
+                    let values = rows.iter().filter_map(|row| row.get(column)).map(|cell| &**cell);
+                    Cell::Owned(match (column_types[column], inferred_types[column]) {
+                        (Some(ColumnType::Percent(_) | ColumnType::Date(_)), _) => String::new(),
+                        (Some(column_type), _) => {
+                            let quantities: Vec<f64> = values.filter_map(|value| column_type.numeric_value(value)).collect();
+                            if quantities.is_empty() {
+                                return Cell::Borrowed("");
+                            }
+                            column_type.format_quantity(quantities.iter().sum())
+                        }
+                        (None, InferredType::Integer | InferredType::Float) => sum(values),
+                        (None, _) => String::new(),
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Adds up numeric values, showing as many decimals as the most precise of them. Groups
+/// without any numbers have no total.
+pub fn sum<'a>(values: impl Iterator<Item = &'a str>) -> String {
+    let numbers: Vec<&str> = values.map(str::trim).filter(|value| is_numeric(value)).collect();
+    if numbers.is_empty() {
+        return String::new();
+    }
+    let decimals = numbers.iter()
+        .map(|value| value.split_once('.').map_or(0, |(_, fraction)| fraction.len()))
+        .max()
+        .unwrap_or(0);
+    let total: f64 = numbers.iter().filter_map(|value| value.parse::<f64>().ok()).sum();
+    format!("{:.*}", decimals, total)
+}
+
+/// Inserts each group's subtotal row below it, with an empty highlight row to keep the
+/// highlights lined up, and moves the groups to match. Returns where the subtotal rows went.
+pub fn insert_subtotals<'a>(records: &mut Vec<Vec<Cell<'a>>>, highlights: &mut Highlights, groups: &mut [Group], subtotals: Vec<Vec<Cell<'a>>>) -> Vec<usize> {
+    let mut inserted = Vec::new();
+    for (index, (group, subtotal)) in groups.iter_mut().zip(subtotals).enumerate() {
+        // Each subtotal row above this group pushes it down by one
+        group.start += index;
+        let row_idx = group.start + group.len;
+        records.insert(row_idx, subtotal);
+        if highlights.len() >= row_idx {
+            highlights.insert(row_idx, Vec::new());
+        }
+        inserted.push(row_idx);
+    }
+    inserted
+}
+
+/// Where each group starts, labelled like `region: north (3 rows)` when `labels` is set.
+pub fn group_starts(groups: &[Group], header: &str, labels: bool) -> Vec<GroupStart> {
+    groups.iter()
+        .map(|group| GroupStart {
+            row: group.start,
+            label: labels.then(|| {
+                let value = if group.value.is_empty() { "(empty)" } else { group.value.as_str() };
+                let plural = if group.len == 1 { "" } else { "s" };
+                format!("{}: {} ({} row{})", header, value, group.len, plural)
+            }),
+        })
+        .collect()
+}
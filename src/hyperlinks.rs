@@ -0,0 +1,54 @@
+//! Clickable URL cells (`--hyperlinks`).
+//!
+//! Terminals that support OSC 8 hyperlinks let a cell show a short label, such as the host
+//! name, while clicking it opens the full URL. Long URLs then no longer have to be wrapped
+//! over several lines to stay usable.
+
+use csvpretty::{Cell, Links};
+
+/// What a linked cell shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LinkLabel {
+    /// The whole URL
+    Url,
+    /// Only the host name, e.g. example.com
+    Host,
+}
+
+/// Whether a value is an http(s) URL. Surrounding whitespace is ignored.
+pub fn is_url(value: &str) -> bool {
+    let value = value.trim();
+    ["http://", "https://"].iter().any(|scheme| {
+        value.get(..scheme.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme)) && value.len() > scheme.len()
+    }) && !value.contains(char::is_whitespace)
+}
+
+/// The host of a URL: what follows the scheme, up to the first `/`, `?` or `#`, without any
+/// user name.
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    authority.rsplit_once('@').map_or(authority, |(_, host)| host)
+}
+
+/// Finds the URLs in `columns` (every column when empty) and returns their link targets.
+/// With the host label, the cells are shortened to the host name.
+pub fn link_cells(records: &mut [Vec<Cell>], columns: &[usize], label: LinkLabel) -> Links {
+    records.iter_mut()
+        .map(|row| {
+            row.iter_mut()
+                .enumerate()
+                .map(|(col_idx, cell)| {
+                    if !(columns.is_empty() || columns.contains(&col_idx)) || !is_url(cell) {
+                        return None;
+                    }
+                    let target = cell.trim().to_string();
+                    if label == LinkLabel::Host {
+                        *cell = host(&target).to_string().into();
+                    }
+                    Some(target)
+                })
+                .collect()
+        })
+        .collect()
+}
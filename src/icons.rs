@@ -0,0 +1,37 @@
+//! Status icons (`--icon-column`).
+//!
+//! Status, severity and result columns are easier to scan as symbols: `ok` → `✅`,
+//! `error` → `❌`. Icons are measured like any other text, including emoji that are drawn two
+//! columns wide.
+
+/// A `--icon-column COLUMN:VALUE=ICON[,VALUE=ICON...]` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconSpec {
+    pub column: String,
+    icons: Vec<(String, String)>,
+}
+
+/// Clap value parser for `--icon-column COLUMN:VALUE=ICON[,VALUE=ICON...]`.
+pub fn parse_icon_spec(s: &str) -> Result<IconSpec, String> {
+    let usage = || format!("invalid icon mapping '{}' (expected COLUMN:VALUE=ICON[,VALUE=ICON...])", s);
+    let (column, mappings) = s.split_once(':').ok_or_else(usage)?;
+    if column.is_empty() {
+        return Err(usage());
+    }
+    let icons = mappings.split(',')
+        .map(|mapping| match mapping.split_once('=') {
+            Some((value, icon)) if !icon.is_empty() => Ok((value.trim().to_string(), icon.to_string())),
+            _ => Err(usage()),
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(IconSpec { column: column.to_string(), icons })
+}
+
+impl IconSpec {
+    /// The icon for a value, if it has one. Surrounding whitespace is ignored.
+    pub fn icon(&self, value: &str) -> Option<&str> {
+        self.icons.iter()
+            .find(|(mapped, _)| mapped == value.trim())
+            .map(|(_, icon)| icon.as_str())
+    }
+}
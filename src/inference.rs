@@ -0,0 +1,97 @@
+//! Column type inference.
+//!
+//! Each column is classified from all of its non-null values. The inferred type drives the
+//! default alignment, `--color-by type`, sort order and the `--stats` summary. Columns with a
+//! declared `--column-type` use that type instead wherever it matters.
+
+use crate::{dates, is_null, is_numeric};
+use csvpretty::Cell;
+
+/// The kind of values a column holds, from most to least specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferredType {
+    Integer,
+    Float,
+    Bool,
+    Date,
+    String,
+}
+
+impl InferredType {
+    pub fn name(self) -> &'static str {
+        match self {
+            InferredType::Integer => "integer",
+            InferredType::Float => "float",
+            InferredType::Bool => "bool",
+            InferredType::Date => "date",
+            InferredType::String => "string",
+        }
+    }
+
+    /// Whether a value can be read as this type. Every value is a string.
+    pub fn matches(self, value: &str) -> bool {
+        match self {
+            InferredType::Integer => value.trim().parse::<i64>().is_ok(),
+            InferredType::Float => is_numeric(value),
+            InferredType::Bool => parse_bool(value).is_some(),
+            InferredType::Date => dates::parse_timestamp(value).is_some(),
+            InferredType::String => true,
+        }
+    }
+
+    pub fn is_numeric(self) -> bool {
+        matches!(self, InferredType::Integer | InferredType::Float)
+    }
+
+    /// Palette entry used for columns of this type with `--color-by type`, so that e.g. all
+    /// numeric columns share one hue and all date columns another.
+    pub fn palette_slot(self) -> usize {
+        match self {
+            InferredType::String => 0,
+            InferredType::Integer | InferredType::Float => 1,
+            InferredType::Date => 2,
+            InferredType::Bool => 3,
+        }
+    }
+}
+
+/// The types a column can be inferred to hold, from most to least specific. Integers come
+/// before booleans, so 0/1 flags stay numeric.
+const SPECIFIC_TYPES: [InferredType; 4] = [InferredType::Integer, InferredType::Float, InferredType::Bool, InferredType::Date];
+
+/// The non-null values of a column.
+fn column_values<'a>(records: &'a [Vec<Cell>], col_idx: usize) -> Vec<&'a str> {
+    records.iter()
+        .filter_map(|row| row.get(col_idx))
+        .map(|cell| &**cell)
+        .filter(|value| !is_null(value))
+        .collect()
+}
+
+/// Infers the type of a column. Columns without any non-null values are strings.
+pub fn infer_column(records: &[Vec<Cell>], col_idx: usize) -> InferredType {
+    let values = column_values(records, col_idx);
+    SPECIFIC_TYPES.into_iter()
+        .find(|inferred_type| !values.is_empty() && values.iter().all(|value| inferred_type.matches(value)))
+        .unwrap_or(InferredType::String)
+}
+
+/// The most specific type that more than half of a column's non-null values have, e.g. integer
+/// for a column of numbers with the odd `n/a`. None when no type has a majority.
+pub fn majority_type(records: &[Vec<Cell>], col_idx: usize) -> Option<InferredType> {
+    let values = column_values(records, col_idx);
+    SPECIFIC_TYPES.into_iter()
+        .find(|inferred_type| values.iter().filter(|value| inferred_type.matches(value)).count() * 2 > values.len())
+}
+
+/// Parses `true`/`false` and `yes`/`no`, ignoring case.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("yes") {
+        Some(true)
+    } else if value.eq_ignore_ascii_case("false") || value.eq_ignore_ascii_case("no") {
+        Some(false)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,390 @@
+//! Column width calculation.
+
+use crate::{is_numeric, Cell, RenderOptions, WrapMode};
+use crate::wrap::{display_units, display_width, lines, set_width_conventions, width_conventions};
+use rayon::prelude::*;
+
+/// Cells in a table above which its columns are measured on several threads. Smaller tables
+/// measure faster than the threads start.
+const PARALLEL_CELLS: usize = 1 << 16;
+
+/// Rows each thread measures at a time.
+const CHUNK_ROWS: usize = 4096;
+
+/// Measures the display width of the widest line of `text`, stopping as soon as it exceeds `cap`.
+/// Used where only "does it fit" matters, so very long cells are not scanned in full.
+pub(crate) fn capped_width(text: &str, cap: usize) -> usize {
+    let mut widest = 0;
+    for line in lines(text) {
+        let mut width = 0;
+        for (_, unit_width) in display_units(line) {
+            width += unit_width;
+            if width > cap {
+                return width;
+            }
+        }
+        widest = widest.max(width);
+    }
+    widest
+}
+
+/// Width of the line-number gutter, or 0 when line numbers are disabled.
+pub fn line_number_width(record_count: usize, show_line_numbers: bool) -> usize {
+    if show_line_numbers {
+        record_count.to_string().len().max(1)
+    } else {
+        0
+    }
+}
+
+/// Natural widths of `columns`: the widest of each one's header and values, measured up to
+/// `cap`. Large tables are measured on several threads, each taking a share of the rows.
+fn natural_widths(headers: &[&str], records: &[Vec<Cell>], columns: &[usize], cap: usize) -> Vec<usize> {
+    let measure = |rows: &[Vec<Cell>]| -> Vec<usize> {
+        columns.iter()
+            .map(|&col_idx| rows.iter().filter_map(|row| row.get(col_idx)).map(|s| capped_width(s, cap)).max().unwrap_or(0))
+            .collect()
+    };
+    let content_widths = if records.len() * columns.len() < PARALLEL_CELLS {
+        measure(records)
+    } else {
+        // Widths are measured by the conventions of the thread doing the measuring
+        let conventions = width_conventions();
+        records.par_chunks(CHUNK_ROWS)
+            .map(|rows| {
+                set_width_conventions(conventions);
+                measure(rows)
+            })
+            .reduce(|| vec![0; columns.len()], |a, b| a.into_iter().zip(b).map(|(a, b)| a.max(b)).collect())
+    };
+    columns.iter()
+        .zip(content_widths)
+        .map(|(&col_idx, width)| capped_width(headers[col_idx], cap).max(width))
+        .collect()
+}
+
+/// Width of the part of a number from its decimal point on, or 0 for a number without one.
+pub(crate) fn fraction_width(number: &str) -> usize {
+    number.find('.').map_or(0, |point| display_width(&number[point..]))
+}
+
+/// Widest integer and fraction parts of the numbers in a column, the fraction counting its
+/// point. Cells that aren't numbers are left out.
+fn decimal_parts(records: &[Vec<Cell>], col_idx: usize) -> (usize, usize) {
+    records.iter()
+        .filter_map(|row| row.get(col_idx))
+        .filter(|cell| is_numeric(cell))
+        .map(|cell| {
+            let fraction = fraction_width(cell);
+            (display_width(cell) - fraction, fraction)
+        })
+        .fold((0, 0), |(integer, fraction), (i, f)| (integer.max(i), fraction.max(f)))
+}
+
+/// Counts how many leading columns fit side by side at their natural width, or their fixed
+/// width where `fixed_widths` has one, with `padding` spaces on either side of each cell. At
+/// least one column is always kept, even if it has to wrap.
+#[allow(clippy::too_many_arguments)]
+pub fn count_fitting_columns(headers: &csv::StringRecord, records: &[Vec<Cell>], max_col_width: usize, fixed_widths: &[Option<usize>], terminal_width: usize, wrap_mode: WrapMode, row_num_width: usize, padding: usize) -> usize {
+    // Each column costs its content plus its padding and separator, and in no-wrap mode the
+    // padding once more
+    let overhead = column_overhead(padding) + if matches!(wrap_mode, WrapMode::None) { no_wrap_padding(padding) } else { 0 };
+    let mut used = if row_num_width > 0 { row_num_width + 3 } else { 0 };
+    let header_vec: Vec<&str> = headers.iter().collect();
+    let columns: Vec<usize> = (0..headers.len()).collect();
+    let natural = natural_widths(&header_vec, records, &columns, max_col_width);
+    for (col_idx, natural) in natural.into_iter().enumerate() {
+        let width = fixed_widths.get(col_idx).copied().flatten().unwrap_or(natural);
+        used += width + overhead;
+        if used > terminal_width {
+            return col_idx.max(1);
+        }
+    }
+    headers.len()
+}
+
+/// Columns a column takes up beyond its content: `padding` spaces on either side and the
+/// one-column separator after it.
+pub(crate) fn column_overhead(padding: usize) -> usize {
+    2 * padding + 1
+}
+
+/// Columns a no-wrap column takes up beyond its content on top of its [`column_overhead`]: the
+/// padding on either side once more, after the text, so columns sized to their content stand
+/// apart. No-wrap widths include it.
+pub(crate) fn no_wrap_padding(padding: usize) -> usize {
+    column_overhead(padding) - 1
+}
+
+/// The part of a column `width` wide that its text may use, which leaves out the
+/// [`no_wrap_padding`] in no-wrap mode.
+pub(crate) fn usable_width(width: usize, options: &RenderOptions) -> usize {
+    match options.wrap_mode {
+        WrapMode::None => width.saturating_sub(no_wrap_padding(options.padding)),
+        _ => width,
+    }
+}
+
+/// Column widths shared by the header and the data rows of a table.
+pub(crate) struct TableLayout {
+    pub(crate) col_widths: Vec<usize>,
+    /// Width of the line-number gutter, 0 when line numbers are disabled.
+    pub(crate) row_num_width: usize,
+    /// Widest fraction part of the numbers in each column lined up on their decimal points,
+    /// and 0 for the other columns.
+    pub(crate) fraction_widths: Vec<usize>,
+}
+
+impl TableLayout {
+    pub(crate) fn new(headers: &csv::StringRecord, records: &[Vec<Cell>], options: &RenderOptions) -> Self {
+        let header_vec: Vec<&str> = headers.iter().collect();
+
+        // Calculate row number width (for the leftmost column); subtotal and totals rows aren't numbered
+        let summary_rows = options.subtotal_rows.iter().copied().chain(options.totals_row).filter(|&row| row < records.len()).count();
+        let numbered_rows = records.len() - summary_rows;
+        let shown_rows = numbered_rows + options.elided_rows.map_or(0, |elided| elided.count);
+        let row_num_width = line_number_width(shown_rows, options.show_line_numbers);
+
+        // Only the rows picked to measure count towards the widths
+        let sample: Vec<Vec<Cell>>;
+        let measured = match &options.measured_rows {
+            Some(rows) => {
+                sample = rows.iter().filter_map(|&row_idx| records.get(row_idx).cloned()).collect();
+                &sample
+            }
+            None => records,
+        };
+
+        // Calculate column widths
+        let col_widths = calculate_column_widths(&header_vec, measured, row_num_width, options);
+
+        let fraction_widths = (0..header_vec.len())
+            .map(|col_idx| if options.is_decimal_column(col_idx) { decimal_parts(measured, col_idx).1 } else { 0 })
+            .collect();
+
+        TableLayout { col_widths, row_num_width, fraction_widths }
+    }
+
+    /// Widens the line-number gutter when `row_num` doesn't fit in it, and in no-wrap mode the
+    /// columns too narrow for `record`, for rows that weren't measured when the layout was made.
+    /// The wrap modes wrap such cells instead. Returns whether anything was widened.
+    pub(crate) fn widen_for(&mut self, record: &[Cell], row_num: usize, options: &RenderOptions) -> bool {
+        let mut widened = false;
+        let row_num_width = line_number_width(row_num, options.show_line_numbers);
+        if row_num_width > self.row_num_width {
+            self.row_num_width = row_num_width;
+            widened = true;
+        }
+        if !matches!(options.wrap_mode, WrapMode::None) {
+            return widened;
+        }
+        let cap = options.max_col_width.unwrap_or(usize::MAX);
+        for (col_idx, (cell, width)) in record.iter().zip(&mut self.col_widths).enumerate() {
+            if options.fixed_col_widths.get(col_idx).copied().flatten().is_some() {
+                continue;
+            }
+            let needed = capped_width(cell, cap).min(cap) + no_wrap_padding(options.padding);
+            if needed > *width {
+                *width = needed;
+                widened = true;
+            }
+        }
+        widened
+    }
+}
+
+/// Calculates column widths based on content and terminal constraints.
+///
+/// For no-wrap mode: columns are sized to fit their content exactly (table may exceed terminal width).
+///
+/// For wrap modes: uses a "waterfall" allocation strategy:
+/// 1. Calculate natural width (max content width) for each column
+/// 2. If all columns fit naturally, use those widths
+/// 3. Otherwise: allocate natural width to smallest columns first, then distribute
+///    remaining space proportionally to larger columns that need wrapping
+///
+/// This ensures narrow columns don't get over-allocated space while wide columns share
+/// the burden of wrapping.
+///
+/// Columns lined up on their decimal points (from --align-decimal) are at least as wide as their
+/// widest integer part next to their widest fraction part.
+///
+/// Columns with a fixed width (from --col-width) get exactly that width in all modes and are left
+/// out of the allocation. `max_col_width` (from --max-col-width) caps every other column's content
+/// width in all modes, and `min_col_width` (from --min-col-width) keeps wrapped columns from being
+/// squeezed below it.
+pub(crate) fn calculate_column_widths(headers: &[&str], records: &[Vec<Cell>], row_num_width: usize, options: &RenderOptions) -> Vec<usize> {
+    let num_cols = headers.len();
+    let cap = options.max_col_width.unwrap_or(usize::MAX);
+    let fixed = |col_idx: usize| options.fixed_col_widths.get(col_idx).copied().flatten();
+    let decimal_width = |col_idx: usize| {
+        if options.is_decimal_column(col_idx) {
+            let (integer, fraction) = decimal_parts(records, col_idx);
+            integer + fraction
+        } else {
+            0
+        }
+    };
+
+    if matches!(options.wrap_mode, WrapMode::None) {
+        // For no-wrap mode, size columns to content
+        let flexible: Vec<usize> = (0..num_cols).filter(|&col_idx| fixed(col_idx).is_none()).collect();
+        let mut natural = natural_widths(headers, records, &flexible, cap).into_iter();
+        (0..num_cols)
+            .map(|col_idx| {
+                let content_width = fixed(col_idx).unwrap_or_else(|| {
+                    natural.next().unwrap_or(0).max(decimal_width(col_idx)).min(cap)
+                });
+                content_width + no_wrap_padding(options.padding)
+            })
+            .collect()
+    } else {
+        // For wrap modes, distribute terminal width
+        // Calculate overhead: row number column + borders + padding
+        // Format with line numbers: "N  │ content │ content │"
+        // Format without line numbers: " content │ content │"
+        // Row number area (if enabled): N (row_num_width) + "  │" (3 chars)
+        // Each column: " content │" (padding on either side + content + 1 separator), which
+        // is content + 3 with the default padding of 1
+        // So overhead is everything except the content widths
+        let row_overhead = if row_num_width > 0 {
+            row_num_width + 3  // "N  │"
+        } else {
+            0  // No row number column
+        };
+        let overhead = row_overhead + num_cols * column_overhead(options.padding) + options.border.frame_overhead();
+
+        // Fixed columns take their share first; the others divide what is left
+        let fixed_total: usize = (0..num_cols).filter_map(fixed).sum();
+        let available_width = options.terminal_width.saturating_sub(overhead).saturating_sub(fixed_total);
+        let flexible: Vec<usize> = (0..num_cols).filter(|&col_idx| fixed(col_idx).is_none()).collect();
+
+        // Calculate natural widths for proportional distribution.
+        // No column can usefully be wider than the available width, so measurement is capped there.
+        let natural_widths: Vec<usize> = natural_widths(headers, records, &flexible, available_width).into_iter()
+            .zip(&flexible)
+            .map(|(natural, &col_idx)| natural.max(decimal_width(col_idx)).min(available_width).min(cap))
+            .collect();
+
+        let mut flexible_widths = allocate_widths(&natural_widths, available_width);
+
+        // Columns narrower than --min-col-width by nature keep their natural width
+        let floors: Vec<usize> = natural_widths.iter()
+            .map(|&natural| natural.min(options.min_col_width.unwrap_or(0)))
+            .collect();
+        raise_to_floors(&mut flexible_widths, &floors);
+
+        // Honor --max-col-width even where the allocator handed out extra space
+        for width in &mut flexible_widths {
+            *width = (*width).min(cap);
+        }
+
+        let mut flexible_widths = flexible_widths.into_iter();
+        (0..num_cols)
+            .map(|col_idx| fixed(col_idx).or_else(|| flexible_widths.next()).unwrap_or(0))
+            .collect()
+    }
+}
+
+/// Shares `available_width` between columns with the given natural widths, waterfall style.
+fn allocate_widths(natural_widths: &[usize], available_width: usize) -> Vec<usize> {
+    let num_cols = natural_widths.len();
+    let total_natural: usize = natural_widths.iter().sum();
+
+    if total_natural == 0 {
+        return vec![10; num_cols]; // Fallback
+    }
+
+    // Strategy: Give columns their natural width if possible, wrap only when needed
+    let mut widths = vec![0; num_cols];
+
+    // Check if all columns fit naturally
+    if total_natural <= available_width {
+        // All columns fit, just give them their natural widths
+        for (i, &natural) in natural_widths.iter().enumerate() {
+            widths[i] = natural;
+        }
+        // Distribute any remaining space to the last column
+        let used: usize = widths.iter().sum();
+        if used < available_width {
+            widths[num_cols - 1] += available_width - used;
+        }
+    } else {
+        // Not all columns fit, need to wrap
+        // Strategy: Give smaller columns their natural width, let bigger columns share remaining
+
+        // Sort column indices by their natural width
+        let mut sorted_cols: Vec<(usize, usize)> = natural_widths.iter()
+            .enumerate()
+            .map(|(i, &w)| (i, w))
+            .collect();
+        sorted_cols.sort_by_key(|&(_, w)| w);
+
+        let mut remaining = available_width;
+        let mut unallocated_cols = num_cols;
+
+        // Allocate to smallest columns first
+        for &(col_idx, natural) in &sorted_cols {
+            let avg_remaining = remaining / unallocated_cols;
+
+            if natural <= avg_remaining {
+                // This column can have its natural width
+                widths[col_idx] = natural;
+                remaining = remaining.saturating_sub(natural);
+            } else {
+                // This and remaining larger columns need to share
+                break;
+            }
+            unallocated_cols -= 1;
+        }
+
+        // Distribute remaining space to unallocated columns proportionally
+        if let Some(per_col_min) = remaining.checked_div(unallocated_cols) {
+            let unallocated_natural: usize = sorted_cols.iter()
+                .filter(|(i, _)| widths[*i] == 0)
+                .map(|(_, w)| w)
+                .sum();
+
+            let mut leftover = remaining;
+
+            for &(col_idx, natural) in &sorted_cols {
+                if widths[col_idx] == 0 {
+                    unallocated_cols -= 1;
+                    if unallocated_cols == 0 {
+                        // Last column gets remainder
+                        widths[col_idx] = leftover.max(5);
+                    } else if let Some(share) = (remaining * natural).checked_div(unallocated_natural) {
+                        // Proportional allocation
+                        let alloc = share.max(per_col_min).max(5);
+                        widths[col_idx] = alloc;
+                        leftover = leftover.saturating_sub(alloc);
+                    } else {
+                        widths[col_idx] = per_col_min.max(5);
+                        leftover = leftover.saturating_sub(per_col_min.max(5));
+                    }
+                }
+            }
+        }
+    }
+
+    widths
+}
+
+/// Widens columns below their floor, taking the space back from the widest columns that are
+/// above theirs. When every column is at its floor, the table grows wider instead.
+fn raise_to_floors(widths: &mut [usize], floors: &[usize]) {
+    let mut deficit = 0;
+    for (width, &floor) in widths.iter_mut().zip(floors) {
+        if *width < floor {
+            deficit += floor - *width;
+            *width = floor;
+        }
+    }
+    while deficit > 0 {
+        let Some(widest) = (0..widths.len()).filter(|&i| widths[i] > floors[i]).max_by_key(|&i| widths[i]) else {
+            break;
+        };
+        widths[widest] -= 1;
+        deficit -= 1;
+    }
+}
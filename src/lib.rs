@@ -0,0 +1,47 @@
+//! Formats CSV data into tables drawn with Unicode box-drawing characters.
+//!
+//! This is the library behind the `csvpretty` command. Read a [`Table`] and render it with
+//! [`RenderOptions`]:
+//!
+//! ```
+//! use csvpretty::{RenderOptions, Table};
+//!
+//! let table = Table::from_reader("name,age\nAlice,30\n".as_bytes()).unwrap();
+//! let mut out = Vec::new();
+//! table.render_to(&mut out, &RenderOptions::default()).unwrap();
+//! assert!(String::from_utf8(out).unwrap().contains("Alice"));
+//! ```
+//!
+//! Reading and rendering fail with an [`Error`] that tells the kinds of failure apart, e.g. a
+//! [`ParseError`] with the line it was found on.
+
+mod error;
+mod layout;
+pub mod logs;
+pub mod palette;
+mod render;
+pub mod spreadsheet;
+pub mod sqlite;
+pub mod structured;
+mod table;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+mod wrap;
+
+pub use error::{Error, ParseError, ParseErrorKind};
+pub use layout::{count_fitting_columns, line_number_width};
+pub use render::{Alignment, BorderStyle, CollapsedRows, ElidedRows, Frame, GroupStart, Highlights, Links, ProgressiveRenderer, RenderOptions, Renderer, RowColors, SortIndicator, TableStyle, VerticalAlignment, WrapMode};
+pub use table::{truncate_cell, Cell, CsvOptions, InputOffset, MalformedRecord, Table};
+pub use wrap::{display_width, escape_control_chars, set_width_conventions, strip_ansi, truncate_at, truncate_to_width, truncate_with_marker, Ellipsis, EmojiWidth, WidthConventions};
+
+/// Returns true if the value looks like a number (integer, decimal or scientific notation).
+/// Words such as "inf" or "NaN" that Rust's float parser accepts are not treated as numeric.
+pub fn is_numeric(value: &str) -> bool {
+    let value = value.trim();
+    value.bytes().any(|b| b.is_ascii_digit()) && value.parse::<f64>().is_ok()
+}
+
+/// Returns true for missing values: empty cells and the literals `NULL` and `NA`.
+pub fn is_null(value: &str) -> bool {
+    matches!(value.trim(), "" | "NULL" | "NA")
+}
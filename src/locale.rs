@@ -0,0 +1,109 @@
+//! Number and date conventions of a locale (`--locale`, or else the LC_ALL, LC_NUMERIC or
+//! LANG environment variable).
+//!
+//! Only values csvpretty formats itself follow the locale: the cells of `--column-type`
+//! columns and the totals and summaries worked out from them. Cells shown as written keep
+//! their decimal points, so input is never misread.
+
+use chrono::{Locale, NaiveDateTime};
+use pure_rust_locales::locale_match;
+use std::cell::Cell;
+
+thread_local! {
+    static LOCALE: Cell<Option<Locale>> = const { Cell::new(None) };
+}
+
+/// Sets the locale formatted values follow. Without one they're written the English way,
+/// e.g. `1,234.5`.
+pub fn set_locale(locale: Option<Locale>) {
+    LOCALE.with(|cell| cell.set(locale));
+}
+
+fn current() -> Option<Locale> {
+    LOCALE.with(Cell::get)
+}
+
+/// Clap value parser for `--locale`. Accepts tags such as `de-DE`, the `de_DE.UTF-8` form of
+/// the environment variables, and a bare language such as `fr` for its main country.
+pub fn parse_locale(tag: &str) -> Result<Locale, String> {
+    if matches!(tag, "C" | "POSIX") || tag.starts_with("C.") {
+        return Ok(Locale::POSIX);
+    }
+    // The codeset and modifier, as in "de_DE.UTF-8" and "de_DE@euro", don't matter here
+    let name = tag.split(['.', '@']).next().unwrap_or_default().replace('-', "_");
+    Locale::try_from(name.as_str())
+        .or_else(|_| Locale::try_from(format!("{}_{}", name, name.to_uppercase()).as_str()))
+        .map_err(|_| format!("unknown locale '{}' (expected e.g. de-DE or fr_FR.UTF-8)", tag))
+}
+
+/// The locale of the environment, if one is set and csvpretty knows it. The C locale, which
+/// many systems default to, counts as none.
+pub fn from_env() -> Option<Locale> {
+    let tag = ["LC_ALL", "LC_NUMERIC", "LANG"].iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())?;
+    parse_locale(&tag).ok().filter(|&locale| locale != Locale::POSIX)
+}
+
+/// Writes the decimal point of a formatted number the locale's way, e.g. `1,5 MiB` in German.
+pub fn localize_decimal(formatted: String) -> String {
+    match current() {
+        Some(locale) => formatted.replacen('.', locale_match!(locale => LC_NUMERIC::DECIMAL_POINT), 1),
+        None => formatted,
+    }
+}
+
+/// Writes a plain decimal number such as `-1234567.5` with its digits grouped and its decimal
+/// point the locale's way, e.g. `-1.234.567,5` in German.
+pub fn group_digits(number: &str) -> String {
+    let (decimal_point, separator, grouping): (&str, &str, &[i64]) = match current() {
+        Some(locale) => (
+            locale_match!(locale => LC_NUMERIC::DECIMAL_POINT),
+            locale_match!(locale => LC_NUMERIC::THOUSANDS_SEP),
+            locale_match!(locale => LC_NUMERIC::GROUPING),
+        ),
+        None => (".", ",", &[3]),
+    };
+    let (sign, digits) = match number.strip_prefix(['-', '+']) {
+        Some(digits) => (&number[..1], digits),
+        None => ("", number),
+    };
+    let (mut integer, fraction) = match digits.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (digits, None),
+    };
+
+    // Sizes of the groups from the right, as in C's localeconv(): the last size repeats
+    // (also when a 0 ends the list), and a negative size ends grouping
+    let mut groups = Vec::new();
+    let mut sizes = grouping.iter();
+    let mut size = None;
+    loop {
+        match sizes.next() {
+            Some(&next) if next > 0 => size = Some(next as usize),
+            Some(&next) if next < 0 => size = None,
+            _ => {}
+        }
+        match size {
+            Some(size) if integer.len() > size => {
+                groups.push(&integer[integer.len() - size..]);
+                integer = &integer[..integer.len() - size];
+            }
+            _ => break,
+        }
+    }
+    groups.push(integer);
+    groups.reverse();
+
+    let mut grouped = format!("{}{}", sign, groups.join(separator));
+    if let Some(fraction) = fraction {
+        grouped.push_str(decimal_point);
+        grouped.push_str(fraction);
+    }
+    grouped
+}
+
+/// Formats a date with a chrono format string, with the month and day names of the locale.
+pub fn format_date(date: NaiveDateTime, format: &str) -> String {
+    date.and_utc().format_localized(format, current().unwrap_or(Locale::POSIX)).to_string()
+}
@@ -0,0 +1,162 @@
+//! Web server log input (`--input accesslog` and `--input w3c`).
+//!
+//! Log lines are split into columns so they can be rendered like any other table. Lines that
+//! don't match the format are skipped and counted, so a stray banner or truncated write
+//! doesn't abort the whole file.
+
+use chrono::DateTime;
+
+/// Columns produced for Apache/NGINX access logs.
+const ACCESS_LOG_HEADERS: [&str; 8] = ["ip", "time", "method", "path", "status", "bytes", "referer", "agent"];
+
+/// Rows parsed from a log, along with the number of lines that couldn't be parsed.
+pub struct ParsedLog {
+    pub headers: csv::StringRecord,
+    pub records: Vec<Vec<String>>,
+    pub skipped: usize,
+}
+
+/// Parses Common Log Format or Combined Log Format lines, e.g.
+/// `127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /a.gif HTTP/1.0" 200 2326 "-" "Mozilla/5.0"`.
+///
+/// Timestamps are rewritten as RFC 3339 so they work with `--since`/`--until`, and `-`
+/// placeholders become empty cells.
+pub fn parse_access_log(input: &str) -> ParsedLog {
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        match parse_access_log_line(line) {
+            Some(row) => records.push(row),
+            None => skipped += 1,
+        }
+    }
+    ParsedLog { headers: ACCESS_LOG_HEADERS.iter().collect(), records, skipped }
+}
+
+fn parse_access_log_line(line: &str) -> Option<Vec<String>> {
+    let fields = split_fields(line)?;
+    // host ident authuser [date] "request" status bytes, optionally followed by "referer" "agent"
+    if fields.len() != 7 && fields.len() != 9 {
+        return None;
+    }
+    let time = fields[3].strip_prefix('[')?.strip_suffix(']')?;
+    let time = DateTime::parse_from_str(time, "%d/%b/%Y:%H:%M:%S %z")
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|_| time.to_string());
+
+    // Malformed requests (e.g. from port scanners) are kept whole in the path column
+    let request = unquote(&fields[4]);
+    let (method, path) = match request.split_whitespace().collect::<Vec<_>>()[..] {
+        [method, path] | [method, path, _] => (method.to_string(), path.to_string()),
+        _ => (String::new(), request.clone()),
+    };
+
+    let optional = |index: usize| fields.get(index).map(|field| dash_to_empty(&unquote(field))).unwrap_or_default();
+    Some(vec![
+        dash_to_empty(&fields[0]),
+        time,
+        method,
+        dash_to_empty(&path),
+        fields[5].clone(),
+        dash_to_empty(&fields[6]),
+        optional(7),
+        optional(8),
+    ])
+}
+
+/// Parses the W3C Extended Log File Format used by IIS and some CDNs. Column names come from
+/// the `#Fields:` directive; other `#` directives are ignored, as are lines before the first
+/// `#Fields:` or with a different number of values.
+pub fn parse_w3c_log(input: &str) -> ParsedLog {
+    let mut headers: Option<csv::StringRecord> = None;
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        if let Some(directive) = line.strip_prefix('#') {
+            if let Some(fields) = directive.strip_prefix("Fields:") {
+                headers.get_or_insert_with(|| fields.split_whitespace().collect());
+            }
+            continue;
+        }
+        let row = headers.as_ref()
+            .and_then(|headers| split_fields(line).filter(|fields| fields.len() == headers.len()));
+        match row {
+            Some(fields) => records.push(fields.iter().map(|field| dash_to_empty(&unquote(field))).collect()),
+            None => skipped += 1,
+        }
+    }
+    ParsedLog { headers: headers.unwrap_or_default(), records, skipped }
+}
+
+/// Splits a line on spaces, keeping `"quoted strings"` (with `\"` escapes) and `[bracketed]`
+/// timestamps together. Returns None for an unterminated quote or bracket.
+fn split_fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+    while let Some(&first) = chars.peek() {
+        if first == ' ' {
+            chars.next();
+            continue;
+        }
+        let mut field = String::new();
+        match first {
+            '"' => {
+                field.push(chars.next()?);
+                loop {
+                    match chars.next()? {
+                        '\\' => {
+                            field.push('\\');
+                            field.push(chars.next()?);
+                        }
+                        '"' => break,
+                        ch => field.push(ch),
+                    }
+                }
+                field.push('"');
+            }
+            '[' => loop {
+                let ch = chars.next()?;
+                field.push(ch);
+                if ch == ']' {
+                    break;
+                }
+            },
+            _ => {
+                while let Some(ch) = chars.next_if(|&ch| ch != ' ') {
+                    field.push(ch);
+                }
+            }
+        }
+        fields.push(field);
+    }
+    Some(fields)
+}
+
+/// Removes surrounding quotes from a quoted field and unescapes `\"` and `\\`. Other escapes
+/// such as the `\x16` that Apache writes for binary junk are kept as they are.
+fn unquote(field: &str) -> String {
+    let Some(inner) = field.strip_prefix('"').and_then(|f| f.strip_suffix('"')) else {
+        return field.to_string();
+    };
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some(escaped @ ('"' | '\\')) => result.push(escaped),
+                Some(other) => {
+                    result.push('\\');
+                    result.push(other);
+                }
+                None => result.push('\\'),
+            },
+            ch => result.push(ch),
+        }
+    }
+    result
+}
+
+/// Logs write `-` for missing values; show those as empty cells.
+fn dash_to_empty(value: &str) -> String {
+    if value == "-" { String::new() } else { value.to_string() }
+}
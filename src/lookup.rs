@@ -0,0 +1,60 @@
+//! Code list lookups (`--lookup`).
+//!
+//! Columns of codes such as ISO country codes, status numbers or enum ids are hard to read on
+//! their own. A lookup file maps each code to a name, which is shown in place of the code or
+//! next to it.
+
+use crate::column_names;
+use csvpretty::Table;
+use std::collections::HashMap;
+
+/// A `--lookup COLUMN=FILE[:KEY:VALUE]` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookupSpec {
+    pub column: String,
+    path: String,
+    /// Columns of the lookup file holding the codes and the names. None means the first and
+    /// second columns.
+    key_value: Option<(String, String)>,
+}
+
+/// Clap value parser for `--lookup COLUMN=FILE[:KEY:VALUE]`.
+pub fn parse_lookup_spec(s: &str) -> Result<LookupSpec, String> {
+    let usage = || format!("invalid lookup '{}' (expected COLUMN=FILE[:KEY:VALUE])", s);
+    let (column, source) = s.split_once('=').ok_or_else(usage)?;
+    // Split from the right, so a path may itself contain colons
+    let (path, key_value) = match source.rsplitn(3, ':').collect::<Vec<_>>()[..] {
+        [value, key, path] => (path, Some((key.to_string(), value.to_string()))),
+        _ => (source, None),
+    };
+    if column.is_empty() || path.is_empty() || key_value.as_ref().is_some_and(|(key, value)| key.is_empty() || value.is_empty()) {
+        return Err(usage());
+    }
+    Ok(LookupSpec { column: column.to_string(), path: path.to_string(), key_value })
+}
+
+impl LookupSpec {
+    /// Reads the lookup file into a map from code to name. Later rows win for repeated codes.
+    pub fn load(&self) -> Result<HashMap<String, String>, String> {
+        let file = std::fs::File::open(&self.path).map_err(|e| format!("{}: {}", self.path, e))?;
+        let table = Table::from_reader(file).map_err(|e| format!("{}: {}", self.path, e))?;
+
+        let position = |name: &str| column_names::position(&table.headers, name)
+            .ok_or_else(|| format!("{}: no column named '{}'", self.path, name));
+        let (key_idx, value_idx) = match &self.key_value {
+            Some((key, value)) => (position(key)?, position(value)?),
+            None if table.headers.len() >= 2 => (0, 1),
+            None => return Err(format!("{}: a lookup file needs at least two columns", self.path)),
+        };
+
+        Ok(table.records.iter()
+            .map(|row| (row[key_idx].to_string(), row[value_idx].to_string()))
+            .collect())
+    }
+
+    /// Name of the column added next to the codes when they are kept.
+    pub fn name_column(&self) -> String {
+        let value = self.key_value.as_ref().map_or("name", |(_, value)| value.as_str());
+        format!("{} {}", self.column, value)
+    }
+}
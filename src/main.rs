@@ -1,557 +1,2325 @@
+mod annotate;
+mod charts;
+mod check;
+mod ci;
+mod clipboard;
+mod coercion;
+mod column_names;
+mod column_types;
+mod concat;
+mod dashboard;
+mod dates;
+mod diff;
+mod estimate;
+mod external_pager;
+mod extract;
+mod failure;
+mod freq;
+mod gen_examples;
+mod grep;
+mod groups;
+mod hyperlinks;
+mod icons;
+mod inference;
+mod locale;
+mod lookup;
+mod output_encoding;
+mod pager;
+mod paging;
+mod panels;
+mod pipeline;
+mod pivot;
+mod progressive;
+mod provenance;
+mod rows;
+mod rules;
+mod sample;
+mod source;
+mod split;
+mod stats;
+mod terminal;
+mod terminal_guard;
+mod totals;
+mod whitespace;
+
+use annotate::Annotations;
+use charts::{BarSpec, Sparkline};
+use extract::Extraction;
+use failure::{ErrorFormat, Failure};
+use hyperlinks::LinkLabel;
+use icons::IconSpec;
+use ci::CiSystem;
+use dashboard::Aggregate;
+use chrono::NaiveDateTime;
 use clap::Parser;
-use csv::ReaderBuilder;
-use owo_colors::{OwoColorize, Rgb};
-use std::io::{self, Read};
-use terminal_colorsaurus::{theme_mode, QueryOptions, ThemeMode};
-use unicode_width::UnicodeWidthStr;
-
-/// Color palette for dark terminal themes.
-/// Colors cycle through columns: Orange → Cyan → Purple → Pink → Yellow → (repeat)
-///
-/// These RGB values are taken from the csvlens project:
-/// https://github.com/YS-L/csvlens/blob/main/src/theme.rs
-const DARK_THEME_COLORS: [(u8, u8, u8); 5] = [
-    (253, 151, 31),  // Orange
-    (102, 217, 239), // Cyan
-    (190, 132, 255), // Purple
-    (249, 38, 114),  // Pink
-    (230, 219, 116), // Yellow
-];
-
-/// Color palette for light terminal themes.
-/// Darker variants of the dark theme colors for better contrast on light backgrounds.
-///
-/// These RGB values are taken from the csvlens project:
-/// https://github.com/YS-L/csvlens/blob/main/src/theme.rs
-const LIGHT_THEME_COLORS: [(u8, u8, u8); 5] = [
-    (207, 112, 0),   // Darker Orange
-    (0, 137, 179),   // Darker Cyan/Blue
-    (104, 77, 153),  // Darker Purple
-    (249, 0, 90),    // Darker Pink
-    (153, 143, 47),  // Darker Yellow/Olive
-];
-
-/// Detects the terminal's theme (dark/light) and returns the appropriate color palette.
-/// Queries the terminal using OSC escape sequences to determine background color.
-/// Falls back to dark theme if detection fails.
-fn detect_theme() -> &'static [(u8, u8, u8); 5] {
-    match theme_mode(QueryOptions::default()) {
-        Ok(ThemeMode::Dark) => &DARK_THEME_COLORS,
-        Ok(ThemeMode::Light) => &LIGHT_THEME_COLORS,
-        _ => &DARK_THEME_COLORS, // Default to dark theme on error
-    }
-}
-
-/// Gets the RGB color for a column index using modulo to cycle through the palette.
-/// Example: columns 0-4 use colors 0-4, column 5 wraps to color 0, etc.
-fn get_column_color(col_index: usize, theme: &[(u8, u8, u8); 5]) -> (u8, u8, u8) {
-    theme[col_index % theme.len()]
-}
-
-/// Configuration for table rendering.
-/// Consolidates display options to reduce function parameter counts.
-struct RenderConfig<'a> {
-    wrap_mode: WrapMode,
-    show_line_numbers: bool,
-    /// Theme colors if enabled. None when --no-color is used.
-    theme: Option<&'a [(u8, u8, u8); 5]>,
-    terminal_width: usize,
-}
+use column_types::ColumnType;
+use csvpretty::palette::{self, ColorDepth, Palette, PaletteSpec};
+use csvpretty::{display_width, set_width_conventions, Cell, CsvOptions, Ellipsis, EmojiWidth, InputOffset, is_null, is_numeric, line_number_width, logs, spreadsheet, sqlite, ParseError, ParseErrorKind, ProgressiveRenderer, strip_ansi, structured, truncate_cell, truncate_to_width, Alignment, BorderStyle, RenderOptions, Renderer, RowColors, SortIndicator, Table, TableStyle, VerticalAlignment, WidthConventions, WrapMode};
+use inference::InferredType;
+use regex::Regex;
+use output_encoding::{EncodedWriter, OutputEncoding};
+use pipeline::{apply_column_types, find_column, palette_slots, resolve_alignments, resolve_column_types, resolve_fixed_widths, resolve_max_lines};
+use lookup::LookupSpec;
+use pivot::PivotSpec;
+use provenance::Provenance;
+use rules::Rule;
+use source::InputEncoding;
+use split::SplitSpec;
+use stats::StatsOrder;
+use terminal::ThemeChoice;
+use totals::TotalsFunction;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Cursor, IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::process::Child;
 
 #[derive(Parser, Debug)]
 #[command(name = "csvpretty")]
 #[command(about = "Format CSV input into a beautiful table", long_about = None)]
+#[command(group(clap::ArgGroup::new("seeded").args(["sample", "measure_random"]).multiple(true)))]
 struct Args {
-    /// Text wrapping mode: word, char, or none
-    #[arg(long, default_value = "word")]
-    wrap: WrapMode,
+    /// Files to read: paths, http:// or https:// URLs (with the http feature), or s3:// or gs://
+    /// URLs (with the cloud feature). Reads stdin if omitted or -. Several files are shown as one
+    /// table with the columns of all of them. Gzip, zstd and bzip2 compressed input is decompressed
+    files: Vec<String>,
+
+    /// Send this header when fetching URLs, e.g. 'Authorization: Bearer TOKEN' (repeatable)
+    #[arg(long = "header", value_name = "NAME: VALUE", value_parser = parse_http_header)]
+    http_headers: Vec<(String, String)>,
+
+    /// Read the input from the clipboard, e.g. a range copied from a spreadsheet (tab-separated
+    /// text is recognized)
+    #[arg(long, conflicts_with = "files")]
+    clipboard_in: bool,
+
+    /// Write the table to this file instead of stdout. Colors are left out unless --color always
+    /// is given, and the terminal isn't asked for its theme
+    #[arg(short = 'o', long, value_name = "FILE", conflicts_with_all = ["alt_screen", "pager"])]
+    output: Option<PathBuf>,
+
+    /// Character encoding of the table: utf-8, utf-8-bom (for Excel on Windows), or latin-1
+    #[arg(long, default_value = "utf-8")]
+    output_encoding: OutputEncoding,
+
+    /// Also copy the table to the clipboard, without colors, e.g. to paste into chat or a document
+    #[arg(long, conflicts_with = "alt_screen")]
+    clipboard_out: bool,
+
+    /// Add the rows of another file below the input's, e.g. to combine stdin with a file (repeatable)
+    #[arg(long, value_name = "FILE")]
+    concat: Vec<String>,
+
+    /// Add a first column with this name holding the file each row came from
+    #[arg(long, value_name = "NAME")]
+    source_column: Option<String>,
+
+    /// Input format. Without it, .json, .ndjson/.jsonl, .yaml/.yml, .xlsx and .sqlite/.db files are
+    /// read as such, and JSON is recognized by its content; anything else is read as CSV
+    #[arg(long, visible_alias = "input-format")]
+    input: Option<InputFormat>,
+
+    /// Worksheet of an Excel workbook to show, by name or by position starting at 1 (default: the
+    /// first)
+    #[arg(long, value_name = "NAME|INDEX")]
+    sheet: Option<String>,
+
+    /// Table or view of a SQLite database to show (default: the first)
+    #[arg(long, value_name = "NAME", conflicts_with = "query")]
+    table: Option<String>,
+
+    /// SQL query whose result to show, run against a SQLite database opened read-only
+    #[arg(long, value_name = "SQL")]
+    query: Option<String>,
+
+    /// Character encoding of the input (a UTF-8 or UTF-16 byte order mark takes precedence)
+    #[arg(long, global = true, default_value = "utf-8")]
+    encoding: InputEncoding,
+
+    /// Treat the first CSV line as data; columns are named 1, 2, ... unless --headers is given
+    #[arg(long, global = true)]
+    no_headers: bool,
+
+    /// Skip this many lines at the start of the input (rows of a worksheet), e.g. a preamble
+    /// before the header
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    skip_lines: usize,
+
+    /// Ignore CSV lines starting with this character, e.g. '#'
+    #[arg(long, global = true, value_name = "CHAR", value_parser = parse_ascii_char)]
+    comment: Option<u8>,
+
+    /// Remove whitespace around CSV headers, fields or both, e.g. from a padded export
+    #[arg(long, global = true, value_enum, default_value = "none")]
+    trim: TrimMode,
+
+    /// Character CSV fields are quoted with, e.g. "'"
+    #[arg(long, global = true, value_name = "CHAR", value_parser = parse_ascii_char, default_value = "\"")]
+    quote: u8,
+
+    /// Character escaping a quote inside a quoted CSV field, e.g. '\' for \"
+    #[arg(long, global = true, value_name = "CHAR", value_parser = parse_ascii_char)]
+    escape: Option<u8>,
+
+    /// Read a doubled quote inside a quoted CSV field as one quote (default)
+    #[arg(long, global = true, overrides_with = "no_double_quote")]
+    double_quote: bool,
+
+    /// Read doubled quotes as they are, e.g. for input that escapes quotes with --escape instead
+    #[arg(long, global = true, overrides_with = "double_quote")]
+    no_double_quote: bool,
+
+    /// Accept CSV records with any number of fields: columns are added for the fields of longer
+    /// records, named by position, and shorter records are padded with empty cells
+    #[arg(long, conflicts_with_all = ["strict", "lenient"])]
+    flexible: bool,
+
+    /// Carry on past CSV records with the wrong number of fields: pad short ones, skip long
+    /// ones and print a warning for each
+    #[arg(long, conflicts_with = "strict")]
+    lenient: bool,
+
+    /// Stop with an error at the first CSV record with the wrong number of fields (the default)
+    #[arg(long)]
+    strict: bool,
+
+    /// Report records with the wrong number of fields or unbalanced quotes instead of showing
+    /// the table. With --lenient, show the table with those rows highlighted and the report on stderr
+    #[arg(long)]
+    check: bool,
+
+    /// Annotate the checked file with the problems --check finds, for a CI system: gha prints
+    /// GitHub Actions error commands along with the report, gitlab writes a Code Quality report
+    /// to gl-code-quality-report.json
+    #[arg(long, value_enum, value_name = "SYSTEM", requires = "check")]
+    annotate: Option<Annotations>,
+
+    /// Replace the column names, as a comma-separated list with one name per column
+    #[arg(long, value_delimiter = ',')]
+    headers: Option<Vec<String>>,
+
+    /// Rename columns before display, as OLD=NEW (e.g. cust_id=customer,amt=amount)
+    #[arg(long, value_delimiter = ',', value_parser = parse_rename)]
+    rename: Vec<(String, String)>,
+
+    /// Match the column names given to flags regardless of case. Surrounding and repeated
+    /// whitespace, invisible characters and typographic dashes and quotes never matter
+    #[arg(long, global = true)]
+    ignore_header_case: bool,
+
+    /// Output format: a terminal table, or a table wrapped for a CI build log
+    #[arg(long, default_value = "table")]
+    format: OutputFormat,
+
+    /// Table width in columns, instead of the terminal width. Without a terminal, COLUMNS is used,
+    /// then 80 (CI formats default to 120)
+    #[arg(long, global = true, value_parser = clap::value_parser!(u16).range(1..))]
+    width: Option<u16>,
+
+    /// Text wrapping mode: word, char, truncate, or none (default: word, or none with --pager)
+    #[arg(long, global = true)]
+    wrap: Option<WrapMode>,
+
+    /// Maximum width of any column's content, in all wrap modes
+    #[arg(long, global = true, value_parser = clap::value_parser!(u16).range(1..))]
+    max_col_width: Option<u16>,
+
+    /// Minimum width of a column's content when columns are squeezed to fit (narrower columns stay narrow)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    min_col_width: Option<u16>,
+
+    /// Fixed content width of a column, overriding --min-col-width and --max-col-width (e.g. name=20,notes=40)
+    #[arg(long, value_delimiter = ',', value_parser = parse_column_width)]
+    col_width: Vec<(String, usize)>,
+
+    /// Size the columns from the first and last N rows only, instead of every row, to start
+    /// faster on huge inputs. Wider cells in the other rows are wrapped, or cut with --wrap none
+    #[arg(long, value_name = "N")]
+    measure_rows: Option<usize>,
+
+    /// Size the columns from a random sample of --measure-rows rows instead of the first and
+    /// last ones (--seed picks the same rows again)
+    #[arg(long, requires = "measure_rows")]
+    measure_random: bool,
+
+    /// Show at most this many lines of any cell; longer cells end in "(+N lines)". In the
+    /// interactive viewer, `o` shows the top row in full
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    max_row_height: Option<u16>,
+
+    /// Show at most this many lines of a column's cells, overriding --max-row-height (e.g. notes=3)
+    #[arg(long, value_name = "COLUMN=LINES", value_delimiter = ',', value_parser = parse_column_lines)]
+    max_lines: Vec<(String, usize)>,
 
     /// Show line numbers
-    #[arg(short = 'n', long)]
+    #[arg(short = 'n', long, global = true)]
     line_numbers: bool,
 
-    /// Disable column colors
-    #[arg(long)]
+    /// When to use colors: auto (only when stdout is a terminal), always, or never
+    #[arg(long, global = true, default_value = "auto")]
+    color: ColorChoice,
+
+    /// Disable column colors (same as --color never)
+    #[arg(long, global = true)]
     no_color: bool,
+
+    /// Theme the colors are picked for, instead of asking the terminal for its background color
+    /// [env: CSVPRETTY_THEME]
+    #[arg(long, global = true)]
+    theme: Option<ThemeChoice>,
+
+    /// Color depth: auto (detect from COLORTERM/TERM), truecolor, 256 or 16
+    #[arg(long, global = true, default_value = "auto")]
+    color_depth: ColorDepth,
+
+    /// Column colors as hex, e.g. ff8800,00aaff [env: CSVPRETTY_COLORS]
+    #[arg(long, global = true, value_parser = PaletteSpec::parse)]
+    palette: Option<PaletteSpec>,
+
+    /// Give columns their own colors instead of the next in the palette, as a color name (black,
+    /// red, green, yellow, blue, magenta, cyan, white) or hex (e.g. status=red,amount=#00ff88)
+    #[arg(long, value_name = "COLUMN=COLOR", value_delimiter = ',', value_parser = parse_column_color)]
+    column_color: Vec<(String, (u8, u8, u8))>,
+
+    /// Per-column alignment: left, right or center (e.g. price=right,name=center)
+    #[arg(long, value_delimiter = ',', value_parser = parse_column_align)]
+    align: Vec<(String, Alignment)>,
+
+    /// Where cells sit in rows made taller by a wrapped cell: top, middle or bottom
+    #[arg(long, default_value = "top")]
+    valign: VerticalAlignment,
+
+    /// Render a column as a typed value, e.g. elapsed=duration(ms) (types: duration[(s|ms)], bytes, percent[(DECIMALS)], number[(DECIMALS)], date[(short|medium|long)])
+    #[arg(long, value_delimiter = ',', value_parser = column_types::parse_column_type_arg)]
+    column_type: Vec<(String, ColumnType)>,
+
+    /// Locale whose decimal point, digit grouping and month names --column-type values follow,
+    /// e.g. de-DE (default: from LC_ALL, LC_NUMERIC or LANG)
+    #[arg(long, value_name = "LOCALE", value_parser = locale::parse_locale)]
+    locale: Option<chrono::Locale>,
+
+    /// Sort rows by this column (numbers and typed values sort by magnitude)
+    #[arg(long)]
+    sort: Option<String>,
+
+    /// Sort in descending order
+    #[arg(long, requires = "sort")]
+    reverse: bool,
+
+    /// Leave out the arrow marking the --sort column's header
+    #[arg(long, requires = "sort")]
+    no_sort_indicator: bool,
+
+    /// Group rows by the values of a column: sort them by it and draw a rule between groups
+    #[arg(long, value_name = "COLUMN", conflicts_with_all = ["vertical", "page_height"])]
+    group_by: Option<String>,
+
+    /// Name each --group-by group and its number of rows in the rule above it
+    #[arg(long, requires = "group_by")]
+    group_labels: bool,
+
+    /// Add a row below each --group-by group with the totals of its numeric columns
+    #[arg(long, requires = "group_by")]
+    group_subtotals: bool,
+
+    /// Add a row below the table with the sum of each numeric column, or their mean, smallest or
+    /// largest value (e.g. --totals mean)
+    #[arg(long, value_name = "FUNCTION", num_args = 0..=1, default_missing_value = "sum")]
+    totals: Option<TotalsFunction>,
+
+    /// Show a live table of --agg aggregates per --group-by group, updated as rows arrive, e.g.
+    /// from a process writing CSV to stdin
+    #[arg(long, requires_all = ["group_by", "agg"], conflicts_with_all = ["alt_screen", "pager"])]
+    dashboard: bool,
+
+    /// Aggregates --dashboard shows (e.g. count(),p95(latency)): count(), or count, sum, avg, min,
+    /// max or a percentile such as p95 of a column
+    #[arg(long, value_name = "AGGREGATE", value_delimiter = ',', value_parser = dashboard::parse_aggregate, requires = "dashboard")]
+    agg: Vec<Aggregate>,
+
+    /// Keep reading the --dashboard file as it grows, like tail -f
+    #[arg(long, requires = "dashboard")]
+    follow: bool,
+
+    /// Dim columns holding the same value in (nearly) every row, so the eye goes to the columns
+    /// that vary
+    #[arg(long)]
+    dim_constant: bool,
+
+    /// Show each run of entirely empty rows, e.g. padding rows in a spreadsheet export, as one
+    /// dimmed "⋮ (12 empty rows)" line
+    #[arg(long, conflicts_with_all = ["vertical", "page_height", "group_by"])]
+    collapse_empty_rows: bool,
+
+    /// Blank values that repeat the row above in these columns, so each shows once per run, e.g.
+    /// region,city with --sort. A column is only blanked where the columns before it are too
+    #[arg(long, value_name = "COLUMNS", value_delimiter = ',', conflicts_with = "vertical")]
+    collapse: Vec<String>,
+
+    /// Add a bar chart of a numeric column next to it, as COLUMN[:WIDTH] (repeatable)
+    #[arg(long, visible_alias = "bars", value_parser = charts::parse_bar_spec)]
+    bar: Vec<BarSpec>,
+
+    /// Scale --bar charts from the column's smallest value instead of 0, to tell apart values
+    /// close together
+    #[arg(long, requires = "bar")]
+    bar_from_min: bool,
+
+    /// Draw the values of a numeric column in row order as a sparkline below the table
+    /// (repeatable)
+    #[arg(long, value_name = "COLUMN")]
+    spark: Vec<String>,
+
+    /// Add columns captured by a regex, as 'COLUMN => NAME[,NAME...]:REGEX' (repeatable)
+    #[arg(long, value_parser = extract::parse_extraction)]
+    extract: Vec<Extraction>,
+
+    /// Show names from a lookup file instead of codes, as COLUMN=FILE[:KEY:VALUE] (repeatable; KEY and VALUE default to the file's first two columns)
+    #[arg(long, value_parser = lookup::parse_lookup_spec)]
+    lookup: Vec<LookupSpec>,
+
+    /// Show --lookup names in a new column next to the codes instead of replacing them
+    #[arg(long, requires = "lookup")]
+    lookup_append: bool,
+
+    /// Spread a column of delimited values over numbered columns, as COLUMN[:DELIMITER] (repeatable)
+    #[arg(long, value_parser = split::parse_split_spec)]
+    split_column: Vec<SplitSpec>,
+
+    /// Repeat each row once per value in a column of delimited values, as COLUMN[:DELIMITER] (repeatable)
+    #[arg(long, value_parser = split::parse_split_spec)]
+    explode: Vec<SplitSpec>,
+
+    /// Draw --bar charts in place of the values instead of next to them
+    #[arg(long, requires = "bar")]
+    bar_replace: bool,
+
+    /// What column colors follow: each column's position, or its inferred type (integer, float, bool, date, string)
+    #[arg(long, default_value = "column")]
+    color_by: ColorBy,
+
+    /// What the column color cycle follows: each column's place in the input (source), so columns
+    /// keep their colors when others are added or moved, or its place in the table (display)
+    #[arg(long, default_value = "source")]
+    color_cycle: ColorCycle,
+
+    /// How to color rows: columns (a color per column), stripe (alternate row backgrounds) or both
+    #[arg(long, default_value = "columns")]
+    row_colors: RowColors,
+
+    /// Make http(s) URL cells clickable with OSC 8 terminal hyperlinks (only when colors are on)
+    #[arg(long)]
+    hyperlinks: bool,
+
+    /// Only link URLs in this column (repeatable)
+    #[arg(long, value_name = "COLUMN", requires = "hyperlinks")]
+    hyperlink_column: Vec<String>,
+
+    /// What a linked cell shows: the whole URL, or only its host with the full URL as the target
+    #[arg(long, default_value = "url", requires = "hyperlinks")]
+    hyperlink_label: LinkLabel,
+
+    /// Color cells matching a rule such as 'rate > 0.1 => red' (repeatable; first match wins)
+    #[arg(long, value_parser = rules::parse_rule)]
+    rule: Vec<Rule>,
+
+    /// Show icons before the values of a column, as 'COLUMN:VALUE=ICON,...' such as 'status:ok=✅,error=❌' (repeatable)
+    #[arg(long, value_parser = icons::parse_icon_spec)]
+    icon_column: Vec<IconSpec>,
+
+    /// Show --icon-column icons instead of the values
+    #[arg(long, requires = "icon_column")]
+    icon_only: bool,
+
+    /// Right-align columns whose values are all numeric (default)
+    #[arg(long, overrides_with = "no_auto_align")]
+    auto_align: bool,
+
+    /// Disable automatic right-alignment of numeric columns
+    #[arg(long, overrides_with = "auto_align")]
+    no_auto_align: bool,
+
+    /// Line up the decimal points of the numbers in numeric columns (unless --align says otherwise)
+    #[arg(long)]
+    align_decimal: bool,
+
+    /// Border style preset (default: unicode, or ascii on Windows consoles that can't show
+    /// box-drawing characters)
+    #[arg(long, global = true)]
+    style: Option<TableStyle>,
+
+    /// Separator after the line numbers, instead of the --style column separator
+    #[arg(long, global = true, value_name = "CHAR", value_parser = parse_border_char)]
+    gutter_char: Option<char>,
+
+    /// Shown in place of the line number on the continuation lines of wrapped rows (e.g. ↳)
+    #[arg(long, global = true, value_name = "CHAR", value_parser = parse_border_char)]
+    continuation_char: Option<char>,
+
+    /// Separator between columns, instead of the --style one (e.g. ┆ or a space)
+    #[arg(long, global = true, value_name = "CHAR", value_parser = parse_border_char)]
+    separator: Option<char>,
+
+    /// Spaces on either side of the text in each cell
+    #[arg(long, global = true, value_name = "N", default_value_t = 1)]
+    padding: u8,
+
+    /// Title shown in the table's top border (and as the block name in CI logs)
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Where the title sits in the top border, and the caption under the table: left, right or center
+    #[arg(long, default_value = "left")]
+    title_align: Alignment,
+
+    /// Text shown under the table, e.g. where the data came from (wrapped to the table's width)
+    #[arg(long)]
+    caption: Option<String>,
+
+    /// How to fit tables wider than the terminal: shrink columns, or overflow (hide columns that don't fit)
+    #[arg(long, default_value = "shrink")]
+    fit: FitMode,
+
+    /// Print tables wider than the terminal as panels of columns stacked one below the other,
+    /// each repeating the line numbers or the --panel-key column
+    #[arg(long, conflicts_with_all = ["fit", "vertical", "alt_screen"])]
+    split_columns: bool,
+
+    /// Split the table into pages of at most N lines, each with the header and a Page X/Y line
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..), conflicts_with_all = ["split_columns", "vertical", "alt_screen"])]
+    page_height: Option<u16>,
+
+    /// Show at most N rows: the first and last ones, with a "⋯ 1,234 more rows ⋯" line for the
+    /// rest. `auto` fits the rows to the terminal's height, and shows every row when the output
+    /// isn't going to a terminal
+    #[arg(long, value_name = "N|auto", value_parser = parse_max_rows, conflicts_with_all = ["vertical", "group_by", "collapse_empty_rows", "page_height"])]
+    max_rows: Option<MaxRows>,
+
+    /// Column repeated at the start of every --split-columns panel, instead of line numbers
+    #[arg(long, value_name = "COLUMN", requires = "split_columns")]
+    panel_key: Option<String>,
+
+    /// Only show rows with a cell matching this regex, e.g. '(?i)error' to ignore case
+    #[arg(long, value_name = "REGEX")]
+    grep: Option<Regex>,
+
+    /// Match --grep against each record as written in the input, quotes and escapes included
+    /// (e.g. to find literal "" or stray quotes), instead of the parsed values. CSV input only
+    #[arg(long, requires = "grep")]
+    raw: bool,
+
+    /// Only show rows whose value in a column equals VALUE, as COLUMN=VALUE (repeatable; rows must
+    /// match all of them, or any with --where-any)
+    #[arg(long = "where", value_name = "COLUMN=VALUE", value_parser = parse_where)]
+    where_equal: Vec<(String, String)>,
+
+    /// Show rows matching any --where instead of all of them
+    #[arg(long, requires = "where_equal")]
+    where_any: bool,
+
+    /// Only show rows at or after this date/time (requires --time-column)
+    #[arg(long, requires = "time_column", value_parser = dates::parse_timestamp_arg)]
+    since: Option<NaiveDateTime>,
+
+    /// Only show rows before this date/time (requires --time-column)
+    #[arg(long, requires = "time_column", value_parser = dates::parse_timestamp_arg)]
+    until: Option<NaiveDateTime>,
+
+    /// Column holding the timestamps used by --since/--until
+    #[arg(long)]
+    time_column: Option<String>,
+
+    /// Show a random sample of N rows, in their input order
+    #[arg(long, value_name = "N")]
+    sample: Option<usize>,
+
+    /// Seed for --sample or --measure-random, to pick the same rows again (--verbose and
+    /// --provenance show the seed used)
+    #[arg(long, requires = "seeded")]
+    seed: Option<u64>,
+
+    /// Drop rows that repeat an earlier row exactly
+    #[arg(long, conflicts_with = "unique_by")]
+    unique: bool,
+
+    /// Drop rows whose values in these columns repeat an earlier row's, e.g. id or name,email
+    #[arg(long, value_name = "COLUMNS", value_delimiter = ',')]
+    unique_by: Vec<String>,
+
+    /// Show a summary of each column (type, nulls, distinct values, min/max/mean) instead of the rows
+    #[arg(long)]
+    stats: bool,
+
+    /// Show how often each value of a column occurs, most common first, instead of the rows
+    /// (add --bar count for a chart)
+    #[arg(long, value_name = "COLUMN", conflicts_with = "stats")]
+    freq: Option<String>,
+
+    /// Show a table with a row per value of the `rows` columns and a column per value of the
+    /// `cols` column, aggregating the rows they share like --agg, instead of the rows (e.g.
+    /// rows=region,cols=month,values=sum(amount); values default to count())
+    #[arg(long, value_name = "SPEC", value_parser = pivot::parse_pivot, conflicts_with_all = ["stats", "freq", "dashboard"])]
+    pivot: Option<PivotSpec>,
+
+    /// Order of the --stats rows: the input's column order, or the columns with the most nulls first
+    #[arg(long, default_value = "input", requires = "stats")]
+    stats_order: StatsOrder,
+
+    /// List the cells that don't match their column's type (its --column-type, or the type most
+    /// of its values have) instead of the table, and exit with status 1 if there are any
+    #[arg(long)]
+    coerce_report: bool,
+
+    /// Show each record as a block of field/value lines (like psql's \x), for very wide tables
+    #[arg(long)]
+    vertical: bool,
+
+    /// Show the table in the terminal's alternate screen with the header pinned while scrolling
+    /// (press c to pick the columns shown)
+    #[arg(long)]
+    alt_screen: bool,
+
+    /// Show the table in $CSVPRETTY_PAGER or $PAGER (default: less), scrolling sideways
+    /// instead of wrapping. Only applies when writing to a terminal
+    #[arg(long, conflicts_with = "alt_screen")]
+    pager: bool,
+
+    /// Text shown in empty cells, e.g. ∅ (empty and NULL/NA cells are dimmed when colors are on)
+    #[arg(long, default_value = "")]
+    null_display: String,
+
+    /// Show spaces and tabs at the start and end of cells as · and →
+    #[arg(long)]
+    show_whitespace: bool,
+
+    /// Width of flags and emoji sequences (with skin tones, VS16 or ZWJ) in columns, for terminals
+    /// that draw them differently from the Unicode tables
+    #[arg(long, global = true, default_value = "auto")]
+    emoji_width: EmojiWidth,
+
+    /// Count East Asian ambiguous-width characters (e.g. ±, ①, ×) as two columns, as CJK terminals draw them
+    #[arg(long, global = true)]
+    ambiguous_wide: bool,
+
+    /// Text marking where cells were cut off by --wrap truncate or --max-col-width with --wrap none;
+    /// {chars} is replaced by the number of characters cut off, e.g. '[+{chars}]'
+    #[arg(long, default_value = "…", value_name = "TEXT")]
+    truncation_marker: String,
+
+    /// Where cells are cut off: at the start, in the middle (keeping the distinguishing end of
+    /// paths and IDs, e.g. /var/…/app.log) or at the end
+    #[arg(long, default_value = "end")]
+    ellipsis: Ellipsis,
+
+    /// Truncate cells longer than this many bytes before layout (0 disables the limit)
+    #[arg(long, default_value_t = 65536)]
+    max_cell_bytes: usize,
+
+    /// Print the number of rows and columns below the table
+    #[arg(long)]
+    footer: bool,
+
+    /// Print a line below the table naming the source, the time it was made, the csvpretty
+    /// version and the filters applied (set SOURCE_DATE_EPOCH to fix the time)
+    #[arg(long)]
+    provenance: bool,
+
+    /// Estimate a CSV file's rows from its first megabyte instead of showing it, with advice on
+    /// showing part of it when it's large
+    #[arg(long, conflicts_with = "check")]
+    estimate: bool,
+
+    /// Read only the first N bytes of the input, dropping the incomplete last record
+    #[arg(long, value_name = "N")]
+    head_bytes: Option<u64>,
+
+    /// Memory-map a whole local file instead of reading it into memory, so its cells take no
+    /// memory of their own. Only for files nothing is changing: a program that truncates or
+    /// rewrites the file while it's mapped can crash csvpretty or garble the table
+    #[arg(long)]
+    mmap: bool,
+
+    /// Print rows as they're read instead of once the whole input is, sizing columns from the
+    /// first --progressive-rows rows; with --wrap none, later rows that don't fit widen their
+    /// columns. The default with --wrap none or a --col-width for every column, unless another
+    /// flag needs every row first (e.g. --sort). CSV input from stdin or a local file only
+    #[arg(long, conflicts_with = "no_progressive")]
+    progressive: bool,
+
+    /// Read the whole input before printing anything, sizing columns to every row
+    #[arg(long)]
+    no_progressive: bool,
+
+    /// Rows read to size the columns before --progressive starts printing
+    #[arg(long, value_name = "N", default_value_t = 100, value_parser = clap::value_parser!(u32).range(1..))]
+    progressive_rows: u32,
+
+    /// How errors are written to standard error: text, or a JSON object with the message and
+    /// where in the input a parse error was found. The exit code tells the kind of error: 2 for
+    /// input that can't be parsed, 3 for empty input, 4 for invalid flags and 1 for anything else
+    #[arg(long, value_enum, value_name = "FORMAT", default_value = "text", global = true)]
+    error_format: ErrorFormat,
+
+    /// Report on standard error how the terminal width and theme were detected
+    #[arg(long, global = true)]
+    verbose: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+impl Args {
+    /// How CSV input is parsed.
+    fn csv_options(&self) -> CsvOptions {
+        CsvOptions {
+            has_headers: !self.no_headers,
+            comment: self.comment,
+            quote: self.quote,
+            escape: self.escape,
+            double_quote: self.double_quote || !self.no_double_quote,
+            flexible: self.flexible,
+            trim: self.trim.csv_trim(),
+            offset: InputOffset::default(),
+        }
+    }
+
+    /// Whether the table goes through a pager: --pager when writing to a terminal, outside CI.
+    fn uses_pager(&self) -> bool {
+        self.pager && self.format.ci_system().is_none() && self.writes_to_terminal()
+    }
+
+    /// Whether the table goes to a terminal: stdout is one, and there's no --output file.
+    fn writes_to_terminal(&self) -> bool {
+        self.output.is_none() && io::stdout().is_terminal()
+    }
+
+    /// The --style preset. Legacy Windows consoles get ASCII borders unless asked for others.
+    fn table_style(&self) -> TableStyle {
+        match self.style {
+            Some(style) => style,
+            None if terminal::is_legacy_console(self.verbose) => TableStyle::Ascii,
+            None => TableStyle::Unicode,
+        }
+    }
+
+    /// The number of rows --max-rows shows, if they're limited. `auto` only limits them on a
+    /// terminal, where rows scrolling out of sight would otherwise be lost.
+    fn row_limit(&self) -> Option<usize> {
+        match self.max_rows? {
+            MaxRows::Rows(rows) => Some(rows),
+            MaxRows::Auto if self.writes_to_terminal() && !self.uses_pager() => {
+                Some(terminal::detect_height().saturating_sub(MAX_ROWS_OVERHEAD).max(2))
+            }
+            MaxRows::Auto => None,
+        }
+    }
+
+    /// The --wrap mode. Pagers can scroll sideways, so tables going through one don't wrap
+    /// unless asked to.
+    fn wrap_mode(&self) -> WrapMode {
+        match self.wrap {
+            Some(wrap) => wrap,
+            None if self.uses_pager() => WrapMode::None,
+            None => WrapMode::Word,
+        }
+    }
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Compare two CSV files, showing added, removed and changed rows in one table. Exits with
+    /// status 1 when the files differ
+    Diff(DiffArgs),
+    /// Render fixture files in every combination of --format, --style and --wrap into a
+    /// directory, to generate the documentation examples and spot rendering changes. Exits
+    /// with status 1 when a file can't be rendered
+    #[command(hide = true)]
+    GenExamples(GenExamplesArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct DiffArgs {
+    /// The file to compare against
+    old: String,
+
+    /// The file with the changes
+    new: String,
+
+    /// Match rows by this column instead of by position, so inserted and deleted rows don't
+    /// show every row after them as changed
+    #[arg(long, value_name = "COLUMN")]
+    key: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct GenExamplesArgs {
+    /// The files to render
+    #[arg(required = true)]
+    fixtures: Vec<PathBuf>,
+
+    /// Directory the examples are written to, one subdirectory per file
+    #[arg(long, value_name = "DIR", default_value = "examples")]
+    out: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ColorBy {
+    Column,
+    Type,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ColorCycle {
+    Display,
+    Source,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum OutputFormat {
+    /// A table for the terminal
+    Table,
+    /// GitHub Actions log: a collapsible group with workflow commands disabled
+    Gha,
+    /// TeamCity build log: a collapsible block with service messages disabled
+    Teamcity,
+}
+
+impl OutputFormat {
+    /// The CI system the output is meant for, if any.
+    fn ci_system(self) -> Option<CiSystem> {
+        match self {
+            OutputFormat::Table => None,
+            OutputFormat::Gha => Some(CiSystem::GitHubActions),
+            OutputFormat::Teamcity => Some(CiSystem::TeamCity),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum InputFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// Apache/NGINX common or combined access log
+    Accesslog,
+    /// W3C extended log file format (IIS), with a #Fields: directive
+    W3c,
+    /// A JSON list of objects, whose keys become the columns
+    Json,
+    /// Newline-delimited JSON: one object per line
+    Ndjson,
+    /// A YAML sequence of mappings
+    Yaml,
+    /// An Excel workbook; --sheet picks the worksheet
+    Xlsx,
+    /// A SQLite database file; --table or --query picks the rows
+    Sqlite,
+}
+
+impl InputFormat {
+    /// The format a file name's extension stands for, if any. A compression extension, as in
+    /// `events.ndjson.gz`, is looked past.
+    fn from_extension(path: &str) -> Option<InputFormat> {
+        // A URL's query string and fragment come after the file name
+        let path = if path.contains("://") { path.split(['?', '#']).next().unwrap_or(path) } else { path };
+        let path = [".gz", ".zst", ".bz2"].iter().find_map(|suffix| path.strip_suffix(suffix)).unwrap_or(path);
+        let (_, extension) = path.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(InputFormat::Json),
+            "ndjson" | "jsonl" => Some(InputFormat::Ndjson),
+            "yaml" | "yml" => Some(InputFormat::Yaml),
+            "xlsx" | "xlsm" => Some(InputFormat::Xlsx),
+            "sqlite" | "sqlite3" | "db" => Some(InputFormat::Sqlite),
+            _ => None,
+        }
+    }
+
+    /// Whether the format is read with the CSV parser.
+    fn is_csv(self) -> bool {
+        matches!(self, InputFormat::Csv)
+    }
+
+    /// Whether the format isn't text, so inputs in it are read straight into tables.
+    fn is_binary(self) -> bool {
+        matches!(self, InputFormat::Xlsx | InputFormat::Sqlite)
+    }
+
+    /// The format the input looks like: JSON starts with an object or a list of values (unlike
+    /// a CSV header such as `[id],[name]`), and NDJSON has an object on each line.
+    fn sniff(input: &str) -> InputFormat {
+        let input = input.trim();
+        if input.starts_with('{') {
+            let one_object_per_line = input.lines().count() > 1 && input.lines().all(|line| {
+                let line = line.trim();
+                line.is_empty() || (line.starts_with('{') && line.ends_with('}'))
+            });
+            return if one_object_per_line { InputFormat::Ndjson } else { InputFormat::Json };
+        }
+        match input.strip_prefix('[').map(|rest| rest.trim_start().chars().next()) {
+            Some(None | Some('{' | '[' | '"' | ']')) => InputFormat::Json,
+            _ => InputFormat::Csv,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
-enum WrapMode {
-    Word,
-    Char,
+enum FitMode {
+    /// Shrink and wrap columns so that every column fits
+    Shrink,
+    /// Keep columns at their natural width and hide the ones that don't fit
+    Overflow,
+}
+
+/// A `--max-rows` limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MaxRows {
+    Rows(usize),
+    /// As many rows as fit on the terminal
+    Auto,
+}
+
+/// Lines the table takes besides its rows when `--max-rows auto` fits it to the terminal: the
+/// top border, header and separator, the elision line, the bottom border, and the shell prompt
+/// after it.
+const MAX_ROWS_OVERHEAD: usize = 6;
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum TrimMode {
+    /// Keep whitespace as it is
     None,
+    /// Trim the header row only
+    Headers,
+    /// Trim data fields only
+    Fields,
+    /// Trim headers and fields
+    All,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+impl TrimMode {
+    fn csv_trim(self) -> csv::Trim {
+        match self {
+            TrimMode::None => csv::Trim::None,
+            TrimMode::Headers => csv::Trim::Headers,
+            TrimMode::Fields => csv::Trim::Fields,
+            TrimMode::All => csv::Trim::All,
+        }
+    }
+}
 
-    // Read all stdin
-    let mut input = String::new();
-    io::stdin().read_to_string(&mut input)?;
+/// Parses a single `column=alignment` entry of the `--align` flag.
+fn parse_column_align(s: &str) -> Result<(String, Alignment), String> {
+    let (column, align) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected COLUMN=ALIGNMENT, got '{}'", s))?;
+    let align = <Alignment as clap::ValueEnum>::from_str(align, true)
+        .map_err(|_| format!("invalid alignment '{}' (expected left, right or center)", align))?;
+    Ok((column.to_string(), align))
+}
 
-    if input.trim().is_empty() {
-        eprintln!("Error: No CSV input provided");
-        std::process::exit(1);
+/// Parses a single `column=color` entry of the `--column-color` flag.
+fn parse_column_color(s: &str) -> Result<(String, (u8, u8, u8)), String> {
+    let (column, color) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected COLUMN=COLOR, got '{}'", s))?;
+    Ok((column.to_string(), palette::parse_color(color.trim())?))
+}
+
+/// Parses a single `column=width` entry of the `--col-width` flag.
+fn parse_column_width(s: &str) -> Result<(String, usize), String> {
+    let (column, width) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected COLUMN=WIDTH, got '{}'", s))?;
+    match width.parse() {
+        Ok(width) if width > 0 => Ok((column.to_string(), width)),
+        _ => Err(format!("invalid width '{}' (expected a positive number)", width)),
     }
+}
 
-    // Parse CSV
-    let mut reader = ReaderBuilder::new()
-        .has_headers(true)
-        .from_reader(input.as_bytes());
+/// Parses a `--header` entry such as `Authorization: Bearer TOKEN`.
+fn parse_http_header(s: &str) -> Result<(String, String), String> {
+    match s.split_once(':') {
+        Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.trim().to_string())),
+        _ => Err(format!("expected NAME: VALUE, got '{}'", s)),
+    }
+}
 
-    let headers = reader.headers()?.clone();
-    let header_count = headers.len();
+/// Parses a `--max-lines` entry such as `notes=3`.
+fn parse_column_lines(s: &str) -> Result<(String, usize), String> {
+    let (column, lines) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected COLUMN=LINES, got '{}'", s))?;
+    match lines.parse() {
+        Ok(lines) if lines > 0 => Ok((column.to_string(), lines)),
+        _ => Err(format!("invalid line count '{}' (expected a positive number)", lines)),
+    }
+}
+
+/// The input after its first `count` lines, and where that starts in the input.
+fn skip_lines(input: &str, count: usize) -> (&str, InputOffset) {
+    let mut rest = input;
+    for lines in 0..count {
+        match rest.find('\n') {
+            Some(end) => rest = &rest[end + 1..],
+            None => return ("", InputOffset { lines: lines as u64, bytes: input.len() as u64 }),
+        }
+    }
+    (rest, InputOffset { lines: count as u64, bytes: (input.len() - rest.len()) as u64 })
+}
+
+/// Parses a `--max-rows` limit: a positive number of rows, or `auto`.
+fn parse_max_rows(s: &str) -> Result<MaxRows, String> {
+    match s {
+        "auto" => Ok(MaxRows::Auto),
+        _ => match s.parse::<usize>() {
+            Ok(rows) if rows > 0 => Ok(MaxRows::Rows(rows)),
+            _ => Err(format!("invalid row count '{}' (expected a positive number or 'auto')", s)),
+        },
+    }
+}
 
-    // Collect all records
-    let mut records: Vec<Vec<String>> = Vec::new();
-    for result in reader.records() {
-        let record = result?;
-        let mut row: Vec<String> = record.iter().map(|s| s.to_string()).collect();
+/// Parses a `--comment`, `--quote` or `--escape` character, which has to be a single ASCII
+/// character.
+fn parse_ascii_char(s: &str) -> Result<u8, String> {
+    match s.as_bytes() {
+        &[byte] => Ok(byte),
+        _ => Err(format!("expected a single ASCII character, got '{}'", s)),
+    }
+}
+
+/// Parses a `--gutter-char`, `--continuation-char` or `--separator`, which has to be one column wide.
+fn parse_border_char(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) if display_width(s) == 1 => Ok(ch),
+        _ => Err(format!("expected a single character one column wide, got '{}'", s)),
+    }
+}
 
-        // Pad row if it has fewer columns than headers
-        while row.len() < header_count {
-            row.push(String::new());
+/// The border characters of `--style`, with the separator and gutter characters given by flags.
+fn border_style(args: &Args) -> BorderStyle {
+    let style = args.table_style().border_style();
+    BorderStyle {
+        vertical: args.separator.unwrap_or(style.vertical),
+        gutter: args.gutter_char.unwrap_or(style.gutter),
+        continuation: args.continuation_char.unwrap_or(style.continuation),
+        ..style
+    }
+}
+
+/// Parses a single `old=new` entry of the `--rename` flag.
+fn parse_rename(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((old, new)) if !old.is_empty() && !new.is_empty() => Ok((old.to_string(), new.to_string())),
+        _ => Err(format!("expected OLD=NEW, got '{}'", s)),
+    }
+}
+
+/// Parses a single `column=value` entry of the `--where` flag. The value may be empty.
+fn parse_where(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((column, value)) if !column.is_empty() => Ok((column.to_string(), value.to_string())),
+        _ => Err(format!("expected COLUMN=VALUE, got '{}'", s)),
+    }
+}
+
+/// Replaces the header row with `--headers`, then applies each `--rename` in order.
+fn rename_columns(headers: &mut csv::StringRecord, replacement: Option<&[String]>, renames: &[(String, String)]) -> Result<(), String> {
+    if let Some(names) = replacement {
+        if names.len() != headers.len() {
+            return Err(format!("--headers gives {} names but the input has {} columns", names.len(), headers.len()));
         }
+        *headers = names.iter().collect();
+    }
+    for (old, new) in renames {
+        let col_idx = find_column(headers, old, "--rename")?;
+        *headers = headers.iter()
+            .enumerate()
+            .map(|(i, name)| if i == col_idx { new.as_str() } else { name })
+            .collect();
+    }
+    Ok(())
+}
 
-        records.push(row);
+/// Takes the rows parsed from a log file, warning about lines that weren't recognized.
+fn read_log(log: logs::ParsedLog) -> Table<'static> {
+    if log.headers.is_empty() {
+        failure::exit(Failure::Parse, "no #Fields: directive found in W3C log input");
     }
+    if log.skipped > 0 {
+        let plural = if log.skipped == 1 { "" } else { "s" };
+        eprintln!("Warning: skipped {} unrecognized log line{}", log.skipped, plural);
+    }
+    Table::from_log(log)
+}
+
+/// Takes the table parsed from JSON or YAML input, stopping at a syntax error.
+fn read_structured(parsed: Result<Table<'static>, csvpretty::Error>, prefix: &str) -> Table<'static> {
+    parsed.unwrap_or_else(|e| {
+        failure::exit_error(&e, format!("{}{}", prefix, e));
+    })
+}
 
-    // Get terminal width (or use large value for no-wrap mode)
-    let terminal_width = match args.wrap {
-        WrapMode::None => usize::MAX,
-        _ => terminal_size::terminal_size()
-            .map(|(w, _)| w.0 as usize)
-            .unwrap_or(80),
+/// Reads the worksheet `--sheet` picks from an Excel workbook, stopping at an error.
+fn read_workbook(source: Option<&str>, args: &Args) -> Table<'static> {
+    let options = spreadsheet::SheetOptions { sheet: args.sheet.as_deref(), skip_rows: args.skip_lines, has_headers: !args.no_headers };
+    source::read_bytes(source)
+        .and_then(|bytes| spreadsheet::parse_xlsx(bytes, &options).map_err(|e| format!("{}: {}", source.unwrap_or("stdin"), e)))
+        .unwrap_or_else(|e| {
+            failure::exit(Failure::Other, e);
+        })
+}
+
+/// Reads the table or query result `--table` or `--query` picks from a SQLite database,
+/// stopping at an error.
+fn read_database(source: Option<&str>, args: &Args) -> Table<'static> {
+    let Some(path) = source else {
+        failure::exit(Failure::Usage, "SQLite databases can't be read from stdin; give the database file");
+    };
+    let query = match (&args.table, &args.query) {
+        (_, Some(query)) => sqlite::SqliteSource::Query(query),
+        (Some(table), None) => sqlite::SqliteSource::Table(table),
+        (None, None) => sqlite::SqliteSource::FirstTable,
     };
+    sqlite::read_sqlite(std::path::Path::new(path), query).unwrap_or_else(|e| {
+        failure::exit(Failure::Other, format!("{}: {}", path, e));
+    })
+}
 
-    // Detect theme and check if colors should be enabled
-    // Colors are enabled by default unless --no-color flag or NO_COLOR env var is set
-    let colors_enabled = !args.no_color && std::env::var("NO_COLOR").is_err();
-    let theme = if colors_enabled {
-        Some(detect_theme())
+fn main() {
+    if let Err(e) = run() {
+        match e.downcast_ref::<csvpretty::Error>() {
+            Some(error) => failure::exit_error(error, error),
+            None => failure::exit(Failure::Other, e),
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::try_parse().unwrap_or_else(|e| failure::exit_usage(e));
+    failure::set_error_format(args.error_format);
+    set_width_conventions(WidthConventions { emoji_width: args.emoji_width, ambiguous_wide: args.ambiguous_wide });
+    source::set_http_headers(args.http_headers.clone());
+    source::set_memory_map(args.mmap);
+    column_names::set_ignore_case(args.ignore_header_case);
+    locale::set_locale(args.locale.or_else(locale::from_env));
+    match &args.command {
+        Some(Command::Diff(diff_args)) => return run_diff(&args, diff_args),
+        Some(Command::GenExamples(gen_args)) => return run_gen_examples(&args, gen_args),
+        None => {}
+    }
+    if args.dashboard {
+        return run_dashboard(&args);
+    }
+    let ci_system = args.format.ci_system();
+    let seed = args.seed.unwrap_or_else(sample::random_seed);
+    // Standard input unless files are given, followed by the files to concatenate
+    let mut sources: Vec<Option<&str>> = if args.files.is_empty() {
+        vec![None]
     } else {
-        None
+        args.files.iter().map(|file| Some(file.as_str()).filter(|file| *file != "-")).collect()
     };
+    sources.extend(args.concat.iter().map(|file| Some(file.as_str())));
+    let stdin_name = if args.clipboard_in { "clipboard" } else { "stdin" };
+    let source_names: Vec<String> = sources.iter().map(|source| source.unwrap_or(stdin_name).to_string()).collect();
+    let provenance = args.provenance.then(|| Provenance {
+        source: match sources.as_slice() {
+            [source] => source.map(String::from),
+            _ => Some(source_names.join(", ")),
+        },
+        filters: active_filters(&args, seed),
+    });
+    if args.check && sources.len() > 1 {
+        failure::exit(Failure::Usage, "--check reads a single file");
+    }
 
-    // Create render configuration
-    let config = RenderConfig {
-        wrap_mode: args.wrap,
-        show_line_numbers: args.line_numbers,
-        theme,
-        terminal_width,
+    let given_formats: Vec<Option<InputFormat>> = sources.iter().map(|source| args.input.or_else(|| source.and_then(InputFormat::from_extension))).collect();
+    if args.estimate {
+        return run_estimate(&args, &sources, &given_formats);
+    }
+    check_format_flags(&args, &given_formats);
+
+    // Rows are printed as they're read when nothing needs the whole table first
+    let streamed_input = match stream_first_input(&args, &sources, &given_formats, &source_names) {
+        Some(Streamed::Printed(result)) => return result,
+        Some(Streamed::Read(input)) => Some(input),
+        None => None,
     };
+    let (inputs, mut binary_tables) = read_inputs(&args, &sources, &given_formats, streamed_input);
+    let input_cut_at = inputs.iter().find_map(|input| input.cut_at);
 
-    // Render the table
-    render_table(&headers, &records, &config);
+    // Positions in parse errors and --check count the skipped lines
+    let (inputs, offsets): (Vec<&str>, Vec<InputOffset>) = inputs.iter().map(|input| skip_lines(input.text(), args.skip_lines)).unzip();
+    if inputs.iter().zip(&binary_tables).all(|(input, table)| table.is_none() && input.trim().is_empty()) {
+        failure::exit(Failure::EmptyInput, "No input provided");
+    }
+    let formats: Vec<InputFormat> = given_formats.iter()
+        .zip(&inputs)
+        .map(|(format, input)| format.unwrap_or_else(|| InputFormat::sniff(input)))
+        .collect();
 
-    Ok(())
-}
+    let report = check_first_input(&args, sources[0], &source_names[0], inputs[0], offsets[0], formats[0])?;
 
-fn render_table(headers: &csv::StringRecord, records: &[Vec<String>], config: &RenderConfig) {
-    let header_vec: Vec<&str> = headers.iter().collect();
+    // --grep --raw searches the source text, so it picks records before they are parsed
+    let (grepped, grep_cut_rows) = grep_raw(&args, &inputs, &formats, &source_names);
+    let inputs: Vec<&str> = inputs.iter().zip(&grepped).map(|(input, grepped)| grepped.as_deref().unwrap_or(input)).collect();
+    let (table, skipped_rows) = parse_inputs(&args, &inputs, &offsets, &formats, &source_names, &mut binary_tables, report.as_ref())?;
+    let Table { mut headers, mut records } = table;
 
-    // Calculate row number width (for the leftmost column)
-    let row_num_width = if config.show_line_numbers {
-        records.len().to_string().len().max(1)
-    } else {
-        0
-    };
-
-    // Calculate column widths
-    let col_widths = calculate_column_widths(&header_vec, records, config.terminal_width, config.wrap_mode, row_num_width);
-
-    // Render top border
-    print_horizontal_border(&col_widths, row_num_width, BorderType::Top, config.show_line_numbers);
-
-    // Render header
-    print_header_row(&header_vec, &col_widths, row_num_width, config);
-
-    // Render separator after header
-    print_horizontal_border(&col_widths, row_num_width, BorderType::HeaderSeparator, config.show_line_numbers);
-
-    // Render data rows
-    for (idx, record) in records.iter().enumerate() {
-        print_data_row(idx + 1, record, &col_widths, row_num_width, config);
-    }
-
-    // Render bottom border (only for no-wrap mode to match the example)
-    if matches!(config.wrap_mode, WrapMode::None) {
-        print_horizontal_border(&col_widths, row_num_width, BorderType::Bottom, config.show_line_numbers);
-    }
-}
-
-/// Calculates column widths based on content and terminal constraints.
-///
-/// For no-wrap mode: columns are sized to fit their content exactly (table may exceed terminal width).
-///
-/// For wrap modes: uses a "waterfall" allocation strategy:
-/// 1. Calculate natural width (max content width) for each column
-/// 2. If all columns fit naturally, use those widths
-/// 3. Otherwise: allocate natural width to smallest columns first, then distribute
-///    remaining space proportionally to larger columns that need wrapping
-///
-/// This ensures narrow columns don't get over-allocated space while wide columns share
-/// the burden of wrapping.
-fn calculate_column_widths(headers: &[&str], records: &[Vec<String>], terminal_width: usize, wrap_mode: WrapMode, row_num_width: usize) -> Vec<usize> {
-    let num_cols = headers.len();
-
-    if matches!(wrap_mode, WrapMode::None) {
-        // For no-wrap mode, size columns to content
-        let mut widths = Vec::new();
-        for col_idx in 0..num_cols {
-            let header_width = UnicodeWidthStr::width(headers[col_idx]);
-            let max_content_width = records.iter()
-                .map(|row| {
-                    row.get(col_idx)
-                        .map(|s| UnicodeWidthStr::width(s.as_str()))
-                        .unwrap_or(0)
-                })
-                .max()
-                .unwrap_or(0);
-            widths.push(header_width.max(max_content_width) + 2); // +2 for padding
-        }
-        widths
-    } else {
-        // For wrap modes, distribute terminal width
-        // Calculate overhead: row number column + borders + padding
-        // Format with line numbers: "N  │ content │ content │"
-        // Format without line numbers: " content │ content │"
-        // Row number area (if enabled): N (row_num_width) + "  │" (3 chars)
-        // Each column: " content │" (1 space before + content + 1 space + 1 separator = content + 3)
-        // So overhead is everything except the content widths
-        let row_overhead = if row_num_width > 0 {
-            row_num_width + 3  // "N  │"
-        } else {
-            0  // No row number column
-        };
-        let overhead = row_overhead + (num_cols * 3);
+    // Names are settled first, so every other flag refers to columns by their displayed name
+    if let Err(e) = rename_columns(&mut headers, args.headers.as_deref(), &args.rename) {
+        failure::exit(Failure::Usage, e);
+    }
 
-        let available_width = terminal_width.saturating_sub(overhead);
+    // Control characters would be interpreted by CI log viewers
+    if ci_system.is_some() {
+        headers = headers.iter().map(ci::escape_control_chars).collect();
+        for cell in records.iter_mut().flatten() {
+            *cell = ci::escape_control_chars(cell).into();
+        }
+    }
 
-        // Calculate natural widths for proportional distribution
-        let mut natural_widths = Vec::new();
-        for col_idx in 0..num_cols {
-            let header_width = UnicodeWidthStr::width(headers[col_idx]);
-            let max_content_width = records.iter()
-                .map(|row| {
-                    row.get(col_idx)
-                        .map(|s| UnicodeWidthStr::width(s.as_str()))
-                        .unwrap_or(0)
-                })
-                .max()
-                .unwrap_or(0);
-            natural_widths.push(header_width.max(max_content_width));
+    // Column colors follow these names, not the columns added or moved below
+    let source_headers = headers.clone();
+
+    let rows_read = records.len() + grep_cut_rows;
+    let (rows_in_window, unique_rows) = pipeline::filter_rows(&args, &headers, &mut records, seed);
+    // Counted before --stats and bar columns change the table, so the footer describes the data
+    let footer = Footer {
+        rows: records.len(),
+        columns: headers.len(),
+        filtered_rows: rows_read - rows_in_window,
+        duplicate_rows: rows_in_window - unique_rows,
+        sampled_from: args.sample.map(|_| unique_rows),
+        skipped_rows,
+        input_cut_at,
+    };
+
+    let mut column_types = pipeline::reshape_table(&args, &mut headers, &mut records)?;
+    let pipeline::Arrangement { groups, collapsed_rows, mut totals, elided_rows } = pipeline::arrange_rows(&args, &headers, &mut records, &column_types);
+    let elided_count = elided_rows.map_or(0, |elided| elided.count);
+
+    // Read before --bar-replace takes the values away
+    let sparklines = match args.spark.iter().map(|column| find_column(&headers, column, "--spark")).collect::<Result<Vec<_>, _>>() {
+        Ok(columns) => columns.into_iter()
+            .map(|col_idx| Sparkline::new(&records, col_idx, column_types[col_idx], &headers[col_idx]))
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            failure::exit(Failure::Usage, e);
         }
+    };
+    if let Err(e) = pipeline::add_bar_columns(&mut headers, &mut records, totals.as_mut(), &mut column_types, &args.bar, args.bar_replace, args.bar_from_min) {
+        failure::exit(Failure::Usage, e);
+    }
 
-        let total_natural: usize = natural_widths.iter().sum();
+    // Problems are matched to rows by position, which these flags change
+    let rows_moved = args.sort.is_some() || rows_read != footer.rows || args.grep.is_some() || !args.explode.is_empty() || args.stats || args.freq.is_some() || args.pivot.is_some() || args.group_by.is_some() || !collapsed_rows.is_empty() || elided_rows.is_some();
+    let mut highlights = pipeline::highlight_cells(&args, &headers, &records, &column_types, report.as_ref(), rows_moved);
+
+    // Infer types from the raw values, before typed columns are reformatted
+    let inferred_types: Vec<InferredType> = (0..headers.len())
+        .map(|col_idx| inference::infer_column(&records, col_idx))
+        .collect();
+    let summaries = pipeline::format_cells(&args, &headers, &mut records, &column_types, &inferred_types, &mut highlights, groups, totals);
+    let mut columns = pipeline::ColumnSettings::resolve(&args, &headers, &column_types, &inferred_types);
+    let breaks: Vec<usize> = summaries.group_starts.iter()
+        .map(|group| group.row)
+        .chain(collapsed_rows.iter().map(|collapsed| collapsed.row))
+        .chain(elided_rows.map(|elided| elided.row))
+        .collect();
+    pipeline::fill_cells(&args, &headers, &mut records, &summaries.summary_rows, &breaks);
+
+    // CI logs have no terminal to measure, so they get a fixed width
+    let detected_width = match (args.width, ci_system) {
+        (Some(width), _) => usize::from(width),
+        (None, Some(_)) => ci::CI_WIDTH,
+        (None, None) => terminal::detect_width(args.verbose),
+    };
+
+    // Found again by name, since bar columns may have moved it
+    let mut sort_indicator = match (&args.sort, args.no_sort_indicator) {
+        (Some(sort_column), false) => column_names::position(&headers, sort_column)
+            .map(|column| SortIndicator { column, descending: args.reverse }),
+        _ => None,
+    };
 
-        if total_natural == 0 {
-            return vec![10; num_cols]; // Fallback
+    // In overflow fit mode, keep only the leading columns that fit and summarize the rest.
+    // The vertical view lists every field, so nothing needs hiding there.
+    let hidden_columns = match args.fit {
+        FitMode::Overflow if !args.vertical => {
+            pipeline::hide_overflowing_columns(&args, &mut headers, &mut records, &mut columns, &mut sort_indicator, detected_width, elided_count)
         }
+        FitMode::Overflow | FitMode::Shrink => Vec::new(),
+    };
 
-        // Strategy: Give columns their natural width if possible, wrap only when needed
-        let mut widths = vec![0; num_cols];
+    // Detect theme and check if colors should be enabled
+    let colors_enabled = colors_enabled(&args, ci_system);
+    let palette_slots = palette_slots(&args, &source_headers, &headers, &column_types, &inferred_types);
+
+    // Escape sequences in the input are colors too. Widths never counted them, so stripping
+    // them doesn't change the layout.
+    if !colors_enabled {
+        headers = headers.iter().map(strip_ansi).collect();
+        for cell in records.iter_mut().flatten().filter(|cell| cell.contains('\x1b')) {
+            *cell = strip_ansi(cell).into();
+        }
+    }
 
-        // Check if all columns fit naturally
-        if total_natural <= available_width {
-            // All columns fit, just give them their natural widths
-            for (i, &natural) in natural_widths.iter().enumerate() {
-                widths[i] = natural;
+    // Links are escape sequences too, and CI logs don't follow them
+    let links = if args.hyperlinks && colors_enabled && ci_system.is_none() {
+        let columns = match args.hyperlink_column.iter().map(|column| find_column(&headers, column, "--hyperlink-column")).collect::<Result<Vec<_>, _>>() {
+            Ok(columns) => columns,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
             }
-            // Distribute any remaining space to the last column
-            let used: usize = widths.iter().sum();
-            if used < available_width {
-                widths[num_cols - 1] += available_width - used;
+        };
+        hyperlinks::link_cells(&mut records, &columns, args.hyperlink_label)
+    } else {
+        Vec::new()
+    };
+
+    let measured_rows = pipeline::measured_rows(&args, records.len(), seed);
+    let options = RenderOptions {
+        fixed_col_widths: columns.fixed_col_widths,
+        measured_rows,
+        max_cell_lines: columns.max_cell_lines,
+        alignments: columns.alignments,
+        decimal_columns: columns.decimal_columns,
+        vertical: args.vertical,
+        highlights,
+        links,
+        palette_slots,
+        column_colors: columns.column_colors,
+        dimmed_columns: summaries.dimmed_columns,
+        groups: summaries.group_starts,
+        collapsed_rows,
+        elided_rows,
+        totals_row: summaries.totals_row,
+        subtotal_rows: summaries.subtotal_rows,
+        sort_indicator,
+        ..render_options(&args, args.wrap_mode(), colors_enabled, detected_width)
+    };
+    let table = Table { headers, records };
+    let renderer = Renderer::new(&table, &options).unwrap_or_else(|e| failure::exit_error(&e, &e));
+    let panels = if args.split_columns { plan_panels(&args, &table, &options, detected_width, elided_count) } else { Vec::new() };
+    if let Some(page_height) = args.page_height {
+        let overhead = paging::page_overhead(&renderer)?;
+        if usize::from(page_height) <= overhead {
+            failure::exit(Failure::Usage, format!("--page-height {} leaves no room for rows; the header and page number take {} lines", page_height, overhead));
+        }
+    }
+    let trailer = Trailer {
+        footer: args.footer.then_some(footer),
+        sparklines,
+        hidden_columns,
+        provenance,
+        colors_enabled,
+        width: detected_width,
+    };
+    show_table(&args, ci_system, &table, &options, &renderer, &panels, &trailer)
+}
+
+/// Runs `--estimate`: sizes up the file from its first rows instead of drawing it.
+fn run_estimate(args: &Args, sources: &[Option<&str>], given_formats: &[Option<InputFormat>]) -> Result<(), Box<dyn std::error::Error>> {
+    let [Some(path)] = sources else {
+        failure::exit(Failure::Usage, "--estimate needs a single file");
+    };
+    if !given_formats[0].is_none_or(InputFormat::is_csv) {
+        failure::exit(Failure::Usage, "--estimate only applies to CSV input");
+    }
+    if source::is_compressed(path).unwrap_or(false) {
+        failure::exit(Failure::Usage, "--estimate can't size up a compressed file from its size; decompress it first");
+    }
+    let estimate = std::fs::metadata(path)
+        .map_err(|e| format!("{}: {}", path, e))
+        .and_then(|metadata| {
+            let sample = source::read_input(Some(path), args.encoding, Some(estimate::SAMPLE_BYTES), true)?;
+            let options = args.csv_options();
+            estimate::estimate(skip_lines(sample.text(), args.skip_lines).0, metadata.len(), &options)
+        });
+    match estimate {
+        Ok(estimate) => Ok(estimate.print(&mut io::stdout(), path)?),
+        Err(e) => {
+            failure::exit(Failure::Other, e);
+        }
+    }
+}
+
+/// Exits when a flag for workbooks or databases is given without one, or one that needs text
+/// is given with one.
+fn check_format_flags(args: &Args, given_formats: &[Option<InputFormat>]) {
+    let reads_workbook = given_formats.iter().any(|format| matches!(format, Some(InputFormat::Xlsx)));
+    if args.sheet.is_some() && !reads_workbook {
+        failure::exit(Failure::Usage, "--sheet only applies to Excel workbooks");
+    }
+    let reads_database = given_formats.iter().any(|format| matches!(format, Some(InputFormat::Sqlite)));
+    if (args.table.is_some() || args.query.is_some()) && !reads_database {
+        failure::exit(Failure::Usage, "--table and --query only apply to SQLite databases");
+    }
+    if args.head_bytes.is_some() && (reads_workbook || reads_database) {
+        failure::exit(Failure::Usage, "--head-bytes doesn't apply to Excel workbooks or SQLite databases, which can't be read in part");
+    }
+}
+
+/// What became of the first input once it was opened as a stream.
+enum Streamed {
+    /// Its rows were printed as they were read, with this outcome
+    Printed(Result<(), Box<dyn std::error::Error>>),
+    /// It wasn't UTF-8 CSV, or the table needs every row first, so it was read in full
+    Read(String),
+}
+
+/// Prints the rows of the first input as they're read when nothing needs the whole table
+/// first. Input that turns out not to be UTF-8 CSV is read in full like any other. Returns
+/// `None` when the input isn't streamed at all.
+fn stream_first_input(args: &Args, sources: &[Option<&str>], given_formats: &[Option<InputFormat>], source_names: &[String]) -> Option<Streamed> {
+    match progressive_blocker(args, sources, given_formats) {
+        _ if args.no_progressive => return None,
+        Some(blocker) if args.progressive => {
+            failure::exit(Failure::Usage, format!("--progressive can't be used with {}", blocker));
+        }
+        Some(_) => return None,
+        None => {}
+    }
+    let mut stream = source::open_stream(sources[0]).unwrap_or_else(|e| {
+        failure::exit(Failure::Other, e);
+    });
+    // The first line tells what the input is, and names the columns
+    let mut start = Vec::new();
+    if let Err(e) = stream.read_until(b'\n', &mut start) {
+        failure::exit(Failure::Other, format!("{}: {}", source_names[0], e));
+    }
+    let format = given_formats[0].unwrap_or_else(|| InputFormat::sniff(&String::from_utf8_lossy(&start)));
+    let streamable = format.is_csv() && source::is_plain_utf8(&start, args.encoding);
+    if args.progressive && !streamable {
+        failure::exit(Failure::Usage, "--progressive only applies to UTF-8 CSV input");
+    }
+    if streamable && (args.progressive || matches!(args.wrap_mode(), WrapMode::None) || all_widths_fixed(args, &start)) {
+        return Some(Streamed::Printed(run_progressive(args, Cursor::new(start).chain(stream), &source_names[0])));
+    }
+    if let Err(e) = stream.read_to_end(&mut start) {
+        failure::exit(Failure::Other, format!("{}: {}", source_names[0], e));
+    }
+    match source::decode_input(&source_names[0], &start, args.encoding, true) {
+        Ok(input) => Some(Streamed::Read(input)),
+        Err(e) => {
+            failure::exit(Failure::Other, e);
+        }
+    }
+}
+
+/// Reads every input, the first from `streamed_input` when it has been read already.
+/// --head-bytes cuts CSV input at the end of a record, which may span lines. Workbooks and
+/// databases aren't text, so they are read straight into tables, given alongside empty inputs.
+fn read_inputs(args: &Args, sources: &[Option<&str>], given_formats: &[Option<InputFormat>], mut streamed_input: Option<String>) -> (Vec<source::Input>, Vec<Option<Table<'static>>>) {
+    let binary_tables = sources.iter()
+        .zip(given_formats)
+        .map(|(&source, format)| match format {
+            Some(InputFormat::Xlsx) => Some(read_workbook(source, args)),
+            Some(InputFormat::Sqlite) => Some(read_database(source, args)),
+            _ => None,
+        })
+        .collect();
+    let inputs = sources.iter()
+        .zip(given_formats)
+        .map(|(&source, format)| match format {
+            Some(format) if format.is_binary() => Ok(source::Input::whole(String::new())),
+            _ if streamed_input.is_some() => Ok(source::Input::whole(streamed_input.take().unwrap_or_default())),
+            _ if args.clipboard_in && source.is_none() => clipboard::paste().map(|text| source::Input::whole(clipboard::tabs_to_commas(text))),
+            format => source::read_input(source, args.encoding, args.head_bytes, format.is_none_or(InputFormat::is_csv)),
+        })
+        .map(|read| read.unwrap_or_else(|e| {
+            failure::exit(Failure::Other, e);
+        }))
+        .collect();
+    (inputs, binary_tables)
+}
+
+/// Runs --check on the first input. Without --lenient the report is all that's printed, so
+/// this exits after printing it; with --lenient it's returned, to be shown with the table.
+fn check_first_input(args: &Args, source: Option<&str>, source_name: &str, input: &str, offset: InputOffset, format: InputFormat) -> io::Result<Option<check::Report>> {
+    let report = match (args.check, format) {
+        (false, _) => return Ok(None),
+        (true, _) if args.annotate.is_some() && source.is_none() => {
+            failure::exit(Failure::Usage, "--annotate needs a file to point the annotations at, not standard input");
+        }
+        (true, format) if format.is_csv() => match check::check(input, &CsvOptions { offset, ..args.csv_options() }) {
+            Ok(report) => report,
+            Err(e) => {
+                failure::exit_error(&e, &e);
             }
-        } else {
-            // Not all columns fit, need to wrap
-            // Strategy: Give smaller columns their natural width, let bigger columns share remaining
-
-            // Sort column indices by their natural width
-            let mut sorted_cols: Vec<(usize, usize)> = natural_widths.iter()
-                .enumerate()
-                .map(|(i, &w)| (i, w))
-                .collect();
-            sorted_cols.sort_by_key(|&(_, w)| w);
-
-            let mut remaining = available_width;
-            let mut unallocated_cols = num_cols;
-
-            // Allocate to smallest columns first
-            for &(col_idx, natural) in &sorted_cols {
-                let avg_remaining = remaining / unallocated_cols;
-
-                if natural <= avg_remaining {
-                    // This column can have its natural width
-                    widths[col_idx] = natural;
-                    remaining = remaining.saturating_sub(natural);
-                } else {
-                    // This and remaining larger columns need to share
-                    break;
+        },
+        (true, _) => {
+            failure::exit(Failure::Usage, "--check only applies to CSV input");
+        }
+    };
+    if args.lenient {
+        return Ok(Some(report));
+    }
+    report.print(&mut io::stdout())?;
+    annotate_problems(args, &report, source_name, &mut io::stdout());
+    std::process::exit(if report.problems.is_empty() { 0 } else { 1 });
+}
+
+/// What errors about an input start with: its name once there are several inputs.
+fn error_prefix(source_name: &str, input_count: usize) -> String {
+    if input_count > 1 { format!("{}: ", source_name) } else { String::new() }
+}
+
+/// Applies --grep --raw to the source text of each CSV input, returning the text kept from
+/// each (`None` without --raw) and how many records were cut.
+fn grep_raw(args: &Args, inputs: &[&str], formats: &[InputFormat], source_names: &[String]) -> (Vec<Option<String>>, usize) {
+    let mut cut_rows = 0;
+    let grepped = inputs.iter()
+        .zip(formats)
+        .zip(source_names)
+        .map(|((input, format), source_name)| match (&args.grep, args.raw) {
+            (Some(regex), true) if format.is_csv() => match grep::filter_raw(input, regex, &args.csv_options(), args.verbose) {
+                Ok((kept, cut)) => {
+                    cut_rows += cut;
+                    Some(kept)
                 }
-                unallocated_cols -= 1;
+                Err(e) => {
+                    let message = format!("{}{}", error_prefix(source_name, inputs.len()), e);
+                    failure::exit_error(&e.into(), message);
+                }
+            },
+            (Some(_), true) => {
+                failure::exit(Failure::Usage, format!("{}--raw only applies to CSV input", error_prefix(source_name, inputs.len())));
             }
+            _ => None,
+        })
+        .collect();
+    (grepped, cut_rows)
+}
 
-            // Distribute remaining space to unallocated columns proportionally
-            if unallocated_cols > 0 {
-                let unallocated_natural: usize = sorted_cols.iter()
-                    .filter(|(i, _)| widths[*i] == 0)
-                    .map(|(_, w)| w)
-                    .sum();
-
-                let per_col_min = remaining / unallocated_cols;
-                let mut leftover = remaining;
-
-                for &(col_idx, natural) in &sorted_cols {
-                    if widths[col_idx] == 0 {
-                        unallocated_cols -= 1;
-                        if unallocated_cols == 0 {
-                            // Last column gets remainder
-                            widths[col_idx] = leftover.max(5);
-                        } else if unallocated_natural > 0 {
-                            // Proportional allocation
-                            let alloc = ((remaining * natural) / unallocated_natural).max(per_col_min).max(5);
-                            widths[col_idx] = alloc;
-                            leftover = leftover.saturating_sub(alloc);
-                        } else {
-                            widths[col_idx] = per_col_min.max(5);
-                            leftover = leftover.saturating_sub(per_col_min.max(5));
+/// Parses each input as its format and puts the tables together, returning the table and the
+/// number of malformed records --lenient left out. Workbooks and databases come from
+/// `binary_tables`, read already.
+fn parse_inputs<'a>(
+    args: &Args,
+    inputs: &[&'a str],
+    offsets: &[InputOffset],
+    formats: &[InputFormat],
+    source_names: &[String],
+    binary_tables: &mut [Option<Table<'static>>],
+    report: Option<&check::Report>,
+) -> Result<(Table<'a>, usize), Box<dyn std::error::Error>> {
+    let mut skipped_rows = 0;
+    let mut tables = Vec::new();
+    for ((((&input, &offset), &format), source_name), binary_table) in inputs.iter().zip(offsets).zip(formats).zip(source_names).zip(binary_tables) {
+        let prefix = error_prefix(source_name, inputs.len());
+        let csv_options = CsvOptions { offset, ..args.csv_options() };
+        let table = match format {
+            format if format.is_csv() && args.lenient => {
+                let (table, malformed) = Table::from_borrowed_str(input, &csv_options, true)?;
+                // A check report covers these records, so it replaces the warnings
+                match report {
+                    Some(report) => {
+                        report.print(&mut io::stderr())?;
+                        annotate_problems(args, report, source_name, &mut io::stderr());
+                    }
+                    None => {
+                        for record in &malformed {
+                            eprintln!("Warning: {}{}", prefix, record);
                         }
                     }
                 }
+                skipped_rows += malformed.iter().filter(|record| record.skipped()).count();
+                table
             }
-        }
+            InputFormat::Csv => {
+                match Table::from_borrowed_str(input, &csv_options, false) {
+                    Ok((table, _)) => table,
+                    Err(e) => {
+                        failure::exit_error(&e, format!("{}{}{}", prefix, e, lenient_hint(&e)));
+                    }
+                }
+            }
+            InputFormat::Accesslog => read_log(logs::parse_access_log(input)),
+            InputFormat::W3c => read_log(logs::parse_w3c_log(input)),
+            InputFormat::Json => read_structured(structured::parse_json(input), &prefix),
+            InputFormat::Ndjson => read_structured(structured::parse_ndjson(input), &prefix),
+            InputFormat::Yaml => read_structured(structured::parse_yaml(input), &prefix),
+            InputFormat::Xlsx | InputFormat::Sqlite => binary_table.take().unwrap_or_default(),
+        };
+        tables.push((source_name.clone(), table));
+    }
+    let mut table = match (tables.len(), &args.source_column) {
+        (1, None) => tables.remove(0).1,
+        _ => concat::concat(tables, args.source_column.as_deref()),
+    };
+    table.truncate_cells(args.max_cell_bytes);
+    Ok((table, skipped_rows))
+}
 
-        widths
+/// The render options every way of drawing a table takes from the command line: borders,
+/// colors, wrapping and the other flags that don't depend on the rows.
+fn render_options(args: &Args, wrap_mode: WrapMode, colors_enabled: bool, detected_width: usize) -> RenderOptions {
+    let (palette, stripe_background) = resolve_palette(args, colors_enabled);
+    RenderOptions {
+        wrap_mode,
+        show_line_numbers: args.line_numbers,
+        border: border_style(args),
+        padding: usize::from(args.padding),
+        max_col_width: args.max_col_width.map(usize::from),
+        min_col_width: args.min_col_width.map(usize::from),
+        max_row_height: args.max_row_height.map(usize::from),
+        vertical_alignment: args.valign,
+        theme: palette,
+        color_depth: color_depth(args),
+        row_colors: args.row_colors,
+        stripe_background,
+        null_display: args.null_display.clone(),
+        truncation_marker: args.truncation_marker.clone(),
+        ellipsis: args.ellipsis,
+        title: args.title.clone(),
+        title_alignment: args.title_align,
+        caption: args.caption.clone(),
+        // No-wrap mode lets rows run as long as they need
+        terminal_width: match wrap_mode {
+            WrapMode::None => usize::MAX,
+            _ => detected_width,
+        },
+        ..RenderOptions::default()
     }
 }
 
-enum BorderType {
-    Top,
-    HeaderSeparator,
-    Bottom,
+/// Splits the table into panels of the columns that fit side by side, for --split-columns.
+/// Without a key column, line numbers tie the rows of the panels together.
+fn plan_panels<'a>(args: &Args, table: &'a Table<'a>, options: &RenderOptions, detected_width: usize, elided_count: usize) -> Vec<(Table<'a>, RenderOptions)> {
+    let key = match args.panel_key.as_deref().map(|column| find_column(&table.headers, column, "--panel-key")).transpose() {
+        Ok(key) => key,
+        Err(e) => {
+            failure::exit(Failure::Usage, e);
+        }
+    };
+    let row_num_width = line_number_width(table.records.len() + elided_count, args.line_numbers || key.is_none());
+    let cap = args.max_col_width.map_or(detected_width, |max| detected_width.min(max as usize));
+    let panels = panels::plan(table, options, key, detected_width, cap, row_num_width);
+    let panel_options: Vec<RenderOptions> = panels.iter()
+        .enumerate()
+        .map(|(index, columns)| RenderOptions {
+            show_line_numbers: options.show_line_numbers || key.is_none(),
+            // The title belongs above the whole table
+            title: if index == 0 { options.title.clone() } else { None },
+            // And the caption below it
+            caption: if index + 1 == panels.len() { options.caption.clone() } else { None },
+            ..options.select_columns(columns)
+        })
+        .collect();
+    panels.into_iter().map(|columns| table.select_columns(&columns)).zip(panel_options).collect()
 }
 
-fn print_horizontal_border(col_widths: &[usize], row_num_width: usize, border_type: BorderType, show_line_numbers: bool) {
-    match border_type {
-        BorderType::Top => {
-            // Top border: just a line across the header
-            let row_area = if show_line_numbers { row_num_width + 3 } else { 0 };
-            // Each column contributes width + 3 (space + content + space + separator)
-            // but the last column has no separator, so subtract 1
-            let total_width: usize = row_area + col_widths.iter().map(|w| w + 3).sum::<usize>() - 1;
-            println!("{}", "─".repeat(total_width));
+/// Everything printed below the table.
+struct Trailer {
+    /// The --footer, if asked for
+    footer: Option<Footer>,
+    sparklines: Vec<Sparkline>,
+    hidden_columns: Vec<HiddenColumn>,
+    provenance: Option<Provenance>,
+    colors_enabled: bool,
+    width: usize,
+}
+
+impl Trailer {
+    fn print(&self, mut out: &mut dyn Write) -> io::Result<()> {
+        if let Some(footer) = &self.footer {
+            footer.print(&mut out)?;
         }
-        BorderType::HeaderSeparator => {
-            // Separator after header: ────┬────┬────
-            if show_line_numbers {
-                // Row number area is: "{:>width$}  │" = row_num_width + 3 chars total
-                // The ┬ replaces the │, so we need row_num_width + 2 dashes before it
-                print!("{}", "─".repeat(row_num_width + 2));
-                print!("┬");
-            }
-            for (i, &width) in col_widths.iter().enumerate() {
-                // Each column prints: " {text}{padding}" with optional " │" between
-                // The ┬ replaces the │, so we need width + 2 dashes before it
-                print!("{}", "─".repeat(width + 2));
-                // Print ┬ only between columns, not after the last one
-                if i < col_widths.len() - 1 {
-                    print!("┬");
-                }
-            }
-            println!();
+        print_sparklines(&mut out, &self.sparklines, self.width)?;
+        print_hidden_columns(&mut out, &self.hidden_columns, self.width)?;
+        match &self.provenance {
+            Some(provenance) => provenance.print(&mut out, self.colors_enabled),
+            None => Ok(()),
         }
-        BorderType::Bottom => {
-            // Bottom border (for no-wrap mode)
-            if show_line_numbers {
-                print!("{}", "─".repeat(row_num_width + 2));
-                print!("┴");
+    }
+}
+
+/// Prints the table and its trailer: into the --alt-screen pager, a CI log block, the
+/// clipboard as well, or the output, which goes through the --pager when there is one.
+fn show_table(
+    args: &Args,
+    ci_system: Option<CiSystem>,
+    table: &Table,
+    options: &RenderOptions,
+    renderer: &Renderer,
+    panels: &[(Table, RenderOptions)],
+    trailer: &Trailer,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let render_table = |mut out: &mut dyn Write| -> io::Result<()> {
+        if let Some(page_height) = args.page_height {
+            return paging::render_pages(out, renderer, table.records.len(), usize::from(page_height));
+        }
+        if panels.len() < 2 {
+            return renderer.render(&mut out);
+        }
+        for (index, (panel, panel_options)) in panels.iter().enumerate() {
+            if index > 0 {
+                writeln!(out)?;
             }
-            for (i, &width) in col_widths.iter().enumerate() {
-                print!("{}", "─".repeat(width + 2));
-                // Print ┴ only between columns, not after the last one
-                if i < col_widths.len() - 1 {
-                    print!("┴");
-                }
+            Renderer::new(panel, panel_options).map_err(render_error)?.render(&mut out)?;
+        }
+        Ok(())
+    };
+
+    // With --alt-screen the table is rendered into memory and paged with the header pinned.
+    // Like colors, this only applies when writing to a terminal.
+    if args.alt_screen && ci_system.is_none() && args.writes_to_terminal() {
+        let columns: Vec<String> = table.headers.iter().map(String::from).collect();
+        pager::show(&columns, |shown, expanded| {
+            let selected: Vec<usize> = (0..shown.len()).filter(|&col_idx| shown[col_idx]).collect();
+            let table = table.select_columns(&selected);
+            let options = RenderOptions { expanded_rows: expanded.to_vec(), ..options.select_columns(&selected) };
+            let renderer = Renderer::new(&table, &options).map_err(render_error)?;
+            let mut header = Vec::new();
+            let mut body = Vec::new();
+            renderer.render_header(&mut header)?;
+            let starts = renderer.render_rows_with_starts(&mut body)?;
+            trailer.print(&mut body)?;
+            Ok((String::from_utf8_lossy(&header).into_owned(), String::from_utf8_lossy(&body).into_owned(), starts))
+        })?;
+        return Ok(());
+    }
+
+    // Render the table, into the pager when there is one
+    let mut pager = spawn_pager(args);
+    let mut out = EncodedWriter::new(open_output(args, pager.as_mut()), args.output_encoding);
+    let result = match ci_system {
+        // Rendered up front, since the block markers depend on the table contents
+        Some(ci_system) => {
+            let mut rendered = Vec::new();
+            render_table(&mut rendered)?;
+            trailer.print(&mut rendered)?;
+            let plural = if table.records.len() == 1 { "" } else { "s" };
+            let title = args.title.clone().unwrap_or_else(|| format!("csvpretty: {} row{}", table.records.len(), plural));
+            if args.clipboard_out {
+                copy_to_clipboard(&rendered);
             }
-            println!();
+            ci_system.begin(&mut out, &title, &rendered)
+                .and_then(|()| out.write_all(&rendered))
+                .and_then(|()| ci_system.end(&mut out, &title, &rendered))
+        }
+        None if args.clipboard_out => {
+            let mut rendered = Vec::new();
+            render_table(&mut rendered)?;
+            trailer.print(&mut rendered)?;
+            copy_to_clipboard(&rendered);
+            out.write_all(&rendered)
+        }
+        None => render_table(&mut out).and_then(|()| trailer.print(&mut out)),
+    };
+    finish_output(args, out, pager, result)
+}
+
+/// Starts the pager for --pager, warning and carrying on without one when that fails.
+fn spawn_pager(args: &Args) -> Option<Child> {
+    match args.uses_pager().then(external_pager::spawn).transpose() {
+        Ok(pager) => pager,
+        Err(e) => {
+            eprintln!("Warning: {}", e);
+            None
         }
     }
 }
 
-/// Prints the header row with optional colors and bold formatting.
-/// Each column gets a color from the theme palette, cycling through colors.
-/// Headers are always bold when colors are enabled.
-fn print_header_row(headers: &[&str], col_widths: &[usize], row_num_width: usize, config: &RenderConfig) {
-    // Match the data row format: "{:>width$}  │" = row_num_width + 3 chars (if line numbers enabled)
-    if config.show_line_numbers {
-        print!("{}", " ".repeat(row_num_width + 3));
+/// Where the table is written: the --output file, the pager, or stdout.
+fn open_output(args: &Args, pager: Option<&mut Child>) -> Box<dyn Write> {
+    match (&args.output, pager.and_then(|pager| pager.stdin.take())) {
+        (Some(path), _) => match File::create(path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(e) => {
+                failure::exit(Failure::Other, format!("{}: {}", path.display(), e));
+            }
+        },
+        (None, Some(stdin)) => Box::new(stdin),
+        (None, None) => Box::new(io::stdout().lock()),
     }
-    for (i, &header) in headers.iter().enumerate() {
-        let width = col_widths[i];
-        let header_width = UnicodeWidthStr::width(header);
-        let padding = width.saturating_sub(header_width);
+}
 
-        // Apply color if theme is enabled (same color as data cells in this column)
-        if let Some(theme) = config.theme {
-            let (r, g, b) = get_column_color(i, theme);
-            print!(" {}{}", header.color(Rgb(r, g, b)).bold(), " ".repeat(padding));
-        } else {
-            print!(" {}{}", header, " ".repeat(padding));
-        }
+/// Flushes the table written with `result`, warns about characters Latin-1 couldn't show and
+/// waits for the pager to be closed.
+fn finish_output(args: &Args, mut out: EncodedWriter<Box<dyn Write>>, pager: Option<Child>, result: io::Result<()>) -> Result<(), Box<dyn std::error::Error>> {
+    let result = result.and_then(|()| out.flush());
+    if out.replaced > 0 {
+        let plural = if out.replaced == 1 { "" } else { "s" };
+        let hint = match args.table_style() {
+            TableStyle::Ascii | TableStyle::None => "",
+            _ => "; --style ascii draws borders Latin-1 can show",
+        };
+        eprintln!("Warning: {} character{} with no Latin-1 form written as ?{}", out.replaced, plural, hint);
+    }
+    // Closing the pipe lets the pager know the table is complete
+    drop(out);
+    if let Some(mut pager) = pager {
+        pager.wait()?;
+    }
+    match result {
+        // The reader went away (e.g. `csvpretty | head`), which isn't an error
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
 
-        // Print separator only between columns, not after the last one
-        if i < headers.len() - 1 {
-            print!(" │");
-        }
+/// An error from laying out or rendering a table as an I/O error, keeping the kind of a failed
+/// write, e.g. a closed pipe.
+fn render_error(e: csvpretty::Error) -> io::Error {
+    match e {
+        csvpretty::Error::Render(e) => e,
+        e => io::Error::other(e),
     }
-    println!();
 }
 
-/// Prints a data row with optional line numbers and colors.
-/// Handles multi-line cells by wrapping text and aligning all cells to the tallest cell.
-/// Each column uses the same color as its header (cycling through the palette).
-fn print_data_row(row_num: usize, record: &[String], col_widths: &[usize], row_num_width: usize, config: &RenderConfig) {
-    // Wrap each cell and determine max lines needed
-    let wrapped_cells: Vec<Vec<String>> = record.iter()
-        .zip(col_widths.iter())
-        .map(|(cell, &width)| wrap_text(cell, width, config.wrap_mode))
-        .collect();
+/// The hint added to a CSV parse error, pointing at --lenient for records with the wrong
+/// number of fields.
+fn lenient_hint(e: &csvpretty::Error) -> &'static str {
+    match e {
+        csvpretty::Error::Parse(ParseError { kind: ParseErrorKind::FieldCount { .. }, .. }) => " (use --lenient to pad or skip such records)",
+        _ => "",
+    }
+}
 
-    let max_lines = wrapped_cells.iter().map(|lines| lines.len()).max().unwrap_or(1);
-
-    // Print each line of the multi-line row
-    for line_idx in 0..max_lines {
-        if config.show_line_numbers {
-            if line_idx == 0 {
-                // First line: show row number
-                print!("{:>width$}  │", row_num, width = row_num_width);
-            } else {
-                // Subsequent lines: empty row number area for alignment
-                print!("{}  │", " ".repeat(row_num_width));
+/// Why the table can't be printed as its rows are read, naming the flag or input that needs
+/// every row first (or that --progressive doesn't support), if anything does.
+fn progressive_blocker(args: &Args, sources: &[Option<&str>], formats: &[Option<InputFormat>]) -> Option<&'static str> {
+    let blockers = [
+        (sources.len() > 1, "several input files"),
+        (sources[0].is_some_and(|source| source.contains("://")), "a URL"),
+        (formats[0].is_some_and(|format| !format.is_csv()), "input that isn't CSV"),
+        (args.clipboard_in, "--clipboard-in"),
+        (args.clipboard_out, "--clipboard-out"),
+        (args.format.ci_system().is_some(), "--format gha or teamcity"),
+        (args.source_column.is_some(), "--source-column"),
+        (args.head_bytes.is_some(), "--head-bytes"),
+        (args.skip_lines > 0, "--skip-lines"),
+        (args.lenient, "--lenient"),
+        (args.flexible, "--flexible"),
+        (args.check, "--check"),
+        (args.measure_rows.is_some(), "--measure-rows"),
+        (args.grep.is_some(), "--grep"),
+        (!args.where_equal.is_empty(), "--where"),
+        (args.since.is_some() || args.until.is_some(), "--since/--until"),
+        (args.unique || !args.unique_by.is_empty(), "--unique"),
+        (args.sample.is_some(), "--sample"),
+        (!args.extract.is_empty(), "--extract"),
+        (!args.split_column.is_empty() || !args.explode.is_empty(), "--split-column or --explode"),
+        (!args.lookup.is_empty(), "--lookup"),
+        (args.coerce_report, "--coerce-report"),
+        (args.stats, "--stats"),
+        (args.freq.is_some(), "--freq"),
+        (args.pivot.is_some(), "--pivot"),
+        (args.sort.is_some(), "--sort"),
+        (args.group_by.is_some(), "--group-by"),
+        (args.totals.is_some(), "--totals"),
+        (args.collapse_empty_rows, "--collapse-empty-rows"),
+        (!args.collapse.is_empty(), "--collapse"),
+        (!args.bar.is_empty(), "--bar"),
+        (!args.spark.is_empty(), "--spark"),
+        (!args.rule.is_empty(), "--rule"),
+        (args.dim_constant, "--dim-constant"),
+        (!args.icon_column.is_empty(), "--icon-column"),
+        (args.align_decimal, "--align-decimal"),
+        (matches!(args.fit, FitMode::Overflow), "--fit overflow"),
+        (args.split_columns, "--split-columns"),
+        (args.page_height.is_some(), "--page-height"),
+        (args.row_limit().is_some(), "--max-rows"),
+        (args.vertical, "--vertical"),
+        (args.alt_screen, "--alt-screen"),
+        (args.hyperlinks, "--hyperlinks"),
+        (args.footer, "--footer"),
+        (args.provenance, "--provenance"),
+    ];
+    blockers.into_iter().find_map(|(given, blocker)| given.then_some(blocker))
+}
+
+/// Whether every column has a --col-width, going by the header row at the `start` of the input.
+fn all_widths_fixed(args: &Args, start: &[u8]) -> bool {
+    if args.col_width.is_empty() {
+        return false;
+    }
+    let Ok((_, mut headers)) = progressive::Rows::new(start, &args.csv_options()) else {
+        return false;
+    };
+    rename_columns(&mut headers, args.headers.as_deref(), &args.rename).is_ok()
+        && resolve_fixed_widths(&headers, &args.col_width).is_ok_and(|widths| widths.iter().all(Option::is_some))
+}
+
+/// Prints the table as its rows are read from `reader`, the input called `name`. The columns are sized from the first
+/// --progressive-rows rows, which is also how many are read before anything is printed.
+fn run_progressive(args: &Args, reader: impl Read, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut rows, mut headers) = match progressive::Rows::new(reader, &args.csv_options()) {
+        Ok(started) if !started.1.is_empty() => started,
+        Ok(_) => {
+            failure::exit(Failure::EmptyInput, "No input provided");
+        }
+        Err(e) => {
+            failure::exit_error(&e, format!("{}{}", e, lenient_hint(&e)));
+        }
+    };
+    if let Err(e) = rename_columns(&mut headers, args.headers.as_deref(), &args.rename) {
+        failure::exit(Failure::Usage, e);
+    }
+    let preview_rows = args.progressive_rows as usize;
+    if args.verbose {
+        eprintln!("Verbose: printing rows as they're read, with columns sized from the first {}", preview_rows);
+    }
+
+    let mut preview = Vec::new();
+    for row in rows.by_ref().take(preview_rows) {
+        match row {
+            Ok(mut row) => {
+                row.iter_mut().for_each(|cell| truncate_cell(cell, args.max_cell_bytes));
+                preview.push(row);
+            }
+            Err(e) => {
+                failure::exit_error(&e, format!("{}{}", e, lenient_hint(&e)));
             }
         }
+    }
 
-        for (col_idx, lines) in wrapped_cells.iter().enumerate() {
-            let width = col_widths[col_idx];
-            let text = lines.get(line_idx).map(|s| s.as_str()).unwrap_or("");
-            let text_width = UnicodeWidthStr::width(text);
-            let padding = width.saturating_sub(text_width);
+    let column_types = match resolve_column_types(&headers, &args.column_type) {
+        Ok(column_types) => column_types,
+        Err(e) => {
+            failure::exit(Failure::Usage, e);
+        }
+    };
+    // Inferred from the raw values of the first rows, which later rows are taken to be like
+    let inferred_types: Vec<InferredType> = (0..headers.len())
+        .map(|col_idx| inference::infer_column(&preview, col_idx))
+        .collect();
+    let alignments = match resolve_alignments(&headers, &args.align, args.auto_align || !args.no_auto_align, &column_types, &inferred_types) {
+        Ok(alignments) => alignments,
+        Err(e) => {
+            failure::exit(Failure::Usage, e);
+        }
+    };
+    let fixed_col_widths = match resolve_fixed_widths(&headers, &args.col_width) {
+        Ok(widths) => widths,
+        Err(e) => {
+            failure::exit(Failure::Usage, e);
+        }
+    };
+    let max_cell_lines = match resolve_max_lines(&headers, &args.max_lines) {
+        Ok(max_lines) => max_lines,
+        Err(e) => {
+            failure::exit(Failure::Usage, e);
+        }
+    };
 
-            // Apply color if theme is enabled
-            if let Some(theme) = config.theme {
-                let (r, g, b) = get_column_color(col_idx, theme);
-                print!(" {}{}", text.color(Rgb(r, g, b)), " ".repeat(padding));
-            } else {
-                print!(" {}{}", text, " ".repeat(padding));
+    let colors_enabled = colors_enabled(args, None);
+    let palette_slots = palette_slots(args, &headers, &headers, &column_types, &inferred_types);
+    if !colors_enabled {
+        headers = headers.iter().map(strip_ansi).collect();
+    }
+    // Each row is shown the way the whole table's rows would be
+    let prepare = |row: &mut Vec<Cell>| {
+        apply_column_types(std::slice::from_mut(row), &column_types);
+        if args.show_whitespace {
+            whitespace::show_in_row(row);
+        }
+        for cell in row.iter_mut() {
+            if cell.trim().is_empty() && !args.null_display.is_empty() {
+                *cell = args.null_display.clone().into();
             }
-
-            // Print separator only between columns, not after the last one
-            if col_idx < wrapped_cells.len() - 1 {
-                print!(" │");
+            if !colors_enabled && cell.contains('\x1b') {
+                *cell = strip_ansi(cell).into();
             }
         }
-        println!();
+    };
+    preview.iter_mut().for_each(prepare);
+
+    let detected_width = args.width.map_or_else(|| terminal::detect_width(args.verbose), usize::from);
+    let options = RenderOptions {
+        fixed_col_widths,
+        max_cell_lines,
+        alignments,
+        palette_slots,
+        ..render_options(args, args.wrap_mode(), colors_enabled, detected_width)
+    };
+    let mut renderer = ProgressiveRenderer::new(&headers, &preview, &options).unwrap_or_else(|e| failure::exit_error(&e, &e));
+
+    let mut pager = spawn_pager(args);
+    let mut out = EncodedWriter::new(open_output(args, pager.as_mut()), args.output_encoding);
+    let mut parse_error = None;
+    let mut render = || -> io::Result<()> {
+        renderer.render_header(&mut out)?;
+        for row in &preview {
+            renderer.render_row(&mut out, row).map_err(render_error)?;
+        }
+        for row in rows.by_ref() {
+            let mut row = match row {
+                Ok(row) => row,
+                Err(e) => {
+                    parse_error = Some(e);
+                    break;
+                }
+            };
+            row.iter_mut().for_each(|cell| truncate_cell(cell, args.max_cell_bytes));
+            prepare(&mut row);
+            renderer.render_row(&mut out, &row).map_err(render_error)?;
+        }
+        renderer.finish(&mut out)
+    };
+    let result = render();
+    if rows.invalid_utf8 {
+        eprintln!("Warning: {} contains bytes that aren't valid UTF-8; they are shown as �", name);
+    }
+    if let Some(e) = parse_error {
+        // The rows before the one that failed have been printed
+        let _ = out.flush();
+        failure::exit_error(&e, format!("{}{}", e, lenient_hint(&e)));
     }
+    finish_output(args, out, pager, result)
 }
 
-fn wrap_text(text: &str, max_width: usize, wrap_mode: WrapMode) -> Vec<String> {
-    if text.is_empty() {
-        return vec![String::new()];
+/// Copies the rendered table to the clipboard without colors, warning when that fails.
+fn copy_to_clipboard(rendered: &[u8]) {
+    if let Err(e) = clipboard::copy(&strip_ansi(&String::from_utf8_lossy(rendered))) {
+        eprintln!("Warning: {}", e);
     }
+}
 
-    match wrap_mode {
-        WrapMode::None => {
-            vec![text.to_string()]
-        }
-        WrapMode::Word => {
-            wrap_text_word(text, max_width)
+/// Annotates the checked file with the problems in `report` when --annotate asks to, writing
+/// to `out` along with the report.
+fn annotate_problems(args: &Args, report: &check::Report, path: &str, out: &mut impl Write) {
+    let written = match args.annotate {
+        Some(annotations) => annotate::annotate(annotations, report, path, out),
+        None => Ok(()),
+    };
+    if let Err(e) = written {
+        failure::exit(Failure::Other, format!("writing the --annotate output: {}", e));
+    }
+}
+
+/// Runs `csvpretty gen-examples`, 80 columns wide unless --width says otherwise. Fails when a
+/// fixture couldn't be rendered.
+fn run_gen_examples(args: &Args, gen_args: &GenExamplesArgs) -> Result<(), Box<dyn std::error::Error>> {
+    match gen_examples::generate(&gen_args.fixtures, &gen_args.out, args.width.unwrap_or(80), args.verbose) {
+        Ok(generated) => {
+            eprintln!("Wrote {} examples to {}", generated.written, gen_args.out.display());
+            if generated.skipped > 0 {
+                let plural = if generated.skipped == 1 { "" } else { "s" };
+                failure::exit(Failure::Other, format!("{} fixture{} couldn't be rendered", generated.skipped, plural));
+            }
+            Ok(())
         }
-        WrapMode::Char => {
-            wrap_text_char(text, max_width)
+        Err(e) => {
+            failure::exit(Failure::Other, format!("{}: {}", gen_args.out.display(), e));
         }
     }
 }
 
-fn wrap_text_word(text: &str, max_width: usize) -> Vec<String> {
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
-    let mut current_width = 0;
-
-    for word in text.split_whitespace() {
-        let word_width = UnicodeWidthStr::width(word);
+/// Runs `csvpretty diff`: renders the two files as one table and exits with status 1 when they differ.
+fn run_diff(args: &Args, diff_args: &DiffArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let read_input = |path: &str| {
+        source::read_input(Some(path), args.encoding, None, true).unwrap_or_else(|e| {
+            failure::exit(Failure::Other, e);
+        })
+    };
+    let read_table = |text, path: &str| {
+        Table::from_borrowed_str(text, &args.csv_options(), false).map(|(table, _)| table).unwrap_or_else(|e| {
+            failure::exit_error(&e, format!("{}: {}", path, e));
+        })
+    };
+    let (old_input, new_input) = (read_input(&diff_args.old), read_input(&diff_args.new));
+    let old = read_table(old_input.text(), &diff_args.old);
+    let new = read_table(new_input.text(), &diff_args.new);
+    let key = match &diff_args.key {
+        Some(key) => match (find_column(&old.headers, key, "--key"), find_column(&new.headers, key, "--key")) {
+            (Ok(old_key), Ok(new_key)) => Some((old_key, new_key)),
+            (Err(e), _) | (_, Err(e)) => {
+                failure::exit(Failure::Usage, e);
+            }
+        },
+        None => None,
+    };
+    let diff = diff::diff(&old, &new, key);
+
+    let colors_enabled = colors_enabled(args, None);
+    let detected_width = args.width.map_or_else(|| terminal::detect_width(args.verbose), usize::from);
+    // The diff is always printed straight to stdout, never through a pager
+    let wrap_mode = args.wrap.unwrap_or(WrapMode::Word);
+    let options = RenderOptions {
+        highlights: diff.highlights,
+        ..render_options(args, wrap_mode, colors_enabled, detected_width)
+    };
+    let table = Table { headers: diff.headers, records: diff.records };
+    let mut out = io::stdout().lock();
+    let renderer = Renderer::new(&table, &options).unwrap_or_else(|e| failure::exit_error(&e, &e));
+    let result = renderer.render(&mut out).and_then(|()| diff.summary.print(&mut out));
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        result => result?,
+    }
+    if diff.summary.has_differences() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-        if current_width == 0 {
-            // First word on line
-            if word_width <= max_width {
-                current_line = word.to_string();
-                current_width = word_width;
-            } else {
-                // Word is too long, split it character by character
-                for line in wrap_text_char(word, max_width) {
-                    lines.push(line);
-                }
+/// Runs `--dashboard`: aggregates the rows of stdin or a file per group as they arrive, drawing
+/// the table again in place on a terminal.
+fn run_dashboard(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let reader: Box<dyn Read + Send> = match args.files.as_slice() {
+        [] => Box::new(io::stdin()),
+        [file] if file == "-" => Box::new(io::stdin()),
+        [path] => match File::open(path) {
+            Ok(file) if args.follow => Box::new(dashboard::Follow(file)),
+            Ok(file) => Box::new(file),
+            Err(e) => {
+                failure::exit(Failure::Other, format!("{}: {}", path, e));
             }
-        } else if current_width + 1 + word_width <= max_width {
-            // Add word to current line
-            current_line.push(' ');
-            current_line.push_str(word);
-            current_width += 1 + word_width;
-        } else {
-            // Start new line
-            lines.push(current_line);
-            if word_width <= max_width {
-                current_line = word.to_string();
-                current_width = word_width;
-            } else {
-                // Word is too long, split it
-                current_line = String::new();
-                current_width = 0;
-                for line in wrap_text_char(word, max_width) {
-                    lines.push(line);
-                }
+        },
+        _ => {
+            failure::exit(Failure::Usage, "--dashboard reads a single file");
+        }
+    };
+    if args.follow && args.files.iter().all(|file| file == "-") {
+        failure::exit(Failure::Usage, "--follow needs a file; stdin is read as it arrives anyway");
+    }
+
+    let live = args.writes_to_terminal();
+    let colors_enabled = colors_enabled(args, None);
+    let detected_width = args.width.map_or_else(|| terminal::detect_width(args.verbose), usize::from);
+    let options = RenderOptions {
+        // The group's value, then numbers
+        alignments: std::iter::once(Alignment::Left).chain(args.agg.iter().map(|_| Alignment::Right)).collect(),
+        ..render_options(args, args.wrap_mode(), colors_enabled, detected_width)
+    };
+    let out: Box<dyn Write> = match &args.output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(e) => {
+                failure::exit(Failure::Other, format!("{}: {}", path.display(), e));
             }
+        },
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut out = EncodedWriter::new(out, args.output_encoding);
+    let mut screen = dashboard::Screen::default();
+    let group_by = args.group_by.as_deref().unwrap_or_default();
+    let result = dashboard::run(reader, &args.csv_options(), group_by, &args.agg, live, |table, rows| {
+        let plural = if rows == 1 { "" } else { "s" };
+        let options = RenderOptions {
+            caption: Some(args.caption.clone().unwrap_or_else(|| format!("{} row{} read", rows, plural))),
+            ..options.clone()
+        };
+        let mut frame = Vec::new();
+        Renderer::new(table, &options).map_err(render_error)?.render(&mut frame)?;
+        screen.show(&mut out, &frame)
+    });
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) if e.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) => Ok(()),
+        Err(e) => {
+            failure::exit(Failure::Other, e);
         }
     }
+}
 
-    if !current_line.is_empty() {
-        lines.push(current_line);
+/// Whether to use colors. In auto mode colors are only used when writing to a terminal (or a
+/// CI log that supports them) and NO_COLOR is unset; otherwise piping into a file or another
+/// tool would capture raw escape sequences.
+fn colors_enabled(args: &Args, ci_system: Option<CiSystem>) -> bool {
+    match (args.no_color, args.color) {
+        (true, _) | (_, ColorChoice::Never) => false,
+        (_, ColorChoice::Always) => true,
+        (_, ColorChoice::Auto) => {
+            // CI log viewers render colors even though stdout is a pipe. Files never get them.
+            let supported = match ci_system {
+                _ if args.output.is_some() => false,
+                Some(ci_system) => ci_system.is_running(),
+                None => io::stdout().is_terminal(),
+            };
+            supported && std::env::var("NO_COLOR").is_err()
+        }
     }
+}
 
-    if lines.is_empty() {
-        lines.push(String::new());
+/// The --color-depth colors are converted to. Legacy Windows consoles get the 16 basic colors.
+fn color_depth(args: &Args) -> ColorDepth {
+    match args.color_depth {
+        ColorDepth::Auto if terminal::is_legacy_console(args.verbose) => ColorDepth::Ansi16,
+        depth => depth.resolve(),
     }
+}
 
-    lines
+/// The column colors and stripe background for the terminal's theme. The theme query is
+/// skipped along with colors, since it also talks to the terminal.
+fn resolve_palette(args: &Args, colors_enabled: bool) -> (Option<Palette>, Option<(u8, u8, u8)>) {
+    if !colors_enabled {
+        return (None, None);
+    }
+    let spec = match &args.palette {
+        Some(spec) => spec.clone(),
+        None => match std::env::var(palette::PALETTE_ENV_VAR) {
+            Ok(value) => PaletteSpec::parse(&value).unwrap_or_else(|e| {
+                failure::exit(Failure::Usage, format!("invalid {}: {}", palette::PALETTE_ENV_VAR, e));
+            }),
+            Err(_) => PaletteSpec::default(),
+        },
+    };
+    let choice = match args.theme {
+        Some(choice) => choice,
+        None => match std::env::var(terminal::THEME_ENV_VAR) {
+            Ok(value) => <ThemeChoice as clap::ValueEnum>::from_str(value.trim(), true).unwrap_or_else(|_| {
+                failure::exit(Failure::Usage, format!("invalid {}: '{}' (expected auto, dark or light)", terminal::THEME_ENV_VAR, value));
+            }),
+            Err(_) => ThemeChoice::Auto,
+        },
+    };
+    let theme_mode = terminal::resolve_theme(choice, args.output.is_some(), args.verbose);
+    (Some(spec.for_theme(theme_mode)), Some(palette::stripe_background(theme_mode)))
 }
 
-fn wrap_text_char(text: &str, max_width: usize) -> Vec<String> {
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
-    let mut current_width = 0;
+/// The flags that dropped input or rows, as given (with the seed --sample used), for the provenance line.
+fn active_filters(args: &Args, seed: u64) -> Vec<String> {
+    let mut filters = Vec::new();
+    if args.skip_lines > 0 {
+        filters.push(format!("--skip-lines {}", args.skip_lines));
+    }
+    if let Some(head_bytes) = args.head_bytes {
+        filters.push(format!("--head-bytes {}", head_bytes));
+    }
+    if let Some(regex) = &args.grep {
+        filters.push(format!("--grep '{}'{}", regex, if args.raw { " --raw" } else { "" }));
+    }
+    for (column, value) in &args.where_equal {
+        filters.push(format!("--where '{}={}'", column, value));
+    }
+    if args.where_any {
+        filters.push("--where-any".to_string());
+    }
+    if let Some(time_column) = args.time_column.as_ref().filter(|_| args.since.is_some() || args.until.is_some()) {
+        filters.push(format!("--time-column {}", time_column));
+        if let Some(since) = args.since {
+            filters.push(format!("--since '{}'", since));
+        }
+        if let Some(until) = args.until {
+            filters.push(format!("--until '{}'", until));
+        }
+    }
+    if args.unique {
+        filters.push("--unique".to_string());
+    }
+    if !args.unique_by.is_empty() {
+        filters.push(format!("--unique-by {}", args.unique_by.join(",")));
+    }
+    if let Some(size) = args.sample {
+        // The seed makes the sample reproducible, even when it was picked at random
+        filters.push(format!("--sample {} --seed {}", size, seed));
+    }
+    if args.lenient {
+        filters.push("--lenient".to_string());
+    }
+    filters
+}
 
-    for ch in text.chars() {
-        let ch_width = UnicodeWidthStr::width(ch.to_string().as_str());
+/// A column left out of the table by overflow fit mode, with a sample value for the summary.
+struct HiddenColumn {
+    name: String,
+    example: String,
+}
 
-        if current_width + ch_width <= max_width {
-            current_line.push(ch);
-            current_width += ch_width;
-        } else {
-            if !current_line.is_empty() {
-                lines.push(current_line);
-            }
-            current_line = ch.to_string();
-            current_width = ch_width;
+/// Collects the columns from `visible` onwards, each with its first non-empty value as an example.
+fn collect_hidden_columns(headers: &csv::StringRecord, records: &[Vec<Cell>], visible: usize) -> Vec<HiddenColumn> {
+    headers.iter()
+        .enumerate()
+        .skip(visible)
+        .map(|(col_idx, name)| HiddenColumn {
+            name: name.to_string(),
+            example: records.iter()
+                .filter_map(|row| row.get(col_idx))
+                .find(|value| !value.trim().is_empty())
+                .map(|value| value.to_string())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Size of the input data, printed below the table with `--footer`.
+struct Footer {
+    rows: usize,
+    columns: usize,
+    /// Rows dropped by `--where` and `--since`/`--until`.
+    filtered_rows: usize,
+    /// Rows dropped by `--unique`/`--unique-by`.
+    duplicate_rows: usize,
+    /// Rows `--sample` picked from.
+    sampled_from: Option<usize>,
+    /// Malformed records left out by `--lenient`.
+    skipped_rows: usize,
+    /// Where `--head-bytes` cut the input, dropping the rows after it. With several inputs,
+    /// the first one cut.
+    input_cut_at: Option<usize>,
+}
+
+impl Footer {
+    /// Prints e.g. `42 rows × 7 columns (3 rows filtered out, 2 duplicate rows dropped, 1 malformed row skipped)`,
+    /// `10 rows × 7 columns (sample of 5000 rows)` or `120 rows × 7 columns (input cut at 65536 bytes)`.
+    fn print(&self, out: &mut impl Write) -> io::Result<()> {
+        let plural = |count: usize, noun: &str| format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" });
+        write!(out, "{} × {}", plural(self.rows, "row"), plural(self.columns, "column"))?;
+        let mut notes = Vec::new();
+        if self.filtered_rows > 0 {
+            notes.push(format!("{} filtered out", plural(self.filtered_rows, "row")));
         }
+        if self.duplicate_rows > 0 {
+            notes.push(format!("{} dropped", plural(self.duplicate_rows, "duplicate row")));
+        }
+        if let Some(sampled_from) = self.sampled_from {
+            notes.push(format!("sample of {}", plural(sampled_from, "row")));
+        }
+        if self.skipped_rows > 0 {
+            notes.push(format!("{} skipped", plural(self.skipped_rows, "malformed row")));
+        }
+        if let Some(cut_at) = self.input_cut_at {
+            notes.push(format!("input cut at {} bytes", cut_at));
+        }
+        if !notes.is_empty() {
+            write!(out, " ({})", notes.join(", "))?;
+        }
+        writeln!(out)
     }
+}
 
-    if !current_line.is_empty() {
-        lines.push(current_line);
+/// Prints the `--spark` sparklines below the table, each after its column name and followed by
+/// the range of its values.
+fn print_sparklines(out: &mut impl Write, sparklines: &[Sparkline], terminal_width: usize) -> io::Result<()> {
+    let name_width = sparklines.iter().map(|sparkline| display_width(&sparkline.column)).max().unwrap_or(0);
+    for sparkline in sparklines {
+        let padding = name_width - display_width(&sparkline.column);
+        let range = match sparkline.range() {
+            Some((min, max)) => format!("{} – {}", min, max),
+            None => "no numbers".to_string(),
+        };
+        // "name  ▁▃█  min – max": leave room for the name, the range and the gaps
+        let spark_width = terminal_width.saturating_sub(name_width + display_width(&range) + 4).max(1);
+        writeln!(out, "{}{}  {}  {}", sparkline.column, " ".repeat(padding), sparkline.draw(spark_width), range)?;
     }
+    Ok(())
+}
 
-    if lines.is_empty() {
-        lines.push(String::new());
+/// Prints a summary of the columns hidden by overflow fit mode below the table.
+fn print_hidden_columns(out: &mut impl Write, hidden: &[HiddenColumn], terminal_width: usize) -> io::Result<()> {
+    if hidden.is_empty() {
+        return Ok(());
     }
 
-    lines
+    let plural = if hidden.len() == 1 { "" } else { "s" };
+    writeln!(out, "{} hidden column{}:", hidden.len(), plural)?;
+
+    let name_width = hidden.iter().map(|c| display_width(&c.name)).max().unwrap_or(0);
+    for column in hidden {
+        let padding = name_width - display_width(&column.name);
+        // "  name  example": leave room for the indent, the name and the gap
+        let example_width = terminal_width.saturating_sub(name_width + 4);
+        writeln!(out, "  {}{}  {}", column.name, " ".repeat(padding), truncate_to_width(&column.example, example_width))?;
+    }
+    Ok(())
 }
+
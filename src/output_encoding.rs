@@ -0,0 +1,80 @@
+//! Writing the table in another encoding (`--output-encoding`).
+//!
+//! Excel and other Windows programs take text without a byte order mark to be in the local
+//! 8-bit code page, so accented letters come out garbled unless the file starts with a UTF-8
+//! byte order mark or is written in Latin-1.
+
+use std::io::{self, Write};
+
+/// The UTF-8 encoding of U+FEFF.
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Encodings accepted by `--output-encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputEncoding {
+    /// UTF-8
+    #[default]
+    #[value(name = "utf-8", alias = "utf8")]
+    Utf8,
+    /// UTF-8 starting with a byte order mark, for Excel on Windows
+    #[value(name = "utf-8-bom", alias = "utf8-bom")]
+    Utf8Bom,
+    /// ISO 8859-1; characters it has no place for are written as ?
+    #[value(name = "latin-1", alias = "latin1")]
+    Latin1,
+}
+
+/// Writes the UTF-8 text written to it in an [`OutputEncoding`].
+pub struct EncodedWriter<W: Write> {
+    inner: W,
+    encoding: OutputEncoding,
+    /// Whether the byte order mark is still to be written
+    bom_pending: bool,
+    /// The start of a character cut off at the end of the last write
+    partial: Vec<u8>,
+    /// Characters written as ? because Latin-1 has no place for them
+    pub replaced: usize,
+}
+
+impl<W: Write> EncodedWriter<W> {
+    pub fn new(inner: W, encoding: OutputEncoding) -> Self {
+        EncodedWriter { inner, encoding, bom_pending: encoding == OutputEncoding::Utf8Bom, partial: Vec::new(), replaced: 0 }
+    }
+
+    /// Writes `buf` in Latin-1, holding back a character cut off at its end until the rest of
+    /// it arrives.
+    fn write_latin1(&mut self, buf: &[u8]) -> io::Result<()> {
+        let mut bytes = std::mem::take(&mut self.partial);
+        bytes.extend_from_slice(buf);
+        let end = match std::str::from_utf8(&bytes) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => bytes.len(),
+        };
+        self.partial = bytes[end..].to_vec();
+        let encoded: Vec<u8> = String::from_utf8_lossy(&bytes[..end])
+            .chars()
+            .map(|c| u8::try_from(c).unwrap_or_else(|_| {
+                self.replaced += 1;
+                b'?'
+            }))
+            .collect();
+        self.inner.write_all(&encoded)
+    }
+}
+
+impl<W: Write> Write for EncodedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.bom_pending {
+            self.inner.write_all(BOM)?;
+            self.bom_pending = false;
+        }
+        match self.encoding {
+            OutputEncoding::Utf8 | OutputEncoding::Utf8Bom => self.inner.write(buf),
+            OutputEncoding::Latin1 => self.write_latin1(buf).map(|()| buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,148 @@
+//! Scrolling the rendered table in the terminal's alternate screen (`--alt-screen`).
+//!
+//! The table is rendered up front, and again when the column picker shows or hides columns or
+//! a row cut short by --max-row-height is shown in full; the header lines stay pinned at the top of the screen while the body scrolls underneath.
+//! Keys are read from the terminal itself, since stdin usually carries the CSV input.
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Print, Stylize};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, queue};
+use crate::terminal_guard::TerminalGuard;
+use csvpretty::{display_width, truncate_to_width};
+use std::io::{self, Write};
+
+/// The header, the body, and the body line each row starts on.
+pub type Rendered = (String, String, Vec<usize>);
+
+/// Shows the table pinned above a scrollable body until the user quits, then restores the
+/// screen as it was. `render` renders the header and the body with only the columns whose
+/// entry is true, and the given rows in full; it runs again whenever either changes.
+///
+/// Keys: j/k or arrows scroll by a line, space/b or PgDn/PgUp by a page, g/G or Home/End
+/// jump to the top/bottom, c opens the column picker, o shows the row at the top in full or
+/// cuts it short again, and q, Esc or Ctrl-C quit.
+pub fn show(columns: &[String], render: impl FnMut(&[bool], &[usize]) -> io::Result<Rendered>) -> io::Result<()> {
+    let mut out = io::stdout();
+    let guard = TerminalGuard::enter()?;
+    let result = run(&mut out, columns, render);
+    // Restore the screen even if drawing or reading keys failed
+    guard.leave()?;
+    result
+}
+
+fn run(out: &mut impl Write, columns: &[String], mut render: impl FnMut(&[bool], &[usize]) -> io::Result<Rendered>) -> io::Result<()> {
+    let mut shown = vec![true; columns.len()];
+    let mut expanded: Vec<usize> = Vec::new();
+    let (mut header, mut body, mut starts) = render(&shown, &expanded)?;
+    let mut top = 0;
+    // The column under the picker's cursor, while the picker is open
+    let mut picker: Option<usize> = None;
+    loop {
+        let (width, rows) = terminal::size()?;
+        let header_lines: Vec<&str> = header.lines().collect();
+        let body_lines: Vec<&str> = body.lines().collect();
+        // Always leave room for at least one body line, even if the header fills the screen
+        let page = (rows as usize).saturating_sub(header_lines.len()).max(1);
+        let last_top = body_lines.len().saturating_sub(page);
+        top = top.min(last_top);
+        draw(out, &header_lines, &body_lines[top..body_lines.len().min(top + page)])?;
+        if let Some(selected) = picker {
+            draw_picker(out, columns, &shown, selected, usize::from(width), usize::from(rows))?;
+        }
+        out.flush()?;
+
+        // Resizes and other events just redraw
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(());
+        }
+
+        if let Some(selected) = &mut picker {
+            match key.code {
+                KeyCode::Char('c') | KeyCode::Char('q') | KeyCode::Esc => picker = None,
+                KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(columns.len().saturating_sub(1)),
+                KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+                KeyCode::Home | KeyCode::Char('g') => *selected = 0,
+                KeyCode::End | KeyCode::Char('G') => *selected = columns.len().saturating_sub(1),
+                // The last shown column stays, or there would be no table to show
+                KeyCode::Char(' ') | KeyCode::Enter if !shown[*selected] || shown.iter().filter(|&&on| on).count() > 1 => {
+                    shown[*selected] = !shown[*selected];
+                    (header, body, starts) = render(&shown, &expanded)?;
+                }
+                KeyCode::Char('a') => {
+                    shown.fill(true);
+                    (header, body, starts) = render(&shown, &expanded)?;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if !columns.is_empty() => picker = Some(0),
+            // The row at the top may have started above the screen
+            KeyCode::Char('o') if !starts.is_empty() => {
+                let row = starts.partition_point(|&start| start <= top).saturating_sub(1);
+                match expanded.iter().position(|&expanded_row| expanded_row == row) {
+                    Some(index) => { expanded.remove(index); }
+                    None => expanded.push(row),
+                }
+                (header, body, starts) = render(&shown, &expanded)?;
+                top = starts[row];
+            }
+            KeyCode::Down | KeyCode::Enter | KeyCode::Char('j') => top += 1,
+            KeyCode::Up | KeyCode::Char('k') => top = top.saturating_sub(1),
+            KeyCode::PageDown | KeyCode::Char(' ') | KeyCode::Char('f') => top += page,
+            KeyCode::PageUp | KeyCode::Char('b') => top = top.saturating_sub(page),
+            KeyCode::Home | KeyCode::Char('g') => top = 0,
+            KeyCode::End | KeyCode::Char('G') => top = last_top,
+            _ => {}
+        }
+    }
+}
+
+fn draw(out: &mut impl Write, header: &[&str], body: &[&str]) -> io::Result<()> {
+    queue!(out, terminal::Clear(ClearType::All))?;
+    for (row, line) in header.iter().chain(body).enumerate() {
+        queue!(out, cursor::MoveTo(0, row as u16), Print(line))?;
+    }
+    Ok(())
+}
+
+/// Draws the column picker over the top left of the table: a checkbox per column, with the
+/// one under the cursor in reverse video. Long lists scroll to keep the cursor in view.
+fn draw_picker(out: &mut impl Write, columns: &[String], shown: &[bool], selected: usize, width: usize, rows: usize) -> io::Result<()> {
+    const HINT: &str = "space: show/hide  a: all  c: close";
+    let inner_width = columns.iter()
+        .map(|name| display_width(name) + 4)
+        .chain([display_width(HINT)])
+        .max()
+        .unwrap_or(0)
+        .min(width.saturating_sub(4));
+    // Borders, hint and a blank line below take 4 rows
+    let visible = rows.saturating_sub(4).max(1).min(columns.len());
+    let first = selected.saturating_sub(visible - 1);
+
+    let mut lines = vec![format!("┌ Columns {}┐", "─".repeat((inner_width + 2).saturating_sub(9)))];
+    for (col_idx, name) in columns.iter().enumerate().skip(first).take(visible) {
+        let checkbox = if shown[col_idx] { "[x]" } else { "[ ]" };
+        let label = truncate_to_width(&format!("{} {}", checkbox, name), inner_width);
+        let padded = format!(" {}{} ", label, " ".repeat(inner_width - display_width(&label)));
+        let padded = if col_idx == selected { padded.reverse().to_string() } else { padded };
+        lines.push(format!("│{}│", padded));
+    }
+    let hint = truncate_to_width(HINT, inner_width);
+    let hint_padding = " ".repeat(inner_width - display_width(&hint));
+    lines.push(format!("│ {}{} │", hint.dim(), hint_padding));
+    lines.push(format!("└{}┘", "─".repeat(inner_width + 2)));
+
+    for (row, line) in lines.iter().enumerate() {
+        queue!(out, cursor::MoveTo(1, row as u16 + 1), Print(line))?;
+    }
+    Ok(())
+}
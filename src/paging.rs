@@ -0,0 +1,52 @@
+//! Splitting the table into pages of a fixed height (`--page-height`).
+//!
+//! Each page repeats the header and ends with a `Page X/Y` line, so the pages can be printed or
+//! pasted into places that only take so many lines. Rows are never split across pages.
+
+use csvpretty::Renderer;
+use std::io::{self, Write};
+
+/// Lines taken by the `Page X/Y` line at the end of each page.
+const PAGE_FOOTER_LINES: usize = 1;
+
+/// The header, bottom border and page footer lines that every page has.
+pub fn page_overhead(renderer: &Renderer) -> io::Result<usize> {
+    let mut frame = Vec::new();
+    renderer.render_header(&mut frame)?;
+    renderer.render_bottom(&mut frame)?;
+    Ok(line_count(&frame) + PAGE_FOOTER_LINES)
+}
+
+/// Renders `row_count` rows in pages of at most `page_height` lines. A row taller than a page
+/// gets a page of its own, which is then taller.
+pub fn render_pages(mut out: &mut dyn Write, renderer: &Renderer, row_count: usize, page_height: usize) -> io::Result<()> {
+    let room = page_height.saturating_sub(page_overhead(renderer)?);
+    let mut pages: Vec<Vec<u8>> = Vec::new();
+    let mut page = Vec::new();
+    let mut page_lines = 0;
+    for index in 0..row_count {
+        let mut row = Vec::new();
+        renderer.render_row(&mut row, index)?;
+        let row_lines = line_count(&row);
+        if page_lines > 0 && page_lines + row_lines > room {
+            pages.push(std::mem::take(&mut page));
+            page_lines = 0;
+        }
+        page.extend(row);
+        page_lines += row_lines;
+    }
+    pages.push(page);
+
+    let page_count = pages.len();
+    for (number, rows) in pages.iter().enumerate() {
+        renderer.render_header(&mut out)?;
+        out.write_all(rows)?;
+        renderer.render_bottom(&mut out)?;
+        writeln!(out, "Page {}/{}", number + 1, page_count)?;
+    }
+    Ok(())
+}
+
+fn line_count(text: &[u8]) -> usize {
+    text.iter().filter(|&&byte| byte == b'\n').count()
+}
@@ -0,0 +1,263 @@
+//! Column color palettes.
+//!
+//! Columns cycle through a palette of RGB colors. The built-in palettes come from csvlens;
+//! users can replace them with `--palette` or the `CSVPRETTY_COLORS` environment variable.
+//! On terminals without 24-bit color support, colors are downgraded to the nearest
+//! 256-color or 16-color equivalent.
+
+use owo_colors::{AnsiColors, DynColors, XtermColors};
+use terminal_colorsaurus::{theme_mode, QueryOptions, ThemeMode};
+
+/// Environment variable holding a palette spec, used when `--palette` is not given.
+pub const PALETTE_ENV_VAR: &str = "CSVPRETTY_COLORS";
+
+/// Color palette for dark terminal themes.
+/// Colors cycle through columns: Orange → Cyan → Purple → Pink → Yellow → (repeat)
+///
+/// These RGB values are taken from the csvlens project:
+/// https://github.com/YS-L/csvlens/blob/main/src/theme.rs
+const DARK_THEME_COLORS: [(u8, u8, u8); 5] = [
+    (253, 151, 31),  // Orange
+    (102, 217, 239), // Cyan
+    (190, 132, 255), // Purple
+    (249, 38, 114),  // Pink
+    (230, 219, 116), // Yellow
+];
+
+/// Color palette for light terminal themes.
+/// Darker variants of the dark theme colors for better contrast on light backgrounds.
+///
+/// These RGB values are taken from the csvlens project:
+/// https://github.com/YS-L/csvlens/blob/main/src/theme.rs
+const LIGHT_THEME_COLORS: [(u8, u8, u8); 5] = [
+    (207, 112, 0),   // Darker Orange
+    (0, 137, 179),   // Darker Cyan/Blue
+    (104, 77, 153),  // Darker Purple
+    (249, 0, 90),    // Darker Pink
+    (153, 143, 47),  // Darker Yellow/Olive
+];
+
+/// A non-empty cycle of column colors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: Vec<(u8, u8, u8)>,
+}
+
+impl Palette {
+    pub fn dark() -> Self {
+        Palette { colors: DARK_THEME_COLORS.to_vec() }
+    }
+
+    pub fn light() -> Self {
+        Palette { colors: LIGHT_THEME_COLORS.to_vec() }
+    }
+
+    /// Gets the RGB color for a column index using modulo to cycle through the palette.
+    /// Example: with 5 colors, columns 0-4 use colors 0-4, column 5 wraps to color 0, etc.
+    pub fn color(&self, col_index: usize) -> (u8, u8, u8) {
+        self.colors[col_index % self.colors.len()]
+    }
+
+    /// Parses a comma-separated list of hex colors such as `ff8800,#00aaff`.
+    fn parse(list: &str) -> Result<Self, String> {
+        let colors = list.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(parse_hex_color)
+            .collect::<Result<Vec<_>, _>>()?;
+        if colors.is_empty() {
+            return Err("palette must contain at least one color".to_string());
+        }
+        Ok(Palette { colors })
+    }
+}
+
+/// Colors that can be given by name instead of as hex, in `--rule` and `--column-color`.
+const NAMED_COLORS: [(&str, (u8, u8, u8)); 8] = [
+    ("black", (0, 0, 0)),
+    ("red", (220, 50, 47)),
+    ("green", (133, 153, 0)),
+    ("yellow", (181, 137, 0)),
+    ("blue", (38, 139, 210)),
+    ("magenta", (211, 54, 130)),
+    ("cyan", (42, 161, 152)),
+    ("white", (238, 232, 213)),
+];
+
+/// Parses a color name such as `red`, or hex as for [`parse_hex_color`].
+pub fn parse_color(color: &str) -> Result<(u8, u8, u8), String> {
+    NAMED_COLORS.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(color))
+        .map(|&(_, rgb)| Ok(rgb))
+        .unwrap_or_else(|| parse_hex_color(color))
+}
+
+/// Parses `rrggbb` (optionally prefixed with `#`) into an RGB triple.
+pub fn parse_hex_color(hex: &str) -> Result<(u8, u8, u8), String> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    if digits.len() != 6 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("invalid color '{}' (expected hex like ff8800)", hex));
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|e| e.to_string());
+    Ok((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// User overrides for the dark and light palettes. Unset entries keep the built-in palette.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaletteSpec {
+    dark: Option<Palette>,
+    light: Option<Palette>,
+}
+
+impl PaletteSpec {
+    /// Parses a palette spec.
+    ///
+    /// A plain list (`ff8800,00aaff`) replaces both palettes. Theme-specific lists are given as
+    /// `;`-separated sections, e.g. `dark:ff8800,00aaff;light:cc6600,0077aa`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut result = PaletteSpec::default();
+        for section in spec.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            if let Some(list) = section.strip_prefix("dark:") {
+                result.dark = Some(Palette::parse(list)?);
+            } else if let Some(list) = section.strip_prefix("light:") {
+                result.light = Some(Palette::parse(list)?);
+            } else {
+                let palette = Palette::parse(section)?;
+                result.dark = Some(palette.clone());
+                result.light = Some(palette);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Picks the palette for the given terminal theme.
+    pub fn for_theme(&self, mode: ThemeMode) -> Palette {
+        match mode {
+            ThemeMode::Dark => self.dark.clone().unwrap_or_else(Palette::dark),
+            ThemeMode::Light => self.light.clone().unwrap_or_else(Palette::light),
+        }
+    }
+}
+
+/// Detects the terminal's theme (dark/light).
+/// Queries the terminal using OSC escape sequences to determine background color.
+/// Falls back to dark theme if detection fails.
+pub fn detect_theme() -> ThemeMode {
+    theme_mode(QueryOptions::default()).unwrap_or(ThemeMode::Dark)
+}
+
+/// Subtle background for alternate rows with `--row-colors stripe`, just off the theme's
+/// own background so the colored text on top stays readable.
+pub fn stripe_background(mode: ThemeMode) -> (u8, u8, u8) {
+    match mode {
+        ThemeMode::Dark => (48, 48, 48),
+        ThemeMode::Light => (234, 234, 234),
+    }
+}
+
+/// How many colors the terminal can display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorDepth {
+    /// Detect from COLORTERM and TERM
+    Auto,
+    /// 24-bit RGB
+    #[value(name = "truecolor")]
+    TrueColor,
+    /// The xterm 256-color palette
+    #[value(name = "256")]
+    Ansi256,
+    /// The 16 basic ANSI colors
+    #[value(name = "16")]
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Resolves `Auto` using the environment: `COLORTERM=truecolor|24bit`, Windows Terminal's
+    /// `WT_SESSION` or a `*-direct` TERM (e.g. `xterm-direct`) means 24-bit color, a TERM
+    /// mentioning 256 colors means the xterm palette, anything else gets 16 colors.
+    pub fn resolve(self) -> ColorDepth {
+        if self != ColorDepth::Auto {
+            return self;
+        }
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default().to_ascii_lowercase();
+        let term = std::env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" || std::env::var_os("WT_SESSION").is_some() || term.ends_with("-direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// Converts an RGB color into the closest color this depth can display.
+    pub fn convert(self, (r, g, b): (u8, u8, u8)) -> DynColors {
+        match self {
+            ColorDepth::Auto | ColorDepth::TrueColor => DynColors::Rgb(r, g, b),
+            ColorDepth::Ansi256 => DynColors::Xterm(XtermColors::from(nearest_xterm_index((r, g, b)))),
+            ColorDepth::Ansi16 => DynColors::Ansi(nearest_ansi_color((r, g, b))),
+        }
+    }
+}
+
+/// Channel levels of the xterm 6×6×6 color cube (indices 16-231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Squared Euclidean distance between two RGB colors.
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).unsigned_abs().pow(2);
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// Finds the closest entry in the xterm 256-color palette, considering both the color cube
+/// and the grayscale ramp (indices 232-255).
+fn nearest_xterm_index(rgb: (u8, u8, u8)) -> u8 {
+    let cube_step = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - channel as i32).abs())
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (cube_step(rgb.0), cube_step(rgb.1), cube_step(rgb.2));
+    let cube_color = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+
+    // Grayscale ramp: 24 steps from 8 to 238
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray_step = (average.saturating_sub(8) / 10).min(23);
+    let gray_level = (8 + gray_step * 10) as u8;
+    let gray_index = 232 + gray_step as usize;
+
+    if distance(rgb, (gray_level, gray_level, gray_level)) < distance(rgb, cube_color) {
+        gray_index as u8
+    } else {
+        cube_index as u8
+    }
+}
+
+/// The 16 ANSI colors with their typical (xterm default) RGB values.
+const ANSI_COLORS: [(AnsiColors, (u8, u8, u8)); 16] = [
+    (AnsiColors::Black, (0, 0, 0)),
+    (AnsiColors::Red, (205, 0, 0)),
+    (AnsiColors::Green, (0, 205, 0)),
+    (AnsiColors::Yellow, (205, 205, 0)),
+    (AnsiColors::Blue, (0, 0, 238)),
+    (AnsiColors::Magenta, (205, 0, 205)),
+    (AnsiColors::Cyan, (0, 205, 205)),
+    (AnsiColors::White, (229, 229, 229)),
+    (AnsiColors::BrightBlack, (127, 127, 127)),
+    (AnsiColors::BrightRed, (255, 0, 0)),
+    (AnsiColors::BrightGreen, (0, 255, 0)),
+    (AnsiColors::BrightYellow, (255, 255, 0)),
+    (AnsiColors::BrightBlue, (92, 92, 255)),
+    (AnsiColors::BrightMagenta, (255, 0, 255)),
+    (AnsiColors::BrightCyan, (0, 255, 255)),
+    (AnsiColors::BrightWhite, (255, 255, 255)),
+];
+
+/// Finds the closest of the 16 ANSI colors.
+fn nearest_ansi_color(rgb: (u8, u8, u8)) -> AnsiColors {
+    ANSI_COLORS.iter()
+        .min_by_key(|(_, ansi_rgb)| distance(rgb, *ansi_rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(AnsiColors::Default)
+}
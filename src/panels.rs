@@ -0,0 +1,31 @@
+//! Splitting a wide table into panels of columns that each fit the terminal (`--split-columns`).
+//!
+//! Without wrapping, a table wider than the terminal is soft-wrapped by the terminal into an
+//! unreadable mess. Panels stack groups of columns vertically instead, each repeating a key
+//! column (or the line numbers) so every row can still be matched up across panels.
+
+use csvpretty::{count_fitting_columns, RenderOptions, Table};
+
+/// Groups the columns of `table` into panels that fit `width`, in order. Each panel starts
+/// with `key` when one is given, and has at least one other column even if it doesn't fit.
+/// Columns are measured as `options` would render them, e.g. with their fixed widths.
+pub fn plan(table: &Table, options: &RenderOptions, key: Option<usize>, width: usize, max_col_width: usize, row_num_width: usize) -> Vec<Vec<usize>> {
+    let rest: Vec<usize> = (0..table.headers.len()).filter(|&col_idx| Some(col_idx) != key).collect();
+    let mut panels = Vec::new();
+    let mut start = 0;
+    while start < rest.len() {
+        // Measure the key column and the columns still to place, as the panel would show them
+        let candidates: Vec<usize> = key.into_iter().chain(rest[start..].iter().copied()).collect();
+        let candidate_table = table.select_columns(&candidates);
+        let candidate_options = options.select_columns(&candidates);
+        let headers = match candidate_options.sort_indicator {
+            Some(indicator) => indicator.mark(&candidate_table.headers),
+            None => candidate_table.headers.clone(),
+        };
+        let fitting = count_fitting_columns(&headers, &candidate_table.records, max_col_width, &candidate_options.fixed_col_widths, width.saturating_sub(options.border.frame_overhead()), options.wrap_mode, row_num_width, options.padding);
+        let taken = fitting.saturating_sub(usize::from(key.is_some())).max(1);
+        panels.push(candidates[..taken + usize::from(key.is_some())].to_vec());
+        start += taken;
+    }
+    panels
+}
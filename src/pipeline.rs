@@ -0,0 +1,650 @@
+//! The stages between parsing the input and drawing the table: filtering and reshaping the
+//! rows, then working out how each column and cell is shown, as the command-line flags ask.
+
+use crate::charts::{self, BarSpec};
+use crate::check;
+use crate::coercion;
+use crate::column_names;
+use crate::column_types::ColumnType;
+use crate::extract::Extraction;
+use crate::failure::{self, Failure};
+use crate::groups::{self, Group};
+use crate::icons::IconSpec;
+use crate::inference::{self, InferredType};
+use crate::lookup::LookupSpec;
+use crate::rules::Rule;
+use crate::split::{self, SplitSpec};
+use crate::{border_style, ColorBy, ColorCycle, collect_hidden_columns, freq, grep, pivot, rows, sample, stats, totals, whitespace, Args, HiddenColumn};
+use csvpretty::{count_fitting_columns, line_number_width, Alignment, Cell, CollapsedRows, ElidedRows, GroupStart, Highlights, SortIndicator};
+use std::io;
+
+/// Determines the alignment of every column.
+///
+/// Columns default to left alignment. Typed columns (see `--column-type`) use their type's
+/// alignment, and when `auto_align` is enabled, columns inferred to be numeric are
+/// right-aligned. Explicit `--align` entries always win.
+pub fn resolve_alignments(
+    headers: &csv::StringRecord,
+    explicit: &[(String, Alignment)],
+    auto_align: bool,
+    column_types: &[Option<ColumnType>],
+    inferred_types: &[InferredType],
+) -> Result<Vec<Alignment>, String> {
+    let mut alignments: Vec<Alignment> = (0..headers.len())
+        .map(|col_idx| {
+            if let Some(column_type) = column_types[col_idx] {
+                return column_type.alignment();
+            }
+            if auto_align && inferred_types[col_idx].is_numeric() {
+                Alignment::Right
+            } else {
+                Alignment::Left
+            }
+        })
+        .collect();
+
+    for (column, align) in explicit {
+        alignments[find_column(headers, column, "--align")?] = *align;
+    }
+
+    Ok(alignments)
+}
+
+/// Picks the numeric columns `--align-decimal` lines up, right-aligning them. Columns given an
+/// alignment by `--align` keep it and aren't lined up.
+fn resolve_decimal_columns(headers: &csv::StringRecord, explicit: &[(String, Alignment)], inferred_types: &[InferredType], alignments: &mut [Alignment]) -> Vec<bool> {
+    (0..headers.len())
+        .map(|col_idx| {
+            let aligned = explicit.iter().any(|(column, _)| column_names::position(headers, column) == Some(col_idx));
+            let decimal = inferred_types[col_idx].is_numeric() && !aligned;
+            if decimal {
+                alignments[col_idx] = Alignment::Right;
+            }
+            decimal
+        })
+        .collect()
+}
+
+/// A color for each column, None for columns colored from the palette.
+pub type ColumnColors = Vec<Option<(u8, u8, u8)>>;
+
+/// Resolves `--column-color` to a color for each column.
+fn resolve_column_colors(headers: &csv::StringRecord, explicit: &[(String, (u8, u8, u8))]) -> Result<ColumnColors, String> {
+    let mut colors = vec![None; headers.len()];
+    for (column, color) in explicit {
+        colors[find_column(headers, column, "--column-color")?] = Some(*color);
+    }
+    Ok(colors)
+}
+
+/// Resolves `--col-width` to a fixed width for each column, None for columns sized by content.
+pub fn resolve_fixed_widths(headers: &csv::StringRecord, explicit: &[(String, usize)]) -> Result<Vec<Option<usize>>, String> {
+    let mut widths = vec![None; headers.len()];
+    for (column, width) in explicit {
+        widths[find_column(headers, column, "--col-width")?] = Some(*width);
+    }
+    Ok(widths)
+}
+
+/// Resolves `--max-lines` to a line bound for each column, None for columns bounded only by
+/// --max-row-height.
+pub fn resolve_max_lines(headers: &csv::StringRecord, explicit: &[(String, usize)]) -> Result<Vec<Option<usize>>, String> {
+    let mut max_lines = vec![None; headers.len()];
+    for (column, lines) in explicit {
+        max_lines[find_column(headers, column, "--max-lines")?] = Some(*lines);
+    }
+    Ok(max_lines)
+}
+
+/// Resolves a column name given on the command line to its index.
+/// `flag` names the option the column came from, for the error message.
+pub fn find_column(headers: &csv::StringRecord, name: &str, flag: &str) -> Result<usize, String> {
+    column_names::position(headers, name)
+        .ok_or_else(|| format!("unknown column '{}' in {}", name, flag))
+}
+
+/// Maps `--column-type` entries onto column positions.
+pub fn resolve_column_types(headers: &csv::StringRecord, entries: &[(String, ColumnType)]) -> Result<Vec<Option<ColumnType>>, String> {
+    let mut column_types = vec![None; headers.len()];
+    for (column, column_type) in entries {
+        column_types[find_column(headers, column, "--column-type")?] = Some(*column_type);
+    }
+    Ok(column_types)
+}
+
+/// Evaluates --rule conditions against the raw cell values.
+/// Returns the matching rule color for each cell, or an empty list when there are no rules.
+fn evaluate_rules(headers: &csv::StringRecord, records: &[Vec<Cell>], column_types: &[Option<ColumnType>], rules: &[Rule]) -> Result<Highlights, String> {
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+    let targets = rules.iter()
+        .map(|rule| find_column(headers, rule.column(), "--rule").map(|col_idx| (col_idx, rule)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(records.iter()
+        .map(|row| {
+            let mut colors = vec![None; row.len()];
+            for &(col_idx, rule) in &targets {
+                if colors[col_idx].is_none() {
+                    colors[col_idx] = rule.color_for(&row[col_idx], column_types[col_idx]);
+                }
+            }
+            colors
+        })
+        .collect())
+}
+
+/// Palette slots keyed to the columns of the input, so a column keeps its color when others are
+/// added before it. Columns that aren't in the input (split, extracted, bar columns, ...) take
+/// the slots after the input's columns.
+fn source_palette_slots(source_headers: &csv::StringRecord, headers: &csv::StringRecord) -> Vec<usize> {
+    let mut next_slot = source_headers.len();
+    headers.iter()
+        .map(|name| source_headers.iter().position(|source| source == name).unwrap_or_else(|| {
+            next_slot += 1;
+            next_slot - 1
+        }))
+        .collect()
+}
+
+/// Palette entry for each column, by --color-by and --color-cycle. Typed columns hold
+/// quantities, so they share the numeric color.
+pub fn palette_slots(args: &Args, source_headers: &csv::StringRecord, headers: &csv::StringRecord, column_types: &[Option<ColumnType>], inferred_types: &[InferredType]) -> Vec<usize> {
+    match args.color_by {
+        ColorBy::Column => match args.color_cycle {
+            ColorCycle::Display => (0..inferred_types.len()).collect(),
+            ColorCycle::Source => source_palette_slots(source_headers, headers),
+        },
+        ColorBy::Type => inferred_types.iter()
+            .zip(column_types)
+            .map(|(inferred_type, column_type)| match column_type {
+                Some(_) => InferredType::Float.palette_slot(),
+                None => inferred_type.palette_slot(),
+            })
+            .collect(),
+    }
+}
+
+/// Replaces the cells of typed columns with their formatted representation.
+/// Cells that don't parse as the column's type are kept as they are.
+pub fn apply_column_types(records: &mut [Vec<Cell>], column_types: &[Option<ColumnType>]) {
+    for row in records {
+        for (cell, column_type) in row.iter_mut().zip(column_types) {
+            if let Some(formatted) = column_type.and_then(|t| t.format(cell)) {
+                *cell = formatted.into();
+            }
+        }
+    }
+}
+
+/// Appends the columns captured by each `--extract`, in the order given.
+fn add_extracted_columns(headers: &mut csv::StringRecord, records: &mut [Vec<Cell>], extractions: &[Extraction]) -> Result<(), String> {
+    for extraction in extractions {
+        let col_idx = find_column(headers, extraction.column(), "--extract")?;
+        for row in records.iter_mut() {
+            let value = row.get(col_idx).map_or("", |cell| &**cell);
+            let extracted = extraction.extract(value);
+            row.extend(extracted.into_iter().map(Cell::Owned));
+        }
+        for name in extraction.names() {
+            headers.push_field(name);
+        }
+    }
+    Ok(())
+}
+
+/// Applies `--split-column` and then `--explode`, each in the order given.
+fn split_multi_value_columns(headers: &mut csv::StringRecord, records: &mut Vec<Vec<Cell>>, splits: &[SplitSpec], explodes: &[SplitSpec]) -> Result<(), String> {
+    for spec in splits {
+        let col_idx = find_column(headers, &spec.column, "--split-column")?;
+        let names = split::split_column(records, col_idx, &spec.column, &spec.delimiter);
+        let mut header_vec: Vec<&str> = headers.iter().collect();
+        header_vec.splice(col_idx..=col_idx, names.iter().map(String::as_str));
+        *headers = header_vec.into_iter().collect();
+    }
+    for spec in explodes {
+        let col_idx = find_column(headers, &spec.column, "--explode")?;
+        *records = split::explode_column(std::mem::take(records), col_idx, &spec.delimiter);
+    }
+    Ok(())
+}
+
+/// Puts the `--icon-column` icon before each mapped value, or in its place with `icon_only`.
+fn apply_icons(headers: &csv::StringRecord, records: &mut [Vec<Cell>], specs: &[IconSpec], icon_only: bool) -> Result<(), String> {
+    for spec in specs {
+        let col_idx = find_column(headers, &spec.column, "--icon-column")?;
+        for cell in records.iter_mut().map(|row| &mut row[col_idx]) {
+            if let Some(icon) = spec.icon(cell) {
+                *cell = if icon_only { icon.to_string() } else { format!("{} {}", icon, cell) }.into();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Replaces codes with their `--lookup` names, or with `append` adds the names in a new column
+/// after the codes. Codes missing from the lookup file are shown as they are.
+fn apply_lookups(headers: &mut csv::StringRecord, records: &mut [Vec<Cell>], lookups: &[LookupSpec], append: bool) -> Result<(), String> {
+    for lookup in lookups {
+        let col_idx = find_column(headers, &lookup.column, "--lookup")?;
+        let names = lookup.load()?;
+        for row in records.iter_mut() {
+            let name = names.get(&*row[col_idx]).cloned();
+            if append {
+                row.insert(col_idx + 1, name.unwrap_or_default().into());
+            } else if let Some(name) = name {
+                row[col_idx] = name.into();
+            }
+        }
+        if append {
+            let mut header_vec: Vec<String> = headers.iter().map(String::from).collect();
+            header_vec.insert(col_idx + 1, lookup.name_column());
+            *headers = csv::StringRecord::from(header_vec);
+        }
+    }
+    Ok(())
+}
+
+/// Adds a bar chart column after each `--bar` column, or replaces the values when `replace` is set.
+/// Bars are computed from the raw values, so this must run before typed columns are formatted.
+/// The `totals` row, which has no bars, gets empty cells to keep it lined up.
+pub fn add_bar_columns(
+    headers: &mut csv::StringRecord,
+    records: &mut [Vec<Cell>],
+    mut totals: Option<&mut Vec<Cell>>,
+    column_types: &mut Vec<Option<ColumnType>>,
+    bars: &[BarSpec],
+    replace: bool,
+    from_min: bool,
+) -> Result<(), String> {
+    let mut resolved = bars.iter()
+        .map(|bar| Ok((find_column(headers, &bar.column, "--bar")?, bar)))
+        .collect::<Result<Vec<_>, String>>()?;
+    // Insert from the right so earlier column indices stay valid
+    resolved.sort_by_key(|&(col_idx, _)| std::cmp::Reverse(col_idx));
+
+    let mut header_vec: Vec<String> = headers.iter().map(String::from).collect();
+    for (col_idx, bar) in resolved {
+        let cells = charts::bar_column(records, col_idx, column_types[col_idx], bar.width, from_min);
+        if replace {
+            for (row, cell) in records.iter_mut().zip(cells) {
+                if let Some(value) = row.get_mut(col_idx) {
+                    *value = cell.into();
+                }
+            }
+            // A total has no bar of its own
+            if let Some(total) = totals.as_deref_mut().and_then(|totals| totals.get_mut(col_idx)) {
+                *total = Cell::Borrowed("");
+            }
+            column_types[col_idx] = None;
+        } else {
+            for (row, cell) in records.iter_mut().zip(cells) {
+                row.insert((col_idx + 1).min(row.len()), cell.into());
+            }
+            if let Some(totals) = totals.as_deref_mut() {
+                totals.insert((col_idx + 1).min(totals.len()), Cell::Borrowed(""));
+            }
+            header_vec.insert(col_idx + 1, format!("{} bar", bar.column));
+            column_types.insert(col_idx + 1, None);
+        }
+    }
+    *headers = csv::StringRecord::from(header_vec);
+    Ok(())
+}
+
+/// Restricts the rows to the requested values and time window, drops duplicates and samples
+/// what's left. Returns the number of rows in the window, and of those left after dropping
+/// duplicates.
+pub fn filter_rows(args: &Args, headers: &csv::StringRecord, records: &mut Vec<Vec<Cell>>, seed: u64) -> (usize, usize) {
+    if let (Some(regex), false) = (&args.grep, args.raw) {
+        grep::filter_parsed(records, headers, regex, args.verbose);
+    }
+    if !args.where_equal.is_empty() {
+        let conditions = match args.where_equal.iter().map(|(column, value)| Ok((find_column(headers, column, "--where")?, value.as_str()))).collect::<Result<Vec<_>, String>>() {
+            Ok(conditions) => conditions,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        rows::filter_by_values(records, &conditions, args.where_any);
+    }
+    if let (Some(time_column), true) = (&args.time_column, args.since.is_some() || args.until.is_some()) {
+        let col_idx = match find_column(headers, time_column, "--time-column") {
+            Ok(col_idx) => col_idx,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        rows::filter_by_time(records, col_idx, args.since, args.until);
+    }
+    let rows_in_window = records.len();
+    if args.unique || !args.unique_by.is_empty() {
+        let key_columns = match args.unique_by.iter().map(|column| find_column(headers, column, "--unique-by")).collect::<Result<Vec<_>, _>>() {
+            Ok(key_columns) => key_columns,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        rows::drop_duplicates(records, &key_columns);
+    }
+    let unique_rows = records.len();
+    if let Some(size) = args.sample {
+        if args.verbose {
+            eprintln!("Verbose: sampling {} of {} rows with --seed {}", size.min(unique_rows), unique_rows, seed);
+        }
+        *records = sample::sample(std::mem::take(records).into_iter(), size, seed);
+    }
+    (rows_in_window, unique_rows)
+}
+
+/// Adds the extracted, split and looked-up columns, then replaces the rows with a summary
+/// for --stats, --freq or --pivot. Returns the --column-type of each column. With
+/// --coerce-report, prints the report and exits instead.
+pub fn reshape_table(args: &Args, headers: &mut csv::StringRecord, records: &mut Vec<Vec<Cell>>) -> io::Result<Vec<Option<ColumnType>>> {
+    if let Err(e) = add_extracted_columns(headers, records, &args.extract) {
+        failure::exit(Failure::Usage, e);
+    }
+    if let Err(e) = split_multi_value_columns(headers, records, &args.split_column, &args.explode) {
+        failure::exit(Failure::Usage, e);
+    }
+    if let Err(e) = apply_lookups(headers, records, &args.lookup, args.lookup_append) {
+        failure::exit(Failure::Other, e);
+    }
+
+    let mut column_types = match resolve_column_types(headers, &args.column_type) {
+        Ok(column_types) => column_types,
+        Err(e) => {
+            failure::exit(Failure::Usage, e);
+        }
+    };
+
+    // Rows are numbered as the table would number them before --stats, --sort or --group-by move them
+    if args.coerce_report {
+        let report = coercion::coercion_report(headers, records, &column_types);
+        report.print(&mut io::stdout())?;
+        std::process::exit(if report.mismatches.is_empty() { 0 } else { 1 });
+    }
+
+    // Replace the rows with a summary of each column; the remaining flags apply to the summary
+    if args.stats {
+        let (summary_headers, summary_rows) = stats::summarize(headers, records, &column_types);
+        (*headers, *records) = (summary_headers, summary_rows);
+        stats::order_rows(records, args.stats_order);
+        column_types = vec![None; headers.len()];
+    }
+    // Likewise with the frequencies of one column's values
+    if let Some(freq_column) = &args.freq {
+        let col_idx = match find_column(headers, freq_column, "--freq") {
+            Ok(col_idx) => col_idx,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        // The values keep their column's type
+        column_types = vec![column_types[col_idx], None, Some(freq::PERCENT_TYPE)];
+        let (freq_headers, freq_rows) = freq::frequencies(headers, records, col_idx);
+        (*headers, *records) = (freq_headers, freq_rows);
+    }
+    // Or with a cross-tabulation of the rows
+    if let Some(spec) = &args.pivot {
+        let (pivot_headers, pivot_rows) = match pivot::pivot(headers, records, spec, &mut column_types) {
+            Ok(pivoted) => pivoted,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        (*headers, *records) = (pivot_headers, pivot_rows);
+    }
+    Ok(column_types)
+}
+
+/// How [`arrange_rows`] left the rows: their groups, where empty rows were collapsed and rows
+/// left out, and the totals row to add below them.
+pub struct Arrangement {
+    pub groups: Vec<Group>,
+    pub collapsed_rows: Vec<CollapsedRows>,
+    pub totals: Option<Vec<Cell<'static>>>,
+    pub elided_rows: Option<ElidedRows>,
+}
+
+/// Sorts and groups the rows, collapses runs of empty rows and leaves out rows beyond
+/// --max-rows, adding up the totals row before any are left out.
+pub fn arrange_rows(args: &Args, headers: &csv::StringRecord, records: &mut Vec<Vec<Cell>>, column_types: &[Option<ColumnType>]) -> Arrangement {
+    // Sort on the raw values, before typed columns are reformatted for display
+    if let Some(sort_column) = &args.sort {
+        let col_idx = match find_column(headers, sort_column, "--sort") {
+            Ok(col_idx) => col_idx,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        let inferred_type = inference::infer_column(records, col_idx);
+        rows::sort_records(records, col_idx, column_types[col_idx], inferred_type, args.reverse);
+    }
+    // Grouping sorts by the group column last, so --sort orders the rows within each group
+    let mut groups = Vec::new();
+    if let Some(group_column) = &args.group_by {
+        let col_idx = match find_column(headers, group_column, "--group-by") {
+            Ok(col_idx) => col_idx,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        let inferred_type = inference::infer_column(records, col_idx);
+        rows::sort_records(records, col_idx, column_types[col_idx], inferred_type, false);
+        groups = groups::find_groups(records, col_idx);
+    }
+    // Collapsed once the rows are in order, so each marker stands where its rows are shown
+    let collapsed_rows = if args.collapse_empty_rows { rows::collapse_empty_rows(records) } else { Vec::new() };
+    // Added up before --max-rows leaves rows out, so the totals cover every row
+    let totals = args.totals.map(|function| {
+        let inferred_types: Vec<InferredType> = (0..headers.len()).map(|col_idx| inference::infer_column(records, col_idx)).collect();
+        totals::totals_row(records, column_types, &inferred_types, function)
+    });
+    // Left out once the rows are in order too, and before anything is worked out from the rows
+    // shown
+    let elided_rows = args.row_limit().and_then(|max_rows| rows::elide_rows(records, max_rows));
+    Arrangement { groups, collapsed_rows, totals, elided_rows }
+}
+
+/// The cell colors from --rule, the null rates of --stats and the problem rows of a --check
+/// report. Rules compare raw values, so this comes before typed columns are reformatted.
+pub fn highlight_cells(args: &Args, headers: &csv::StringRecord, records: &[Vec<Cell>], column_types: &[Option<ColumnType>], report: Option<&check::Report>, rows_moved: bool) -> Highlights {
+    let mut highlights = match evaluate_rules(headers, records, column_types, &args.rule) {
+        Ok(highlights) => highlights,
+        Err(e) => {
+            failure::exit(Failure::Usage, e);
+        }
+    };
+    if args.stats {
+        stats::highlight_null_rates(&mut highlights, headers, records);
+    }
+    if let Some(report) = report {
+        if rows_moved {
+            eprintln!("Warning: problem rows aren't highlighted when --sort, --grep, --where, --since/--until, --unique, --sample, --explode, --stats, --freq, --pivot, --group-by, --collapse-empty-rows or --max-rows change the rows");
+        } else {
+            report.highlight_rows(&mut highlights, records);
+        }
+    }
+    highlights
+}
+
+/// What [`format_cells`] added to the rows: the subtotal and totals rows, and where the groups
+/// start.
+pub struct Summaries {
+    pub dimmed_columns: Vec<bool>,
+    pub subtotal_rows: Vec<usize>,
+    pub totals_row: Option<usize>,
+    /// Rows of figures rather than data, which placeholders and --collapse leave alone
+    pub summary_rows: Vec<usize>,
+    pub group_starts: Vec<GroupStart>,
+}
+
+/// Reformats the typed columns and adds --icon-column icons, then the subtotal rows of each
+/// group and the totals row. Subtotals are added up from the raw values first.
+#[allow(clippy::too_many_arguments)]
+pub fn format_cells<'a>(
+    args: &Args,
+    headers: &csv::StringRecord,
+    records: &mut Vec<Vec<Cell<'a>>>,
+    column_types: &[Option<ColumnType>],
+    inferred_types: &[InferredType],
+    highlights: &mut Highlights,
+    mut groups: Vec<Group>,
+    totals: Option<Vec<Cell<'a>>>,
+) -> Summaries {
+    // Bar columns may have moved the group column
+    let group_column = args.group_by.as_deref().and_then(|column| find_column(headers, column, "--group-by").ok());
+    let subtotals = match group_column {
+        Some(col_idx) if args.group_subtotals => groups::subtotal_rows(records, &groups, col_idx, column_types, inferred_types),
+        _ => Vec::new(),
+    };
+
+    apply_column_types(records, column_types);
+    // Measured before subtotal rows are added
+    let dimmed_columns = if args.dim_constant { rows::constant_columns(records, headers.len()) } else { Vec::new() };
+    if let Err(e) = apply_icons(headers, records, &args.icon_column, args.icon_only) {
+        failure::exit(Failure::Usage, e);
+    }
+    let subtotal_rows = groups::insert_subtotals(records, highlights, &mut groups, subtotals);
+    let totals_row = totals.map(|row| {
+        records.push(row);
+        records.len() - 1
+    });
+    let summary_rows = subtotal_rows.iter().copied().chain(totals_row).collect();
+    let group_starts = match group_column {
+        Some(col_idx) => groups::group_starts(&groups, &headers[col_idx], args.group_labels),
+        None => Vec::new(),
+    };
+    Summaries { dimmed_columns, subtotal_rows, totals_row, summary_rows, group_starts }
+}
+
+/// The per-column settings given by name on the command line, one for each column shown.
+pub struct ColumnSettings {
+    pub alignments: Vec<Alignment>,
+    pub decimal_columns: Vec<bool>,
+    pub fixed_col_widths: Vec<Option<usize>>,
+    pub max_cell_lines: Vec<Option<usize>>,
+    pub column_colors: ColumnColors,
+}
+
+impl ColumnSettings {
+    /// Looks up the columns of --align, --col-width, --max-lines and --column-color, filling in
+    /// the inferred alignment of the others.
+    pub fn resolve(args: &Args, headers: &csv::StringRecord, column_types: &[Option<ColumnType>], inferred_types: &[InferredType]) -> ColumnSettings {
+        // Summary values line up on the right even when they aren't plain numbers; --align still wins
+        let mut explicit_alignments = Vec::new();
+        if args.stats {
+            explicit_alignments.extend(stats::RIGHT_ALIGNED.iter().map(|column| (column.to_string(), Alignment::Right)));
+        }
+        explicit_alignments.extend(args.align.iter().cloned());
+
+        let mut alignments = match resolve_alignments(headers, &explicit_alignments, args.auto_align || !args.no_auto_align, column_types, inferred_types) {
+            Ok(alignments) => alignments,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        let decimal_columns = if args.align_decimal { resolve_decimal_columns(headers, &args.align, inferred_types, &mut alignments) } else { Vec::new() };
+        let fixed_col_widths = match resolve_fixed_widths(headers, &args.col_width) {
+            Ok(widths) => widths,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        let max_cell_lines = match resolve_max_lines(headers, &args.max_lines) {
+            Ok(max_lines) => max_lines,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        let column_colors = match resolve_column_colors(headers, &args.column_color) {
+            Ok(colors) => colors,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        ColumnSettings { alignments, decimal_columns, fixed_col_widths, max_cell_lines, column_colors }
+    }
+
+    /// Keeps the settings of the first `visible` columns.
+    pub fn truncate(&mut self, visible: usize) {
+        self.alignments.truncate(visible);
+        self.decimal_columns.truncate(visible);
+        self.fixed_col_widths.truncate(visible);
+        self.max_cell_lines.truncate(visible);
+        self.column_colors.truncate(visible);
+    }
+}
+
+/// Marks whitespace for --show-whitespace, fills empty cells with the --null-display
+/// placeholder and blanks the --collapse repeats, leaving the `summary_rows` alone. Runs of
+/// repeats start over at the `breaks` rows.
+pub fn fill_cells(args: &Args, headers: &csv::StringRecord, records: &mut [Vec<Cell>], summary_rows: &[usize], breaks: &[usize]) {
+    // Marked after type inference, which looks past the whitespace, and before layout
+    if args.show_whitespace {
+        records.iter_mut().for_each(|row| whitespace::show_in_row(row));
+    }
+
+    // Fill empty cells with the placeholder before layout, so it counts towards column widths
+    if !args.null_display.is_empty() {
+        let data_rows = records.iter_mut().enumerate().filter(|(row_idx, _)| !summary_rows.contains(row_idx));
+        for cell in data_rows.flat_map(|(_, row)| row).filter(|cell| cell.trim().is_empty()) {
+            *cell = args.null_display.clone().into();
+        }
+    }
+
+    // Blanked once placeholders are in, so the blanks aren't taken for missing values
+    if !args.collapse.is_empty() {
+        let columns = match args.collapse.iter().map(|column| find_column(headers, column, "--collapse")).collect::<Result<Vec<_>, _>>() {
+            Ok(columns) => columns,
+            Err(e) => {
+                failure::exit(Failure::Usage, e);
+            }
+        };
+        rows::collapse_repeats(records, &columns, breaks, summary_rows);
+    }
+}
+
+/// Keeps only the leading columns that fit `detected_width`, for --fit overflow, returning the
+/// others to be listed below the table.
+pub fn hide_overflowing_columns(
+    args: &Args,
+    headers: &mut csv::StringRecord,
+    records: &mut [Vec<Cell>],
+    columns: &mut ColumnSettings,
+    sort_indicator: &mut Option<SortIndicator>,
+    detected_width: usize,
+    elided_count: usize,
+) -> Vec<HiddenColumn> {
+    let row_num_width = line_number_width(records.len() + elided_count, args.line_numbers);
+    let cap = args.max_col_width.map_or(detected_width, |max| detected_width.min(max as usize));
+    let shown_headers = sort_indicator.map_or_else(|| headers.clone(), |indicator| indicator.mark(headers));
+    let visible = count_fitting_columns(&shown_headers, records, cap, &columns.fixed_col_widths, detected_width.saturating_sub(border_style(args).frame_overhead()), args.wrap_mode(), row_num_width, usize::from(args.padding));
+    let hidden = collect_hidden_columns(headers, records, visible);
+    *headers = headers.iter().take(visible).collect();
+    columns.truncate(visible);
+    *sort_indicator = sort_indicator.filter(|indicator| indicator.column < visible);
+    for row in records {
+        row.truncate(visible);
+    }
+    hidden
+}
+
+/// The rows --measure-rows sizes the columns from, if not all of them: the first and last, or
+/// a random pick with --measure-random.
+pub fn measured_rows(args: &Args, row_count: usize, seed: u64) -> Option<Vec<usize>> {
+    args.measure_rows.filter(|&rows| rows < row_count).map(|rows| {
+        let picked = if args.measure_random {
+            sample::sample(0..row_count, rows, seed)
+        } else {
+            (0..rows).chain(row_count.saturating_sub(rows).max(rows)..row_count).collect()
+        };
+        if args.verbose {
+            let seeded = if args.measure_random { format!(" picked with --seed {}", seed) } else { String::new() };
+            eprintln!("Verbose: sizing columns from {} of {} rows{}", picked.len(), row_count, seeded);
+        }
+        picked
+    })
+}
@@ -0,0 +1,140 @@
+//! Cross-tabulating rows (`--pivot`).
+//!
+//! Instead of the rows themselves, `--pivot rows=region,cols=month,values=sum(amount)` renders
+//! one row per distinct value of the `rows` columns and one column per distinct value of the
+//! `cols` column, each cell aggregating the input rows the two share, like a spreadsheet's pivot
+//! table. Long-format data with a row per measurement becomes a grid read at a glance.
+
+use crate::column_names;
+use crate::column_types::ColumnType;
+use crate::dashboard::{self, Aggregate, Tally};
+use crate::{is_numeric, stats};
+use csvpretty::Cell;
+use std::collections::HashMap;
+
+/// Header of a column whose value in the `cols` column is empty.
+const EMPTY_LABEL: &str = "(empty)";
+
+/// A `--pivot` spec such as `rows=region,cols=month,values=sum(amount)`.
+#[derive(Debug, Clone)]
+pub struct PivotSpec {
+    rows: Vec<String>,
+    cols: String,
+    values: Aggregate,
+}
+
+/// Clap value parser for `--pivot`: `rows=COLUMNS,cols=COLUMN,values=AGGREGATE`. Further names
+/// after `rows=` are more row columns (`rows=region,city,cols=month`), and the aggregate is one
+/// of `--agg`'s, `count()` when left out.
+pub fn parse_pivot(s: &str) -> Result<PivotSpec, String> {
+    let usage = || format!("invalid pivot '{}' (expected rows=COLUMNS,cols=COLUMN,values=AGGREGATE, e.g. rows=region,cols=month,values=sum(amount))", s);
+    let mut rows = Vec::new();
+    let mut cols = None;
+    let mut values = None;
+    let mut key = "";
+    for part in s.split(',') {
+        let value = match part.split_once('=') {
+            Some((name, value)) => {
+                key = name.trim();
+                value
+            }
+            None => part,
+        };
+        let value = value.trim();
+        match key {
+            _ if value.is_empty() => return Err(usage()),
+            "rows" => rows.push(value.to_string()),
+            "cols" if cols.is_none() => cols = Some(value.to_string()),
+            "values" if values.is_none() => values = Some(dashboard::parse_aggregate(value)?),
+            "cols" => return Err(format!("cols= takes one column in pivot '{}'", s)),
+            "values" => return Err(format!("values= takes one aggregate in pivot '{}'", s)),
+            "" => return Err(usage()),
+            _ => return Err(format!("unknown pivot part '{}' in '{}' (expected rows, cols or values)", key, s)),
+        }
+    }
+    let cols = cols.ok_or_else(usage)?;
+    if rows.is_empty() {
+        return Err(usage());
+    }
+    let values = match values {
+        Some(values) => values,
+        None => dashboard::parse_aggregate("count()")?,
+    };
+    Ok(PivotSpec { rows, cols, values })
+}
+
+/// The input rows sharing a row and a column of the pivot table.
+struct PivotCell {
+    rows: usize,
+    /// Their values in the `values` column
+    tally: Tally,
+}
+
+/// Builds the pivot table: the `rows` columns, then a column per value of the `cols` column.
+/// Rows and columns come in the order their values first appear, ignoring surrounding
+/// whitespace; combinations without any input rows are left empty. The aggregated values keep
+/// their column's type, which reads their numbers; counts have no type. `column_types` is
+/// changed to match the pivot table's columns.
+pub fn pivot(headers: &csv::StringRecord, records: &[Vec<Cell>], spec: &PivotSpec, column_types: &mut Vec<Option<ColumnType>>) -> Result<(csv::StringRecord, Vec<Vec<Cell<'static>>>), String> {
+    let find = |name: &str| column_names::position(headers, name).ok_or_else(|| format!("unknown column '{}' in --pivot", name));
+    let row_columns = spec.rows.iter().map(|name| find(name)).collect::<Result<Vec<_>, _>>()?;
+    let col_column = find(&spec.cols)?;
+    let value_column = spec.values.column().map(find).transpose()?;
+    let value_type = value_column.and_then(|col_idx| column_types[col_idx]).filter(|_| !spec.values.is_count());
+
+    let value_at = |row: &[Cell], col_idx: usize| row.get(col_idx).map_or("", |value| value.trim()).to_string();
+    let mut row_keys: Vec<Vec<String>> = Vec::new();
+    let mut row_positions: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut col_keys: Vec<String> = Vec::new();
+    let mut col_positions: HashMap<String, usize> = HashMap::new();
+    let mut cells: HashMap<(usize, usize), PivotCell> = HashMap::new();
+    for row in records {
+        let row_key: Vec<String> = row_columns.iter().map(|&col_idx| value_at(row, col_idx)).collect();
+        let row_position = *row_positions.entry(row_key.clone()).or_insert_with(|| {
+            row_keys.push(row_key);
+            row_keys.len() - 1
+        });
+        let col_key = value_at(row, col_column);
+        let col_position = *col_positions.entry(col_key.clone()).or_insert_with(|| {
+            col_keys.push(col_key);
+            col_keys.len() - 1
+        });
+
+        let cell = cells.entry((row_position, col_position)).or_insert_with(|| PivotCell { rows: 0, tally: spec.values.tally() });
+        cell.rows += 1;
+        let Some(value) = value_column.map(|col_idx| value_at(row, col_idx)).filter(|value| !value.is_empty()) else {
+            continue;
+        };
+        cell.tally.add(match value_type {
+            Some(column_type) => column_type.numeric_value(&value),
+            None if is_numeric(&value) => value.parse().ok(),
+            None => None,
+        });
+    }
+
+    let pivot_headers = row_columns.iter()
+        .map(|&col_idx| &headers[col_idx])
+        .chain(col_keys.iter().map(|key| if key.is_empty() { EMPTY_LABEL } else { key.as_str() }))
+        .collect();
+    let rows = row_keys.into_iter()
+        .enumerate()
+        .map(|(row_position, row_key)| {
+            let values = (0..col_keys.len()).map(|col_position| {
+                let value = cells.get(&(row_position, col_position))
+                    .and_then(|cell| spec.values.evaluate(cell.rows, &cell.tally));
+                // Typed values are formatted by their type later, so they keep every decimal
+                Cell::Owned(match (value, value_type) {
+                    (Some(value), Some(_)) => value.to_string(),
+                    (Some(value), None) => stats::format_number(value),
+                    (None, _) => String::new(),
+                })
+            });
+            row_key.into_iter().map(Cell::Owned).chain(values).collect()
+        })
+        .collect();
+    *column_types = row_columns.iter()
+        .map(|&col_idx| column_types[col_idx])
+        .chain(std::iter::repeat_n(value_type, col_keys.len()))
+        .collect();
+    Ok((pivot_headers, rows))
+}
@@ -0,0 +1,51 @@
+//! Printing rows as they're read (`--progressive`).
+//!
+//! Columns are sized from the first rows, so the table starts appearing while a large input is
+//! still arriving rather than once all of it has been parsed and measured. That only works
+//! when nothing needs every row first, such as sorting or sizing columns to their widest cell.
+
+use csv::{ByteRecord, Reader, StringRecord};
+use csvpretty::{Cell, CsvOptions};
+use std::io::Read;
+
+/// The records of CSV input, parsed one at a time. Bytes that aren't valid UTF-8 are shown as
+/// `�`, as when the whole input is read.
+pub struct Rows<R: Read> {
+    reader: Reader<R>,
+    record: ByteRecord,
+    /// Whether a cell had bytes that aren't valid UTF-8
+    pub invalid_utf8: bool,
+}
+
+impl<R: Read> Rows<R> {
+    /// Starts reading `reader`, returning the column names along with the rows. Without a
+    /// header row, the columns are numbered from 1.
+    pub fn new(reader: R, options: &CsvOptions) -> Result<(Rows<R>, StringRecord), csvpretty::Error> {
+        let mut reader = options.reader_builder().from_reader(reader);
+        let first = reader.byte_headers()?;
+        let mut invalid_utf8 = std::str::from_utf8(first.as_slice()).is_err();
+        let headers = if options.has_headers {
+            first.iter().map(|name| String::from_utf8_lossy(name)).collect()
+        } else {
+            // The first record sets the number of columns, and is read again as a row
+            invalid_utf8 = false;
+            (1..=first.len()).map(|n| n.to_string()).collect()
+        };
+        Ok((Rows { reader, record: ByteRecord::new(), invalid_utf8 }, headers))
+    }
+}
+
+impl<R: Read> Iterator for Rows<R> {
+    type Item = Result<Vec<Cell<'static>>, csvpretty::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.reader.read_byte_record(&mut self.record) {
+            Ok(true) => {
+                self.invalid_utf8 |= std::str::from_utf8(self.record.as_slice()).is_err();
+                Some(Ok(self.record.iter().map(|cell| Cell::Owned(String::from_utf8_lossy(cell).into_owned())).collect()))
+            }
+            Ok(false) => None,
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
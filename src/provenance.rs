@@ -0,0 +1,47 @@
+//! A line describing where a table came from (`--provenance`).
+//!
+//! Tables pasted into tickets and chat lose their context. The provenance line names the
+//! source, when and by which csvpretty version the table was made, and the filters that
+//! dropped rows from it.
+
+use chrono::{DateTime, Utc};
+use owo_colors::OwoColorize;
+use std::io::{self, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Overrides the generation time with seconds since the Unix epoch, as in reproducible builds.
+const SOURCE_DATE_EPOCH_ENV_VAR: &str = "SOURCE_DATE_EPOCH";
+
+pub struct Provenance {
+    /// File name or URL, or None for standard input
+    pub source: Option<String>,
+    /// Filters that dropped rows, as the flags that were given, e.g. `--since 2024-01-01 00:00:00`
+    pub filters: Vec<String>,
+}
+
+impl Provenance {
+    /// Prints e.g. `data.csv · 2026-10-16 09:30:00 UTC · csvpretty 0.1.0 · --skip-lines 2`,
+    /// dimmed when colors are on.
+    pub fn print(&self, out: &mut impl Write, colored: bool) -> io::Result<()> {
+        let mut parts = vec![
+            self.source.clone().unwrap_or_else(|| "stdin".to_string()),
+            generated_at().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            format!("csvpretty {}", env!("CARGO_PKG_VERSION")),
+        ];
+        parts.extend(self.filters.iter().cloned());
+        let line = parts.join(" · ");
+        if colored {
+            writeln!(out, "{}", line.dimmed())
+        } else {
+            writeln!(out, "{}", line)
+        }
+    }
+}
+
+/// The current time, or the time given by `SOURCE_DATE_EPOCH`.
+fn generated_at() -> DateTime<Utc> {
+    let time = std::env::var(SOURCE_DATE_EPOCH_ENV_VAR).ok()
+        .and_then(|value| value.parse().ok())
+        .map_or_else(SystemTime::now, |seconds| UNIX_EPOCH + Duration::from_secs(seconds));
+    DateTime::from(time)
+}
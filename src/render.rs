@@ -0,0 +1,1154 @@
+//! Drawing a table: borders, the header row, data rows and the vertical record view.
+
+use crate::layout::{capped_width, column_overhead, fraction_width, usable_width, TableLayout};
+use crate::palette::{ColorDepth, Palette};
+use crate::wrap::{display_width, escape_control_chars, truncate_to_width, wrap_text, Ellipsis};
+use crate::{is_null, is_numeric, Cell, Error, Table};
+use owo_colors::{DynColors, OwoColorize};
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+/// Per-cell colors, indexed by row then column.
+pub type Highlights = Vec<Vec<Option<(u8, u8, u8)>>>;
+
+/// Per-cell hyperlink targets, indexed by row then column.
+pub type Links = Vec<Vec<Option<String>>>;
+
+/// How a table is drawn. The defaults give an uncolored, word-wrapped table 80 columns wide.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub wrap_mode: WrapMode,
+    pub show_line_numbers: bool,
+    /// Characters used to draw borders and separators.
+    pub border: BorderStyle,
+    /// Spaces on either side of the text in a cell.
+    pub padding: usize,
+    /// Upper bound on every column's content width.
+    pub max_col_width: Option<usize>,
+    /// Lower bound on a column's content width when columns are squeezed to fit. Columns with
+    /// narrower content keep their natural width.
+    pub min_col_width: Option<usize>,
+    /// Content width of individual columns, indexed by column position, overriding the other
+    /// bounds. Columns without an entry are sized from their content.
+    pub fixed_col_widths: Vec<Option<usize>>,
+    /// Rows the column widths are measured from, by index, to lay out huge tables without
+    /// scanning every cell. Cells of the other rows that don't fit are wrapped, or cut in no-wrap
+    /// mode. None measures every row.
+    pub measured_rows: Option<Vec<usize>>,
+    /// Horizontal alignment for each column, indexed by column position. Columns without an
+    /// entry are left-aligned.
+    pub alignments: Vec<Alignment>,
+    /// Right-aligned columns whose numbers are lined up on their decimal points, indexed by
+    /// column position, e.g. `  1.5` over `123.25`. Columns without an entry aren't.
+    pub decimal_columns: Vec<bool>,
+    /// Where cells sit within rows made taller by a wrapped cell.
+    pub vertical_alignment: VerticalAlignment,
+    /// Column colors. None renders without colors.
+    pub theme: Option<Palette>,
+    /// Color depth the theme colors are converted to.
+    pub color_depth: ColorDepth,
+    /// Print each record as a block of field/value lines instead of a table.
+    pub vertical: bool,
+    /// Colors for individual cells, taking precedence over column colors.
+    pub highlights: Highlights,
+    /// Targets of OSC 8 terminal hyperlinks around cells. The cell text is the visible label.
+    pub links: Links,
+    /// Palette entry for each column. Columns without an entry use their position.
+    pub palette_slots: Vec<usize>,
+    /// Colors given to individual columns, indexed by column position, in place of their palette
+    /// entry. Columns without one keep cycling through the palette.
+    pub column_colors: Vec<Option<(u8, u8, u8)>>,
+    /// Columns drawn dimmed when colors are on, indexed by column position, e.g. ones holding
+    /// the same value in every row. Columns without an entry aren't dimmed.
+    pub dimmed_columns: Vec<bool>,
+    /// Whether text is colored per column, rows are striped, or both.
+    pub row_colors: RowColors,
+    /// Background of every other data row when striping. None disables the stripes.
+    pub stripe_background: Option<(u8, u8, u8)>,
+    /// Placeholder shown in empty cells.
+    pub null_display: String,
+    /// Marks where truncated cells were cut. `{chars}` stands for the number of characters cut off.
+    pub truncation_marker: String,
+    /// Where truncated cells are cut: at the start, in the middle or at the end.
+    pub ellipsis: Ellipsis,
+    /// Title drawn in the top border.
+    pub title: Option<String>,
+    /// Where the title sits in the top border, and the caption under the table.
+    pub title_alignment: Alignment,
+    /// Text shown under the table, wrapped to its width.
+    pub caption: Option<String>,
+    /// Groups of rows set apart by rules, in row order. The vertical view ignores them.
+    pub groups: Vec<GroupStart>,
+    /// Runs of empty rows left out of the table, each shown as a marker line, in row order.
+    /// The vertical view ignores them.
+    pub collapsed_rows: Vec<CollapsedRows>,
+    /// Rows left out of the middle of a long table, shown as one `⋯ 1,234 more rows ⋯` line in
+    /// their place. Line numbers below it count them. The vertical view ignores it.
+    pub elided_rows: Option<ElidedRows>,
+    /// Index of the row holding column totals, which is set apart by a rule, drawn in bold when
+    /// colors are on and has no line number. The vertical view names it `TOTALS`.
+    pub totals_row: Option<usize>,
+    /// Indexes of the `--group-subtotals` rows, in row order. Like the totals row they have no
+    /// line number, and the rows below them are numbered as if they weren't there.
+    pub subtotal_rows: Vec<usize>,
+    /// The column the rows are sorted by, marked with an arrow in its header.
+    pub sort_indicator: Option<SortIndicator>,
+    /// Upper bound on the lines of a data row. Taller cells are cut, their last line ending in
+    /// `(+N lines)`. The vertical view ignores it.
+    pub max_row_height: Option<usize>,
+    /// Upper bound on the lines of each column's cells, indexed by column position, overriding
+    /// `max_row_height`.
+    pub max_cell_lines: Vec<Option<usize>>,
+    /// Rows shown at full height whatever the bounds on their lines, e.g. rows opened in a pager.
+    pub expanded_rows: Vec<usize>,
+    pub terminal_width: usize,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            wrap_mode: WrapMode::Word,
+            show_line_numbers: false,
+            border: TableStyle::Unicode.border_style(),
+            padding: 1,
+            max_col_width: None,
+            min_col_width: None,
+            fixed_col_widths: Vec::new(),
+            measured_rows: None,
+            alignments: Vec::new(),
+            decimal_columns: Vec::new(),
+            vertical_alignment: VerticalAlignment::Top,
+            theme: None,
+            color_depth: ColorDepth::TrueColor,
+            vertical: false,
+            highlights: Vec::new(),
+            links: Vec::new(),
+            palette_slots: Vec::new(),
+            column_colors: Vec::new(),
+            dimmed_columns: Vec::new(),
+            row_colors: RowColors::Columns,
+            stripe_background: None,
+            null_display: String::new(),
+            truncation_marker: "…".to_string(),
+            ellipsis: Ellipsis::End,
+            title: None,
+            title_alignment: Alignment::Left,
+            caption: None,
+            groups: Vec::new(),
+            collapsed_rows: Vec::new(),
+            elided_rows: None,
+            totals_row: None,
+            subtotal_rows: Vec::new(),
+            sort_indicator: None,
+            max_row_height: None,
+            max_cell_lines: Vec::new(),
+            expanded_rows: Vec::new(),
+            terminal_width: 80,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// The options for [`Table::select_columns`] with the same `columns`: per-column settings
+    /// follow their columns, and columns keep their colors.
+    pub fn select_columns(&self, columns: &[usize]) -> RenderOptions {
+        // Settings lists may stop short of the last columns, which then get the default
+        fn select<T: Clone>(values: &[T], columns: &[usize], default: T) -> Vec<T> {
+            columns.iter().map(|&col_idx| values.get(col_idx).cloned().unwrap_or_else(|| default.clone())).collect()
+        }
+        RenderOptions {
+            alignments: select(&self.alignments, columns, Alignment::Left),
+            decimal_columns: select(&self.decimal_columns, columns, false),
+            fixed_col_widths: select(&self.fixed_col_widths, columns, None),
+            max_cell_lines: select(&self.max_cell_lines, columns, None),
+            dimmed_columns: select(&self.dimmed_columns, columns, false),
+            column_colors: select(&self.column_colors, columns, None),
+            highlights: self.highlights.iter().map(|row| select(row, columns, None)).collect(),
+            links: self.links.iter().map(|row| select(row, columns, None)).collect(),
+            palette_slots: columns.iter()
+                .map(|&col_idx| self.palette_slots.get(col_idx).copied().unwrap_or(col_idx))
+                .collect(),
+            // The arrow goes if its column does
+            sort_indicator: self.sort_indicator.and_then(|indicator| {
+                let column = columns.iter().position(|&col_idx| col_idx == indicator.column)?;
+                Some(SortIndicator { column, ..indicator })
+            }),
+            ..self.clone()
+        }
+    }
+
+    /// The group starting at a row, if any.
+    fn group_at(&self, row_index: usize) -> Option<&GroupStart> {
+        self.groups.iter().find(|group| group.row == row_index)
+    }
+
+    /// The run of empty rows collapsed just above a row, if any.
+    fn collapsed_at(&self, row_index: usize) -> Option<&CollapsedRows> {
+        self.collapsed_rows.iter().find(|collapsed| collapsed.row == row_index)
+    }
+
+    /// The rows elided just above a row, if any.
+    fn elided_at(&self, row_index: usize) -> Option<ElidedRows> {
+        self.elided_rows.filter(|elided| elided.row == row_index)
+    }
+
+    /// Whether a row holds the column totals.
+    fn is_totals_row(&self, row_index: usize) -> bool {
+        self.totals_row == Some(row_index)
+    }
+
+    /// The line number shown for a row, which counts the elided rows above it but not the
+    /// subtotal rows. Subtotal rows and the totals row have none.
+    fn line_number(&self, row_index: usize) -> Option<usize> {
+        if self.is_totals_row(row_index) || self.subtotal_rows.contains(&row_index) {
+            return None;
+        }
+        // Rows are elided before subtotal rows are added, so this is the index they go by
+        let data_index = row_index - self.subtotal_rows.iter().filter(|&&row| row < row_index).count();
+        match self.elided_rows {
+            Some(elided) if data_index >= elided.row => Some(data_index + elided.count + 1),
+            _ => Some(data_index + 1),
+        }
+    }
+
+    /// The terminal color for a column, or None when colors are disabled
+    /// or columns aren't colored (`--row-colors stripe`). Headers and data cells both take it
+    /// from here, so a column's header always matches its values.
+    fn column_color(&self, col_index: usize) -> Option<DynColors> {
+        if self.row_colors == RowColors::Stripe {
+            return None;
+        }
+        let theme = self.theme.as_ref()?;
+        let rgb = match self.column_colors.get(col_index).copied().flatten() {
+            Some(rgb) => rgb,
+            None => theme.color(self.palette_slots.get(col_index).copied().unwrap_or(col_index)),
+        };
+        Some(self.color_depth.convert(rgb))
+    }
+
+    /// Styles header text: bold in the column's color (same color as data cells in this column).
+    /// Uncolored columns still get a bold header when colors are on, and dimmed columns a dimmed one.
+    fn paint_header(&self, text: &str, col_index: usize) -> String {
+        let dimmed = self.is_dimmed_column(col_index);
+        match self.column_color(col_index) {
+            Some(color) if dimmed => text.color(color).bold().dimmed().to_string(),
+            Some(color) => text.color(color).bold().to_string(),
+            None if dimmed => text.bold().dimmed().to_string(),
+            None if self.theme.is_some() => text.bold().to_string(),
+            None => text.to_string(),
+        }
+    }
+
+    /// Styles one line of a data cell whose full value is `value`: colored by a matching --rule
+    /// or the column color, dimmed for missing values and in dimmed columns, bold in the totals
+    /// row, and linked when the cell has a link.
+    fn paint_cell(&self, text: &str, value: &str, row_index: usize, col_index: usize) -> String {
+        let dimmed = (self.theme.is_some() && self.is_null_cell(value)) || self.is_dimmed_column(col_index);
+        let painted = match self.cell_color(row_index, col_index) {
+            Some(color) => text.color(color).to_string(),
+            None => text.to_string(),
+        };
+        let painted = if dimmed { intensity(DIM, &painted) } else { painted };
+        let painted = if self.theme.is_some() && self.is_totals_row(row_index) { intensity(BOLD, &painted) } else { painted };
+        // Each line is linked on its own, so a link never spans the borders between lines
+        match self.links.get(row_index).and_then(|row| row.get(col_index)).and_then(Option::as_ref) {
+            Some(target) if !text.is_empty() => format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", target, painted),
+            _ => painted,
+        }
+    }
+
+    /// Whether a data cell holds a missing value and should be dimmed.
+    fn is_null_cell(&self, value: &str) -> bool {
+        is_null(value) || (!self.null_display.is_empty() && value == self.null_display)
+    }
+
+    /// Whether a column is drawn dimmed, which takes colors.
+    fn is_dimmed_column(&self, col_index: usize) -> bool {
+        self.theme.is_some() && self.dimmed_columns.get(col_index).copied().unwrap_or(false)
+    }
+
+    /// The number of lines a cell may take, if bounded.
+    fn max_lines(&self, row_index: usize, col_index: usize) -> Option<usize> {
+        if self.expanded_rows.contains(&row_index) {
+            return None;
+        }
+        self.max_cell_lines.get(col_index).copied().flatten().or(self.max_row_height)
+    }
+
+    /// Whether a column's numbers are lined up on their decimal points.
+    pub(crate) fn is_decimal_column(&self, col_index: usize) -> bool {
+        self.decimal_columns.get(col_index).copied().unwrap_or(false)
+    }
+
+    /// The alignment of a column, left unless given.
+    fn alignment(&self, col_index: usize) -> Alignment {
+        self.alignments.get(col_index).copied().unwrap_or(Alignment::Left)
+    }
+
+    /// The background for a data row, set on every other row when striping.
+    fn row_background(&self, row_index: usize) -> Option<DynColors> {
+        if self.row_colors == RowColors::Columns || row_index.is_multiple_of(2) {
+            return None;
+        }
+        self.stripe_background.map(|rgb| self.color_depth.convert(rgb))
+    }
+
+    /// The terminal color for a data cell: a matching --rule color, else the column color.
+    fn cell_color(&self, row_index: usize, col_index: usize) -> Option<DynColors> {
+        self.theme.as_ref()?;
+        let highlight = self.highlights.get(row_index).and_then(|row| row.get(col_index).copied().flatten());
+        match highlight {
+            Some(rgb) => Some(self.color_depth.convert(rgb)),
+            None => self.column_color(col_index),
+        }
+    }
+}
+
+/// The first row of a group of rows, which is set apart from the rows above it by a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupStart {
+    /// Index of the row, counting from 0.
+    pub row: usize,
+    /// Text set into the rule above the group, e.g. the value its rows share.
+    pub label: Option<String>,
+}
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+
+/// Makes data cell text bold or dim. Unlike owo-colors' `bold()` and `dimmed()`, which end with a
+/// full reset, this ends with `\x1b[22m` (normal intensity), so the stripe background set around
+/// the whole line carries on past the cell.
+fn intensity(code: &str, text: &str) -> String {
+    format!("{}{}\x1b[22m", code, text)
+}
+
+/// A run of empty rows left out of the table and shown as one dimmed `⋮ (12 empty rows)` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollapsedRows {
+    /// Index of the row the marker goes above, counting from 0. The number of rows puts it
+    /// below the last row.
+    pub row: usize,
+    /// Number of rows left out.
+    pub count: usize,
+}
+
+/// Rows left out of the middle of a long table and shown as one dimmed, centered
+/// `⋯ 1,234 more rows ⋯` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElidedRows {
+    /// Index of the row the line goes above, counting from 0. The number of rows puts it below
+    /// the last row.
+    pub row: usize,
+    /// Number of rows left out.
+    pub count: usize,
+}
+
+/// Which column the rows are sorted by, and in which direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortIndicator {
+    /// Position of the column.
+    pub column: usize,
+    pub descending: bool,
+}
+
+impl SortIndicator {
+    /// The headers with an arrow after the sorted column's name: `▲` when ascending, `▼` when
+    /// descending. Measure these rather than the plain headers when fitting columns.
+    pub fn mark(self, headers: &csv::StringRecord) -> csv::StringRecord {
+        let arrow = if self.descending { "▼" } else { "▲" };
+        headers.iter()
+            .enumerate()
+            .map(|(col_idx, header)| if col_idx == self.column { format!("{} {}", header, arrow) } else { header.to_string() })
+            .collect()
+    }
+}
+
+/// How cells wider than their column are fitted.
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum WrapMode {
+    Word,
+    Char,
+    Truncate,
+    None,
+}
+
+/// How rows are colored when a theme is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RowColors {
+    Columns,
+    Stripe,
+    Both,
+}
+
+/// A named set of border characters.
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum TableStyle {
+    /// Thin box-drawing lines (─ │ ┼)
+    Unicode,
+    /// Plain ASCII (- | +) for dumb terminals and logs
+    Ascii,
+    /// Thin box-drawing lines in a frame with rounded corners (╭ ╮ ╰ ╯)
+    Rounded,
+    /// Double box-drawing lines (═ ║ ╬)
+    Double,
+    /// Heavy box-drawing lines (━ ┃ ╋)
+    Heavy,
+    /// Whitespace only, no rules
+    None,
+}
+
+/// Characters used to draw the table grid, selected via `--style`.
+#[derive(Debug, Clone, Copy)]
+pub struct BorderStyle {
+    /// Horizontal rule used for the top/bottom borders and the header separator
+    pub horizontal: char,
+    /// Separator between columns
+    pub vertical: char,
+    /// Separator between the line-number gutter and the first column
+    pub gutter: char,
+    /// Shown in the line-number gutter on the continuation lines of a wrapped row
+    pub continuation: char,
+    /// Junction where a separator meets a rule from below only (top border)
+    pub down_junction: char,
+    /// Junction where a separator meets a rule from above only (bottom border)
+    pub up_junction: char,
+    /// Junction where a separator crosses a rule (header separator)
+    pub cross: char,
+    /// Frame around the edges of the table, or None to leave them open. The record view
+    /// (`--vertical`) has no frame.
+    pub frame: Option<Frame>,
+}
+
+/// Characters of the frame a style draws around the table.
+#[derive(Debug, Clone, Copy)]
+pub struct Frame {
+    /// Left and right edges of the rows
+    pub side: char,
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    /// Where a rule inside the table meets the left edge
+    pub left_junction: char,
+    /// Where a rule inside the table meets the right edge
+    pub right_junction: char,
+}
+
+impl BorderStyle {
+    /// Columns the frame takes up beyond those the layout leaves for separators. Its two edges
+    /// count one more than the separator after the last column, which is never drawn.
+    pub fn frame_overhead(&self) -> usize {
+        usize::from(self.frame.is_some())
+    }
+
+    /// Whitespace-only styles skip horizontal rules entirely instead of printing blank lines.
+    fn has_rules(&self) -> bool {
+        !self.horizontal.is_whitespace()
+    }
+
+    /// The character where a rule meets column separators from above and/or below.
+    fn junction(&self, above: bool, below: bool) -> char {
+        match (above, below) {
+            (true, true) => self.cross,
+            (true, false) => self.up_junction,
+            (false, true) => self.down_junction,
+            (false, false) => self.horizontal,
+        }
+    }
+}
+
+impl TableStyle {
+    /// The characters this style draws with.
+    pub fn border_style(self) -> BorderStyle {
+        match self {
+            TableStyle::Unicode => BorderStyle {
+                horizontal: '─',
+                vertical: '│',
+                gutter: '│',
+                continuation: ' ',
+                down_junction: '┬',
+                up_junction: '┴',
+                cross: '┼',
+                frame: None,
+            },
+            TableStyle::Rounded => BorderStyle {
+                frame: Some(Frame {
+                    side: '│',
+                    top_left: '╭',
+                    top_right: '╮',
+                    bottom_left: '╰',
+                    bottom_right: '╯',
+                    left_junction: '├',
+                    right_junction: '┤',
+                }),
+                ..TableStyle::Unicode.border_style()
+            },
+            TableStyle::Ascii => BorderStyle {
+                horizontal: '-',
+                vertical: '|',
+                gutter: '|',
+                continuation: ' ',
+                down_junction: '+',
+                up_junction: '+',
+                cross: '+',
+                frame: None,
+            },
+            TableStyle::Double => BorderStyle {
+                horizontal: '═',
+                vertical: '║',
+                gutter: '║',
+                continuation: ' ',
+                down_junction: '╦',
+                up_junction: '╩',
+                cross: '╬',
+                frame: None,
+            },
+            TableStyle::Heavy => BorderStyle {
+                horizontal: '━',
+                vertical: '┃',
+                gutter: '┃',
+                continuation: ' ',
+                down_junction: '┳',
+                up_junction: '┻',
+                cross: '╋',
+                frame: None,
+            },
+            TableStyle::None => BorderStyle {
+                horizontal: ' ',
+                vertical: ' ',
+                gutter: ' ',
+                continuation: ' ',
+                down_junction: ' ',
+                up_junction: ' ',
+                cross: ' ',
+                frame: None,
+            },
+        }
+    }
+}
+
+/// Repeats a border character `count` times.
+fn rule(ch: char, count: usize) -> String {
+    std::iter::repeat_n(ch, count).collect()
+}
+
+/// Horizontal alignment of the text in a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Alignment {
+    Left,
+    Right,
+    Center,
+}
+
+/// Where a cell with fewer lines sits within a taller row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum VerticalAlignment {
+    Top,
+    Middle,
+    Bottom,
+}
+
+impl VerticalAlignment {
+    /// Number of blank lines above a cell of `lines` lines in a row of `row_lines` lines.
+    fn offset(self, lines: usize, row_lines: usize) -> usize {
+        let spare = row_lines.saturating_sub(lines);
+        match self {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Middle => spare / 2,
+            VerticalAlignment::Bottom => spare,
+        }
+    }
+}
+
+/// Splits the padding for a cell into (left, right) amounts according to its alignment.
+fn split_padding(padding: usize, alignment: Alignment) -> (usize, usize) {
+    match alignment {
+        Alignment::Left => (0, padding),
+        Alignment::Right => (padding, 0),
+        Alignment::Center => (padding / 2, padding - padding / 2),
+    }
+}
+
+/// Renders one table with fixed options. The column layout is computed up front, so the
+/// header and the rows can be written to different places, e.g. a pager that pins the header.
+pub struct Renderer<'a> {
+    table: &'a Table<'a>,
+    /// The table's headers as shown, with the sort arrow
+    headers: csv::StringRecord,
+    options: &'a RenderOptions,
+    layout: TableLayout,
+}
+
+impl<'a> Renderer<'a> {
+    /// Lays the table out. A row without a cell for every header is an [`Error::Layout`].
+    pub fn new(table: &'a Table<'a>, options: &'a RenderOptions) -> Result<Self, Error> {
+        for (index, row) in table.records.iter().enumerate() {
+            check_row_length(index, row, table.headers.len())?;
+        }
+        let headers = match options.sort_indicator {
+            Some(indicator) => indicator.mark(&table.headers),
+            None => table.headers.clone(),
+        };
+        let layout = TableLayout::new(&headers, &table.records, options);
+        Ok(Renderer { table, headers, options, layout })
+    }
+
+    /// Renders the whole table: the header followed by the rows.
+    pub fn render(&self, out: &mut impl Write) -> io::Result<()> {
+        self.render_header(out)?;
+        self.render_rows(out)
+    }
+
+    /// Renders the top border, the header row and the separator below it. The vertical view
+    /// has no header row, so this renders nothing there.
+    pub fn render_header(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.options.vertical {
+            return Ok(());
+        }
+        render_header(out, &self.headers, &self.layout, self.options)
+    }
+
+    /// Renders the data rows, or the records of the vertical view.
+    pub fn render_rows(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.options.vertical {
+            return render_vertical(out, &self.headers, &self.table.records, self.options, None);
+        }
+        render_rows(out, &self.table.records, &self.layout, self.options, None)
+    }
+
+    /// Renders the data rows like [`Renderer::render_rows`], returning the line each row starts
+    /// on, counting from 0. The vertical view gives the line of each record's rule.
+    pub fn render_rows_with_starts(&self, out: &mut impl Write) -> io::Result<Vec<usize>> {
+        let mut starts = Vec::new();
+        if self.options.vertical {
+            render_vertical(out, &self.headers, &self.table.records, self.options, Some(&mut starts))?;
+        } else {
+            render_rows(out, &self.table.records, &self.layout, self.options, Some(&mut starts))?;
+        }
+        Ok(starts)
+    }
+
+    /// Renders the data row at `index`, counting from 0, e.g. to fill pages of a fixed height.
+    /// Its line number counts from 1. The totals row comes with the rule above it.
+    pub fn render_row(&self, out: &mut impl Write, index: usize) -> io::Result<()> {
+        if self.options.is_totals_row(index) {
+            print_rule(out, &self.layout, RuleSide::Data, RuleSide::Data, self.options)?;
+        }
+        print_data_row(out, index + 1, &self.table.records[index], &self.layout, self.options)
+    }
+
+    /// Renders the border below the last row, which only the no-wrap mode has, and the caption.
+    pub fn render_bottom(&self, out: &mut impl Write) -> io::Result<()> {
+        render_bottom(out, &self.layout, self.options)
+    }
+}
+
+/// Renders a table whose rows arrive one at a time, e.g. while a large input is still being
+/// read. The layout is made from the rows read before the first is rendered; a later row that
+/// doesn't fit it widens the columns (in no-wrap mode) or the line-number gutter, with a rule
+/// marking where the widths change. The vertical view isn't supported.
+pub struct ProgressiveRenderer<'a> {
+    /// The table's headers as shown, with the sort arrow
+    headers: csv::StringRecord,
+    options: &'a RenderOptions,
+    layout: TableLayout,
+    /// Rows rendered so far
+    rows: usize,
+}
+
+impl<'a> ProgressiveRenderer<'a> {
+    /// Lays the table out from its headers and `sample`, the first rows. A sample row without a
+    /// cell for every header is an [`Error::Layout`].
+    pub fn new(headers: &csv::StringRecord, sample: &[Vec<Cell>], options: &'a RenderOptions) -> Result<Self, Error> {
+        for (index, row) in sample.iter().enumerate() {
+            check_row_length(index, row, headers.len())?;
+        }
+        let headers = match options.sort_indicator {
+            Some(indicator) => indicator.mark(headers),
+            None => headers.clone(),
+        };
+        let layout = TableLayout::new(&headers, sample, options);
+        Ok(ProgressiveRenderer { headers, options, layout, rows: 0 })
+    }
+
+    /// Renders the top border, the header row and the separator below it.
+    pub fn render_header(&self, out: &mut impl Write) -> io::Result<()> {
+        render_header(out, &self.headers, &self.layout, self.options)
+    }
+
+    /// Renders the next data row, widening the layout first if the row doesn't fit it. A row
+    /// without a cell for every header is an [`Error::Layout`], and nothing of it is rendered.
+    pub fn render_row(&mut self, out: &mut impl Write, record: &[Cell]) -> Result<(), Error> {
+        let index = self.rows;
+        check_row_length(index, record, self.headers.len())?;
+        if let Some(group) = self.options.group_at(index).filter(|_| index > 0) {
+            print_group_rule(out, &self.layout, RuleSide::Data, group.label.as_deref(), self.options)?;
+        }
+        if let Some(collapsed) = self.options.collapsed_at(index) {
+            print_collapsed_rows(out, collapsed.count, &self.layout, self.options)?;
+        }
+        if let Some(elided) = self.options.elided_at(index) {
+            print_elided_rows(out, elided.count, &self.layout, self.options)?;
+        }
+        if self.layout.widen_for(record, index + 1, self.options) {
+            print_rule(out, &self.layout, RuleSide::Data, RuleSide::Data, self.options)?;
+        }
+        self.rows += 1;
+        Ok(print_data_row(out, index + 1, record, &self.layout, self.options)?)
+    }
+
+    /// Renders what comes after the last row: in no-wrap mode the bottom border, and the caption.
+    pub fn finish(&self, out: &mut impl Write) -> io::Result<()> {
+        if let Some(collapsed) = self.options.collapsed_at(self.rows) {
+            print_collapsed_rows(out, collapsed.count, &self.layout, self.options)?;
+        }
+        if let Some(elided) = self.options.elided_at(self.rows) {
+            print_elided_rows(out, elided.count, &self.layout, self.options)?;
+        }
+        render_bottom(out, &self.layout, self.options)
+    }
+}
+
+/// Counts the lines written through it, so that renderers can note where rows start.
+struct LineCounter<'a, W: Write> {
+    out: &'a mut W,
+    lines: usize,
+}
+
+impl<W: Write> Write for LineCounter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.out.write(buf)?;
+        self.lines += buf[..written].iter().filter(|&&byte| byte == b'\n').count();
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// The error for the row at `index`, counting from 0, when it doesn't have one cell per column.
+fn check_row_length(index: usize, row: &[Cell], columns: usize) -> Result<(), Error> {
+    if row.len() == columns {
+        return Ok(());
+    }
+    let noun = if row.len() == 1 { "cell" } else { "cells" };
+    Err(Error::Layout(format!("row {} has {} {} but the table has {} columns", index + 1, row.len(), noun, columns)))
+}
+
+/// Renders each record as a block of `field │ value` lines under a `─[ RECORD n ]─` rule,
+/// like psql's expanded display. Values wrap in the space left over by the field names.
+/// The line each record's rule is on goes into `starts`, when given.
+fn render_vertical(out: &mut impl Write, headers: &csv::StringRecord, records: &[Vec<Cell>], options: &RenderOptions, mut starts: Option<&mut Vec<usize>>) -> io::Result<()> {
+    let out = &mut LineCounter { out, lines: 0 };
+    let field_width = headers.iter().map(display_width).max().unwrap_or(0);
+    // Each line is "field │ value": the field names plus the separator and its padding
+    let overhead = column_overhead(options.padding);
+    let available = options.terminal_width.saturating_sub(field_width + overhead).max(1);
+    let value_width = options.max_col_width.map_or(available, |max| available.min(max));
+    let widest_value = records.iter().flatten().map(|cell| capped_width(cell, value_width)).max().unwrap_or(0);
+    let total_width = field_width + overhead + widest_value.min(value_width);
+    let pad = " ".repeat(options.padding);
+
+    let horizontal = options.border.horizontal;
+    if let Some(title) = &options.title {
+        writeln!(out, "{}", title_rule(title, &rule(horizontal, total_width), options.title_alignment, options))?;
+    }
+    for (row_index, record) in records.iter().enumerate() {
+        if let Some(starts) = starts.as_deref_mut() {
+            starts.push(out.lines);
+        }
+        // The rule meets the field/value separator of the records above and below it
+        let record_rule = format!(
+            "{}{}{}",
+            rule(horizontal, field_width + options.padding),
+            options.border.junction(row_index > 0, true),
+            rule(horizontal, total_width.saturating_sub(field_width + options.padding + 1)),
+        );
+        let label = if options.is_totals_row(row_index) {
+            format!("{}[ TOTALS ]", horizontal)
+        } else {
+            format!("{}[ RECORD {} ]", horizontal, row_index + 1)
+        };
+        let label_width = display_width(label.as_str());
+        writeln!(out, "{}{}", label, record_rule.chars().skip(label_width).collect::<String>())?;
+
+        for (col_index, header) in headers.iter().enumerate() {
+            let value = &record[col_index];
+            for (line_idx, line) in wrap_text(value, value_width, options.wrap_mode, &options.truncation_marker, options.ellipsis).iter().enumerate() {
+                // The field name goes on the first line of a wrapped value only
+                let field = if line_idx == 0 { escape_control_chars(header) } else { Cow::Borrowed("") };
+                let padding = field_width - display_width(&field);
+                writeln!(
+                    out,
+                    "{}{}{}{}{}{}",
+                    options.paint_header(&field, col_index),
+                    " ".repeat(padding),
+                    pad,
+                    options.border.vertical,
+                    pad,
+                    options.paint_cell(line, value, row_index, col_index),
+                )?;
+            }
+        }
+    }
+    if let Some(caption) = &options.caption {
+        print_caption(out, caption, total_width, options)?;
+    }
+    Ok(())
+}
+
+/// Renders the top border, the header row and the separator below it.
+fn render_header(out: &mut impl Write, headers: &csv::StringRecord, layout: &TableLayout, options: &RenderOptions) -> io::Result<()> {
+    let header_vec: Vec<&str> = headers.iter().collect();
+
+    // Render top border
+    print_top_border(out, layout, options)?;
+
+    // Render header
+    print_header_row(out, &header_vec, &layout.col_widths, layout.row_num_width, options)?;
+
+    // Render separator after header, which names the first group
+    let label = options.group_at(0).and_then(|group| group.label.as_deref());
+    print_group_rule(out, layout, RuleSide::Header, label, options)
+}
+
+/// Renders the data rows and, in no-wrap mode, the bottom border. The line each row starts on
+/// goes into `starts`, when given.
+fn render_rows(out: &mut impl Write, records: &[Vec<Cell>], layout: &TableLayout, options: &RenderOptions, mut starts: Option<&mut Vec<usize>>) -> io::Result<()> {
+    let out = &mut LineCounter { out, lines: 0 };
+    // Render data rows
+    for (idx, record) in records.iter().enumerate() {
+        // The header separator already sets the first group apart
+        if let Some(group) = options.group_at(idx).filter(|_| idx > 0) {
+            print_group_rule(out, layout, RuleSide::Data, group.label.as_deref(), options)?;
+        }
+        if let Some(collapsed) = options.collapsed_at(idx) {
+            print_collapsed_rows(out, collapsed.count, layout, options)?;
+        }
+        if let Some(elided) = options.elided_at(idx) {
+            print_elided_rows(out, elided.count, layout, options)?;
+        }
+        if options.is_totals_row(idx) {
+            print_rule(out, layout, RuleSide::Data, RuleSide::Data, options)?;
+        }
+        if let Some(starts) = starts.as_deref_mut() {
+            starts.push(out.lines);
+        }
+        print_data_row(out, idx + 1, record, layout, options)?;
+    }
+    if let Some(collapsed) = options.collapsed_at(records.len()) {
+        print_collapsed_rows(out, collapsed.count, layout, options)?;
+    }
+    if let Some(elided) = options.elided_at(records.len()) {
+        print_elided_rows(out, elided.count, layout, options)?;
+    }
+
+    render_bottom(out, layout, options)
+}
+
+/// Renders the bottom border (only for no-wrap mode to match the example, or to close a frame)
+/// and the caption.
+fn render_bottom(out: &mut impl Write, layout: &TableLayout, options: &RenderOptions) -> io::Result<()> {
+    if matches!(options.wrap_mode, WrapMode::None) || options.border.frame.is_some() {
+        print_rule(out, layout, RuleSide::Data, RuleSide::Outside, options)?;
+    }
+    if let Some(caption) = &options.caption {
+        // Every rule character is one column wide
+        let width = compose_rule(layout, RuleSide::Data, RuleSide::Outside, options).chars().count();
+        print_caption(out, caption, width, options)?;
+    }
+    Ok(())
+}
+
+/// Prints the caption in italics under a table `width` columns wide, wrapped to fit and placed
+/// like the title. Left-aligned captions line up with the cell text.
+fn print_caption(out: &mut impl Write, caption: &str, width: usize, options: &RenderOptions) -> io::Result<()> {
+    let text_width = width.saturating_sub(2 * options.padding).max(1);
+    for line in wrap_text(caption, text_width, WrapMode::Word, &options.truncation_marker, options.ellipsis) {
+        let fill = width.saturating_sub(display_width(&line));
+        let left = match options.title_alignment {
+            Alignment::Left => options.padding.min(fill),
+            Alignment::Right => fill.saturating_sub(options.padding),
+            Alignment::Center => fill / 2,
+        };
+        let line = if options.theme.is_some() { line.italic().to_string().into() } else { line };
+        writeln!(out, "{}{}", " ".repeat(left), line)?;
+    }
+    Ok(())
+}
+
+/// Prints the line standing in for `count` collapsed empty rows.
+fn print_collapsed_rows(out: &mut impl Write, count: usize, layout: &TableLayout, options: &RenderOptions) -> io::Result<()> {
+    let plural = if count == 1 { "" } else { "s" };
+    print_marker_line(out, &format!("⋮ ({} empty row{})", count, plural), Alignment::Left, layout, options)
+}
+
+/// Prints the line standing in for `count` elided rows, e.g. `⋯ 1,234 more rows ⋯`.
+fn print_elided_rows(out: &mut impl Write, count: usize, layout: &TableLayout, options: &RenderOptions) -> io::Result<()> {
+    // Digits grouped in threes, as the count is read at a glance
+    let digits = count.to_string();
+    let mut grouped = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let plural = if count == 1 { "" } else { "s" };
+    print_marker_line(out, &format!("⋯ {} more row{} ⋯", grouped, plural), Alignment::Center, layout, options)
+}
+
+/// Prints a line of `text` standing in for rows left out, across all columns and dimmed when
+/// colors are on.
+fn print_marker_line(out: &mut impl Write, text: &str, alignment: Alignment, layout: &TableLayout, options: &RenderOptions) -> io::Result<()> {
+    let mut line = String::new();
+    if options.show_line_numbers {
+        line += &format!("{}  {}", " ".repeat(layout.row_num_width), options.border.gutter);
+    }
+    // Data lines run " {cell}" per column with " │" between them, for the default padding of 1
+    let overhead = column_overhead(options.padding);
+    let width = layout.col_widths.iter().map(|&width| width + overhead).sum::<usize>().saturating_sub(overhead);
+    let text = truncate_to_width(text, width);
+    let (pad_left, pad_right) = split_padding(width.saturating_sub(display_width(text.as_str())), alignment);
+    let text = if options.theme.is_some() { text.dimmed().to_string() } else { text };
+    let line = format!("{}{}{}{}{}", line, " ".repeat(options.padding), " ".repeat(pad_left), text, " ".repeat(pad_right));
+    writeln!(out, "{}", framed(line, options))
+}
+
+/// Puts a line of the table between the edges of the frame, when the style has one. The line
+/// ends at the last column's text, so its padding goes in front of the right edge.
+fn framed(line: String, options: &RenderOptions) -> String {
+    match options.border.frame {
+        Some(frame) => format!("{}{}{}{}", frame.side, line, " ".repeat(options.padding), frame.side),
+        None => line,
+    }
+}
+
+/// What lies on one side of a horizontal rule. This decides which column separators run into
+/// the rule, and so which junction is drawn where they meet it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleSide {
+    /// Nothing: the rule is the top or bottom edge of the table
+    Outside,
+    /// The header row, which has separators between columns but leaves the line-number gutter blank
+    Header,
+    /// Data rows, with separators between columns and after the line-number gutter
+    Data,
+}
+
+impl RuleSide {
+    /// Whether a separator runs into the rule at the boundary after the line-number gutter.
+    fn gutter_separator(self) -> bool {
+        self == RuleSide::Data
+    }
+
+    /// Whether separators run into the rule at the boundaries between columns.
+    fn column_separator(self) -> bool {
+        self != RuleSide::Outside
+    }
+}
+
+/// Composes a horizontal rule across the grid between `above` and `below`, with a junction
+/// wherever it meets a column separator: `┬` where one starts, `┴` where one ends and `┼`
+/// where one crosses it.
+fn compose_rule(layout: &TableLayout, above: RuleSide, below: RuleSide, options: &RenderOptions) -> String {
+    let style = &options.border;
+    let mut line = String::new();
+    if options.show_line_numbers {
+        // The gutter is "{:>width$}  │" = row_num_width + 3 chars, the junction replacing the │
+        line.push_str(&rule(style.horizontal, layout.row_num_width + 2));
+        line.push(style.junction(above.gutter_separator(), below.gutter_separator()));
+    }
+    for (i, &width) in layout.col_widths.iter().enumerate() {
+        if i > 0 {
+            line.push(style.junction(above.column_separator(), below.column_separator()));
+        }
+        // Each column prints its text between the padding on either side
+        line.push_str(&rule(style.horizontal, width + 2 * options.padding));
+    }
+    let Some(frame) = style.frame else {
+        return line;
+    };
+    let (left, right) = match (above, below) {
+        (RuleSide::Outside, _) => (frame.top_left, frame.top_right),
+        (_, RuleSide::Outside) => (frame.bottom_left, frame.bottom_right),
+        _ => (frame.left_junction, frame.right_junction),
+    };
+    format!("{}{}{}", left, line, right)
+}
+
+/// Prints a rule between `above` and `below`. Styles without rules print nothing.
+fn print_rule(out: &mut impl Write, layout: &TableLayout, above: RuleSide, below: RuleSide, options: &RenderOptions) -> io::Result<()> {
+    if !options.border.has_rules() {
+        return Ok(());
+    }
+    writeln!(out, "{}", compose_rule(layout, above, below, options))
+}
+
+/// Prints the rule above a group of rows, with the group's label set into it when it has one.
+fn print_group_rule(out: &mut impl Write, layout: &TableLayout, above: RuleSide, label: Option<&str>, options: &RenderOptions) -> io::Result<()> {
+    match label {
+        Some(label) => {
+            let rule = compose_rule(layout, above, RuleSide::Data, options);
+            writeln!(out, "{}", title_rule(label, &rule, Alignment::Left, options))
+        }
+        None => print_rule(out, layout, above, RuleSide::Data, options),
+    }
+}
+
+/// Prints the line across the top of the table, with the title embedded in it when there is
+/// one. Styles without rules only print a line for the title.
+fn print_top_border(out: &mut impl Write, layout: &TableLayout, options: &RenderOptions) -> io::Result<()> {
+    match &options.title {
+        Some(title) => {
+            let top = compose_rule(layout, RuleSide::Outside, RuleSide::Header, options);
+            writeln!(out, "{}", title_rule(title, &top, options.title_alignment, options))
+        }
+        None => print_rule(out, layout, RuleSide::Outside, RuleSide::Header, options),
+    }
+}
+
+/// Sets the title into a rule, e.g. `── Q3 Revenue ──┬──`, covering any junctions under it.
+/// Titles too long for the rule are cut off.
+fn title_rule(title: &str, rule: &str, alignment: Alignment, options: &RenderOptions) -> String {
+    // Every rule character is one column wide
+    let rule: Vec<char> = rule.chars().collect();
+    let width = rule.len();
+    // Keep at least two rule characters and a space on either side of the title
+    let title = truncate_to_width(title, width.saturating_sub(6).max(1));
+    let title_width = display_width(title.as_str());
+    let fill = width.saturating_sub(title_width + 2);
+    let left = match alignment {
+        Alignment::Left => 2.min(fill),
+        Alignment::Right => fill.saturating_sub(2),
+        Alignment::Center => fill / 2,
+    };
+    let title = if options.theme.is_some() { title.bold().to_string() } else { title };
+    let before: String = rule[..left].iter().collect();
+    let after: String = rule[(left + title_width + 2).min(width)..].iter().collect();
+    format!("{} {} {}", before, title, after)
+}
+
+/// Prints the header row with optional colors and bold formatting.
+/// Each column gets a color from the theme palette, cycling through colors.
+/// Headers are always bold when colors are enabled.
+fn print_header_row(out: &mut impl Write, headers: &[&str], col_widths: &[usize], row_num_width: usize, options: &RenderOptions) -> io::Result<()> {
+    let mut line = String::new();
+    // Match the data row format: "{:>width$}  │" = row_num_width + 3 chars (if line numbers enabled)
+    if options.show_line_numbers {
+        line += &" ".repeat(row_num_width + 3);
+    }
+    let pad = " ".repeat(options.padding);
+    for (i, &header) in headers.iter().enumerate() {
+        let width = col_widths[i];
+        // Headers never wrap, so cut them off rather than spill into the next column
+        let header = truncate_to_width(header, usable_width(width, options));
+        let header = header.as_str();
+        let header_width = display_width(header);
+        let (pad_left, pad_right) = split_padding(width.saturating_sub(header_width), options.alignment(i));
+
+        line += &format!("{}{}{}{}", pad, " ".repeat(pad_left), options.paint_header(header, i), " ".repeat(pad_right));
+
+        // Print separator only between columns, not after the last one
+        if i < headers.len() - 1 {
+            line += &format!("{}{}", pad, options.border.vertical);
+        }
+    }
+    writeln!(out, "{}", framed(line, options))
+}
+
+/// Keeps the first `max_lines` lines of a cell, ending the last one in `(+N lines)` for the
+/// lines left out. The count takes the place of the end of the line when there's no room
+/// next to it.
+fn cut_lines(mut lines: Vec<Cow<'_, str>>, max_lines: usize, width: usize) -> Vec<Cow<'_, str>> {
+    let max_lines = max_lines.max(1);
+    if lines.len() <= max_lines {
+        return lines;
+    }
+    let hidden = lines.len() - max_lines;
+    lines.truncate(max_lines);
+    let plural = if hidden == 1 { "" } else { "s" };
+    let count = format!("(+{} line{})", hidden, plural);
+    let last = lines.last_mut().expect("at least one line is kept");
+    let room = width.saturating_sub(display_width(count.as_str()) + 1);
+    *last = match room {
+        0 => truncate_to_width(&count, width),
+        room => format!("{} {}", truncate_to_width(last, room), count),
+    }.into();
+    lines
+}
+
+/// Prints a data row with optional line numbers and colors.
+/// Handles multi-line cells by wrapping text and aligning all cells to the tallest cell.
+/// Each column uses the same color as its header (cycling through the palette), and with
+/// `--row-colors stripe|both` every other row gets a background across its full width.
+fn print_data_row(out: &mut impl Write, row_num: usize, record: &[Cell], layout: &TableLayout, options: &RenderOptions) -> io::Result<()> {
+    let col_widths = &layout.col_widths;
+    let row_num_width = layout.row_num_width;
+    // Wrap each cell, cut it to its line bound and determine max lines needed
+    let wrapped_cells: Vec<Vec<Cow<str>>> = record.iter()
+        .zip(col_widths.iter())
+        .enumerate()
+        .map(|(col_idx, (cell, &width))| {
+            let width = usable_width(width, options);
+            let lines = wrap_text(cell, width, options.wrap_mode, &options.truncation_marker, options.ellipsis);
+            match options.max_lines(row_num - 1, col_idx) {
+                Some(max_lines) => cut_lines(lines, max_lines, width),
+                None => lines,
+            }
+        })
+        .collect();
+
+    let max_lines = wrapped_cells.iter().map(|lines| lines.len()).max().unwrap_or(1);
+
+    // Build each line of the multi-line row, then print it with the row's stripe background
+    let background = options.row_background(row_num - 1);
+    let pad = " ".repeat(options.padding);
+    for line_idx in 0..max_lines {
+        let mut line = String::new();
+        if options.show_line_numbers {
+            if let (0, Some(line_number)) = (line_idx, options.line_number(row_num - 1)) {
+                // First line: show row number
+                line += &format!("{:>width$}  {}", line_number, options.border.gutter, width = row_num_width);
+            } else {
+                // Subsequent lines and summary rows: the continuation mark where the row number ends
+                line += &format!("{:>width$}  {}", options.border.continuation, options.border.gutter, width = row_num_width);
+            }
+        }
+
+        for (col_idx, lines) in wrapped_cells.iter().enumerate() {
+            let width = col_widths[col_idx];
+            let offset = options.vertical_alignment.offset(lines.len(), max_lines);
+            let text = line_idx.checked_sub(offset).and_then(|idx| lines.get(idx)).map_or("", |line| line.as_ref());
+            let text_width = display_width(text);
+            let (mut pad_left, mut pad_right) = split_padding(width.saturating_sub(text_width), options.alignment(col_idx));
+            // Numbers with a shorter fraction than the column's widest stop short of the right
+            // edge, so that the decimal points line up
+            let fraction = layout.fraction_widths.get(col_idx).copied().unwrap_or(0);
+            if fraction > 0 && lines.len() == 1 && is_numeric(text) {
+                let shift = fraction.saturating_sub(fraction_width(text)).min(pad_left);
+                pad_left -= shift;
+                pad_right += shift;
+            }
+
+            let text = options.paint_cell(text, &record[col_idx], row_num - 1, col_idx);
+            line += &format!("{}{}{}{}", pad, " ".repeat(pad_left), text, " ".repeat(pad_right));
+
+            // Print separator only between columns, not after the last one
+            if col_idx < wrapped_cells.len() - 1 {
+                line += &format!("{}{}", pad, options.border.vertical);
+            }
+        }
+
+        // The background runs on through the padding in front of the frame's right edge
+        let line = match (background, options.border.frame) {
+            (Some(background), Some(frame)) => format!("{}{}{}", frame.side, format!("{}{}", line, pad).on_color(background), frame.side),
+            (Some(background), None) => line.on_color(background).to_string(),
+            (None, _) => framed(line, options),
+        };
+        writeln!(out, "{}", line)?;
+    }
+    Ok(())
+}
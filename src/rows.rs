@@ -0,0 +1,162 @@
+//! Reshaping the rows before they're drawn: filtering them, dropping duplicates, sorting,
+//! and collapsing or leaving out runs of rows. Each works on the rows alone, with the columns
+//! already looked up.
+
+use crate::column_types::ColumnType;
+use crate::dates;
+use crate::inference::{self, InferredType};
+use chrono::NaiveDateTime;
+use csvpretty::{is_numeric, Cell, CollapsedRows, ElidedRows};
+use std::collections::{HashMap, HashSet};
+
+/// A cell value as seen by the sort: numbers order before dates, booleans and text,
+/// and empty cells come last.
+#[derive(PartialEq, PartialOrd)]
+enum SortKey<'a> {
+    Number(f64),
+    Date(NaiveDateTime),
+    Bool(bool),
+    Text(&'a str),
+    Empty,
+}
+
+impl<'a> SortKey<'a> {
+    fn new(value: &'a str, column_type: Option<ColumnType>, inferred_type: InferredType) -> Self {
+        if value.trim().is_empty() {
+            return SortKey::Empty;
+        }
+        let key = match (column_type, inferred_type) {
+            (Some(column_type), _) => column_type.numeric_value(value).map(SortKey::Number),
+            (None, InferredType::Date) => dates::parse_timestamp(value).map(SortKey::Date),
+            (None, InferredType::Bool) => inference::parse_bool(value).map(SortKey::Bool),
+            (None, _) if is_numeric(value) => value.trim().parse().ok().map(SortKey::Number),
+            (None, _) => None,
+        };
+        key.unwrap_or(SortKey::Text(value))
+    }
+}
+
+/// Stable-sorts rows by a column. Typed columns compare by their underlying value
+/// (e.g. bytes, not the rendered `1.5 MiB`), numeric cells compare as numbers, and
+/// columns inferred to hold dates or booleans compare chronologically or false-first.
+pub fn sort_records(records: &mut [Vec<Cell>], col_idx: usize, column_type: Option<ColumnType>, inferred_type: InferredType, descending: bool) {
+    records.sort_by(|a, b| {
+        let key_a = SortKey::new(a.get(col_idx).map_or("", |cell| &**cell), column_type, inferred_type);
+        let key_b = SortKey::new(b.get(col_idx).map_or("", |cell| &**cell), column_type, inferred_type);
+        let ordering = key_a.partial_cmp(&key_b).unwrap_or(std::cmp::Ordering::Equal);
+        // Empty cells stay at the bottom in both directions
+        let involves_empty = key_a == SortKey::Empty || key_b == SortKey::Empty;
+        if descending && !involves_empty { ordering.reverse() } else { ordering }
+    });
+}
+
+/// Keeps the first of each set of rows with the same values in `key_columns`, or in every
+/// column when there are none.
+pub fn drop_duplicates(records: &mut Vec<Vec<Cell>>, key_columns: &[usize]) {
+    let mut seen = HashSet::new();
+    records.retain(|row| {
+        let key: Vec<Cell> = if key_columns.is_empty() {
+            row.clone()
+        } else {
+            key_columns.iter().map(|&col_idx| row.get(col_idx).cloned().unwrap_or_default()).collect()
+        };
+        seen.insert(key)
+    });
+}
+
+/// Share of the rows a column's most common value must fill for `--dim-constant` to dim it.
+const NEAR_CONSTANT_SHARE: f64 = 0.95;
+
+/// Which columns hold the same value in at least [`NEAR_CONSTANT_SHARE`] of the rows. A single
+/// row says nothing about how a column varies, so no column is constant then.
+pub fn constant_columns(records: &[Vec<Cell>], column_count: usize) -> Vec<bool> {
+    if records.len() < 2 {
+        return vec![false; column_count];
+    }
+    (0..column_count)
+        .map(|col_idx| {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for row in records {
+                *counts.entry(row.get(col_idx).map_or("", |value| value.trim())).or_default() += 1;
+            }
+            let most_common = counts.values().copied().max().unwrap_or(0);
+            most_common as f64 >= records.len() as f64 * NEAR_CONSTANT_SHARE
+        })
+        .collect()
+}
+
+/// Removes the rows whose cells are all blank, returning where each run of them was.
+pub fn collapse_empty_rows(records: &mut Vec<Vec<Cell>>) -> Vec<CollapsedRows> {
+    let mut collapsed: Vec<CollapsedRows> = Vec::new();
+    let mut kept = 0;
+    records.retain(|row| {
+        if !row.iter().all(|cell| cell.trim().is_empty()) {
+            kept += 1;
+            return true;
+        }
+        match collapsed.last_mut() {
+            Some(run) if run.row == kept => run.count += 1,
+            _ => collapsed.push(CollapsedRows { row: kept, count: 1 }),
+        }
+        false
+    });
+    collapsed
+}
+
+/// Keeps the first and last rows, at most `max_rows` of them with the odd one at the start,
+/// returning where the rows in between were left out.
+pub fn elide_rows(records: &mut Vec<Vec<Cell>>, max_rows: usize) -> Option<ElidedRows> {
+    if records.len() <= max_rows {
+        return None;
+    }
+    let head = max_rows.div_ceil(2);
+    let count = records.len() - max_rows;
+    records.drain(head..head + count);
+    Some(ElidedRows { row: head, count })
+}
+
+/// Blanks the values in `columns` that repeat the row above, ignoring surrounding whitespace.
+/// A column is only blanked where the columns before it in `columns` are too, so the runs nest
+/// like the levels of a report. Runs start over at the `breaks` rows (group starts, collapsed
+/// empty rows and elided rows), and the `skipped` rows (subtotals) are left alone.
+pub fn collapse_repeats(records: &mut [Vec<Cell>], columns: &[usize], breaks: &[usize], skipped: &[usize]) {
+    let mut previous: Option<Vec<Cell>> = None;
+    for (row_idx, row) in records.iter_mut().enumerate() {
+        if skipped.contains(&row_idx) || breaks.contains(&row_idx) {
+            previous = None;
+            if skipped.contains(&row_idx) {
+                continue;
+            }
+        }
+        let values: Vec<Cell> = columns.iter().map(|&col_idx| row[col_idx].clone()).collect();
+        if let Some(previous) = &previous {
+            for ((&col_idx, value), above) in columns.iter().zip(&values).zip(previous) {
+                if value.trim() != above.trim() {
+                    break;
+                }
+                row[col_idx] = Cell::Borrowed("");
+            }
+        }
+        previous = Some(values);
+    }
+}
+
+/// Keeps only the rows whose value in each column equals the one given, or in any of them with
+/// `any`. Surrounding whitespace is ignored.
+pub fn filter_by_values(records: &mut Vec<Vec<Cell>>, conditions: &[(usize, &str)], any: bool) {
+    records.retain(|row| {
+        let mut matches = conditions.iter().map(|&(col_idx, value)| row.get(col_idx).is_some_and(|cell| cell.trim() == value.trim()));
+        if any { matches.any(|matched| matched) } else { matches.all(|matched| matched) }
+    });
+}
+
+/// Keeps only the rows whose timestamp in `col_idx` falls within `[since, until)`.
+/// Rows whose value is empty or not a recognizable date are dropped.
+pub fn filter_by_time(records: &mut Vec<Vec<Cell>>, col_idx: usize, since: Option<NaiveDateTime>, until: Option<NaiveDateTime>) {
+    records.retain(|row| {
+        let Some(timestamp) = row.get(col_idx).and_then(|value| dates::parse_timestamp(value)) else {
+            return false;
+        };
+        since.is_none_or(|since| timestamp >= since) && until.is_none_or(|until| timestamp < until)
+    });
+}
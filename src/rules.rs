@@ -0,0 +1,96 @@
+//! Conditional cell colors (`--rule`).
+//!
+//! A rule such as `rate > 0.1 => red` compares each cell of a column with a value, reading it as
+//! a number when the column's type allows, and colors the cells it matches.
+
+use crate::column_types::ColumnType;
+use crate::palette;
+
+/// Comparison operator of a coloring rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+// Two-character operators come first so `>=` isn't read as `>` followed by `=1`
+const OPERATORS: [(&str, Comparison); 6] = [
+    (">=", Comparison::GreaterOrEqual),
+    ("<=", Comparison::LessOrEqual),
+    ("==", Comparison::Equal),
+    ("!=", Comparison::NotEqual),
+    (">", Comparison::Greater),
+    ("<", Comparison::Less),
+];
+
+/// A conditional coloring rule such as `rate > 0.1 => red`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    column: String,
+    comparison: Comparison,
+    value: String,
+    color: (u8, u8, u8),
+}
+
+/// Parses a `COLUMN OP VALUE => COLOR` rule. OP is one of `<`, `<=`, `>`, `>=`, `==` or `!=`,
+/// and COLOR is a name like `red` or a hex color like `ff8800`.
+pub fn parse_rule(rule: &str) -> Result<Rule, String> {
+    let (condition, color) = rule.rsplit_once("=>")
+        .ok_or_else(|| format!("invalid rule '{}' (expected COLUMN OP VALUE => COLOR)", rule))?;
+    let (column, comparison, value) = OPERATORS.iter()
+        .filter_map(|&(op, comparison)| condition.find(op).map(|pos| (pos, op, comparison)))
+        .min_by_key(|&(pos, op, _)| (pos, std::cmp::Reverse(op.len())))
+        .map(|(pos, op, comparison)| (&condition[..pos], comparison, &condition[pos + op.len()..]))
+        .ok_or_else(|| format!("invalid rule '{}' (expected one of <, <=, >, >=, ==, !=)", rule))?;
+
+    let column = column.trim();
+    if column.is_empty() {
+        return Err(format!("invalid rule '{}' (missing column)", rule));
+    }
+    Ok(Rule {
+        column: column.to_string(),
+        comparison,
+        value: value.trim().to_string(),
+        color: palette::parse_color(color.trim())?,
+    })
+}
+
+impl Rule {
+    /// The column this rule tests.
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// The color for a raw cell value, if the rule matches it.
+    ///
+    /// Typed columns are compared by their underlying quantity, so `rate > 0.1` matches a
+    /// percent cell shown as `12.0%` and `elapsed > 60000` matches a duration over a minute.
+    /// Numbers in the rule may use the same notation as the column (e.g. `rate > 10%`).
+    pub fn color_for(&self, value: &str, column_type: Option<ColumnType>) -> Option<(u8, u8, u8)> {
+        let number = |text: &str| match column_type {
+            Some(column_type) => column_type.numeric_value(text),
+            None => text.trim().parse::<f64>().ok().filter(|_| text.bytes().any(|b| b.is_ascii_digit())),
+        };
+        let matches = match (number(value), number(&self.value)) {
+            (Some(cell), Some(threshold)) => match self.comparison {
+                Comparison::Less => cell < threshold,
+                Comparison::LessOrEqual => cell <= threshold,
+                Comparison::Greater => cell > threshold,
+                Comparison::GreaterOrEqual => cell >= threshold,
+                Comparison::Equal => cell == threshold,
+                Comparison::NotEqual => cell != threshold,
+            },
+            // Non-numeric values can only be tested for (in)equality
+            _ => match self.comparison {
+                Comparison::Equal => value.trim() == self.value,
+                Comparison::NotEqual => value.trim() != self.value,
+                _ => false,
+            },
+        };
+        matches.then_some(self.color)
+    }
+}
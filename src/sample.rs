@@ -0,0 +1,50 @@
+//! Random samples of rows (`--sample`).
+//!
+//! A sample gives a feel for a file too long to read through, without the bias of only ever
+//! seeing its first rows. The generator is built in, so a `--seed` picks the same rows with
+//! every csvpretty version.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The SplitMix64 generator: small, fast and good enough for picking rows.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..=max`. The modulo bias is negligible for row counts.
+    fn up_to(&mut self, max: usize) -> usize {
+        (self.next() % (max as u64 + 1)) as usize
+    }
+}
+
+/// A seed that differs from run to run.
+pub fn random_seed() -> u64 {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    nanos ^ u64::from(std::process::id()).rotate_left(32)
+}
+
+/// Keeps a uniform random sample of `size` rows (or row numbers), in their input order.
+/// Reservoir sampling looks at each row once, so the rows could come from a reader as well.
+pub fn sample<T>(records: impl ExactSizeIterator<Item = T>, size: usize, seed: u64) -> Vec<T> {
+    let mut rng = SplitMix64(seed);
+    let mut reservoir: Vec<(usize, T)> = Vec::with_capacity(size.min(records.len()));
+    for (index, row) in records.enumerate() {
+        if index < size {
+            reservoir.push((index, row));
+        } else {
+            let slot = rng.up_to(index);
+            if slot < size {
+                reservoir[slot] = (index, row);
+            }
+        }
+    }
+    reservoir.sort_by_key(|&(index, _)| index);
+    reservoir.into_iter().map(|(_, row)| row).collect()
+}
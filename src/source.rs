@@ -0,0 +1,587 @@
+//! Where the input comes from: stdin, a local file, (with the `http` feature) an HTTP(S) URL,
+//! or (with the `cloud` feature) an object in S3 or Google Cloud Storage. Gzip, zstd and bzip2
+//! compressed input is decompressed on the way.
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use memmap2::Mmap;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::sync::OnceLock;
+
+/// URL schemes read through an object store.
+const OBJECT_STORE_SCHEMES: [&str; 2] = ["s3://", "gs://"];
+
+/// URL schemes fetched over HTTP.
+const HTTP_SCHEMES: [&str; 2] = ["http://", "https://"];
+
+/// Extra headers sent with HTTP requests, from `--header`.
+static HTTP_HEADERS: OnceLock<Vec<(String, String)>> = OnceLock::new();
+
+/// Whether whole local files are memory-mapped rather than read, from `--mmap`.
+static MEMORY_MAP: OnceLock<bool> = OnceLock::new();
+
+/// How long ago a file must have last changed to be memory-mapped rather than read. One that
+/// changed more recently may still be being written, e.g. by a download or a running program.
+const SETTLED_AGE: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How much of the input is looked at to tell text from binary data.
+const SNIFF_LENGTH: usize = 8192;
+
+/// Share of bytes in invalid UTF-8 sequences or control characters above which input is taken
+/// to be binary. Text in an 8-bit encoding has only its accented letters out of place.
+const MAX_BINARY_SHARE: f64 = 0.3;
+
+/// Signatures of binary formats that end up piped into csvpretty: what the input is, and what
+/// to do about it.
+const BINARY_SIGNATURES: [(&[u8], &str, &str); 4] = [
+    (b"PAR1", "a Parquet file", "export it as CSV first"),
+    (b"PK\x03\x04", "a ZIP archive, such as an Excel workbook (.xlsx)", "read a workbook with --input xlsx"),
+    (b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1", "an Excel workbook (.xls)", "export it as CSV first"),
+    (b"SQLite format 3\0", "an SQLite database", "export a table with sqlite3 -csv -header"),
+];
+
+/// Compression formats, recognized by the bytes they start with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// Length of the longest signature.
+    const SIGNATURE_LENGTH: usize = 4;
+
+    /// The compression of data starting with `bytes`, if it is compressed.
+    fn detect(bytes: &[u8]) -> Option<Compression> {
+        match bytes {
+            [0x1f, 0x8b, ..] => Some(Compression::Gzip),
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Compression::Zstd),
+            // The digit is the block size
+            [b'B', b'Z', b'h', b'1'..=b'9', ..] => Some(Compression::Bzip2),
+            _ => None,
+        }
+    }
+}
+
+/// Text encodings accepted by `--encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum InputEncoding {
+    /// UTF-8, with invalid bytes shown as �
+    #[value(name = "utf-8")]
+    Utf8,
+    /// ISO 8859-1, where every byte is the character with the same number
+    #[value(name = "latin-1")]
+    Latin1,
+    /// UTF-16, little-endian
+    #[value(name = "utf-16le")]
+    Utf16Le,
+    /// UTF-16, big-endian
+    #[value(name = "utf-16be")]
+    Utf16Be,
+    /// The Windows superset of Latin-1 used by Excel exports
+    #[value(name = "windows-1252")]
+    Windows1252,
+}
+
+impl InputEncoding {
+    fn encoding(self) -> &'static Encoding {
+        match self {
+            InputEncoding::Utf8 => UTF_8,
+            InputEncoding::Utf16Le => UTF_16LE,
+            InputEncoding::Utf16Be => UTF_16BE,
+            InputEncoding::Latin1 | InputEncoding::Windows1252 => WINDOWS_1252,
+        }
+    }
+}
+
+/// Sets the headers sent along when fetching URLs, e.g. an `Authorization` token. Only the first
+/// call has an effect.
+pub fn set_http_headers(headers: Vec<(String, String)>) {
+    let _ = HTTP_HEADERS.set(headers);
+}
+
+/// Sets whether whole local files are memory-mapped rather than read (`--mmap`). Only the
+/// first call has an effect.
+pub fn set_memory_map(memory_map: bool) {
+    let _ = MEMORY_MAP.set(memory_map);
+}
+
+/// Whether `source` names a URL fetched over HTTP rather than a file.
+pub fn is_http_url(source: &str) -> bool {
+    HTTP_SCHEMES.iter().any(|scheme| source.starts_with(scheme))
+}
+
+/// Reads the input and decodes it as `encoding`. `None` and `-` mean stdin.
+///
+/// A UTF-8 or UTF-16 byte order mark overrides `encoding`. Bytes that aren't valid in the
+/// encoding are shown as `�` with a warning, rather than failing.
+///
+/// With `head_bytes`, at most that many bytes are read and the input is cut after the last
+/// complete record, so a preview never ends in half a row. `csv_quoting` says whether line
+/// breaks inside quotes belong to the record rather than ending it.
+///
+/// With `--mmap`, a whole local file that nobody seems to be writing is memory-mapped, and when
+/// it's plain UTF-8 the mapping is the text, so cells borrowed from it never take up memory of
+/// their own. Nothing stops another program from changing the file while it's mapped, though:
+/// truncating it ends the process, and rewriting it changes or garbles the table. Files are
+/// read into memory unless asked.
+pub fn read_input(file: Option<&str>, encoding: InputEncoding, head_bytes: Option<u64>, csv_quoting: bool) -> Result<Input, String> {
+    let name = file.unwrap_or("stdin");
+    let mapped = match (file, head_bytes) {
+        (Some(path), None) if MEMORY_MAP.get().copied().unwrap_or(false) => map_file(path)?,
+        _ => None,
+    };
+    if let Some(mapped) = mapped {
+        if !is_plain_utf8(&mapped.map, encoding) {
+            return decode_input(name, &mapped.map, encoding, true).map(Input::whole);
+        }
+        return match MappedText::new(mapped) {
+            Ok(text) => Ok(Input { text: Text::Mapped(text), cut_at: None }),
+            Err(mapped) => decode_input(name, &mapped.map, encoding, true).map(Input::whole),
+        };
+    }
+    let (bytes, truncated) = read_raw(file, head_bytes)?;
+    // A cut-off input may end inside a character, which isn't an encoding error
+    let mut text = decode_input(name, &bytes, encoding, !truncated)?;
+    let mut cut_at = None;
+    if truncated {
+        let end = last_record_end(text.as_bytes(), csv_quoting).ok_or_else(|| {
+            format!("the first {} bytes of {} don't contain a complete record; increase --head-bytes", bytes.len(), name)
+        })?;
+        text.truncate(end);
+        cut_at = Some(end);
+    }
+    Ok(Input { text: Text::Read(text), cut_at })
+}
+
+/// Text read by [`read_input`]. Tables borrow their cells from it (see
+/// [`csvpretty::Table::from_borrowed_str`]) rather than copying each, so it's kept for as long
+/// as they are.
+pub struct Input {
+    text: Text,
+    /// Where `head_bytes` cut the input, after its last complete record, when there was more.
+    pub cut_at: Option<usize>,
+}
+
+/// Where the text of an [`Input`] is.
+enum Text {
+    Mapped(MappedText),
+    Read(String),
+}
+
+impl Input {
+    /// Text that was read in full.
+    pub fn whole(text: String) -> Self {
+        Input { text: Text::Read(text), cut_at: None }
+    }
+
+    /// The text, without a byte order mark.
+    pub fn text(&self) -> &str {
+        match &self.text {
+            Text::Mapped(text) => text.as_str(),
+            Text::Read(text) => text,
+        }
+    }
+}
+
+/// A memory-mapped file. The file stays open and locked for as long as it's mapped.
+struct MappedFile {
+    map: Mmap,
+    _file: std::fs::File,
+}
+
+/// A memory-mapped file found to be UTF-8 when it was mapped.
+struct MappedText {
+    mapped: MappedFile,
+    /// Where the text starts, after a byte order mark
+    start: usize,
+}
+
+impl MappedText {
+    /// Checks that the file is UTF-8, giving it back when it isn't.
+    fn new(mapped: MappedFile) -> Result<MappedText, MappedFile> {
+        if std::str::from_utf8(&mapped.map).is_err() {
+            return Err(mapped);
+        }
+        let start = if mapped.map.starts_with("\u{feff}".as_bytes()) { "\u{feff}".len() } else { 0 };
+        Ok(MappedText { mapped, start })
+    }
+
+    fn as_str(&self) -> &str {
+        // SAFETY: the bytes were checked to be UTF-8 in `new`, and `start` is at a character
+        // boundary. They stay so unless another program changes the file, the risk `--mmap`
+        // takes (see `map_file`).
+        unsafe { std::str::from_utf8_unchecked(&self.mapped.map[self.start..]) }
+    }
+}
+
+/// Decodes input read some other way like [`read_input`] does, e.g. the rest of a stream that
+/// turned out not to suit rendering as it's read. Unless `last` is set, an incomplete character
+/// at the end is dropped.
+pub fn decode_input(name: &str, bytes: &[u8], encoding: InputEncoding, last: bool) -> Result<String, String> {
+    if let Some(reason) = binary_reason(bytes, encoding) {
+        return Err(format!("{} doesn't look like CSV: it's {}", name, reason));
+    }
+    let (text, had_errors) = decode(bytes, encoding, last);
+    if had_errors {
+        eprintln!("Warning: {} contains bytes that aren't valid {}; they are shown as �", name, text_encoding_name(bytes, encoding));
+    }
+    Ok(text)
+}
+
+/// Opens stdin (`None` and `-`) or a local file to read as the data arrives, decompressing it
+/// on the way.
+pub fn open_stream(file: Option<&str>) -> Result<Box<dyn BufRead>, String> {
+    let reader = match file {
+        None | Some("-") => decompress(io::stdin().lock()).map_err(|e| format!("reading stdin: {}", e))?,
+        Some(path) => std::fs::File::open(path).and_then(decompress).map_err(|e| format!("{}: {}", path, e))?,
+    };
+    Ok(Box::new(BufReader::new(reader)))
+}
+
+/// Whether input starting with `start` can be parsed as it's read: it's UTF-8 text, without
+/// the byte order mark of another encoding.
+pub fn is_plain_utf8(start: &[u8], encoding: InputEncoding) -> bool {
+    encoding == InputEncoding::Utf8
+        && binary_reason(start, encoding).is_none()
+        && Encoding::for_bom(start).is_none_or(|(found, _)| found == UTF_8)
+}
+
+/// Reads the whole input as it is, for binary formats such as Excel workbooks. `None` and `-`
+/// mean stdin.
+pub fn read_bytes(file: Option<&str>) -> Result<Vec<u8>, String> {
+    read_raw(file, None).map(|(bytes, _)| bytes)
+}
+
+/// Whether the file at `path` is compressed, in which case its size says little about how
+/// much data it holds.
+pub fn is_compressed(path: &str) -> io::Result<bool> {
+    let mut start = Vec::new();
+    std::fs::File::open(path)?.take(Compression::SIGNATURE_LENGTH as u64).read_to_end(&mut start)?;
+    Ok(Compression::detect(&start).is_some())
+}
+
+/// Maps the local file at `path` into memory. URLs, stdin, files that aren't regular (such as
+/// pipes), empty files and compressed files give None, to be read as a stream instead, and so
+/// do files that may be being written: locked by another program, or changed within the last
+/// [`SETTLED_AGE`].
+fn map_file(path: &str) -> Result<Option<MappedFile>, String> {
+    if path == "-" || is_http_url(path) || OBJECT_STORE_SCHEMES.iter().any(|scheme| path.starts_with(scheme)) {
+        return Ok(None);
+    }
+    let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    let settled = |metadata: std::fs::Metadata| {
+        metadata.modified().ok().and_then(|modified| modified.elapsed().ok()).is_some_and(|age| age >= SETTLED_AGE)
+    };
+    if !file.metadata().is_ok_and(|metadata| metadata.is_file() && metadata.len() > 0 && settled(metadata)) {
+        return Ok(None);
+    }
+    // Programs that lock the file keep it from being written while it's mapped
+    if file.try_lock_shared().is_err() {
+        return Ok(None);
+    }
+    // SAFETY: the file is regular, hasn't changed lately and nobody holds a lock to write it, so
+    // it's taken not to change while it's mapped. That can't be proven: if a program that
+    // doesn't lock files truncates it meanwhile, reading the missing part ends the process, and
+    // if it rewrites it, the text changes under the table. This is why mapping is opt-in.
+    let map = unsafe { Mmap::map(&file) }.map_err(|e| format!("{}: {}", path, e))?;
+    if Compression::detect(&map).is_some() {
+        return Ok(None);
+    }
+    Ok(Some(MappedFile { map, _file: file }))
+}
+
+/// Reads at most `head_bytes` of the (decompressed) input, returning whether there was more.
+fn read_raw(file: Option<&str>, head_bytes: Option<u64>) -> Result<(Vec<u8>, bool), String> {
+    match file {
+        None | Some("-") => decompress(io::stdin().lock())
+            .and_then(|reader| read_head(reader, head_bytes))
+            .map_err(|e| format!("reading stdin: {}", e)),
+        Some(url) if OBJECT_STORE_SCHEMES.iter().any(|scheme| url.starts_with(scheme)) => {
+            let (bytes, truncated) = read_object(url, head_bytes)?;
+            if Compression::detect(&bytes).is_none() {
+                return Ok((bytes, truncated));
+            }
+            if truncated {
+                return Err(format!("{}: --head-bytes can't read part of a compressed object", url));
+            }
+            decompress(Cursor::new(bytes))
+                .and_then(|reader| read_head(reader, head_bytes))
+                .map_err(|e| format!("{}: {}", url, e))
+        }
+        // With --head-bytes, the rest of the response is never downloaded
+        Some(url) if is_http_url(url) => decompress(open_url(url)?)
+            .and_then(|reader| read_head(reader, head_bytes))
+            .map_err(|e| format!("{}: {}", url, e)),
+        Some(path) => std::fs::File::open(path)
+            .and_then(decompress)
+            .and_then(|reader| read_head(reader, head_bytes))
+            .map_err(|e| format!("{}: {}", path, e)),
+    }
+}
+
+/// Wraps `reader` in a decoder if the data it reads is compressed. Like zcat, concatenated
+/// gzip and bzip2 streams are read one after the other.
+fn decompress<'a>(mut reader: impl Read + 'a) -> io::Result<Box<dyn Read + 'a>> {
+    // Pipes may deliver the start of the data in several pieces
+    let mut start = Vec::new();
+    (&mut reader).take(Compression::SIGNATURE_LENGTH as u64).read_to_end(&mut start)?;
+    let compression = Compression::detect(&start);
+    let reader = Cursor::new(start).chain(reader);
+    Ok(match compression {
+        None => Box::new(reader),
+        Some(Compression::Gzip) => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+        Some(Compression::Zstd) => Box::new(zstd::Decoder::new(reader)?),
+        Some(Compression::Bzip2) => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+    })
+}
+
+/// Decodes `bytes`, replacing invalid sequences with `�`. Also returns whether there were any.
+/// Unless `last` is set, an incomplete character at the end is dropped instead.
+fn decode(bytes: &[u8], encoding: InputEncoding, last: bool) -> (String, bool) {
+    let (decoder_encoding, bom_length) = match Encoding::for_bom(bytes) {
+        Some(found) => found,
+        // encoding_rs follows the web platform in treating Latin-1 as Windows-1252
+        None if encoding == InputEncoding::Latin1 => return (bytes.iter().map(|&byte| char::from(byte)).collect(), false),
+        None => (encoding.encoding(), 0),
+    };
+    let bytes = &bytes[bom_length..];
+    let mut decoder = decoder_encoding.new_decoder_without_bom_handling();
+    let capacity = decoder.max_utf8_buffer_length(bytes.len()).unwrap_or(bytes.len());
+    let mut text = String::with_capacity(capacity);
+    let (_, _, had_errors) = decoder.decode_to_string(bytes, &mut text, last);
+    (text, had_errors)
+}
+
+/// Why the input looks like binary data rather than text, or None if it looks like text.
+fn binary_reason(bytes: &[u8], encoding: InputEncoding) -> Option<String> {
+    if let Some((_, what, advice)) = BINARY_SIGNATURES.iter().find(|(signature, ..)| bytes.starts_with(signature)) {
+        return Some(format!("{}; {}", what, advice));
+    }
+    // UTF-16 text is full of NUL bytes, and every byte is some character in the 8-bit encodings
+    let utf16 = matches!(encoding, InputEncoding::Utf16Le | InputEncoding::Utf16Be)
+        || Encoding::for_bom(bytes).is_some_and(|(found, _)| found != UTF_8);
+    if utf16 {
+        return None;
+    }
+    let sample = &bytes[..bytes.len().min(SNIFF_LENGTH)];
+    if sample.contains(&0) {
+        return Some("binary data (it contains NUL bytes); if it's UTF-16 text, pass --encoding utf-16le or utf-16be".to_string());
+    }
+    if encoding == InputEncoding::Utf8 && binary_share(sample) > MAX_BINARY_SHARE {
+        return Some("binary data (many of its bytes aren't valid UTF-8 or are control characters); if it's text, pass its --encoding".to_string());
+    }
+    None
+}
+
+/// The share of bytes in `sample` that are control characters other than whitespace, or that
+/// aren't part of a valid UTF-8 sequence. A character cut off at the end of the sample counts as
+/// valid.
+fn binary_share(sample: &[u8]) -> f64 {
+    if sample.is_empty() {
+        return 0.0;
+    }
+    let mut suspicious = 0;
+    let mut rest = sample;
+    loop {
+        let (valid, error) = match std::str::from_utf8(rest) {
+            Ok(valid) => (valid, None),
+            Err(e) => (std::str::from_utf8(&rest[..e.valid_up_to()]).unwrap_or_default(), e.error_len()),
+        };
+        suspicious += valid.chars().filter(|c| c.is_control() && !c.is_whitespace()).count();
+        match error {
+            Some(len) => {
+                suspicious += len;
+                rest = &rest[valid.len() + len..];
+            }
+            None => break,
+        }
+    }
+    suspicious as f64 / sample.len() as f64
+}
+
+/// The name of the encoding the input was decoded with, for warnings.
+fn text_encoding_name(bytes: &[u8], encoding: InputEncoding) -> &'static str {
+    Encoding::for_bom(bytes).map_or(encoding.encoding(), |(found, _)| found).name()
+}
+
+/// Reads everything, or the first `head_bytes` bytes. Also returns whether anything was left
+/// unread.
+fn read_head(mut reader: impl Read, head_bytes: Option<u64>) -> io::Result<(Vec<u8>, bool)> {
+    let mut bytes = Vec::new();
+    match head_bytes {
+        None => {
+            reader.read_to_end(&mut bytes)?;
+            Ok((bytes, false))
+        }
+        Some(limit) => {
+            // One extra byte tells a file of exactly `limit` bytes apart from a longer one
+            reader.take(limit + 1).read_to_end(&mut bytes)?;
+            let truncated = bytes.len() as u64 > limit;
+            bytes.truncate(limit as usize);
+            Ok((bytes, truncated))
+        }
+    }
+}
+
+/// The offset just past the last line break that ends a record, or None if there isn't one.
+fn last_record_end(bytes: &[u8], csv_quoting: bool) -> Option<usize> {
+    let mut in_quotes = false;
+    let mut end = None;
+    for (index, &byte) in bytes.iter().enumerate() {
+        match byte {
+            // An escaped `""` toggles twice, leaving the state unchanged
+            b'"' if csv_quoting => in_quotes = !in_quotes,
+            b'\n' if !in_quotes => end = Some(index + 1),
+            _ => {}
+        }
+    }
+    end
+}
+
+/// Splits `s3://bucket/key` into its scheme, bucket and key.
+#[cfg(feature = "cloud")]
+fn split_object_url(url: &str) -> Result<(&str, &str, &str), String> {
+    let (scheme, rest) = url.split_once("://").ok_or_else(|| format!("invalid object URL '{}'", url))?;
+    match rest.split_once('/') {
+        Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => Ok((scheme, bucket, key)),
+        _ => Err(format!("invalid object URL '{}' (expected {}://BUCKET/KEY)", url, scheme)),
+    }
+}
+
+/// Downloads an object, or with `head_bytes` only its first bytes using a range request. Also
+/// returns whether part of the object was left out. Credentials and region come from the
+/// standard environment variables (`AWS_ACCESS_KEY_ID`, `AWS_REGION`,
+/// `GOOGLE_APPLICATION_CREDENTIALS`, ...).
+#[cfg(feature = "cloud")]
+fn read_object(url: &str, head_bytes: Option<u64>) -> Result<(Vec<u8>, bool), String> {
+    use object_store::aws::AmazonS3Builder;
+    use object_store::gcp::GoogleCloudStorageBuilder;
+    use object_store::path::Path;
+    use object_store::{ObjectStore, ObjectStoreExt};
+
+    let (scheme, bucket, key) = split_object_url(url)?;
+    let store: Box<dyn ObjectStore> = match scheme {
+        "s3" => Box::new(AmazonS3Builder::from_env().with_bucket_name(bucket).build().map_err(|e| e.to_string())?),
+        _ => Box::new(GoogleCloudStorageBuilder::from_env().with_bucket_name(bucket).build().map_err(|e| e.to_string())?),
+    };
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.to_string())?;
+    let location = Path::from(key);
+    runtime
+        .block_on(async {
+            if let Some(limit) = head_bytes {
+                let size = store.head(&location).await?.size;
+                if size > limit {
+                    return Ok((store.get_range(&location, 0..limit).await?.to_vec(), true));
+                }
+            }
+            Ok((store.get(&location).await?.bytes().await?.to_vec(), false))
+        })
+        .map_err(|e: object_store::Error| format!("{}: {}", url, e))
+}
+
+#[cfg(not(feature = "cloud"))]
+fn read_object(url: &str, _head_bytes: Option<u64>) -> Result<(Vec<u8>, bool), String> {
+    Err(format!("reading {} requires csvpretty to be built with the `cloud` feature", url))
+}
+
+/// Requests `url`, returning a reader of the response body as it arrives. Servers may send it
+/// gzip-compressed, which [`decompress`] undoes like compressed files; the reader passes on the
+/// bytes as sent, so that a download cut short can be resumed where it stopped.
+#[cfg(feature = "http")]
+fn open_url(url: &str) -> Result<impl Read, String> {
+    let mut retries = 0;
+    let response = http_get(url, 0, &mut retries)?;
+    Ok(Download { url: url.to_string(), body: response.into_body().into_reader(), received: 0, retries })
+}
+
+/// Retries of a download after a failed request or a dropped connection, shared by all the
+/// requests it takes. The first waits [`HTTP_RETRY_DELAY`], and each further one twice as long.
+#[cfg(feature = "http")]
+const HTTP_RETRIES: u32 = 3;
+
+#[cfg(feature = "http")]
+const HTTP_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Sends a GET request for `url`, for the bytes from `from` on unless it's 0. Connection
+/// failures and the statuses that mean "try again later" (5xx and 429) are retried after a
+/// growing delay while `retries` hasn't run out.
+#[cfg(feature = "http")]
+fn http_get(url: &str, from: u64, retries: &mut u32) -> Result<ureq::http::Response<ureq::Body>, String> {
+    loop {
+        // Asked for by hand, since ureq's own decompression would hide the offsets in the body
+        let mut request = ureq::get(url).header("Accept-Encoding", "gzip");
+        for (name, value) in HTTP_HEADERS.get().into_iter().flatten() {
+            request = request.header(name, value);
+        }
+        if from > 0 {
+            request = request.header("Range", format!("bytes={}-", from));
+        }
+        let e = match request.call() {
+            Ok(response) => return Ok(response),
+            Err(e) => e,
+        };
+        let transient = match e {
+            ureq::Error::StatusCode(status) => status == 429 || status >= 500,
+            ureq::Error::Io(_) | ureq::Error::ConnectionFailed | ureq::Error::Timeout(_) => true,
+            _ => false,
+        };
+        if !transient || *retries == HTTP_RETRIES {
+            return Err(format!("{}: {}", url, e));
+        }
+        back_off(retries);
+    }
+}
+
+/// Waits before the next retry, longer with each one.
+#[cfg(feature = "http")]
+fn back_off(retries: &mut u32) {
+    std::thread::sleep(HTTP_RETRY_DELAY * 2u32.pow(*retries));
+    *retries += 1;
+}
+
+/// The body of an HTTP response. When the connection drops partway through, the rest is
+/// requested with a `Range` header, which the server has to answer with just those bytes.
+#[cfg(feature = "http")]
+struct Download {
+    url: String,
+    body: ureq::BodyReader<'static>,
+    /// Bytes of the body read so far
+    received: u64,
+    retries: u32,
+}
+
+#[cfg(feature = "http")]
+impl Read for Download {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let e = match self.body.read(buf) {
+                Ok(read) => {
+                    self.received += read as u64;
+                    return Ok(read);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted || self.retries == HTTP_RETRIES => return Err(e),
+                Err(e) => e,
+            };
+            back_off(&mut self.retries);
+            let response = http_get(&self.url, self.received, &mut self.retries).map_err(io::Error::other)?;
+            // A server that ignores the range sends the whole body again (200)
+            let expected = format!("bytes {}-", self.received);
+            let resumed = response.status() == ureq::http::StatusCode::PARTIAL_CONTENT
+                && response.headers().get("Content-Range").and_then(|value| value.to_str().ok()).is_some_and(|range| range.starts_with(&expected));
+            if self.received > 0 && !resumed {
+                return Err(io::Error::other(format!("{} (after {} bytes, and the server can't resume the download)", e, self.received)));
+            }
+            self.body = response.into_body().into_reader();
+        }
+    }
+}
+
+#[cfg(not(feature = "http"))]
+fn open_url(url: &str) -> Result<io::Empty, String> {
+    Err(format!("reading {} requires csvpretty to be built with the `http` feature", url))
+}
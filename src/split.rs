@@ -0,0 +1,72 @@
+//! Multi-value cells (`--split-column` and `--explode`).
+//!
+//! Fields such as `tags` often pack several values into one cell. Splitting spreads them over
+//! numbered columns (`tags_1`, `tags_2`, ...), while exploding repeats the row once per value.
+
+use csvpretty::Cell;
+
+/// Delimiter used when a spec doesn't give one.
+const DEFAULT_DELIMITER: &str = ",";
+
+/// A `COLUMN[:DELIMITER]` spec for `--split-column` or `--explode`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitSpec {
+    pub column: String,
+    pub delimiter: String,
+}
+
+/// Clap value parser for `COLUMN[:DELIMITER]`. The delimiter defaults to a comma.
+pub fn parse_split_spec(s: &str) -> Result<SplitSpec, String> {
+    let (column, delimiter) = match s.rsplit_once(':') {
+        // `tags::` splits on colons
+        Some((column, "")) if column.ends_with(':') => (&column[..column.len() - 1], ":"),
+        Some((column, delimiter)) if !delimiter.is_empty() => (column, delimiter),
+        _ => (s, DEFAULT_DELIMITER),
+    };
+    if column.is_empty() {
+        return Err(format!("invalid split '{}' (expected COLUMN[:DELIMITER])", s));
+    }
+    Ok(SplitSpec { column: column.to_string(), delimiter: delimiter.to_string() })
+}
+
+/// The values packed into a cell, with surrounding whitespace removed. Empty cells hold none.
+fn split_values<'a>(value: &'a str, delimiter: &str) -> Vec<&'a str> {
+    if value.trim().is_empty() {
+        return Vec::new();
+    }
+    value.split(delimiter).map(str::trim).collect()
+}
+
+/// Replaces column `col_idx` with one column per value, as many as the fullest cell needs.
+/// Returns the names of the new columns.
+pub fn split_column(records: &mut [Vec<Cell>], col_idx: usize, name: &str, delimiter: &str) -> Vec<String> {
+    let split: Vec<Vec<Cell>> = records.iter()
+        .map(|row| split_values(&row[col_idx], delimiter).into_iter().map(|value| Cell::Owned(value.to_owned())).collect())
+        .collect();
+    let count = split.iter().map(Vec::len).max().unwrap_or(0).max(1);
+
+    for (row, mut values) in records.iter_mut().zip(split) {
+        values.resize(count, Cell::Borrowed(""));
+        row.splice(col_idx..=col_idx, values);
+    }
+    (1..=count).map(|n| format!("{}_{}", name, n)).collect()
+}
+
+/// Repeats each row once per value in column `col_idx`. Rows with an empty cell are kept as
+/// they are.
+pub fn explode_column<'a>(records: Vec<Vec<Cell<'a>>>, col_idx: usize, delimiter: &str) -> Vec<Vec<Cell<'a>>> {
+    let mut exploded = Vec::with_capacity(records.len());
+    for row in records {
+        let values: Vec<Cell<'a>> = split_values(&row[col_idx], delimiter).into_iter().map(|value| Cell::Owned(value.to_owned())).collect();
+        if values.is_empty() {
+            exploded.push(row);
+            continue;
+        }
+        for value in values {
+            let mut copy = row.clone();
+            copy[col_idx] = value;
+            exploded.push(copy);
+        }
+    }
+    exploded
+}
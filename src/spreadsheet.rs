@@ -0,0 +1,106 @@
+//! Excel workbook input (`--input xlsx`).
+//!
+//! A worksheet is read as a table: its first row names the columns, like a CSV header. Cells
+//! are shown the way Excel would show them without number formatting: whole numbers without a
+//! decimal point, dates as `2025-10-13` or `2025-10-13 12:59:02`, and errors such as `#DIV/0!`
+//! as they are.
+
+use crate::{Cell, Error, Table};
+use calamine::{Data, ExcelDateTime, Reader, Xlsx};
+use std::io::Cursor;
+
+/// Significant digits Excel shows of a number, which hides the noise of binary floating point,
+/// e.g. `0.30000000000000004` for `=0.1+0.2`.
+const SIGNIFICANT_DIGITS: usize = 15;
+
+/// How a worksheet is read.
+#[derive(Debug, Clone, Copy)]
+pub struct SheetOptions<'a> {
+    /// The worksheet's name, in any case, or its position with the first sheet as `1`. `None`
+    /// reads the first sheet.
+    pub sheet: Option<&'a str>,
+    /// Rows at the top of the sheet to leave out, such as a title above the header.
+    pub skip_rows: usize,
+    /// Whether the first row names the columns. Without it, columns are named by position.
+    pub has_headers: bool,
+}
+
+impl Default for SheetOptions<'_> {
+    fn default() -> Self {
+        SheetOptions { sheet: None, skip_rows: 0, has_headers: true }
+    }
+}
+
+/// Reads a worksheet of the `.xlsx` workbook in `bytes`.
+pub fn parse_xlsx(bytes: Vec<u8>, options: &SheetOptions) -> Result<Table<'static>, Error> {
+    parse(bytes, options).map_err(Error::Input)
+}
+
+fn parse(bytes: Vec<u8>, options: &SheetOptions) -> Result<Table<'static>, String> {
+    let mut workbook = Xlsx::new(Cursor::new(bytes)).map_err(|e| format!("invalid Excel workbook: {}", e))?;
+    let names = workbook.sheet_names();
+    let name = match options.sheet {
+        None => names.first().ok_or("the workbook has no sheets")?,
+        Some(sheet) => names.iter()
+            .find(|name| *name == sheet)
+            // Excel doesn't allow two sheets whose names differ only in case
+            .or_else(|| names.iter().find(|name| name.to_lowercase() == sheet.to_lowercase()))
+            .or_else(|| sheet.parse::<usize>().ok().and_then(|n| names.get(n.checked_sub(1)?)))
+            .ok_or_else(|| format!("no sheet '{}' in the workbook; its sheets are {}", sheet, names.join(", ")))?,
+    };
+    let range = workbook.worksheet_range(name).map_err(|e| format!("reading sheet '{}': {}", name, e))?;
+
+    // The range starts at the first cell with anything in it, not necessarily the first row
+    let first_row = range.start().map_or(0, |(row, _)| row as usize);
+    let mut rows = range.rows()
+        .skip(options.skip_rows.saturating_sub(first_row))
+        .map(|row| row.iter().map(|cell| Cell::Owned(cell_text(cell))).collect::<Vec<Cell>>());
+    let headers = match (options.has_headers, rows.next()) {
+        (_, None) => return Ok(Table::default()),
+        (true, Some(header)) => header.into_iter().collect(),
+        (false, Some(first)) => {
+            let headers = (1..=first.len()).map(|n| n.to_string()).collect();
+            return Ok(Table { headers, records: std::iter::once(first).chain(rows).collect() });
+        }
+    };
+    Ok(Table { headers, records: rows.collect() })
+}
+
+/// The text of a cell, as Excel shows it in the General number format.
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::Float(number) => format_number(*number),
+        Data::Bool(value) => if *value { "TRUE" } else { "FALSE" }.to_string(),
+        Data::DateTime(datetime) => format_datetime(datetime),
+        cell => cell.to_string(),
+    }
+}
+
+/// A number rounded to the digits Excel shows, e.g. `3` rather than `3.0`.
+fn format_number(number: f64) -> String {
+    let rounded: f64 = format!("{:.*e}", SIGNIFICANT_DIGITS - 1, number).parse().unwrap_or(number);
+    rounded.to_string()
+}
+
+/// A date as `2025-10-13`, with the time of day when there is one, a time of day alone as
+/// `12:59:02`, and a duration as hours, minutes and seconds, e.g. `36:00:00`.
+fn format_datetime(datetime: &ExcelDateTime) -> String {
+    if datetime.is_duration() {
+        let seconds = (datetime.as_f64() * 86_400.0).round() as i64;
+        let sign = if seconds < 0 { "-" } else { "" };
+        let seconds = seconds.abs();
+        return format!("{}{}:{:02}:{:02}", sign, seconds / 3600, seconds / 60 % 60, seconds % 60);
+    }
+    let (year, month, day, hour, minute, second, milli) = datetime.to_ymd_hms_milli();
+    let date = format!("{:04}-{:02}-{:02}", year, month, day);
+    let time = match milli {
+        0 => format!("{:02}:{:02}:{:02}", hour, minute, second),
+        milli => format!("{:02}:{:02}:{:02}.{:03}", hour, minute, second, milli),
+    };
+    match (datetime.as_f64() < 1.0, (hour, minute, second, milli) == (0, 0, 0, 0)) {
+        (true, _) => time,
+        (false, true) => date,
+        (false, false) => format!("{} {}", date, time),
+    }
+}
@@ -0,0 +1,91 @@
+//! SQLite database input (`--input sqlite`).
+//!
+//! A table of the database, or the result of a query against it, is read as a table whose
+//! columns are the result columns. Integers and reals are shown as numbers, text as it is,
+//! blobs as hex literals such as `X'CAFE'`, and `NULL` as an empty cell.
+
+use crate::{Cell, Error, Table};
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use std::path::Path;
+
+/// What is read from a database.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum SqliteSource<'a> {
+    /// The first table, in the order the tables were created.
+    #[default]
+    FirstTable,
+    /// A table or view, by name.
+    Table(&'a str),
+    /// The rows a `SELECT` statement returns.
+    Query(&'a str),
+}
+
+/// Reads a table or query result from the SQLite database at `path`. The database is opened
+/// read-only, so queries can't change it.
+pub fn read_sqlite(path: &Path, source: SqliteSource) -> Result<Table<'static>, Error> {
+    read(path, source).map_err(Error::Input)
+}
+
+fn read(path: &Path, source: SqliteSource) -> Result<Table<'static>, String> {
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| format!("opening SQLite database: {}", e))?;
+    let query = match source {
+        SqliteSource::Query(query) => query.to_string(),
+        SqliteSource::Table(name) => format!("SELECT * FROM {}", quote_identifier(name)),
+        SqliteSource::FirstTable => {
+            let names = table_names(&connection)?;
+            let name = names.first().ok_or("the database has no tables")?;
+            format!("SELECT * FROM {}", quote_identifier(name))
+        }
+    };
+    let mut statement = connection.prepare(&query).map_err(|e| match (source, table_names(&connection)) {
+        (SqliteSource::Table(name), Ok(names)) if !names.iter().any(|table| table.eq_ignore_ascii_case(name)) => {
+            format!("no table '{}' in the database; its tables are {}", name, names.join(", "))
+        }
+        _ => format!("invalid query: {}", e),
+    })?;
+
+    let headers = statement.column_names().into_iter().collect();
+    let columns = statement.column_count();
+    let mut rows = statement.query([]).map_err(|e| format!("running query: {}", e))?;
+    let mut records = Vec::new();
+    while let Some(row) = rows.next().map_err(|e| format!("reading rows: {}", e))? {
+        let record = (0..columns)
+            .map(|col_idx| row.get_ref(col_idx).map(|value| Cell::Owned(cell_text(value))))
+            .collect::<Result<Vec<Cell>, _>>()
+            .map_err(|e| format!("reading rows: {}", e))?;
+        records.push(record);
+    }
+    Ok(Table { headers, records })
+}
+
+/// Names of the database's tables and views, in the order they were created. SQLite's own
+/// tables are left out.
+fn table_names(connection: &Connection) -> Result<Vec<String>, String> {
+    let mut statement = connection
+        .prepare("SELECT name FROM sqlite_schema WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY rowid")
+        .map_err(|e| format!("reading SQLite database: {}", e))?;
+    statement.query_map([], |row| row.get(0))
+        .and_then(|names| names.collect())
+        .map_err(|e| format!("reading SQLite database: {}", e))
+}
+
+/// A name quoted for use in SQL, e.g. `"order items"`.
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// The text of a cell.
+fn cell_text(value: ValueRef) -> String {
+    match value {
+        ValueRef::Null => String::new(),
+        ValueRef::Integer(number) => number.to_string(),
+        ValueRef::Real(number) => number.to_string(),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned(),
+        ValueRef::Blob(bytes) => {
+            let hex: String = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+            format!("X'{}'", hex)
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Per-column summary statistics (`--stats`).
+//!
+//! Instead of the rows themselves, `--stats` renders one row per input column describing its
+//! values, which is a quick first look at an unfamiliar file. Typed columns (`--column-type`)
+//! are summarized by their underlying quantity and shown in the type's own format.
+
+use crate::column_types::ColumnType;
+use crate::inference::{self, InferredType};
+use crate::{dates, is_null};
+use csvpretty::{display_width, Cell, Highlights};
+use std::collections::HashSet;
+
+const STATS_HEADERS: [&str; 10] = ["column", "type", "count", "nulls", "distinct", "min", "max", "mean", "min length", "max length"];
+
+/// Positions of the count and nulls columns in the summary.
+const COUNT_COLUMN: usize = 2;
+const NULLS_COLUMN: usize = 3;
+
+/// Colors of the nulls cells, from no nulls through half to all nulls.
+const NULL_RATE_GRADIENT: [(u8, u8, u8); 3] = [(38, 162, 105), (230, 180, 0), (220, 50, 47)];
+
+/// The order of the rows of the summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatsOrder {
+    /// The order of the columns in the input
+    Input,
+    /// Columns with the most nulls first, as a list of what to look at
+    Problems,
+}
+
+/// Summary columns holding values, which are right-aligned even when they are dates or
+/// formatted quantities.
+pub const RIGHT_ALIGNED: [&str; 3] = ["min", "max", "mean"];
+
+/// Builds the summary table: headers plus one row per column of the input.
+pub fn summarize(headers: &csv::StringRecord, records: &[Vec<Cell>], column_types: &[Option<ColumnType>]) -> (csv::StringRecord, Vec<Vec<Cell<'static>>>) {
+    let rows = headers.iter()
+        .enumerate()
+        .map(|(col_idx, header)| summarize_column(header, records, col_idx, column_types[col_idx]).into_iter().map(Cell::Owned).collect())
+        .collect();
+    (STATS_HEADERS.iter().collect(), rows)
+}
+
+fn summarize_column(header: &str, records: &[Vec<Cell>], col_idx: usize, column_type: Option<ColumnType>) -> Vec<String> {
+    let values: Vec<&str> = records.iter()
+        .map(|row| &*row[col_idx])
+        .filter(|value| !is_null(value))
+        .collect();
+    let distinct: HashSet<&str> = values.iter().copied().collect();
+
+    // min, max, mean, min length, max length
+    let (type_name, summary): (&str, [String; 5]) = if values.is_empty() {
+        ("empty", Default::default())
+    } else if let Some(column_type) = column_type {
+        let quantities: Vec<f64> = values.iter().filter_map(|value| column_type.numeric_value(value)).collect();
+        (column_type.name(), numeric_summary(&quantities, |quantity| column_type.format_quantity(quantity)))
+    } else {
+        let inferred_type = inference::infer_column(records, col_idx);
+        (inferred_type.name(), inferred_summary(&values, inferred_type))
+    };
+
+    let [min, max, mean, min_length, max_length] = summary;
+    vec![
+        header.to_string(),
+        type_name.to_string(),
+        records.len().to_string(),
+        (records.len() - values.len()).to_string(),
+        distinct.len().to_string(),
+        min,
+        max,
+        mean,
+        min_length,
+        max_length,
+    ]
+}
+
+/// The share of null values in a row of the summary, from 0 to 1.
+fn null_rate(row: &[Cell]) -> f64 {
+    let count: f64 = row[COUNT_COLUMN].parse().unwrap_or(0.0);
+    let nulls: f64 = row[NULLS_COLUMN].parse().unwrap_or(0.0);
+    if count == 0.0 { 0.0 } else { nulls / count }
+}
+
+/// Puts the rows of the summary in `order`. Rows with the same null rate keep their order.
+pub fn order_rows(rows: &mut [Vec<Cell>], order: StatsOrder) {
+    if order == StatsOrder::Problems {
+        rows.sort_by(|a, b| null_rate(b).total_cmp(&null_rate(a)));
+    }
+}
+
+/// Colors the nulls cell of each summary row from green through yellow to red as the share of
+/// nulls grows. Cells colored by a --rule keep their color. Does nothing when the nulls column
+/// has been renamed or moved away.
+pub fn highlight_null_rates(highlights: &mut Highlights, headers: &csv::StringRecord, rows: &[Vec<Cell>]) {
+    if headers.get(NULLS_COLUMN) != Some(STATS_HEADERS[NULLS_COLUMN]) {
+        return;
+    }
+    highlights.resize_with(rows.len(), Vec::new);
+    for (row, row_highlights) in rows.iter().zip(highlights.iter_mut()) {
+        if row_highlights.len() <= NULLS_COLUMN {
+            row_highlights.resize(NULLS_COLUMN + 1, None);
+        }
+        row_highlights[NULLS_COLUMN].get_or_insert(gradient(null_rate(row)));
+    }
+}
+
+/// The color at `position` (0 to 1) along [`NULL_RATE_GRADIENT`].
+fn gradient(position: f64) -> (u8, u8, u8) {
+    let scaled = position.clamp(0.0, 1.0) * (NULL_RATE_GRADIENT.len() - 1) as f64;
+    let index = (scaled as usize).min(NULL_RATE_GRADIENT.len() - 2);
+    let (from, to) = (NULL_RATE_GRADIENT[index], NULL_RATE_GRADIENT[index + 1]);
+    let t = scaled - index as f64;
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+/// The min/max/mean and length columns for a column of the given inferred type.
+fn inferred_summary(values: &[&str], inferred_type: InferredType) -> [String; 5] {
+    match inferred_type {
+        InferredType::Integer | InferredType::Float => {
+            let numbers: Vec<f64> = values.iter().filter_map(|value| value.trim().parse().ok()).collect();
+            numeric_summary(&numbers, format_number)
+        }
+        InferredType::Date => {
+            // Dates have no meaningful mean; show the earliest and latest values as written
+            let dated = values.iter().filter_map(|value| dates::parse_timestamp(value).map(|timestamp| (timestamp, *value)));
+            let earliest = dated.clone().min_by_key(|&(timestamp, _)| timestamp).map(|(_, value)| value);
+            let latest = dated.max_by_key(|&(timestamp, _)| timestamp).map(|(_, value)| value);
+            [earliest.unwrap_or_default().to_string(), latest.unwrap_or_default().to_string(), String::new(), String::new(), String::new()]
+        }
+        InferredType::Bool => Default::default(),
+        InferredType::String => {
+            let lengths = values.iter().map(|value| display_width(value));
+            let min_length = lengths.clone().min().unwrap_or(0);
+            let max_length = lengths.max().unwrap_or(0);
+            [String::new(), String::new(), String::new(), min_length.to_string(), max_length.to_string()]
+        }
+    }
+}
+
+/// Min, max and mean of `numbers`, formatted with `format`, followed by empty length columns.
+fn numeric_summary(numbers: &[f64], format: impl Fn(f64) -> String) -> [String; 5] {
+    if numbers.is_empty() {
+        return Default::default();
+    }
+    let min = numbers.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mean = numbers.iter().sum::<f64>() / numbers.len() as f64;
+    [format(min), format(max), format(mean), String::new(), String::new()]
+}
+
+/// Formats a number with at most two decimals, dropping trailing zeros (`30`, `12.5`, `0.33`).
+pub fn format_number(number: f64) -> String {
+    let formatted = format!("{:.2}", number);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
@@ -0,0 +1,82 @@
+//! JSON, NDJSON and YAML input (`--input json`, `ndjson` and `yaml`).
+//!
+//! Many tools print a list of flat objects. Each object becomes a row, and the keys of all of
+//! them become the columns, in the order they first appear; rows without a key leave its cell
+//! empty. Nested arrays and objects are shown as compact JSON, and `null` as an empty cell.
+
+use crate::{Cell, Error, ParseError, ParseErrorKind, Table};
+use serde_json::{Map, Value};
+
+/// Column of the values in a list that aren't objects, e.g. `[1, 2, 3]`.
+const VALUE_COLUMN: &str = "value";
+
+/// Parses a JSON document: a list of objects, or a single object shown as one row.
+pub fn parse_json(input: &str) -> Result<Table<'static>, Error> {
+    let document: Value = serde_json::from_str(input).map_err(|e| syntax_error(format!("invalid JSON: {}", e), e.line(), e.column(), None))?;
+    Ok(table_from_document(document))
+}
+
+/// Parses newline-delimited JSON, one object per line. Blank lines are skipped.
+pub fn parse_ndjson(input: &str) -> Result<Table<'static>, Error> {
+    let values = input.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_idx, line)| {
+            serde_json::from_str(line).map_err(|e| syntax_error(format!("invalid JSON on line {}: {}", line_idx + 1, e), line_idx + 1, e.column(), None))
+        })
+        .collect::<Result<Vec<Value>, Error>>()?;
+    Ok(table_from_values(values))
+}
+
+/// Parses a YAML document: a sequence of mappings, or a single mapping shown as one row.
+pub fn parse_yaml(input: &str) -> Result<Table<'static>, Error> {
+    let document: Value = serde_yaml::from_str(input).map_err(|e| {
+        let (line, column, byte) = e.location().map_or((0, 0, None), |location| (location.line(), location.column(), Some(location.index() as u64)));
+        syntax_error(format!("invalid YAML: {}", e), line, column, byte)
+    })?;
+    Ok(table_from_document(document))
+}
+
+/// A syntax error at a line and column counting from 1, where 0 stands for unknown, and
+/// possibly a byte offset.
+fn syntax_error(message: String, line: usize, column: usize, byte: Option<u64>) -> Error {
+    let known = |n: usize| (n > 0).then_some(n as u64);
+    Error::Parse(ParseError { line: known(line), column: known(column), record: None, byte, kind: ParseErrorKind::Syntax, message })
+}
+
+fn table_from_document(document: Value) -> Table<'static> {
+    match document {
+        Value::Array(values) => table_from_values(values),
+        value => table_from_values(vec![value]),
+    }
+}
+
+/// Lays out one row per value, with a column for every key of any object.
+fn table_from_values(values: Vec<Value>) -> Table<'static> {
+    let objects: Vec<Map<String, Value>> = values.into_iter()
+        .map(|value| match value {
+            Value::Object(object) => object,
+            value => Map::from_iter([(VALUE_COLUMN.to_string(), value)]),
+        })
+        .collect();
+
+    let mut headers: Vec<&str> = Vec::new();
+    for key in objects.iter().flat_map(Map::keys) {
+        if !headers.contains(&key.as_str()) {
+            headers.push(key);
+        }
+    }
+    let records = objects.iter()
+        .map(|object| headers.iter().map(|&key| Cell::Owned(object.get(key).map(cell_text).unwrap_or_default())).collect())
+        .collect();
+    Table { headers: headers.into_iter().collect(), records }
+}
+
+/// The text of a cell: strings as they are, `null` as nothing and anything else as JSON.
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,283 @@
+//! Tabular data read from CSV or a log file.
+
+use crate::logs::ParsedLog;
+use crate::{Error, RenderOptions, Renderer};
+use csv::{ReaderBuilder, StringRecord, Trim};
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{Read, Write};
+
+/// How CSV input is parsed.
+#[derive(Debug, Clone, Copy)]
+pub struct CsvOptions {
+    /// Whether the first record names the columns. Without it, columns are named by position:
+    /// `1`, `2`, ...
+    pub has_headers: bool,
+    /// Lines starting with this byte are skipped.
+    pub comment: Option<u8>,
+    /// Byte fields are quoted with, so they can hold delimiters and line breaks.
+    pub quote: u8,
+    /// Byte escaping the next one inside a quoted field, e.g. `\` for `\"`.
+    pub escape: Option<u8>,
+    /// Whether a doubled quote inside a quoted field stands for a single quote.
+    pub double_quote: bool,
+    /// Whether records may have any number of fields. The table gets a column for every field
+    /// of the longest record, and shorter records are padded with empty cells.
+    pub flexible: bool,
+    /// Whether whitespace around headers, fields or both is removed.
+    pub trim: Trim,
+    /// Where the parsed text starts in the input it was cut from, e.g. after a preamble that
+    /// was skipped. Positions in errors and in [`MalformedRecord`]s count from the input's start.
+    pub offset: InputOffset,
+}
+
+/// Lines and bytes of an input before the part that's parsed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputOffset {
+    pub lines: u64,
+    pub bytes: u64,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions { has_headers: true, comment: None, quote: b'"', escape: None, double_quote: true, flexible: false, trim: Trim::None, offset: InputOffset::default() }
+    }
+}
+
+impl CsvOptions {
+    /// A CSV reader builder set up with these options.
+    pub fn reader_builder(&self) -> ReaderBuilder {
+        let mut builder = ReaderBuilder::new();
+        builder
+            .has_headers(self.has_headers)
+            .comment(self.comment)
+            .quote(self.quote)
+            .escape(self.escape)
+            .double_quote(self.double_quote)
+            .flexible(self.flexible)
+            .trim(self.trim);
+        builder
+    }
+}
+
+/// A record with the wrong number of fields, found by [`Table::from_reader_lenient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedRecord {
+    /// Position among the data records, starting at 1
+    pub record: usize,
+    /// Input line the record starts on, starting at 1
+    pub line: u64,
+    /// Number of fields the record has
+    pub fields: usize,
+    /// Number of fields the header (or first record) has
+    pub expected: usize,
+}
+
+impl MalformedRecord {
+    /// Whether the record was left out. Records with too many fields are; records with too
+    /// few are padded with empty cells instead.
+    pub fn skipped(&self) -> bool {
+        self.fields > self.expected
+    }
+}
+
+impl fmt::Display for MalformedRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = if self.skipped() { "skipped" } else { "padded with empty cells" };
+        let noun = if self.fields == 1 { "field" } else { "fields" };
+        write!(f, "record {} (line {}) has {} {} instead of {}; {}", self.record, self.line, self.fields, noun, self.expected, action)
+    }
+}
+
+/// The text of a cell. Cells of a table parsed with [`Table::from_borrowed_str`] borrow from
+/// the input where they can; all others own their text.
+pub type Cell<'a> = Cow<'a, str>;
+
+/// A header row and the records below it. Records have a cell for every header. Cells may
+/// borrow from the text the table was parsed from, which `'a` is the lifetime of.
+#[derive(Debug, Clone, Default)]
+pub struct Table<'a> {
+    pub headers: csv::StringRecord,
+    pub records: Vec<Vec<Cell<'a>>>,
+}
+
+impl<'a> Table<'a> {
+    /// Parses CSV input with a header row, padding short rows.
+    pub fn from_reader(reader: impl Read) -> Result<Table<'a>, Error> {
+        Self::from_reader_with_options(reader, &CsvOptions::default())
+    }
+
+    /// Parses CSV input as described by `options`. A record with a different number of fields
+    /// than the header is an error.
+    pub fn from_reader_with_options(reader: impl Read, options: &CsvOptions) -> Result<Table<'a>, Error> {
+        Self::parse(reader, None, options, false).map(|(table, _)| table)
+    }
+
+    /// Parses CSV input like [`Table::from_reader_with_options`], but carries on past records
+    /// with the wrong number of fields: short records are padded and long ones left out. Those
+    /// records are returned alongside the table, in input order.
+    pub fn from_reader_lenient(reader: impl Read, options: &CsvOptions) -> Result<(Table<'a>, Vec<MalformedRecord>), Error> {
+        Self::parse(reader, None, options, true)
+    }
+
+    /// Parses CSV input like [`Table::from_reader_with_options`] (or
+    /// [`Table::from_reader_lenient`] when `lenient`), with cells borrowing their text from
+    /// `input` rather than each being copied. Only quoted cells with doubled quotes in them need
+    /// copies. The table can't outlive `input`, such as a memory-mapped file.
+    pub fn from_borrowed_str(input: &'a str, options: &CsvOptions, lenient: bool) -> Result<(Table<'a>, Vec<MalformedRecord>), Error> {
+        Self::parse(input.as_bytes(), Some(input), options, lenient)
+    }
+
+    fn parse(reader: impl Read, source: Option<&'a str>, options: &CsvOptions, lenient: bool) -> Result<(Table<'a>, Vec<MalformedRecord>), Error> {
+        let mut reader = options.reader_builder()
+            .flexible(lenient || options.flexible)
+            .from_reader(reader);
+        let error = |e| Error::from_csv(e, options.offset);
+
+        let mut headers = if options.has_headers {
+            reader.headers().map_err(error)?.clone()
+        } else {
+            // The first record sets the number of columns, as a header row would
+            let columns = reader.headers().map_err(error)?.len();
+            (1..=columns).map(|n| n.to_string()).collect()
+        };
+        let header_count = headers.len();
+
+        // Collect all records
+        let mut records: Vec<Vec<Cell<'a>>> = Vec::new();
+        let mut malformed = Vec::new();
+        for (index, result) in reader.records().enumerate() {
+            let record = result.map_err(error)?;
+            if record.len() != header_count && !options.flexible {
+                let line = record.position().map_or(0, |position| position.line() + options.offset.lines);
+                let problem = MalformedRecord { record: index + 1, line, fields: record.len(), expected: header_count };
+                let skipped = problem.skipped();
+                malformed.push(problem);
+                if skipped {
+                    continue;
+                }
+            }
+            let mut row = match source {
+                Some(source) => borrow_cells(&record, source, options),
+                None => record.iter().map(|cell| Cell::Owned(cell.to_owned())).collect(),
+            };
+
+            // Pad row if it has fewer columns than headers
+            while row.len() < header_count {
+                row.push(Cell::Borrowed(""));
+            }
+
+            records.push(row);
+        }
+
+        // Records longer than the header add columns, named by position
+        let columns = records.iter().map(Vec::len).max().unwrap_or(0);
+        if columns > header_count {
+            headers.extend((header_count + 1..=columns).map(|n| n.to_string()));
+            for row in &mut records {
+                row.resize(columns, Cell::Borrowed(""));
+            }
+        }
+
+        Ok((Table { headers, records }, malformed))
+    }
+
+    /// Takes the rows parsed from a log file. Lines that weren't recognized are left out.
+    pub fn from_log(log: ParsedLog) -> Table<'a> {
+        Table { headers: log.headers, records: log.records.into_iter().map(|row| row.into_iter().map(Cell::Owned).collect()).collect() }
+    }
+
+    /// Cuts every cell longer than `max_bytes` (on a char boundary), marking the cut with `…`.
+    ///
+    /// A single enormous cell (e.g. a multi-megabyte blob) would otherwise dominate width
+    /// measurement and wrapping, so extreme values are shortened before any layout work happens.
+    /// A limit of 0 disables truncation.
+    pub fn truncate_cells(&mut self, max_bytes: usize) {
+        if max_bytes == 0 {
+            return;
+        }
+        for cell in self.records.iter_mut().flatten() {
+            truncate_cell(cell, max_bytes);
+        }
+    }
+
+    /// A copy with only the given columns, in the given order.
+    pub fn select_columns(&self, columns: &[usize]) -> Table<'a> {
+        Table {
+            headers: columns.iter().map(|&col_idx| &self.headers[col_idx]).collect(),
+            records: self.records.iter()
+                .map(|row| columns.iter().map(|&col_idx| row[col_idx].clone()).collect())
+                .collect(),
+        }
+    }
+
+    /// Renders the table. See [`Renderer`] to render the header and rows separately. A row
+    /// without a cell for every header is an [`Error::Layout`].
+    pub fn render_to(&self, out: &mut impl Write, options: &RenderOptions) -> Result<(), Error> {
+        Ok(Renderer::new(self, options)?.render(out)?)
+    }
+}
+
+/// Cuts a cell longer than `max_bytes` like [`Table::truncate_cells`], e.g. in rows rendered as
+/// they're read. A limit of 0 disables truncation.
+pub fn truncate_cell(cell: &mut Cell, max_bytes: usize) {
+    if max_bytes == 0 || cell.len() <= max_bytes {
+        return;
+    }
+    let mut end = max_bytes;
+    while !cell.is_char_boundary(end) {
+        end -= 1;
+    }
+    *cell = format!("{}…", &cell[..end]).into();
+}
+
+/// The cells of `record`, borrowed from where they appear in `source`. The raw text of each
+/// field is found by following the record from its start, and only borrowed when it reads the
+/// same as the parsed cell; anything else (an escaped quote, a line the parser skipped) is
+/// copied instead.
+fn borrow_cells<'a>(record: &StringRecord, source: &'a str, options: &CsvOptions) -> Vec<Cell<'a>> {
+    // After a CRLF the position can be that of the LF
+    let mut rest = record.position()
+        .and_then(|position| source.get(position.byte() as usize..))
+        .map_or("", |rest| rest.trim_start_matches(['\r', '\n']));
+    record.iter()
+        .map(|cell| {
+            let (raw, after) = match rest.strip_prefix(options.quote as char) {
+                Some(quoted) => match closing_quote(quoted, options) {
+                    Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                    None => ("", ""),
+                },
+                None => {
+                    let end = rest.find([',', '\n', '\r']).unwrap_or(rest.len());
+                    rest.split_at(end)
+                }
+            };
+            rest = after.strip_prefix(',').unwrap_or("");
+            match raw {
+                raw if raw == cell => Cell::Borrowed(raw),
+                // A field read with `trim`
+                raw if raw.trim() == cell => Cell::Borrowed(raw.trim()),
+                _ => Cell::Owned(cell.to_owned()),
+            }
+        })
+        .collect()
+}
+
+/// Where the quoted field `quoted` (without its opening quote) ends, skipping doubled and
+/// escaped quotes.
+fn closing_quote(quoted: &str, options: &CsvOptions) -> Option<usize> {
+    let bytes = quoted.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == options.quote {
+            if !options.double_quote || bytes.get(i + 1) != Some(&options.quote) {
+                return Some(i);
+            }
+            i += 1;
+        } else if Some(bytes[i]) == options.escape {
+            i += 1;
+        }
+        i += 1;
+    }
+    None
+}
@@ -0,0 +1,260 @@
+//! Terminal width and theme detection, with fallbacks for terminal multiplexers and Windows
+//! consoles.
+//!
+//! Inside tmux and GNU screen the standard streams may not be the terminal (popups, `run-shell`,
+//! pipes), sizes read while a pane is being attached or resized can be 0, and screen passes the
+//! background color query on to whichever terminals happen to be attached. Each detection
+//! therefore tries a chain of sources, which `--verbose` reports on standard error.
+//!
+//! Windows consoles only act on escape sequences once virtual terminal processing is turned on,
+//! and consoles from before Windows 10 can't turn it on at all. Those get ASCII borders and the
+//! 16 basic colors, and aren't asked for their background color.
+
+use std::fmt::Display;
+use std::io::{self, IsTerminal};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::Duration;
+use terminal_colorsaurus::{theme_mode, QueryOptions, ThemeMode};
+
+/// Environment variable naming the theme, used when `--theme` is not given.
+pub const THEME_ENV_VAR: &str = "CSVPRETTY_THEME";
+
+/// How long to wait for the terminal to report its background color. Terminals that can't
+/// answer are mostly found out straight away; this bounds the wait on ones that stay silent.
+const THEME_QUERY_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Width when no terminal can be measured.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Height when no terminal can be measured.
+pub const DEFAULT_HEIGHT: usize = 24;
+
+/// A terminal multiplexer the program is running inside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplexer {
+    Tmux,
+    Screen,
+}
+
+impl Multiplexer {
+    /// The multiplexer named by the environment: `TMUX` is set by tmux and `STY` by screen.
+    pub fn detect() -> Option<Multiplexer> {
+        if std::env::var_os("TMUX").is_some() {
+            Some(Multiplexer::Tmux)
+        } else if std::env::var_os("STY").is_some() {
+            Some(Multiplexer::Screen)
+        } else {
+            None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Multiplexer::Tmux => "tmux",
+            Multiplexer::Screen => "GNU screen",
+        }
+    }
+}
+
+/// Where a width can be read from, named for `--verbose`, and how to read it.
+type WidthSource = (&'static str, fn() -> Option<usize>);
+
+/// Prints a diagnostic line on standard error with `--verbose`.
+fn note(verbose: bool, message: impl Display) {
+    if verbose {
+        eprintln!("Verbose: {}", message);
+    }
+}
+
+/// Detects the terminal width from, in order: the standard streams, the controlling terminal
+/// and tmux's own idea of the pane size (inside a multiplexer only), `COLUMNS`, and finally
+/// [`DEFAULT_WIDTH`]. A width of 0 counts as not found.
+pub fn detect_width(verbose: bool) -> usize {
+    let multiplexer = Multiplexer::detect();
+    if let Some(multiplexer) = multiplexer {
+        note(verbose, format!("running inside {}", multiplexer.name()));
+    }
+
+    let usable = |width: Option<usize>| width.filter(|&width| width > 0);
+    let mut sources: Vec<WidthSource> = vec![("the standard streams", standard_streams_width)];
+    // A Windows console can be opened even when every standard stream is redirected, e.g. under
+    // Git Bash
+    if multiplexer.is_some() || cfg!(windows) {
+        sources.push(("the controlling terminal", controlling_terminal_width));
+    }
+    if multiplexer == Some(Multiplexer::Tmux) {
+        sources.push(("tmux display-message", tmux_pane_width));
+    }
+    sources.push(("COLUMNS", columns_width));
+
+    for (source, width) in sources {
+        match usable(width()) {
+            Some(width) => {
+                note(verbose, format!("terminal width {} from {}", width, source));
+                return width;
+            }
+            None => note(verbose, format!("no terminal width from {}", source)),
+        }
+    }
+    note(verbose, format!("terminal width {} by default", DEFAULT_WIDTH));
+    DEFAULT_WIDTH
+}
+
+/// The width of the terminal that stdout, stderr or stdin is connected to.
+fn standard_streams_width() -> Option<usize> {
+    terminal_size::terminal_size().map(|(w, _)| usize::from(w.0))
+}
+
+/// The width of the process's controlling terminal, which is still the pane when every
+/// standard stream is redirected.
+fn controlling_terminal_width() -> Option<usize> {
+    // The console's screen buffer is only measured when opened for writing as well
+    let tty = std::fs::OpenOptions::new().read(true).write(true).open(CONTROLLING_TERMINAL).ok()?;
+    terminal_size::terminal_size_of(&tty).map(|(w, _)| usize::from(w.0))
+}
+
+/// The file standing for the process's controlling terminal.
+#[cfg(windows)]
+const CONTROLLING_TERMINAL: &str = "CONOUT$";
+#[cfg(not(windows))]
+const CONTROLLING_TERMINAL: &str = "/dev/tty";
+
+/// Whether stdout is a Windows console that shows escape sequences as text, and whose font may
+/// lack the box-drawing characters. Virtual terminal processing is turned on first where the
+/// console has it, as Windows 10 consoles leave it off, so this has to be asked before anything
+/// colored is written. Always false elsewhere.
+pub fn is_legacy_console(verbose: bool) -> bool {
+    static LEGACY: OnceLock<bool> = OnceLock::new();
+    *LEGACY.get_or_init(|| legacy_console(verbose))
+}
+
+#[cfg(windows)]
+fn legacy_console(verbose: bool) -> bool {
+    if !io::stdout().is_terminal() {
+        return false;
+    }
+    // Also true for consoles such as Git Bash's, which set TERM and handle escapes themselves
+    if crossterm::ansi_support::supports_ansi() {
+        note(verbose, "escape sequences are on in the Windows console");
+        false
+    } else {
+        note(verbose, "the Windows console can't show escape sequences; using ASCII borders and 16 colors");
+        true
+    }
+}
+
+#[cfg(not(windows))]
+fn legacy_console(_verbose: bool) -> bool {
+    false
+}
+
+/// The width of the current tmux pane, as tmux itself reports it.
+fn tmux_pane_width() -> Option<usize> {
+    let mut command = Command::new("tmux");
+    command.args(["display-message", "-p"]);
+    if let Ok(pane) = std::env::var("TMUX_PANE") {
+        command.args(["-t", &pane]);
+    }
+    let output = command.arg("#{pane_width}").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()?.trim().parse().ok()
+}
+
+/// The width given by the `COLUMNS` variable, which some shells export.
+fn columns_width() -> Option<usize> {
+    std::env::var("COLUMNS").ok()?.trim().parse().ok()
+}
+
+/// Detects the terminal height from the standard streams or `LINES`, falling back to
+/// [`DEFAULT_HEIGHT`].
+pub fn detect_height() -> usize {
+    terminal_size::terminal_size()
+        .map(|(_, h)| usize::from(h.0))
+        .or_else(|| std::env::var("LINES").ok()?.trim().parse().ok())
+        .filter(|&height| height > 0)
+        .unwrap_or(DEFAULT_HEIGHT)
+}
+
+/// Which theme the colors are picked for (`--theme`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ThemeChoice {
+    /// Ask the terminal for its background color, then look at COLORFGBG
+    Auto,
+    /// Colors for a dark background
+    Dark,
+    /// Colors for a light background
+    Light,
+}
+
+/// The theme for the table: the one chosen, or else the file theme for output to a file and
+/// the detected one for the terminal.
+pub fn resolve_theme(choice: ThemeChoice, to_file: bool, verbose: bool) -> ThemeMode {
+    match choice {
+        ThemeChoice::Dark | ThemeChoice::Light => {
+            let mode = if choice == ThemeChoice::Dark { ThemeMode::Dark } else { ThemeMode::Light };
+            note(verbose, format!("{} theme as chosen; not asking the terminal", theme_name(mode)));
+            mode
+        }
+        ThemeChoice::Auto if to_file => file_theme(verbose),
+        ThemeChoice::Auto => detect_theme(verbose),
+    }
+}
+
+/// Detects whether the terminal has a dark or light background by asking the terminal, then
+/// from `COLORFGBG`, falling back to dark. Screen isn't asked: it passes the question on to the
+/// attached terminals and either none or several of them answer. Nor is the terminal asked when
+/// stdout isn't one, as the table isn't going to be seen against its background.
+pub fn detect_theme(verbose: bool) -> ThemeMode {
+    if Multiplexer::detect() == Some(Multiplexer::Screen) {
+        note(verbose, "not asking GNU screen for its background color");
+    } else if !io::stdout().is_terminal() {
+        note(verbose, "not asking for the background color, as stdout isn't a terminal");
+    } else if is_legacy_console(verbose) {
+        note(verbose, "not asking the Windows console for its background color");
+    } else {
+        let mut options = QueryOptions::default();
+        options.timeout = THEME_QUERY_TIMEOUT;
+        match theme_mode(options) {
+            Ok(mode) => {
+                note(verbose, format!("{} theme from the terminal's background color", theme_name(mode)));
+                return mode;
+            }
+            Err(e) => note(verbose, format!("no background color from the terminal: {}", e)),
+        }
+    }
+
+    match std::env::var("COLORFGBG").ok().as_deref().and_then(colorfgbg_theme) {
+        Some(mode) => {
+            note(verbose, format!("{} theme from COLORFGBG", theme_name(mode)));
+            mode
+        }
+        None => {
+            note(verbose, "dark theme by default");
+            ThemeMode::Dark
+        }
+    }
+}
+
+/// The theme for a table written to a file with --color always. A file has no background to
+/// ask about, so it gets the dark theme, as when a terminal doesn't answer.
+fn file_theme(verbose: bool) -> ThemeMode {
+    note(verbose, "dark theme for output to a file; not asking the terminal");
+    ThemeMode::Dark
+}
+
+/// The theme for a `COLORFGBG` value such as `15;0`, set by rxvt and Konsole: the last field is
+/// the background's ANSI color number, and white (7 or 15) means a light background.
+fn colorfgbg_theme(value: &str) -> Option<ThemeMode> {
+    let background: u8 = value.rsplit(';').next()?.parse().ok()?;
+    Some(if matches!(background, 7 | 15) { ThemeMode::Light } else { ThemeMode::Dark })
+}
+
+fn theme_name(mode: ThemeMode) -> &'static str {
+    match mode {
+        ThemeMode::Dark => "dark",
+        ThemeMode::Light => "light",
+    }
+}
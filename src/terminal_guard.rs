@@ -0,0 +1,88 @@
+//! Putting the terminal back as it was, however csvpretty exits.
+//!
+//! The interactive viewer switches the terminal to raw mode and the alternate screen. Leaving
+//! it that way would leave the shell without echo or a cursor, so the changes are undone when
+//! the viewer returns, when csvpretty panics, and (on Unix) when it's killed by a signal.
+
+use crossterm::terminal;
+use crossterm::{cursor, execute};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+/// Whether the terminal has been changed and not yet restored.
+static CHANGED: AtomicBool = AtomicBool::new(false);
+
+/// Keeps the terminal in raw mode on the alternate screen, with line wrapping off and the
+/// cursor hidden, until it's dropped or [`TerminalGuard::leave`] is called.
+pub struct TerminalGuard(());
+
+impl TerminalGuard {
+    pub fn enter() -> io::Result<TerminalGuard> {
+        install_handlers();
+        // Marked first, so that whatever part of the switch succeeded is undone on failure
+        CHANGED.store(true, Ordering::SeqCst);
+        let guard = TerminalGuard(());
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, terminal::DisableLineWrap, cursor::Hide)?;
+        Ok(guard)
+    }
+
+    /// Restores the terminal, reporting whether that worked.
+    pub fn leave(self) -> io::Result<()> {
+        restore()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Nothing more can be done about a failure here
+        let _ = restore();
+    }
+}
+
+/// Undoes the changes, if they haven't been undone already.
+fn restore() -> io::Result<()> {
+    if !CHANGED.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let shown = execute!(io::stdout(), cursor::Show, terminal::EnableLineWrap, terminal::LeaveAlternateScreen);
+    terminal::disable_raw_mode().and(shown)
+}
+
+/// Installs the panic hook and signal handlers, the first time the terminal is changed.
+fn install_handlers() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        // Restored before the message is printed, so it lands on the normal screen and stays
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = restore();
+            previous(info);
+        }));
+        #[cfg(unix)]
+        install_signal_handlers();
+    });
+}
+
+/// Restores the terminal on the signals that end a process by default, then lets the signal
+/// end csvpretty as it would have. Raw mode stops Ctrl-C from sending SIGINT, but `kill` and
+/// a closing terminal still can.
+#[cfg(unix)]
+fn install_signal_handlers() {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGQUIT, SIGTERM};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = match Signals::new([SIGINT, SIGTERM, SIGHUP, SIGQUIT]) {
+        Ok(signals) => signals,
+        // The panic hook and the guard still cover the other ways out
+        Err(_) => return,
+    };
+    std::thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            let _ = restore();
+            let _ = signal_hook::low_level::emulate_default_handler(signal);
+            std::process::exit(128 + signal);
+        }
+    });
+}
@@ -0,0 +1,87 @@
+//! Helpers for snapshot tests of rendered tables, enabled by the `test-support` feature.
+//!
+//! Rendering to a string gives the same output on every machine, so crates embedding the
+//! renderer can snapshot tables without running the `csvpretty` binary in a terminal:
+//!
+//! ```
+//! use csvpretty::test_support::{fixtures, render_to_string, snapshot_options};
+//!
+//! let output = render_to_string(&fixtures::people(), &snapshot_options(40));
+//! assert!(output.contains("Alice"));
+//! ```
+
+use crate::wrap::{set_width_conventions, width_conventions, WidthConventions};
+use crate::{RenderOptions, Table};
+
+/// Options for snapshots: no colors, word wrapping and Unicode borders at `width` columns.
+pub fn snapshot_options(width: usize) -> RenderOptions {
+    RenderOptions {
+        terminal_width: width,
+        ..RenderOptions::default()
+    }
+}
+
+/// Renders a table to a string. Text is measured with the default [`WidthConventions`]
+/// whatever the current thread uses, so snapshots don't depend on the order tests run in.
+pub fn render_to_string(table: &Table, options: &RenderOptions) -> String {
+    let conventions = width_conventions();
+    set_width_conventions(WidthConventions::default());
+    let mut out = Vec::new();
+    let result = table.render_to(&mut out, options);
+    set_width_conventions(conventions);
+    result.expect("writing to a Vec can't fail");
+    String::from_utf8(out).expect("rendered tables are UTF-8")
+}
+
+/// Tables with the kinds of content that are hard to lay out, for testing a renderer with.
+pub mod fixtures {
+    use crate::{Cell, Table};
+
+    /// Words the generated cells are made of.
+    const WORDS: [&str; 12] = [
+        "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliet", "kilo", "lima",
+    ];
+
+    fn table(headers: &[&str], records: Vec<Vec<Cell<'static>>>) -> Table<'static> {
+        Table { headers: headers.iter().copied().collect(), records }
+    }
+
+    /// Three people with a name, an age and a city.
+    pub fn people() -> Table<'static> {
+        let records = [["Alice", "30", "New York"], ["Bob", "25", "Los Angeles"], ["Charlie", "35", "Chicago"]];
+        table(&["name", "age", "city"], records.iter().map(|row| row.map(Cell::Borrowed).to_vec()).collect())
+    }
+
+    /// A table of `rows` × `columns` cells named after their position: `r1c1`, `r1c2`, ...
+    /// under headers `col_1`, `col_2`, ...
+    pub fn grid(rows: usize, columns: usize) -> Table<'static> {
+        let headers: Vec<String> = (1..=columns).map(|col| format!("col_{}", col)).collect();
+        Table {
+            headers: headers.iter().collect(),
+            records: (1..=rows).map(|row| (1..=columns).map(|col| Cell::Owned(format!("r{}c{}", row, col))).collect()).collect(),
+        }
+    }
+
+    /// An id column and a text column whose cells hold `words` words each, for testing
+    /// wrapping and truncation. The same arguments always give the same text.
+    pub fn long_text(rows: usize, words: usize) -> Table<'static> {
+        let records = (0..rows)
+            .map(|row| {
+                let text: Vec<&str> = (0..words).map(|word| WORDS[(row * 7 + word * 5) % WORDS.len()]).collect();
+                vec![Cell::Owned((row + 1).to_string()), Cell::Owned(text.join(" "))]
+            })
+            .collect();
+        table(&["id", "text"], records)
+    }
+
+    /// Cells whose display width differs from their length: wide CJK characters, emoji
+    /// sequences, combining accents, embedded line breaks and empty cells.
+    pub fn unicode() -> Table<'static> {
+        let records = [
+            ["東京", "🇯🇵", "Tōkyō"],
+            ["Zürich", "🇨🇭", "Zu\u{308}rich"],
+            ["", "👩‍💻", "line one\nline two"],
+        ];
+        table(&["name", "flag", "note"], records.iter().map(|row| row.map(Cell::Borrowed).to_vec()).collect())
+    }
+}
@@ -0,0 +1,71 @@
+//! A totals row under the table (`--totals`).
+//!
+//! Each numeric column gets one figure for all of its rows: their sum, mean, minimum or
+//! maximum. Columns count as numeric when type inference finds them so, and typed columns are
+//! totalled by their underlying quantity and shown in the type's format, like the subtotal rows
+//! of `--group-subtotals`. Text columns are left empty.
+
+use crate::column_types::ColumnType;
+use crate::inference::InferredType;
+use crate::{groups, is_numeric, stats};
+use csvpretty::Cell;
+
+/// The figure the totals row shows for each numeric column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TotalsFunction {
+    /// The sum of the column's values
+    Sum,
+    /// Their mean
+    Mean,
+    /// The smallest value
+    Min,
+    /// The largest value
+    Max,
+}
+
+/// The totals row for `records`, with one cell per column. Sums of percentages and dates, and
+/// means of dates, mean nothing, so those columns are left empty like text columns.
+pub fn totals_row(records: &[Vec<Cell>], column_types: &[Option<ColumnType>], inferred_types: &[InferredType], function: TotalsFunction) -> Vec<Cell<'static>> {
+    (0..column_types.len())
+        .map(|column| {
+            let values = records.iter().filter_map(|row| row.get(column)).map(|cell| &**cell);
+            Cell::Owned(match (column_types[column], inferred_types[column], function) {
+                (Some(ColumnType::Percent(_)), _, TotalsFunction::Sum) => String::new(),
+                (Some(ColumnType::Date(_)), _, TotalsFunction::Sum | TotalsFunction::Mean) => String::new(),
+                (Some(column_type), _, _) => {
+                    let quantities: Vec<f64> = values.filter_map(|value| column_type.numeric_value(value)).collect();
+                    aggregate(&quantities, function).map_or_else(String::new, |quantity| column_type.format_quantity(quantity))
+                }
+                (None, InferredType::Integer | InferredType::Float, TotalsFunction::Sum) => groups::sum(values),
+                (None, InferredType::Integer | InferredType::Float, TotalsFunction::Mean) => {
+                    let numbers: Vec<f64> = values.filter(|value| is_numeric(value)).filter_map(|value| value.trim().parse().ok()).collect();
+                    aggregate(&numbers, function).map_or_else(String::new, stats::format_number)
+                }
+                // The smallest or largest value as written, so it keeps its decimals
+                (None, InferredType::Integer | InferredType::Float, TotalsFunction::Min | TotalsFunction::Max) => {
+                    let numbers = values.filter(|value| is_numeric(value)).filter_map(|value| Some((value.trim().parse::<f64>().ok()?, value.trim())));
+                    let extreme = match function {
+                        TotalsFunction::Min => numbers.min_by(|a, b| a.0.total_cmp(&b.0)),
+                        _ => numbers.max_by(|a, b| a.0.total_cmp(&b.0)),
+                    };
+                    extreme.map_or_else(String::new, |(_, value)| value.to_string())
+                }
+                (None, _, _) => String::new(),
+            })
+        })
+        .collect()
+}
+
+/// `function` applied to `numbers`, or None when there are none.
+fn aggregate(numbers: &[f64], function: TotalsFunction) -> Option<f64> {
+    if numbers.is_empty() {
+        return None;
+    }
+    let sum: f64 = numbers.iter().sum();
+    Some(match function {
+        TotalsFunction::Sum => sum,
+        TotalsFunction::Mean => sum / numbers.len() as f64,
+        TotalsFunction::Min => numbers.iter().copied().fold(f64::INFINITY, f64::min),
+        TotalsFunction::Max => numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+    })
+}
@@ -0,0 +1,39 @@
+//! Making whitespace at the edges of cells visible (`--show-whitespace`).
+//!
+//! Padded exports and values with a stray trailing space look the same as clean ones in a
+//! table, but don't match when compared or joined. Leading and trailing spaces are shown as `·`
+//! and tabs as `→`, so they can be told apart from the padding around cells.
+
+use csvpretty::Cell;
+
+/// The symbol standing in for a whitespace character, if it's one that's marked.
+fn symbol(ch: char) -> Option<char> {
+    match ch {
+        ' ' => Some('·'),
+        '\t' => Some('→'),
+        _ => None,
+    }
+}
+
+/// `text` with its leading and trailing spaces and tabs replaced by visible symbols, or None if
+/// it has none.
+pub fn show(text: &str) -> Option<String> {
+    let inner = text.trim_matches([' ', '\t']);
+    if inner.len() == text.len() {
+        return None;
+    }
+    let start = text.len() - text.trim_start_matches([' ', '\t']).len();
+    let (leading, rest) = text.split_at(start);
+    let trailing = &rest[inner.len()..];
+    let mark = |edge: &str| edge.chars().filter_map(symbol).collect::<String>();
+    Some(format!("{}{}{}", mark(leading), inner, mark(trailing)))
+}
+
+/// Marks the edge whitespace of every cell in `row`.
+pub fn show_in_row(row: &mut [Cell]) {
+    for cell in row {
+        if let Some(shown) = show(cell) {
+            *cell = shown.into();
+        }
+    }
+}
@@ -0,0 +1,394 @@
+//! Fitting cell text into a column: word and character wrapping, or truncation with `…`.
+
+use crate::WrapMode;
+use std::borrow::Cow;
+use std::cell::Cell;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Resets all colors and styles. Ends lines that contain escape sequences from the input.
+const RESET: &str = "\x1b[0m";
+
+/// How many columns a terminal draws emoji sequences in: flags, and emoji made of several
+/// characters with a variation selector, skin tone or zero-width joiner.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum EmojiWidth {
+    /// As the Unicode width tables say, which is 2 for most of them
+    #[default]
+    Auto,
+    /// One column, as terminals without emoji sequence support often draw them
+    #[value(name = "1")]
+    One,
+    /// Two columns
+    #[value(name = "2")]
+    Two,
+}
+
+/// Widths that terminals disagree about. Border misalignment on a terminal with other
+/// conventions can be fixed by setting these to match it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WidthConventions {
+    pub emoji_width: EmojiWidth,
+    /// Draw East Asian ambiguous-width characters, such as `±` or `①`, two columns wide, as
+    /// terminals set up for CJK text do.
+    pub ambiguous_wide: bool,
+}
+
+thread_local! {
+    static WIDTH_CONVENTIONS: Cell<WidthConventions> = Cell::new(WidthConventions::default());
+}
+
+/// Sets the width conventions used to measure text on the current thread.
+pub fn set_width_conventions(conventions: WidthConventions) {
+    WIDTH_CONVENTIONS.with(|cell| cell.set(conventions));
+}
+
+/// The width conventions in use on the current thread.
+pub(crate) fn width_conventions() -> WidthConventions {
+    WIDTH_CONVENTIONS.with(Cell::get)
+}
+
+/// Width of one grapheme cluster under the current [`WidthConventions`].
+fn unit_width(unit: &str) -> usize {
+    let conventions = width_conventions();
+    let width = if conventions.ambiguous_wide { unit.width_cjk() } else { unit.width() };
+    match conventions.emoji_width {
+        EmojiWidth::One if is_emoji_sequence(unit) => 1,
+        EmojiWidth::Two if is_emoji_sequence(unit) => 2,
+        _ => width,
+    }
+}
+
+/// Whether a grapheme cluster is a flag or an emoji sequence: regional indicators, or several
+/// characters including a variation selector 16, a skin tone modifier or a zero-width joiner.
+fn is_emoji_sequence(unit: &str) -> bool {
+    unit.chars().any(|c| {
+        matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+            || (unit.chars().nth(1).is_some() && matches!(c, '\u{FE0F}' | '\u{1F3FB}'..='\u{1F3FF}' | '\u{200D}'))
+    })
+}
+
+/// Length in bytes of the ANSI escape sequence at the start of `text`, if there is one.
+/// Recognizes the sequences that only style text: SGR sequences such as colors
+/// (`ESC [ 31 m`), erasing to the end of the line (`ESC [ K`, which grep writes after colors)
+/// and OSC 8 hyperlinks (ended by BEL or `ESC \`). Others, such as cursor movement, would
+/// corrupt the table, so their ESC is a control character like any other.
+fn escape_len(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    if bytes.first() != Some(&0x1b) {
+        return None;
+    }
+    match bytes.get(1) {
+        Some(b'[') => {
+            // Parameter and intermediate bytes, then one final byte
+            let end = bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b))?;
+            matches!(bytes[end + 2], b'm' | b'K').then_some(end + 3)
+        }
+        Some(b']') if bytes[2..].starts_with(b"8;") => {
+            let end = (2..bytes.len()).find(|&i| bytes[i] == 0x07 || (bytes[i] == 0x1b && bytes.get(i + 1) == Some(&b'\\')))?;
+            Some(if bytes[end] == 0x07 { end + 1 } else { end + 2 })
+        }
+        _ => None,
+    }
+}
+
+/// How a control character is shown: `\r`, `\n` and `\t` as escapes, ESC as `␛` and the rest
+/// by their code, e.g. `\x07` for a bell.
+fn control_escape(ch: char) -> Cow<'static, str> {
+    match ch {
+        '\r' => Cow::Borrowed("\\r"),
+        '\n' => Cow::Borrowed("\\n"),
+        '\t' => Cow::Borrowed("\\t"),
+        '\x1b' => Cow::Borrowed("␛"),
+        ch if (ch as u32) < 0x80 => Cow::Owned(format!("\\x{:02x}", ch as u32)),
+        ch => Cow::Owned(format!("\\u{{{:x}}}", ch as u32)),
+    }
+}
+
+/// Whether a unit from [`display_units`] is made of control characters, which are shown as
+/// escapes rather than passed to the terminal.
+fn is_control(unit: &str) -> bool {
+    unit.starts_with(char::is_control) && escape_len(unit).is_none()
+}
+
+/// Replaces the control characters in `text` with visible escapes, e.g. `\r` or `␛`, since a
+/// terminal would act on them: move the cursor, ring the bell or change its settings. Escape
+/// sequences that only style text are kept, as they're drawn with colors or stripped.
+pub fn escape_control_chars(text: &str) -> Cow<'_, str> {
+    if !text.contains(char::is_control) || !display_units(text).any(|(unit, _)| is_control(unit)) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for (unit, _) in display_units(text) {
+        if is_control(unit) {
+            unit.chars().for_each(|ch| escaped.push_str(&control_escape(ch)));
+        } else {
+            escaped.push_str(unit);
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Splits text into the pieces a terminal draws as one symbol: grapheme clusters, such as a
+/// letter with its combining accents, a flag made of two regional indicators, or an emoji
+/// sequence joined with U+200D. `⚠️` is two characters of width 1 and 0 but is drawn two
+/// columns wide, so it has to be measured whole, and wrapping mustn't split it.
+/// ANSI escape sequences in the text are units of width 0, and control characters are as wide
+/// as the escapes they're shown as.
+pub(crate) fn display_units(text: &str) -> impl Iterator<Item = (&str, usize)> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if let Some(len) = escape_len(rest) {
+            let (unit, tail) = rest.split_at(len);
+            rest = tail;
+            return Some((unit, 0));
+        }
+        // ESC is a control character and so a cluster of its own: clusters never swallow the
+        // start of an escape sequence
+        let unit = rest.graphemes(true).next()?;
+        rest = &rest[unit.len()..];
+        if unit.starts_with(char::is_control) {
+            return Some((unit, unit.chars().map(|ch| control_escape(ch).chars().count()).sum()));
+        }
+        Some((unit, unit_width(unit)))
+    })
+}
+
+/// Display width of `text`, not counting ANSI escape sequences.
+pub fn display_width(text: &str) -> usize {
+    display_units(text).map(|(_, width)| width).sum()
+}
+
+/// Removes ANSI escape sequences, for output without colors.
+pub fn strip_ansi(text: &str) -> String {
+    display_units(text).filter(|&(unit, _)| escape_len(unit).is_none()).map(|(unit, _)| unit).collect()
+}
+
+/// The lines of a cell. Newlines inside quoted CSV fields are hard line breaks, written as
+/// `\n` or `\r\n`.
+pub(crate) fn lines(text: &str) -> impl Iterator<Item = &str> {
+    text.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line))
+}
+
+/// Where truncated text is cut, which is where the marker goes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Ellipsis {
+    /// Keep the end, e.g. `…/logs/app.log`
+    Start,
+    /// Keep both ends, e.g. `/var/…/app.log`, for paths and identifiers with meaningful suffixes
+    Middle,
+    /// Keep the start, e.g. `/var/lib/…`
+    #[default]
+    End,
+}
+
+/// Fits a cell into `max_width` columns: each of its lines becomes one line in the no-wrap
+/// and truncate modes, cut off with `marker` where `ellipsis` says, otherwise as many lines as
+/// it takes. Lines are borrowed from `text` unless they had to be changed, e.g. to escape
+/// control characters.
+pub(crate) fn wrap_text<'a>(text: &'a str, max_width: usize, wrap_mode: WrapMode, marker: &str, ellipsis: Ellipsis) -> Vec<Cow<'a, str>> {
+    let mut lines = fit_text(text, max_width, wrap_mode, marker, ellipsis);
+    for line in &mut lines {
+        if let Cow::Owned(escaped) = escape_control_chars(line) {
+            *line = Cow::Owned(escaped);
+        }
+    }
+    // Colors from the input stop at the end of the line instead of running into the borders
+    for line in lines.iter_mut().filter(|line| line.contains('\x1b') && !line.ends_with(RESET)) {
+        line.to_mut().push_str(RESET);
+    }
+    lines
+}
+
+fn fit_text<'a>(text: &'a str, max_width: usize, wrap_mode: WrapMode, marker: &str, ellipsis: Ellipsis) -> Vec<Cow<'a, str>> {
+    if text.is_empty() {
+        return vec![Cow::Borrowed("")];
+    }
+    if text.contains('\n') {
+        // A line break at the very end of a cell would only add a blank line
+        let text = text.trim_end_matches(['\r', '\n']);
+        return lines(text).flat_map(|line| fit_text(line, max_width, wrap_mode, marker, ellipsis)).collect();
+    }
+
+    match wrap_mode {
+        // No-wrap columns only get narrower than their content when --max-col-width caps them
+        WrapMode::None | WrapMode::Truncate => {
+            vec![cut_to_width(text, max_width, marker, ellipsis)]
+        }
+        WrapMode::Word => {
+            wrap_text_word(text, max_width)
+        }
+        WrapMode::Char => {
+            wrap_text_char(text, max_width).into_iter().map(Cow::Borrowed).collect()
+        }
+    }
+}
+
+/// The words of `text`, split at whitespace like [`str::split_whitespace`], with the byte
+/// offset each starts at. Tabs and other whitespace control characters are part of the words,
+/// so they're shown escaped rather than turned into spaces.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let is_space = |ch: char| ch.is_whitespace() && !ch.is_control();
+    let mut start = 0;
+    text.split_inclusive(is_space).filter_map(move |piece| {
+        let word_start = start;
+        start += piece.len();
+        let word = piece.trim_end_matches(is_space);
+        (!word.is_empty()).then_some((word_start, word))
+    })
+}
+
+fn wrap_text_word(text: &str, max_width: usize) -> Vec<Cow<'_, str>> {
+    let mut lines = Vec::new();
+    // The current line runs from the start of its first word to the end of its last. It's
+    // `plain` while the words are one space apart, so it can be shown as it is in the text.
+    let mut current_line: Option<(usize, usize)> = None;
+    let mut plain = true;
+    let mut current_width = 0;
+    let line_text = |(start, end): (usize, usize), plain: bool| -> Cow<'_, str> {
+        let line = &text[start..end];
+        if plain { Cow::Borrowed(line) } else { Cow::Owned(words(line).map(|(_, word)| word).collect::<Vec<_>>().join(" ")) }
+    };
+
+    for (start, word) in words(text) {
+        let word_width = display_width(word);
+        let end = start + word.len();
+
+        match current_line {
+            Some((line_start, line_end)) if current_width + 1 + word_width <= max_width => {
+                // Add word to current line
+                plain &= &text[line_end..start] == " ";
+                current_line = Some((line_start, end));
+                current_width += 1 + word_width;
+            }
+            _ => {
+                // Start new line
+                if let Some(line) = current_line.take() {
+                    lines.push(line_text(line, plain));
+                }
+                if word_width <= max_width {
+                    current_line = Some((start, end));
+                    current_width = word_width;
+                    plain = true;
+                } else {
+                    // Word is too long, split it character by character
+                    lines.extend(wrap_text_char(word, max_width).into_iter().map(Cow::Borrowed));
+                }
+            }
+        }
+    }
+
+    if let Some(line) = current_line {
+        lines.push(line_text(line, plain));
+    }
+
+    if lines.is_empty() {
+        lines.push(Cow::Borrowed(""));
+    }
+
+    lines
+}
+
+fn wrap_text_char(text: &str, max_width: usize) -> Vec<&str> {
+    let mut lines = Vec::new();
+    // Byte range of the current line
+    let mut start = 0;
+    let mut end = 0;
+    let mut current_width = 0;
+
+    for (unit, unit_width) in display_units(text) {
+        if current_width + unit_width > max_width && end > start {
+            lines.push(&text[start..end]);
+            start = end;
+            current_width = 0;
+        }
+        end += unit.len();
+        current_width += unit_width;
+    }
+
+    if end > start || lines.is_empty() {
+        lines.push(&text[start..end]);
+    }
+
+    lines
+}
+
+/// Placeholder in a truncation marker for the number of characters cut off.
+const HIDDEN_CHARS_PLACEHOLDER: &str = "{chars}";
+
+/// Shortens `text` to at most `max_width` display columns, marking the cut with `…`.
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    truncate_with_marker(text, max_width, "…")
+}
+
+/// Shortens `text` to at most `max_width` display columns, marking the cut with `marker`, in
+/// which `{chars}` stands for the number of characters cut off, e.g. `[+{chars}]`. A marker
+/// wider than `max_width` gives way to `…`.
+pub fn truncate_with_marker(text: &str, max_width: usize, marker: &str) -> String {
+    truncate_at(text, max_width, marker, Ellipsis::End)
+}
+
+/// Like [`truncate_with_marker`], cutting the text at its start, middle or end. Escape
+/// sequences cut out ahead of the kept end stay in, so the end keeps its colors. Control
+/// characters are replaced as by [`escape_control_chars`], and measured that way.
+pub fn truncate_at(text: &str, max_width: usize, marker: &str, ellipsis: Ellipsis) -> String {
+    cut_to_width(text, max_width, marker, ellipsis).into_owned()
+}
+
+/// [`truncate_at`], borrowing text that already fits.
+fn cut_to_width<'a>(text: &'a str, max_width: usize, marker: &str, ellipsis: Ellipsis) -> Cow<'a, str> {
+    if display_width(text) <= max_width {
+        return escape_control_chars(text);
+    }
+    let units: Vec<(&str, usize)> = display_units(text).collect();
+    let is_char = |unit: &str| escape_len(unit).is_none();
+    let total = units.iter().filter(|(unit, _)| is_char(unit)).count();
+    let label_for = |hidden: usize| marker.replace(HIDDEN_CHARS_PLACEHOLDER, &hidden.to_string());
+    // How many of the leading units fit in `room`, and how many characters they hold
+    fn fitting<'a>(units: impl Iterator<Item = &'a (&'a str, usize)>, room: usize) -> (usize, usize) {
+        let mut width = 0;
+        let mut kept = 0;
+        let mut shown = 0;
+        for &(unit, unit_width) in units {
+            if width + unit_width > room {
+                break;
+            }
+            width += unit_width;
+            kept += 1;
+            shown += usize::from(escape_len(unit).is_none());
+        }
+        (kept, shown)
+    }
+
+    // The marker's width depends on the count it shows, which depends on where the cut falls.
+    // Starting from the widest count, each round makes room for more text until they agree.
+    let mut label = label_for(total);
+    if display_width(&label) > max_width {
+        return if marker == "…" { Cow::Borrowed("") } else { cut_to_width(text, max_width, "…", ellipsis) };
+    }
+    loop {
+        let room = max_width - display_width(&label);
+        let (head_room, tail_room) = match ellipsis {
+            Ellipsis::Start => (0, room),
+            Ellipsis::Middle => (room - room / 2, room / 2),
+            Ellipsis::End => (room, 0),
+        };
+        let (head, head_shown) = fitting(units.iter(), head_room);
+        let (tail, tail_shown) = fitting(units.iter().rev(), tail_room);
+        let next = label_for(total - head_shown - tail_shown);
+        if next == label {
+            let mut result: String = units[..head].iter().map(|&(unit, _)| unit).collect();
+            let cut = &units[head..units.len() - tail];
+            if tail > 0 {
+                result.extend(cut.iter().map(|&(unit, _)| unit).filter(|unit| !is_char(unit)));
+            }
+            result.push_str(&label);
+            result.extend(units[units.len() - tail..].iter().map(|&(unit, _)| unit));
+            if let Cow::Owned(escaped) = escape_control_chars(&result) {
+                result = escaped;
+            }
+            return Cow::Owned(result);
+        }
+        label = next;
+    }
+}
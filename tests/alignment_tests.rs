@@ -0,0 +1,92 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_auto_align_numeric_columns() {
+    let csv_input = load_fixture("numbers.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("auto_align_numeric_columns", output);
+}
+
+#[test]
+fn test_no_auto_align() {
+    let csv_input = load_fixture("numbers.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--no-auto-align"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("no_auto_align", output);
+}
+
+#[test]
+fn test_explicit_align() {
+    let csv_input = load_fixture("numbers.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--wrap", "none", "--align", "item=center,price=left"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("explicit_align", output);
+}
+
+#[test]
+fn test_align_unknown_column() {
+    let csv_input = load_fixture("numbers.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--align", "missing=right"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("align_unknown_column", output);
+}
+
+#[test]
+fn test_valign_middle() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 60, &["--valign", "middle"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("valign_middle", output);
+}
+
+#[test]
+fn test_valign_bottom() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 60, &["--valign", "bottom", "-n"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("valign_bottom", output);
+}
+
+#[test]
+fn test_align_decimal() {
+    let csv_input = load_fixture("numbers.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--align-decimal", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("align_decimal", output);
+}
+
+#[test]
+fn test_align_decimal_lines_up_points() {
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--align-decimal"])
+        .expect("Failed to run csvpretty");
+
+    // Whole numbers end where the others' points are
+    let column = |line: &str, text: &str| line.find(text).map(|start| line[..start].chars().count());
+    let lines: Vec<&str> = output.lines().collect();
+    let point = column(lines[3], "1250.").expect("North row") + 4;
+    assert_eq!(column(lines[4], "980").map(|start| start + 3), Some(point), "{}", output);
+    assert_eq!(column(lines[5], "2210.").map(|start| start + 4), Some(point), "{}", output);
+}
+
+#[test]
+fn test_align_decimal_yields_to_explicit_align() {
+    let csv_input = load_fixture("numbers.csv");
+    let decimal = run_csvpretty_piped(&csv_input, &["--align-decimal", "--align", "price=left"])
+        .expect("Failed to run csvpretty");
+    let plain = run_csvpretty_piped(&csv_input, &["--align", "price=left"])
+        .expect("Failed to run csvpretty");
+
+    // quantity has no fractions, so only price could differ
+    assert_eq!(decimal, plain);
+}
@@ -0,0 +1,60 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_alt_screen_falls_back_when_piped() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--alt-screen"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("alt_screen_falls_back_when_piped", output);
+}
+
+/// A viewer killed by a signal still leaves the alternate screen and shows the cursor again.
+#[cfg(unix)]
+#[test]
+fn test_alt_screen_restored_on_signal() {
+    use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+    use std::io::Read;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let pair = native_pty_system()
+        .openpty(PtySize { rows: 24, cols: 80, pixel_width: 0, pixel_height: 0 })
+        .expect("Failed to open PTY");
+    let mut cmd = CommandBuilder::new(get_binary_path());
+    cmd.args(["--no-color", "--alt-screen"]);
+    cmd.arg(fixture_path("simple.csv"));
+    let mut child = pair.slave.spawn_command(cmd).expect("Failed to spawn csvpretty");
+    drop(pair.slave);
+
+    // Collect the output on another thread, since reads block until the viewer draws
+    let mut reader = pair.master.try_clone_reader().expect("Failed to read PTY");
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buffer = [0; 4096];
+        while let Ok(read) = reader.read(&mut buffer) {
+            if read == 0 || sender.send(buffer[..read].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+    let mut output = Vec::new();
+    while !String::from_utf8_lossy(&output).contains("Alice") {
+        output.extend(receiver.recv_timeout(Duration::from_secs(10)).expect("The viewer never drew the table"));
+    }
+
+    let pid = child.process_id().expect("No process id").to_string();
+    let killed = std::process::Command::new("kill").args(["-TERM", &pid]).status().expect("Failed to run kill");
+    assert!(killed.success());
+    let status = child.wait().expect("Failed to wait for csvpretty");
+    assert!(!status.success());
+    while let Ok(chunk) = receiver.recv_timeout(Duration::from_millis(500)) {
+        output.extend(chunk);
+    }
+
+    let output = String::from_utf8_lossy(&output);
+    assert!(output.contains("\x1b[?1049l"), "alternate screen not left: {:?}", output);
+    assert!(output.contains("\x1b[?25h"), "cursor not shown: {:?}", output);
+}
@@ -0,0 +1,50 @@
+mod helpers;
+
+use helpers::*;
+use std::process::Command;
+
+#[test]
+fn test_annotate_gha() {
+    let path = fixture_path("ragged.csv");
+    let output = run_csvpretty_piped("", &["--check", "--annotate", "gha", path.to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+
+    // The report comes first, then a command per problem pointing at its line
+    let path = path.to_str().unwrap();
+    let commands: Vec<&str> = output.lines().filter(|line| line.starts_with("::error")).collect();
+    assert_eq!(commands.len(), 4, "unexpected output: {}", output);
+    assert_eq!(commands[0], format!("::error file={},line=3,title=csvpretty --check::record 2: too few fields (2 instead of 3)", path));
+    assert!(output.starts_with("record 2 (line 3, byte 32)"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_annotate_gitlab() {
+    let dir = std::env::temp_dir().join(format!("csvpretty-annotate-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create a directory");
+    let output = Command::new(get_binary_path())
+        .args(["--check", "--annotate", "gitlab", fixture_path("ragged.csv").to_str().unwrap()])
+        .current_dir(&dir)
+        .output()
+        .expect("Failed to run csvpretty");
+    let report = std::fs::read_to_string(dir.join("gl-code-quality-report.json")).unwrap_or_default();
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert_eq!(output.status.code(), Some(1));
+    let issues: serde_json::Value = serde_json::from_str(&report).expect("Expected a JSON report");
+    let issues = issues.as_array().expect("Expected an array of issues");
+    assert_eq!(issues.len(), 4);
+    assert_eq!(issues[1]["check_name"], "too-many-fields");
+    assert_eq!(issues[1]["description"], "record 3: too many fields (4 instead of 3)");
+    assert_eq!(issues[1]["location"]["lines"]["begin"], 4);
+    assert!(issues[1]["location"]["path"].as_str().is_some_and(|path| path.ends_with("ragged.csv")));
+    // Fingerprints tell the issues apart
+    assert_ne!(issues[0]["fingerprint"], issues[1]["fingerprint"]);
+}
+
+#[test]
+fn test_annotate_needs_a_file() {
+    let (code, stderr) = run_csvpretty_failing("a,b\n1,2\n", &["--check", "--annotate", "gha"]).expect("Failed to run csvpretty");
+
+    assert_eq!(code, Some(4));
+    assert_eq!(stderr, "Error: --annotate needs a file to point the annotations at, not standard input");
+}
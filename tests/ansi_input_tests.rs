@@ -0,0 +1,32 @@
+mod helpers;
+
+use helpers::*;
+
+// Cells colored by the tool that produced the CSV: a red "FAIL" and a green "ok"
+const COLORED_INPUT: &str = "test,result\nlogin,\u{1b}[31mFAIL\u{1b}[0m\nsignup,\u{1b}[32mok\u{1b}[0m\n";
+
+#[test]
+fn test_colored_input_lines_up() {
+    let output = run_csvpretty_colored(COLORED_INPUT, &["--color", "always", "--wrap", "none"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("colored_input_lines_up", output);
+}
+
+#[test]
+fn test_colored_input_stripped_without_colors() {
+    let output = run_csvpretty_piped(COLORED_INPUT, &["--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("colored_input_stripped_without_colors", output);
+}
+
+#[test]
+fn test_colored_input_wrapped() {
+    // The color is reset at the end of each line so it doesn't run into the border
+    let csv_input = "id,message\n1,\u{1b}[33mdisk usage above the warning threshold\u{1b}[0m\n";
+    let output = run_csvpretty_colored(csv_input, &["--color", "always", "--max-col-width", "16"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("colored_input_wrapped", output);
+}
@@ -0,0 +1,78 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_bar_column_default_width() {
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--bar", "amount"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("bar_column_default_width", output);
+}
+
+#[test]
+fn test_bar_column_custom_width() {
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--bar", "units:10", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("bar_column_custom_width", output);
+}
+
+#[test]
+fn test_multiple_bar_columns() {
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--bar", "amount:12", "--bar", "units:8"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("multiple_bar_columns", output);
+}
+
+#[test]
+fn test_bar_replace() {
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--bar", "amount:16", "--bar-replace"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("bar_replace", output);
+}
+
+#[test]
+fn test_bar_uses_typed_value() {
+    let csv_input = load_fixture("artifacts.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--column-type", "size=bytes", "--bar", "size:10"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("bar_uses_typed_value", output);
+}
+
+#[test]
+fn test_bars_from_min() {
+    // Scaled from the smallest amount, which gets no bar
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--bars", "amount:10", "--bar-from-min", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("bars_from_min", output);
+}
+
+#[test]
+fn test_spark() {
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--spark", "amount", "--spark", "units", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("spark", output);
+}
+
+#[test]
+fn test_spark_averages_rows_beyond_width() {
+    let csv_input: String = std::iter::once("n".to_string()).chain((1..=200).map(|n| n.to_string())).collect::<Vec<_>>().join("\n");
+    let output = run_csvpretty_piped(&csv_input, &["--spark", "n", "--width", "40"])
+        .expect("Failed to run csvpretty");
+
+    // 28 characters fit beside the name and range, each the mean of 7 or so rows
+    let spark = output.lines().last().expect("no sparkline");
+    assert_eq!(spark, "n  ▁▁▂▂▂▂▃▃▃▃▄▄▄▄▅▅▅▅▆▆▆▆▇▇▇▇██  1 – 200");
+}
@@ -0,0 +1,46 @@
+mod helpers;
+
+use helpers::*;
+
+fn run_on_fixture(name: &str, args: &[&str]) -> String {
+    let path = fixture_path(name);
+    let mut full_args = vec![path.to_str().unwrap()];
+    full_args.extend_from_slice(args);
+    run_csvpretty_piped("", &full_args).expect("Failed to run csvpretty")
+}
+
+#[test]
+fn test_parquet_file() {
+    let output = run_on_fixture("table.parquet", &[]);
+
+    assert!(output.contains("table.parquet doesn't look like CSV: it's a Parquet file; export it as CSV first"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_nul_bytes() {
+    let output = run_csvpretty_piped("id\u{0}name\n1\u{0}a\n", &[]).expect("Failed to run csvpretty");
+
+    assert!(output.contains("stdin doesn't look like CSV: it's binary data (it contains NUL bytes)"), "unexpected output: {}", output);
+    assert!(output.contains("--encoding utf-16le"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_mostly_invalid_utf8() {
+    let output = run_on_fixture("noise.bin", &[]);
+
+    assert!(output.contains("doesn't look like CSV: it's binary data (many of its bytes aren't valid UTF-8 or are control characters)"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_utf16_without_bom_with_encoding() {
+    // UTF-16 text is full of NUL bytes, which are fine once the encoding is given
+    insta::assert_snapshot!("utf16_without_bom_with_encoding", run_on_fixture("utf16le_no_bom.csv", &["--encoding", "utf-16le"]));
+}
+
+#[test]
+fn test_a_few_invalid_bytes_still_read() {
+    // A Latin-1 file read as UTF-8 has only the odd invalid byte, so it's shown with a warning
+    let output = run_on_fixture("latin1.csv", &[]);
+
+    assert!(!output.contains("doesn't look like CSV"), "unexpected output: {}", output);
+}
@@ -0,0 +1,57 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_check() {
+    let csv_input = load_fixture("ragged.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--check"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("check", output);
+}
+
+#[test]
+fn test_check_without_problems() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--check"])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(output, "No problems in 3 records");
+}
+
+#[test]
+fn test_check_after_skipped_lines() {
+    // Positions count the skipped lines, so they point into the file as it is
+    let output = run_csvpretty_piped("pre\na,b\n1,2\n3,4,5\n", &["--skip-lines", "1", "--check"])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(output, "record 2 (line 4, byte 12): too many fields (3 instead of 2)\n1 problem in 2 records");
+}
+
+#[test]
+fn test_check_lenient_highlights_rows() {
+    let csv_input = load_fixture("ragged.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--check", "--lenient", "--color", "always"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("check_lenient_highlights_rows", output);
+}
+
+#[test]
+fn test_check_lenient_sorted() {
+    let csv_input = load_fixture("ragged.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--check", "--lenient", "--sort", "age"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Warning: problem rows aren't highlighted when --sort"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_check_log_input() {
+    let log_input = load_fixture("access.log");
+    let output = run_csvpretty_piped(&log_input, &["--check", "--input", "accesslog"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: --check only applies to CSV input"), "unexpected output: {}", output);
+}
@@ -0,0 +1,48 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_gha_format() {
+    let csv_input = load_fixture("ci.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--format", "gha"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("gha_format", output);
+}
+
+#[test]
+fn test_gha_format_colors_on_actions() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--format", "gha"], &[("GITHUB_ACTIONS", "true")])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("gha_format_colors_on_actions", output);
+}
+
+#[test]
+fn test_gha_format_no_colors_elsewhere() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--format", "gha"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("gha_format_no_colors_elsewhere", output);
+}
+
+#[test]
+fn test_teamcity_format() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--format", "teamcity", "--width", "40"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("teamcity_format", output);
+}
+
+#[test]
+fn test_width_override() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--width", "30"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("width_override", output);
+}
@@ -0,0 +1,86 @@
+// The clipboard is reached through wl-paste and wl-copy, stood in for here by shell scripts
+// that read and write a file
+#![cfg(unix)]
+
+mod helpers;
+
+use csvpretty::strip_ansi;
+use helpers::*;
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A directory with fake wl-paste and wl-copy commands, and the file they keep the clipboard in.
+fn fake_clipboard(name: &str, contents: &str) -> (PathBuf, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("csvpretty-clipboard-{}-{}", std::process::id(), name));
+    std::fs::create_dir_all(&dir).expect("Failed to create directory");
+    for (command, script) in [("wl-paste", "cat \"$FAKE_CLIPBOARD\""), ("wl-copy", "cat > \"$FAKE_CLIPBOARD\"")] {
+        let path = dir.join(command);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).expect("Failed to write script");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).expect("Failed to make script executable");
+    }
+    let clipboard = dir.join("clipboard");
+    std::fs::write(&clipboard, contents).expect("Failed to write clipboard");
+    (dir, clipboard)
+}
+
+fn run_with_clipboard(dir: &Path, clipboard: &Path, args: &[&str], stdin: &str) -> String {
+    let mut cmd = Command::new(get_binary_path());
+    cmd.args(args)
+        .env("PATH", format!("{}:/usr/bin:/bin", dir.display()))
+        .env("WAYLAND_DISPLAY", "wayland-0")
+        .env("FAKE_CLIPBOARD", clipboard)
+        .env_remove("DISPLAY")
+        .env_remove("COLUMNS")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    let mut child = cmd.spawn().expect("Failed to run csvpretty");
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).expect("Failed to write stdin");
+    let output = child.wait_with_output().expect("Failed to run csvpretty");
+    String::from_utf8_lossy(&output.stdout).into_owned() + &String::from_utf8_lossy(&output.stderr)
+}
+
+#[test]
+fn test_clipboard_in_reads_spreadsheet_range() {
+    let (dir, clipboard) = fake_clipboard("in", "name\tage\tcity\nAlice\t30\tNew York, NY\nBob\t25\tLos Angeles\n");
+    let output = run_with_clipboard(&dir, &clipboard, &["--no-color", "--clipboard-in"], "");
+
+    insta::assert_snapshot!("clipboard_in_tsv", output.trim_end());
+}
+
+#[test]
+fn test_clipboard_in_reads_csv() {
+    let (dir, clipboard) = fake_clipboard("in-csv", &load_fixture("simple.csv"));
+    let output = run_with_clipboard(&dir, &clipboard, &["--no-color", "--clipboard-in"], "");
+    let expected = run_csvpretty_piped(&load_fixture("simple.csv"), &[]).expect("Failed to run csvpretty");
+
+    assert_eq!(output.trim_end(), expected);
+}
+
+#[test]
+fn test_clipboard_out_copies_table_without_colors() {
+    let (dir, clipboard) = fake_clipboard("out", "");
+    let output = run_with_clipboard(&dir, &clipboard, &["--color", "always", "--clipboard-out"], &load_fixture("simple.csv"));
+    let copied = std::fs::read_to_string(&clipboard).expect("Failed to read clipboard");
+
+    assert!(output.contains('\x1b'), "the printed table should keep its colors");
+    assert!(!copied.contains('\x1b'), "the copied table shouldn't have colors: {}", copied);
+    assert_eq!(copied, strip_ansi(&output));
+}
+
+#[test]
+fn test_clipboard_without_tool() {
+    let (dir, clipboard) = fake_clipboard("none", "");
+    let mut cmd = Command::new(get_binary_path());
+    let output = cmd.args(["--clipboard-in"])
+        .env("PATH", format!("{}:/usr/bin:/bin", dir.join("missing").display()))
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .env("FAKE_CLIPBOARD", clipboard)
+        .output()
+        .expect("Failed to run csvpretty");
+
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no clipboard tool found"));
+}
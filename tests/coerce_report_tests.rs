@@ -0,0 +1,30 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_coerce_report() {
+    let csv_input = load_fixture("imports.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--coerce-report"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("coerce_report", output);
+}
+
+#[test]
+fn test_coerce_report_declared_type() {
+    let csv_input = load_fixture("imports.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--coerce-report", "--column-type", "size=bytes"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("row 4, size: expected bytes, got 'lots'"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_coerce_report_clean() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--coerce-report"])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(output, "All cells match their column's type in 3 rows");
+}
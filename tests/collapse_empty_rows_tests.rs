@@ -0,0 +1,54 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_collapse_empty_rows() {
+    let csv_input = load_fixture("padded.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--collapse-empty-rows"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("collapse_empty_rows", output);
+}
+
+#[test]
+fn test_collapse_empty_rows_with_line_numbers() {
+    let csv_input = load_fixture("padded.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--collapse-empty-rows", "--line-numbers"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("collapse_empty_rows_with_line_numbers", output);
+}
+
+#[test]
+fn test_collapse_empty_rows_after_sort() {
+    let csv_input = load_fixture("padded.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--collapse-empty-rows", "--sort", "region"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("collapse_empty_rows_after_sort", output);
+}
+
+#[test]
+fn test_collapsed_rows_are_dimmed() {
+    let output = run_csvpretty_colored("a,b\n1,2\n,\n", &["--collapse-empty-rows", "--color", "always"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[2m⋮ (1 empty row)␛[0m"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_rows_with_whitespace_only_collapse() {
+    let output = run_csvpretty_piped("a,b\n1,2\n  , \n3,4\n", &["--collapse-empty-rows"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("⋮ (1 empty row)"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_collapse_empty_rows_conflicts_with_group_by() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--collapse-empty-rows", "--group-by", "a"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("cannot be used with"), "unexpected output: {}", output);
+}
@@ -0,0 +1,40 @@
+mod helpers;
+
+use helpers::*;
+
+const STORES: &str = "region,city,store,sales\nNorth,Oslo,A,10\nSouth,Rome,C,7\nNorth,Oslo,B,12\nNorth,Bergen,D,3\nSouth,Rome,E,9\n";
+
+#[test]
+fn test_collapse_sorted_columns() {
+    let output = run_csvpretty_piped(STORES, &["--sort", "region", "--collapse", "region,city"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("collapse_sorted_columns", output);
+}
+
+#[test]
+fn test_collapse_nests_in_column_order() {
+    // Rome repeats in the rows of both regions, but the region changes in between
+    let csv_input = "region,city\nNorth,Rome\nSouth,Rome\nSouth,Rome\n";
+    let output = run_csvpretty_piped(csv_input, &["--collapse", "region,city", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    let rows: Vec<&str> = output.lines().skip(3).take(3).collect();
+    assert_eq!(rows, [" North    │ Rome  ", " South    │ Rome  ", "          │       "]);
+}
+
+#[test]
+fn test_collapse_starts_over_in_each_group() {
+    let output = run_csvpretty_piped(STORES, &["--group-by", "region", "--group-subtotals", "--collapse", "city"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("collapse_starts_over_in_each_group", output);
+}
+
+#[test]
+fn test_collapse_unknown_column() {
+    let output = run_csvpretty_piped(STORES, &["--collapse", "country"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: unknown column 'country' in --collapse"), "{}", output);
+}
@@ -0,0 +1,60 @@
+mod helpers;
+
+use helpers::*;
+
+const COLOR_ESCAPE: &str = "[38;2;";
+
+#[test]
+fn test_auto_colors_in_terminal() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty_with_colors(&csv_input, 80, &[], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains(COLOR_ESCAPE), "expected colors: {:?}", output);
+}
+
+#[test]
+fn test_auto_no_colors_when_piped() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "auto"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains('␛'), "unexpected escapes: {:?}", output);
+    insta::assert_snapshot!("auto_no_colors_when_piped", output);
+}
+
+#[test]
+fn test_auto_respects_no_color_env() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty_with_colors(&csv_input, 80, &[], &[("NO_COLOR", "1")])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains(COLOR_ESCAPE), "unexpected colors: {:?}", output);
+}
+
+#[test]
+fn test_always_colors_when_piped() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always"], &[("NO_COLOR", "1")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[38;2;"), "expected colors: {:?}", output);
+}
+
+#[test]
+fn test_never_colors_in_terminal() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty_with_colors(&csv_input, 80, &["--color", "never"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains(COLOR_ESCAPE), "unexpected colors: {:?}", output);
+}
+
+#[test]
+fn test_no_color_flag_beats_always() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--no-color"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains('␛'), "unexpected escapes: {:?}", output);
+}
@@ -0,0 +1,79 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_color_depth_256() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--color-depth", "256"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("color_depth_256", output);
+}
+
+#[test]
+fn test_color_depth_16() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--color-depth", "16"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("color_depth_16", output);
+}
+
+#[test]
+fn test_auto_depth_from_colorterm() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always"], &[("COLORTERM", "24bit")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[38;2;"), "expected 24-bit colors: {:?}", output);
+}
+
+#[test]
+fn test_auto_depth_from_term_256color() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always"], &[("COLORTERM", ""), ("TERM", "xterm-256color")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[38;5;"), "expected 256 colors: {:?}", output);
+    assert!(!output.contains("␛[38;2;"), "unexpected 24-bit colors: {:?}", output);
+}
+
+#[test]
+fn test_auto_depth_from_term_direct() {
+    // terminfo's *-direct entries describe terminals with 24-bit color
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always"], &[("COLORTERM", ""), ("TERM", "xterm-direct")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[38;2;"), "expected 24-bit colors: {:?}", output);
+}
+
+#[test]
+fn test_auto_depth_basic_terminal() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always"], &[("COLORTERM", ""), ("TERM", "xterm")])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains("␛[38;"), "expected only basic colors: {:?}", output);
+    assert!(output.contains("␛[33m"), "expected yellow for orange: {:?}", output);
+}
+
+#[test]
+fn test_auto_depth_windows_terminal() {
+    // Windows Terminal has 24-bit color but sets neither COLORTERM nor TERM
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always"], &[("COLORTERM", ""), ("WT_SESSION", "0d3f6c2e-1b7a-4c55-9b0e-7d1f2a9c4e11")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[38;2;"), "expected 24-bit colors: {:?}", output);
+}
+
+#[test]
+fn test_explicit_depth_overrides_environment() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--color-depth", "truecolor"], &[("COLORTERM", ""), ("TERM", "xterm")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[38;2;"), "expected 24-bit colors: {:?}", output);
+}
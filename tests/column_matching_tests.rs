@@ -0,0 +1,40 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_names_match_despite_whitespace_dashes_and_quotes() {
+    let csv_input = load_fixture("excel_headers.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--sort", "Ship-Date", "--reverse", "--align", "Order ID=right", "--rename", "Customer's Note=note", "--col-width", "Customer Name=14"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("names_match_despite_whitespace_dashes_and_quotes", output);
+}
+
+#[test]
+fn test_case_matters_by_default() {
+    let csv_input = load_fixture("excel_headers.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--sort", "order id"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unknown column 'order id' in --sort"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_ignore_header_case() {
+    let csv_input = load_fixture("excel_headers.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--ignore-header-case", "--sort", "order id", "--reverse"])
+        .expect("Failed to run csvpretty");
+
+    // The header keeps its casing, with the sort arrow after it
+    assert!(output.contains("Order ID  ▼"), "unexpected output: {}", output);
+    assert!(output.find("1002").unwrap() < output.find("1001").unwrap(), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_exact_name_wins() {
+    let output = run_csvpretty_piped("a b,a  b\n1,2\n", &["--rename", "a  b=second"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains(" a b │ ") && output.contains(" second"), "unexpected output: {}", output);
+}
@@ -0,0 +1,137 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_duration_milliseconds() {
+    let csv_input = load_fixture("durations.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--column-type", "elapsed_ms=duration(ms)"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("duration_milliseconds", output);
+}
+
+#[test]
+fn test_duration_all_columns() {
+    let csv_input = load_fixture("durations.csv");
+    let output = run_csvpretty_in_pty(
+        &csv_input,
+        80,
+        &["--column-type", "elapsed_ms=duration(ms),wall_s=duration", "--column-type", "timeout=duration(s)"],
+    )
+    .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("duration_all_columns", output);
+}
+
+#[test]
+fn test_duration_explicit_alignment_wins() {
+    let csv_input = load_fixture("durations.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--column-type", "timeout=duration", "--align", "timeout=left"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("duration_explicit_alignment_wins", output);
+}
+
+#[test]
+fn test_unknown_column_type() {
+    let csv_input = load_fixture("durations.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--column-type", "wall_s=duration(h)"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unknown duration unit 'h'"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_bytes_column() {
+    let csv_input = load_fixture("artifacts.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--column-type", "size=bytes"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("bytes_column", output);
+}
+
+#[test]
+fn test_sort_bytes_by_underlying_value() {
+    let csv_input = load_fixture("artifacts.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--column-type", "size=bytes", "--sort", "size", "--reverse"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("sort_bytes_by_underlying_value", output);
+}
+
+#[test]
+fn test_sort_duration_by_underlying_value() {
+    let csv_input = load_fixture("artifacts.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--column-type", "build_time=duration", "--sort", "build_time"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("sort_duration_by_underlying_value", output);
+}
+
+#[test]
+fn test_sort_text_column() {
+    let csv_input = load_fixture("artifacts.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--sort", "artifact"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("sort_text_column", output);
+}
+
+#[test]
+fn test_percent() {
+    let csv_input = load_fixture("conversions.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--column-type", "rate=percent,target=percent"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("percent", output);
+}
+
+#[test]
+fn test_percent_decimals() {
+    let csv_input = load_fixture("conversions.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--column-type", "rate=percent(2)", "--sort", "rate", "--reverse"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("percent_decimals", output);
+}
+
+#[test]
+fn test_number_locale() {
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--column-type", "amount=number(1),units=number", "--locale", "de-DE", "--width", "60"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("number_locale", output);
+}
+
+#[test]
+fn test_date_locale() {
+    let csv_input = load_fixture("events.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--column-type", "created_at=date(long)", "--locale", "fr_FR.UTF-8", "--width", "60"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("date_locale", output);
+}
+
+#[test]
+fn test_locale_from_environment() {
+    let mut cmd = std::process::Command::new(get_binary_path());
+    cmd.args(["--no-color", "--column-type", "amount=number"])
+        .arg(fixture_path("sales.csv"))
+        .env_remove("LC_ALL")
+        .env("LC_NUMERIC", "de_DE.UTF-8");
+    let output = cmd.output().expect("Failed to run csvpretty");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("1.250,50"), "unexpected output: {}", stdout);
+}
+
+#[test]
+fn test_unknown_locale() {
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--locale", "xx-YY"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unknown locale 'xx-YY'"), "unexpected output: {}", output);
+}
@@ -0,0 +1,98 @@
+mod helpers;
+
+use helpers::*;
+
+const LONG_MIDDLE_ROW: &str = "name,id\nab,1\na much longer value,2\ncd,3\n";
+
+#[test]
+fn test_fixed_col_width_wraps() {
+    // The description is pinned to 30 columns; the product column takes the rest
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--col-width", "description=30"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("fixed_col_width_wraps", output);
+}
+
+#[test]
+fn test_fixed_col_width_no_wrap() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--col-width", "email=10,country=4"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("fixed_col_width_no_wrap", output);
+}
+
+#[test]
+fn test_fixed_col_width_overrides_max() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--max-col-width", "6", "--col-width", "email=18"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("fixed_col_width_overrides_max", output);
+}
+
+#[test]
+fn test_min_col_width() {
+    // Without a floor the squeezed columns would wrap after six characters; with it the table
+    // grows past 80 columns, since all of them are at their floor
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--width", "80", "--min-col-width", "8"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("min_col_width", output);
+}
+
+#[test]
+fn test_col_width_unknown_column() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--col-width", "nickname=10"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--col-width") && output.contains("nickname"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_col_width_invalid() {
+    let output = run_csvpretty_piped("a\n1\n", &["--col-width", "a=0"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("invalid width '0'"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_measure_rows_wraps_unmeasured_cells() {
+    let output = run_csvpretty_piped(LONG_MIDDLE_ROW, &["--measure-rows", "1", "--width", "40"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("measure_rows_wraps_unmeasured_cells", output);
+}
+
+#[test]
+fn test_measure_rows_cuts_unmeasured_cells_without_wrapping() {
+    let output = run_csvpretty_piped(LONG_MIDDLE_ROW, &["--measure-rows", "1", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("measure_rows_cuts_unmeasured_cells_without_wrapping", output);
+}
+
+#[test]
+fn test_measure_random_with_seed_is_repeatable() {
+    let csv_input = load_fixture("many_rows.csv");
+    let args = ["--measure-rows", "3", "--measure-random", "--seed", "7", "--width", "60"];
+    let first = run_csvpretty_piped(&csv_input, &args).expect("Failed to run csvpretty");
+    let second = run_csvpretty_piped(&csv_input, &args).expect("Failed to run csvpretty");
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_measure_rows_covering_every_row_changes_nothing() {
+    let csv_input = load_fixture("many_rows.csv");
+    let measured = run_csvpretty_piped(&csv_input, &["--measure-rows", "8", "--width", "60"])
+        .expect("Failed to run csvpretty");
+    let whole = run_csvpretty_piped(&csv_input, &["--width", "60"])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(measured, whole);
+}
@@ -0,0 +1,59 @@
+mod helpers;
+
+use helpers::*;
+
+fn path(name: &str) -> String {
+    fixture_path(name).to_str().unwrap().to_string()
+}
+
+/// Runs csvpretty with a fixture file on stdin, which the string-based helpers can't carry.
+fn run_with_fixture_on_stdin(name: &str, args: &[&str]) -> String {
+    let stdin = std::fs::File::open(fixture_path(name)).expect("Failed to open fixture");
+    let output = std::process::Command::new(get_binary_path())
+        .arg("--no-color")
+        .args(args)
+        .stdin(stdin)
+        .output()
+        .expect("Failed to run csvpretty");
+    String::from_utf8_lossy(&output.stdout).into_owned() + &String::from_utf8_lossy(&output.stderr)
+}
+
+#[test]
+fn test_compressed_files_match_plain() {
+    let plain = run_csvpretty_piped("", &[&path("simple.csv")]).expect("Failed to run csvpretty");
+
+    for name in ["simple.csv.gz", "simple.csv.zst", "simple.csv.bz2"] {
+        let output = run_csvpretty_piped("", &[&path(name)]).expect("Failed to run csvpretty");
+        assert_eq!(output, plain, "{} differs", name);
+    }
+}
+
+#[test]
+fn test_compressed_stdin() {
+    let output = run_with_fixture_on_stdin("simple.csv.zst", &[]);
+
+    insta::assert_snapshot!("compressed_stdin", output.trim_end());
+}
+
+#[test]
+fn test_compressed_format_from_inner_extension() {
+    let output = run_csvpretty_piped("", &[&path("events.ndjson.gz")]).expect("Failed to run csvpretty");
+    let plain = run_csvpretty_piped("", &[&path("events.ndjson")]).expect("Failed to run csvpretty");
+
+    assert_eq!(output, plain);
+}
+
+#[test]
+fn test_head_bytes_of_compressed_file() {
+    // The limit applies to the decompressed data
+    let output = run_csvpretty_piped("", &["--head-bytes", "40", &path("simple.csv.gz")]).expect("Failed to run csvpretty");
+
+    assert!(output.contains("Alice") && !output.contains("Bob"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_estimate_rejects_compressed_file() {
+    let output = run_csvpretty_piped("", &["--estimate", &path("simple.csv.gz")]).expect("Failed to run csvpretty");
+
+    assert!(output.contains("--estimate can't size up a compressed file"), "unexpected output: {}", output);
+}
@@ -0,0 +1,60 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_concat_files() {
+    let march = fixture_path("sales_march.csv");
+    let april = fixture_path("sales_april.csv");
+    let output = run_csvpretty_piped("", &[march.to_str().unwrap(), april.to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("concat_files", output);
+}
+
+#[test]
+fn test_concat_source_column() {
+    let output = run_csvpretty_piped("", &["--source-column", "file", "tests/fixtures/sales_march.csv", "tests/fixtures/sales_april.csv"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("concat_source_column", output);
+}
+
+#[test]
+fn test_concat_flag_appends_to_stdin() {
+    let csv_input = load_fixture("sales_march.csv");
+    let april = fixture_path("sales_april.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--concat", april.to_str().unwrap(), "--source-column", "from"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains(" from "), "unexpected output: {}", output);
+    assert!(output.contains(" stdin "), "unexpected output: {}", output);
+    assert!(output.contains("store"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_concat_repeated_column_names() {
+    let first = fixture_path("duplicate_headers.csv");
+    let output = run_csvpretty_piped("", &[first.to_str().unwrap(), first.to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("concat_repeated_column_names", output);
+}
+
+#[test]
+fn test_concat_names_file_with_error() {
+    let march = fixture_path("sales_march.csv");
+    let output = run_csvpretty_piped("a,b\n1\n", &[march.to_str().unwrap(), "-"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: stdin: CSV error"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_check_reads_a_single_file() {
+    let march = fixture_path("sales_march.csv");
+    let output = run_csvpretty_piped("", &["--check", march.to_str().unwrap(), march.to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: --check reads a single file"), "unexpected output: {}", output);
+}
@@ -0,0 +1,43 @@
+mod helpers;
+
+use helpers::*;
+
+// A bell, a carriage return, a screen-clearing escape sequence and a tab, one per row
+const CONTROL_INPUT: &str = "id,message\n1,\"ring\x07bell\"\n2,\"50%\r100%\"\n3,\"\x1b[2Jcleared\"\n4,\"a\tb\"\n";
+
+#[test]
+fn test_control_chars_escaped() {
+    let output = run_csvpretty_piped(CONTROL_INPUT, &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("control_chars_escaped", output);
+}
+
+#[test]
+fn test_control_chars_escaped_without_wrapping() {
+    // The escapes count towards the column width, so the border lines up
+    let output = run_csvpretty_piped(CONTROL_INPUT, &["--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("control_chars_escaped_without_wrapping", output);
+}
+
+#[test]
+fn test_cursor_movement_escaped_while_colors_stripped() {
+    // Colors are styling and are stripped without colors on, but moving the cursor up would
+    // draw over the table
+    let csv_input = "status\n\"\x1b[31mfailed\x1b[0m\x1b[1A\"\n";
+    let output = run_csvpretty_piped(csv_input, &["--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains(" failed␛[1A "), "{:?}", output);
+    assert!(!output.contains('\x1b'), "{:?}", output);
+}
+
+#[test]
+fn test_control_chars_in_header_escaped() {
+    let output = run_csvpretty_piped("\"na\x08me\",id\nAnn,1\n", &["--vertical"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("na\\x08me │ Ann"), "{}", output);
+}
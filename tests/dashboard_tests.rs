@@ -0,0 +1,58 @@
+mod helpers;
+
+use helpers::*;
+
+const REQUESTS: &str = "endpoint,latency,status\n/a,10,200\n/b,30,500\n/a,20,200\n/a,,200\n/b,50,200\n";
+
+#[test]
+fn test_dashboard_piped_prints_final_table() {
+    let output = run_csvpretty_piped(REQUESTS, &["--dashboard", "--group-by", "endpoint", "--agg", "count(),p95(latency),avg(latency)"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("dashboard_piped_prints_final_table", output);
+}
+
+#[test]
+fn test_dashboard_aggregates() {
+    let output = run_csvpretty_piped(REQUESTS, &["--dashboard", "--group-by", "endpoint", "--agg", "count(latency),sum(latency),min(latency),max(latency),p50(latency)", "--width", "120"])
+        .expect("Failed to run csvpretty");
+
+    let cells = |group: &str| -> Vec<String> {
+        let line = output.lines().find(|line| line.trim_start().starts_with(group)).expect("Group row missing");
+        line.split('│').map(|cell| cell.trim().to_string()).collect()
+    };
+    assert_eq!(cells("/a"), ["/a", "2", "30", "10", "20", "10"], "{}", output);
+    assert_eq!(cells("/b"), ["/b", "2", "80", "30", "50", "30"], "{}", output);
+}
+
+#[test]
+fn test_dashboard_in_terminal() {
+    let output = run_csvpretty_in_pty("", 80, &["--dashboard", "--group-by", "city", "--agg", "count(),max(age)", fixture_path("simple.csv").to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("New York") && output.contains("3 rows read"), "{}", output);
+}
+
+#[test]
+fn test_dashboard_needs_group_by_and_agg() {
+    let output = run_csvpretty_piped(REQUESTS, &["--dashboard", "--group-by", "endpoint"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--agg"), "{}", output);
+}
+
+#[test]
+fn test_dashboard_unknown_column() {
+    let output = run_csvpretty_piped(REQUESTS, &["--dashboard", "--group-by", "endpoint", "--agg", "p95(duration)"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: unknown column 'duration' in --agg"), "{}", output);
+}
+
+#[test]
+fn test_invalid_aggregate() {
+    let output = run_csvpretty_piped(REQUESTS, &["--dashboard", "--group-by", "endpoint", "--agg", "median(latency)"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("invalid aggregate 'median(latency)'"), "{}", output);
+}
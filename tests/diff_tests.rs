@@ -0,0 +1,67 @@
+mod helpers;
+
+use helpers::*;
+
+fn diff_args<'a>(old: &'a str, new: &'a str, extra: &[&'a str]) -> Vec<String> {
+    let mut args = vec!["diff".to_string(), fixture_path(old).display().to_string(), fixture_path(new).display().to_string()];
+    args.extend(extra.iter().map(|arg| arg.to_string()));
+    args
+}
+
+fn run_diff(old: &str, new: &str, extra: &[&str]) -> String {
+    let args = diff_args(old, new, extra);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    run_csvpretty_piped("", &args).expect("Failed to run csvpretty")
+}
+
+#[test]
+fn test_diff_by_position() {
+    let output = run_diff("diff_old.csv", "diff_new.csv", &[]);
+
+    insta::assert_snapshot!("diff_by_position", output);
+}
+
+#[test]
+fn test_diff_by_key() {
+    let output = run_diff("diff_old.csv", "diff_new.csv", &["--key", "id"]);
+
+    insta::assert_snapshot!("diff_by_key", output);
+}
+
+#[test]
+fn test_diff_matches_columns_by_name() {
+    let output = run_diff("diff_old.csv", "diff_columns.csv", &["--key", "id"]);
+
+    insta::assert_snapshot!("diff_matches_columns_by_name", output);
+}
+
+#[test]
+fn test_diff_identical_files() {
+    let output = run_diff("diff_old.csv", "diff_old.csv", &[]);
+
+    assert!(output.ends_with("0 added, 0 removed, 0 changed, 4 unchanged"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_diff_colors() {
+    let args = diff_args("diff_old.csv", "diff_new.csv", &["--key", "id", "--color", "always", "--style", "ascii"]);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = run_csvpretty_colored("", &args, &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("diff_colors", output);
+}
+
+#[test]
+fn test_diff_unknown_key() {
+    let output = run_diff("diff_old.csv", "diff_new.csv", &["--key", "nope"]);
+
+    assert!(output.contains("unknown column 'nope' in --key"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_diff_missing_file() {
+    let output = run_diff("diff_old.csv", "no_such_file.csv", &[]);
+
+    assert!(output.starts_with("Error:"), "unexpected output: {}", output);
+}
@@ -0,0 +1,53 @@
+mod helpers;
+
+use helpers::*;
+
+const HOSTS: &str = "host,region,status\nweb1,eu,ok\nweb2,eu,ok\nweb3,eu,down\n";
+
+#[test]
+fn test_dim_constant_dims_constant_column() {
+    let output = run_csvpretty_colored(HOSTS, &["--dim-constant", "--color", "always"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[2m␛[1m␛[38;2;102;217;239mregion"), "unexpected output: {}", output);
+    assert!(output.contains("␛[2m␛[38;2;102;217;239meu"), "unexpected output: {}", output);
+    assert!(!output.contains("␛[2m␛[38;2;253;151;31mweb1"), "unexpected output: {}", output);
+    assert!(!output.contains("␛[2m␛[38;2;190;132;255mok"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_dim_constant_dims_near_constant_column() {
+    let mut csv_input = String::from("id,env\n");
+    for id in 1..=20 {
+        csv_input.push_str(&format!("{},{}\n", id, if id == 7 { "staging" } else { "prod" }));
+    }
+    let output = run_csvpretty_colored(&csv_input, &["--dim-constant", "--color", "always"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[2m␛[38;2;102;217;239mstaging"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_dim_constant_keeps_varied_columns() {
+    let csv_input = "id,env\n1,prod\n2,prod\n3,staging\n";
+    let output = run_csvpretty_colored(csv_input, &["--dim-constant", "--color", "always"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains("␛[2m"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_dim_constant_ignores_single_row() {
+    let output = run_csvpretty_colored("a,b\n1,2\n", &["--dim-constant", "--color", "always"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains("␛[2m"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_dim_constant_without_color() {
+    let output = run_csvpretty_piped(HOSTS, &["--dim-constant"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("│ eu     │"), "unexpected output: {}", output);
+}
@@ -0,0 +1,39 @@
+mod helpers;
+
+use helpers::*;
+
+fn run_on_fixture(name: &str, args: &[&str]) -> String {
+    let path = fixture_path(name);
+    let mut full_args = vec![path.to_str().unwrap()];
+    full_args.extend_from_slice(args);
+    run_csvpretty_piped("", &full_args).expect("Failed to run csvpretty")
+}
+
+#[test]
+fn test_latin1() {
+    insta::assert_snapshot!("latin1", run_on_fixture("latin1.csv", &["--encoding", "latin-1"]));
+}
+
+#[test]
+fn test_windows_1252() {
+    insta::assert_snapshot!("windows_1252", run_on_fixture("cp1252.csv", &["--encoding", "windows-1252"]));
+}
+
+#[test]
+fn test_utf16_bom_detected() {
+    insta::assert_snapshot!("utf16_bom_detected", run_on_fixture("utf16le_bom.csv", &[]));
+}
+
+#[test]
+fn test_utf8_bom_stripped() {
+    // The BOM must not become part of the first column name
+    insta::assert_snapshot!("utf8_bom_stripped", run_on_fixture("utf8_bom.csv", &["--align", "name=right"]));
+}
+
+#[test]
+fn test_invalid_utf8_replaced() {
+    let output = run_on_fixture("latin1.csv", &[]);
+
+    assert!(output.contains("S�o Paulo"), "unexpected output: {}", output);
+    assert!(output.contains("contains bytes that aren't valid UTF-8; they are shown as �"), "unexpected output: {}", output);
+}
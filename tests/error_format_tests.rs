@@ -0,0 +1,69 @@
+mod helpers;
+
+use helpers::*;
+
+const RAGGED: &str = "name,age\nAlice,30\nBob\n";
+
+#[test]
+fn test_parse_error_exit_code() {
+    let (code, stderr) = run_csvpretty_failing(RAGGED, &[]).expect("Failed to run csvpretty");
+
+    assert_eq!(code, Some(2));
+    assert!(stderr.starts_with("Error: CSV error: record 2"), "{}", stderr);
+}
+
+#[test]
+fn test_parse_error_as_json() {
+    let (code, stderr) = run_csvpretty_failing(RAGGED, &["--error-format", "json"]).expect("Failed to run csvpretty");
+
+    assert_eq!(code, Some(2));
+    insta::assert_snapshot!("parse_error_as_json", stderr);
+}
+
+#[test]
+fn test_parse_error_after_skipped_lines() {
+    let input = format!("exported 2024-05-01\n{}", RAGGED);
+    let (code, stderr) = run_csvpretty_failing(&input, &["--skip-lines", "1", "--error-format", "json"]).expect("Failed to run csvpretty");
+    assert_eq!(code, Some(2));
+    assert!(stderr.contains(r#""line":4,"record":2,"byte":38"#), "{}", stderr);
+    assert!(stderr.contains("(line: 4, byte: 38)"), "{}", stderr);
+
+    let (_, stderr) = run_csvpretty_failing(&input, &["--skip-lines", "1", "--lenient"]).expect("Failed to run csvpretty");
+    assert!(stderr.starts_with("Warning: record 2 (line 4) has 1 field instead of 2"), "{}", stderr);
+}
+
+#[test]
+fn test_empty_input_exit_code() {
+    let (code, stderr) = run_csvpretty_failing("\n\n", &["--error-format", "json"]).expect("Failed to run csvpretty");
+
+    assert_eq!(code, Some(3));
+    assert_eq!(stderr, r#"{"kind":"empty_input","exit_code":3,"message":"No input provided"}"#);
+}
+
+#[test]
+fn test_unknown_flag_exit_code() {
+    let (code, stderr) = run_csvpretty_failing(RAGGED, &["--no-such-flag"]).expect("Failed to run csvpretty");
+    assert_eq!(code, Some(4));
+    assert!(stderr.starts_with("error: unexpected argument '--no-such-flag'"), "{}", stderr);
+
+    // The format is found even though the flags can't be parsed
+    let (code, stderr) = run_csvpretty_failing(RAGGED, &["--no-such-flag", "--error-format=json"]).expect("Failed to run csvpretty");
+    assert_eq!(code, Some(4));
+    assert_eq!(stderr, r#"{"kind":"usage","exit_code":4,"message":"unexpected argument '--no-such-flag' found"}"#);
+}
+
+#[test]
+fn test_unknown_column_exit_code() {
+    let (code, stderr) = run_csvpretty_failing("name,age\nAlice,30\n", &["--sort", "city"]).expect("Failed to run csvpretty");
+
+    assert_eq!(code, Some(4));
+    assert_eq!(stderr, "Error: unknown column 'city' in --sort");
+}
+
+#[test]
+fn test_missing_file_exit_code() {
+    let (code, stderr) = run_csvpretty_failing("", &["no/such/file.csv", "--error-format", "json"]).expect("Failed to run csvpretty");
+
+    assert_eq!(code, Some(1));
+    assert!(stderr.starts_with(r#"{"kind":"other","exit_code":1,"message":"no/such/file.csv: "#), "{}", stderr);
+}
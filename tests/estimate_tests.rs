@@ -0,0 +1,53 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_estimate_small_file() {
+    let output = run_csvpretty_piped("", &["--estimate", "tests/fixtures/people.csv"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("estimate_small_file", output);
+}
+
+#[test]
+fn test_estimate_large_file() {
+    // About 3 MiB of identical rows, so the estimate comes out exact
+    let path = std::env::temp_dir().join(format!("csvpretty-estimate-{}.csv", std::process::id()));
+    let rows = "2024-05-01,north,1234.50,shipped\n".repeat(100_000);
+    std::fs::write(&path, format!("date,region,amount,status\n{}", rows)).expect("Failed to write test file");
+    let output = run_csvpretty_piped("", &["--estimate", path.to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+    std::fs::remove_file(&path).ok();
+
+    assert!(output.contains("3.1 MiB, about 100,000 rows of 33 B each (measured on the first 31,774 rows)"), "unexpected output: {}", output);
+    assert!(output.contains("Small enough to show in full"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_estimate_advises_on_huge_file() {
+    let path = std::env::temp_dir().join(format!("csvpretty-estimate-huge-{}.csv", std::process::id()));
+    std::fs::write(&path, format!("a,b\n{}", "1,2\n".repeat(400_000))).expect("Failed to write test file");
+    let output = run_csvpretty_piped("", &["--estimate", path.to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+    std::fs::remove_file(&path).ok();
+
+    assert!(output.contains("about 400,000 rows"), "unexpected output: {}", output);
+    assert!(output.contains("try --head-bytes 1048576 to read only the start, or --sample 1000"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_estimate_needs_a_file() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--estimate"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: --estimate needs a single file"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_estimate_only_applies_to_csv() {
+    let output = run_csvpretty_piped("", &["--estimate", "tests/fixtures/releases.json"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: --estimate only applies to CSV input"), "unexpected output: {}", output);
+}
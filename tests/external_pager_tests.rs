@@ -0,0 +1,32 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_pager_defaults_to_no_wrap() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_in_pty_with_colors(&csv_input, 60, &["--no-color", "--pager"], &[("PAGER", "cat")])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("pager_defaults_to_no_wrap", output);
+}
+
+#[test]
+fn test_pager_keeps_explicit_wrap() {
+    let csv_input = load_fixture("long_text.csv");
+    let paged = run_csvpretty_in_pty_with_colors(&csv_input, 60, &["--no-color", "--pager", "--wrap", "word"], &[("PAGER", "cat")])
+        .expect("Failed to run csvpretty");
+    let unpaged = run_csvpretty_in_pty(&csv_input, 60, &["--wrap", "word"])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(paged, unpaged);
+}
+
+#[test]
+fn test_pager_ignored_when_piped() {
+    let csv_input = load_fixture("long_text.csv");
+    let with_pager = run_csvpretty_piped(&csv_input, &["--pager"]).expect("Failed to run csvpretty");
+    let without = run_csvpretty_piped(&csv_input, &[]).expect("Failed to run csvpretty");
+
+    assert_eq!(with_pager, without);
+}
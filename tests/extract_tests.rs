@@ -0,0 +1,48 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_extract_capture_group() {
+    let csv_input = load_fixture("links.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 100, &["--extract", "url => host:^https?://([^/:]+)"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("extract_capture_group", output);
+}
+
+#[test]
+fn test_extract_multiple_groups() {
+    let csv_input = load_fixture("links.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 100, &["--extract", "url => scheme,host,port:^(\\w+)://([^/:]+)(?::(\\d+))?"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("extract_multiple_groups", output);
+}
+
+#[test]
+fn test_extract_whole_match() {
+    let csv_input = load_fixture("links.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 100, &["--extract", "note => ticket:JIRA-\\d+", "--extract", "url => host:://([^/:]+)"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("extract_whole_match", output);
+}
+
+#[test]
+fn test_extract_name_count_mismatch() {
+    let csv_input = load_fixture("links.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 100, &["--extract", "url => host:^(\\w+)://([^/]+)"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("names 1 column(s) but the regex captures 2"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_extract_unknown_column() {
+    let csv_input = load_fixture("links.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 100, &["--extract", "link => host:://([^/]+)"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("extract_unknown_column", output);
+}
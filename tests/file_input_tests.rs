@@ -0,0 +1,113 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_file_argument() {
+    let path = fixture_path("simple.csv");
+    let output = run_csvpretty_piped("", &[path.to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("file_argument", output);
+}
+
+#[test]
+fn test_dash_reads_stdin() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["-"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("dash_reads_stdin", output);
+}
+
+#[test]
+fn test_missing_file() {
+    let output = run_csvpretty_piped("", &["no/such/file.csv"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("missing_file", output);
+}
+
+#[cfg(not(feature = "cloud"))]
+#[test]
+fn test_object_url_without_cloud_feature() {
+    let output = run_csvpretty_piped("", &["s3://bucket/exports/data.csv"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("object_url_without_cloud_feature", output);
+}
+
+#[test]
+fn test_head_bytes_drops_partial_record() {
+    // The first 54 bytes end after the line break inside record 3's quoted note
+    let path = fixture_path("multiline.csv");
+    let output = run_csvpretty_piped("", &[path.to_str().unwrap(), "--head-bytes", "54"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("head_bytes_drops_partial_record", output);
+}
+
+#[test]
+fn test_head_bytes_in_footer() {
+    let path = fixture_path("multiline.csv");
+    let output = run_csvpretty_piped("", &[path.to_str().unwrap(), "--head-bytes", "54", "--footer"])
+        .expect("Failed to run csvpretty");
+
+    // The partial record after the cut is dropped, which the footer points out
+    let footer = output.lines().last().unwrap_or_default();
+    assert!(footer.ends_with("(input cut at 43 bytes)"), "unexpected footer: {}", footer);
+}
+
+#[test]
+fn test_head_bytes_footer_without_cut() {
+    let csv_input = load_fixture("multiline.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--head-bytes", "66", "--footer"])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains("input cut"), "unexpected footer: {}", output);
+}
+
+#[test]
+fn test_head_bytes_larger_than_input() {
+    let csv_input = load_fixture("multiline.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--head-bytes", "66"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("head_bytes_larger_than_input", output);
+}
+
+#[test]
+fn test_head_bytes_without_complete_record() {
+    let csv_input = load_fixture("multiline.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--head-bytes", "5"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("head_bytes_without_complete_record", output);
+}
+
+#[test]
+fn test_file_argument_matches_stdin_with_quoted_cells() {
+    // A file is parsed straight from memory, with cells borrowed around the quotes
+    let path = fixture_path("multiline.csv");
+    let from_file = run_csvpretty_piped("", &[path.to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+    let from_stdin = run_csvpretty_piped(&load_fixture("multiline.csv"), &[])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(from_file, from_stdin);
+}
+
+#[test]
+fn test_mmap_matches_reading() {
+    // Files are only mapped when they're read in full before anything is printed
+    for (name, args) in [("multiline.csv", &[][..]), ("utf8_bom.csv", &[]), ("latin1.csv", &["--encoding", "latin-1"])] {
+        let path = fixture_path(name);
+        let mut read_args = vec![path.to_str().unwrap(), "--no-progressive"];
+        read_args.extend_from_slice(args);
+        let read = run_csvpretty_piped("", &read_args).expect("Failed to run csvpretty");
+        read_args.push("--mmap");
+        let mapped = run_csvpretty_piped("", &read_args).expect("Failed to run csvpretty");
+
+        assert_eq!(mapped, read, "{}", name);
+    }
+}
@@ -0,0 +1,39 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_overflow_hides_columns() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--fit", "overflow"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("overflow_hides_columns", output);
+}
+
+#[test]
+fn test_overflow_with_line_numbers() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 60, &["--fit", "overflow", "-n"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("overflow_with_line_numbers", output);
+}
+
+#[test]
+fn test_overflow_everything_fits() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 200, &["--fit", "overflow"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("overflow_everything_fits", output);
+}
+
+#[test]
+fn test_overflow_no_wrap() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--fit", "overflow", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("overflow_no_wrap", output);
+}
@@ -0,0 +1,39 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_footer() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--footer"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("footer", output);
+}
+
+#[test]
+fn test_footer_counts_filtered_rows() {
+    let csv_input = load_fixture("events.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--footer", "--since", "2024-01-01", "--until", "2024-02-01", "--time-column", "created_at"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("footer_counts_filtered_rows", output);
+}
+
+#[test]
+fn test_footer_describes_input_with_stats() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--footer", "--stats"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("footer_describes_input_with_stats", output);
+}
+
+#[test]
+fn test_footer_vertical() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--footer", "--vertical"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("footer_vertical", output);
+}
@@ -0,0 +1,37 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_freq() {
+    let csv_input = load_fixture("orders.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--freq", "status"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("freq", output);
+}
+
+#[test]
+fn test_freq_with_bar() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--freq", "team", "--bar", "count:10"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("freq_with_bar", output);
+}
+
+#[test]
+fn test_freq_unknown_column() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--freq", "c"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unknown column 'c' in --freq"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_freq_conflicts_with_stats() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--freq", "a", "--stats"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("cannot be used with"), "unexpected output: {}", output);
+}
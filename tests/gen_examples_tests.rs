@@ -0,0 +1,47 @@
+mod helpers;
+
+use helpers::*;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// A directory in the temporary directory for the examples of the test called `name`.
+fn examples_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("csvpretty-examples-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn test_gen_examples_renders_every_combination() {
+    let dir = examples_dir("all");
+    let output = Command::new(get_binary_path())
+        .args(["gen-examples", "--out", dir.to_str().unwrap(), fixture_path("simple.csv").to_str().unwrap()])
+        .output()
+        .expect("Failed to run csvpretty");
+    let files = std::fs::read_dir(dir.join("simple.csv")).map(|entries| entries.count()).unwrap_or(0);
+    let example = std::fs::read_to_string(dir.join("simple.csv").join("table-ascii-none.txt")).unwrap_or_default();
+    std::fs::remove_dir_all(&dir).ok();
+
+    // 3 formats, 6 styles and 4 wrap modes
+    assert_eq!(files, 72, "{}", String::from_utf8_lossy(&output.stderr));
+    let direct = run_csvpretty_piped(&load_fixture("simple.csv"), &["--style", "ascii", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+    assert_eq!(example.trim_end(), direct);
+}
+
+#[test]
+fn test_gen_examples_fails_on_unreadable_fixtures() {
+    let dir = examples_dir("skip");
+    let output = Command::new(get_binary_path())
+        .args(["gen-examples", "--out", dir.to_str().unwrap(), fixture_path("noise.bin").to_str().unwrap(), fixture_path("simple.csv").to_str().unwrap()])
+        .output()
+        .expect("Failed to run csvpretty");
+    let skipped = dir.join("noise.bin").exists();
+    std::fs::remove_dir_all(&dir).ok();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    // The other fixtures are still rendered, but the run fails
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(!skipped);
+    assert!(stderr.contains("Warning: skipped") && stderr.contains("noise.bin"), "{}", stderr);
+    assert!(stderr.contains("Wrote 72 examples"), "{}", stderr);
+    assert!(stderr.contains("1 fixture couldn't be rendered"), "{}", stderr);
+}
@@ -0,0 +1,58 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_grapheme_clusters_line_up() {
+    // Each family, flag and accented letter is one symbol and must be measured as one
+    let csv_input = load_fixture("emoji.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("grapheme_clusters_line_up", output);
+}
+
+#[test]
+fn test_char_wrap_keeps_clusters_whole() {
+    let csv_input = load_fixture("emoji.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "char", "--max-col-width", "5"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("char_wrap_keeps_clusters_whole", output);
+}
+
+#[test]
+fn test_truncate_keeps_clusters_whole() {
+    let csv_input = load_fixture("emoji.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "truncate", "--max-col-width", "6"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("truncate_keeps_clusters_whole", output);
+}
+
+#[test]
+fn test_combining_marks_stay_with_their_letter() {
+    // "Zoë" is written as "Zoe" and a combining diaeresis, which mustn't start the next line
+    let output = run_csvpretty_piped("name\nZoe\u{308}\n", &["--wrap", "char", "--max-col-width", "3"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Zoe\u{308}"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_emoji_width_one() {
+    // For terminals that draw flags and emoji sequences one column wide
+    let csv_input = load_fixture("emoji.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--emoji-width", "1"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("emoji_width_one", output);
+}
+
+#[test]
+fn test_ambiguous_wide() {
+    let output = run_csvpretty_piped("symbol,name\n±,plus-minus\n①,circled one\n×,times\n", &["--wrap", "none", "--ambiguous-wide"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("ambiguous_wide", output);
+}
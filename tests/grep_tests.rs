@@ -0,0 +1,50 @@
+mod helpers;
+
+use helpers::*;
+
+const QUOTED: &str = "name,quote\nAlice,\"She said \"\"hi\"\"\"\nBob,plain hi\n\"Car\"\"ol\",\"multi\nline hi\"\n";
+
+#[test]
+fn test_grep_parsed_values() {
+    let output = run_csvpretty_piped(QUOTED, &["--grep", "\"hi\""]).expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("grep_parsed_values", output);
+}
+
+#[test]
+fn test_grep_raw_finds_escaped_quotes() {
+    let output = run_csvpretty_piped(QUOTED, &["--grep", "\"\"", "--raw"]).expect("Failed to run csvpretty");
+
+    assert!(output.contains("Alice") && output.contains("Car\"ol"), "{}", output);
+    assert!(!output.contains("Bob"), "{}", output);
+}
+
+#[test]
+fn test_grep_parsed_doesnt_see_escapes() {
+    let output = run_csvpretty_piped(QUOTED, &["--grep", "\"\""]).expect("Failed to run csvpretty");
+
+    assert!(!output.contains("Alice") && !output.contains("Bob"), "{}", output);
+}
+
+#[test]
+fn test_grep_raw_multiline_record() {
+    let output = run_csvpretty_piped(QUOTED, &["--grep", "(?m)^line", "--raw", "--footer"]).expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("grep_raw_multiline_record", output);
+}
+
+#[test]
+fn test_grep_verbose_reports_mode() {
+    let parsed = run_csvpretty_piped(QUOTED, &["--grep", "plain", "--verbose"]).expect("Failed to run csvpretty");
+    let raw = run_csvpretty_piped(QUOTED, &["--grep", "plain", "--raw", "--verbose"]).expect("Failed to run csvpretty");
+
+    assert!(parsed.contains("--grep matched row 2 in the parsed value of column 'quote'"), "{}", parsed);
+    assert!(raw.contains("--grep matched record 2 (line 3) in its raw text"), "{}", raw);
+}
+
+#[test]
+fn test_grep_raw_needs_csv() {
+    let output = run_csvpretty_piped("[{\"a\": 1}]", &["--input", "json", "--grep", "1", "--raw"]).expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: --raw only applies to CSV input"), "{}", output);
+}
@@ -0,0 +1,67 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_group_by() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--group-by", "team"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("group_by", output);
+}
+
+#[test]
+fn test_group_by_keeps_sort_within_groups() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--group-by", "team", "--sort", "age", "--reverse"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("group_by_keeps_sort_within_groups", output);
+}
+
+#[test]
+fn test_group_labels() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--group-by", "team", "--group-labels", "--line-numbers"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("group_labels", output);
+}
+
+#[test]
+fn test_group_subtotals() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--group-by", "team", "--group-subtotals", "--column-type", "elapsed_ms=duration(ms)", "--null-display", "∅"])
+        .expect("Failed to run csvpretty");
+
+    // Typed columns are added up by their quantity; subtotal rows don't get the null placeholder
+    assert!(output.contains("269.25"), "unexpected output: {}", output);
+    insta::assert_snapshot!("group_subtotals", output);
+}
+
+#[test]
+fn test_group_subtotals_line_numbers() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["-n", "--group-by", "team", "--group-subtotals"])
+        .expect("Failed to run csvpretty");
+
+    // Subtotal rows aren't numbered, and the rows below them carry on from the last data row
+    insta::assert_snapshot!("group_subtotals_line_numbers", output);
+}
+
+#[test]
+fn test_group_by_unknown_column() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--group-by", "c"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unknown column 'c' in --group-by"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_group_labels_requires_group_by() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--group-labels"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--group-by"), "unexpected output: {}", output);
+}
@@ -0,0 +1,48 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_no_headers() {
+    let csv_input = "Alice,30,New York\nBob,25,Los Angeles\n";
+    let output = run_csvpretty_in_pty(csv_input, 80, &["--no-headers"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("no_headers", output);
+}
+
+#[test]
+fn test_no_headers_with_headers() {
+    let csv_input = "Alice,30,New York\nBob,25,Los Angeles\n";
+    let output = run_csvpretty_in_pty(csv_input, 80, &["--no-headers", "--headers", "name,age,city"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("no_headers_with_headers", output);
+}
+
+#[test]
+fn test_headers_wrong_count() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--headers", "name,age"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("headers_wrong_count", output);
+}
+
+#[test]
+fn test_rename() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--rename", "name=person,city=home town", "--align", "person=center"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("rename", output);
+}
+
+#[test]
+fn test_rename_unknown_column() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--rename", "town=city"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("rename_unknown_column", output);
+}
@@ -1,4 +1,7 @@
-use portable_pty::{CommandBuilder, PtySize, PtySystem, native_pty_system};
+// Each test binary uses a different subset of these helpers
+#![allow(dead_code)]
+
+use portable_pty::{CommandBuilder, PtySize, native_pty_system};
 use std::io::{Read, Write};
 use std::path::PathBuf;
 
@@ -31,6 +34,28 @@ pub fn run_csvpretty_in_pty(
     csv_input: &str,
     terminal_cols: u16,
     args: &[&str],
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut full_args = vec!["--no-color"];
+    full_args.extend_from_slice(args);
+    run_in_pty(csv_input, terminal_cols, &full_args, &[])
+}
+
+/// Run csvpretty in a PTY without forcing --no-color, with extra environment variables.
+/// Escape sequences lose their ESC byte during cleanup, so colors show up as e.g. `[38;2;…m`.
+pub fn run_csvpretty_in_pty_with_colors(
+    csv_input: &str,
+    terminal_cols: u16,
+    args: &[&str],
+    envs: &[(&str, &str)],
+) -> Result<String, Box<dyn std::error::Error>> {
+    run_in_pty(csv_input, terminal_cols, args, envs)
+}
+
+fn run_in_pty(
+    csv_input: &str,
+    terminal_cols: u16,
+    args: &[&str],
+    envs: &[(&str, &str)],
 ) -> Result<String, Box<dyn std::error::Error>> {
     let binary_path = get_binary_path();
 
@@ -43,12 +68,19 @@ pub fn run_csvpretty_in_pty(
         pixel_height: 0,
     })?;
 
-    // Build command - always add --no-color first for tests
+    // Build command
     let mut cmd = CommandBuilder::new(&binary_path);
-    cmd.arg("--no-color");
     for arg in args {
         cmd.arg(arg);
     }
+    cmd.env_remove("NO_COLOR");
+    for name in ["LC_ALL", "LC_NUMERIC", "LANG"] {
+        cmd.env_remove(name);
+    }
+    cmd.env("COLORTERM", "truecolor");
+    for (key, value) in envs {
+        cmd.env(key, value);
+    }
 
     // Spawn process
     let mut child = pair.slave.spawn_command(cmd)?;
@@ -75,6 +107,101 @@ pub fn run_csvpretty_in_pty(
     Ok(cleaned)
 }
 
+/// Run csvpretty with stdin/stdout connected to pipes instead of a PTY.
+///
+/// PTYs in canonical mode cap input lines at a few KiB, so inputs with very long
+/// lines have to be fed through a plain pipe. Terminal width detection falls back to 80 columns,
+/// since the variables it would otherwise consult (COLUMNS, and TMUX/STY inside a multiplexer)
+/// are cleared.
+pub fn run_csvpretty_piped(
+    csv_input: &str,
+    args: &[&str],
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut cmd = std::process::Command::new(get_binary_path());
+    cmd.arg("--no-color").args(args);
+    clear_terminal_env(&mut cmd);
+    run_piped_command(cmd, csv_input)
+}
+
+/// Run csvpretty through pipes with colors left on and extra environment variables set.
+/// Color-related variables from the test environment are cleared (and 24-bit color
+/// assumed via COLORTERM) first, and ESC bytes
+/// are shown as `␛` so that snapshots of escape sequences stay readable.
+pub fn run_csvpretty_colored(
+    csv_input: &str,
+    args: &[&str],
+    envs: &[(&str, &str)],
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut cmd = std::process::Command::new(get_binary_path());
+    cmd.args(args)
+        .env_remove("NO_COLOR")
+        .env_remove("CSVPRETTY_COLORS")
+        .env_remove("GITHUB_ACTIONS")
+        .env_remove("TEAMCITY_VERSION")
+        .env("COLORTERM", "truecolor");
+    clear_terminal_env(&mut cmd);
+    cmd.envs(envs.iter().copied());
+    Ok(run_piped_command(cmd, csv_input)?.replace('\u{1b}', "␛"))
+}
+
+/// Clears the environment variables that terminal width and theme detection and the locale
+/// fall back on, so that the test terminal doesn't leak into the output.
+fn clear_terminal_env(cmd: &mut std::process::Command) {
+    for name in ["COLUMNS", "TMUX", "TMUX_PANE", "STY", "COLORFGBG", "CSVPRETTY_THEME", "WT_SESSION", "LC_ALL", "LC_NUMERIC", "LANG"] {
+        cmd.env_remove(name);
+    }
+}
+
+/// Spawns `cmd` with piped stdio, feeds it `csv_input` and returns stdout followed by stderr.
+fn run_piped_command(
+    cmd: std::process::Command,
+    csv_input: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let output = piped_output(cmd, csv_input)?;
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(text.trim_end().to_string())
+}
+
+/// Run csvpretty through pipes like [`run_csvpretty_piped`], returning its exit code and what
+/// it wrote to stderr.
+pub fn run_csvpretty_failing(
+    csv_input: &str,
+    args: &[&str],
+) -> Result<(Option<i32>, String), Box<dyn std::error::Error>> {
+    let mut cmd = std::process::Command::new(get_binary_path());
+    cmd.arg("--no-color").args(args);
+    clear_terminal_env(&mut cmd);
+    let output = piped_output(cmd, csv_input)?;
+    Ok((output.status.code(), String::from_utf8_lossy(&output.stderr).trim_end().to_string()))
+}
+
+fn piped_output(
+    mut cmd: std::process::Command,
+    csv_input: &str,
+) -> Result<std::process::Output, Box<dyn std::error::Error>> {
+    use std::process::Stdio;
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Write stdin from a separate thread so a large input can't deadlock against stdout
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    let input = csv_input.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = child.wait_with_output()?;
+    // csvpretty may exit before reading stdin (e.g. on a bad flag), so a broken pipe is fine
+    match writer.join().expect("stdin writer panicked") {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(e.into()),
+        _ => {}
+    }
+    Ok(output)
+}
+
 /// Cleans PTY output by removing echoed input and control characters.
 ///
 /// PTYs echo stdin back to the output and inject control characters. This function:
@@ -0,0 +1,151 @@
+mod helpers;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use helpers::*;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+
+/// Serves one request with `body`, gzip-compressed when the client accepts that. Returns the
+/// server's address and a handle yielding the request's header lines.
+fn serve_once(status: &'static str, body: &'static str) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+    let address = listener.local_addr().expect("No local address").to_string();
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().expect("Failed to accept");
+        let mut reader = BufReader::new(stream.try_clone().expect("Failed to clone stream"));
+        let mut request = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).expect("Failed to read request");
+            if line.trim().is_empty() {
+                break;
+            }
+            request.push(line.trim().to_string());
+        }
+
+        let gzip = request.iter().any(|line| line.to_ascii_lowercase().starts_with("accept-encoding:") && line.contains("gzip"));
+        let (encoding, body) = if gzip {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(body.as_bytes()).expect("Failed to compress");
+            ("Content-Encoding: gzip\r\n", encoder.finish().expect("Failed to compress"))
+        } else {
+            ("", body.as_bytes().to_vec())
+        };
+        let mut stream = stream;
+        write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n", status, body.len(), encoding)
+            .and_then(|()| stream.write_all(&body))
+            .expect("Failed to respond");
+        request
+    });
+    (address, handle)
+}
+
+/// Serves a request per response in `responses`, each written as is: status line, headers and
+/// body, which may stop short of its Content-Length. Returns the server's address and a handle
+/// yielding each request's header lines.
+fn serve_script(responses: Vec<Vec<u8>>) -> (String, JoinHandle<Vec<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+    let address = listener.local_addr().expect("No local address").to_string();
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().expect("Failed to accept");
+            let mut reader = BufReader::new(stream.try_clone().expect("Failed to clone stream"));
+            let mut request = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).expect("Failed to read request");
+                if line.trim().is_empty() {
+                    break;
+                }
+                request.push(line.trim().to_string());
+            }
+            stream.write_all(&response).expect("Failed to respond");
+            requests.push(request);
+        }
+        requests
+    });
+    (address, handle)
+}
+
+#[test]
+fn test_url_input_retries_and_resumes() {
+    let body = "name,age\nAlice,30\nBob,25\nCarol,41\n";
+    let cut = 20;
+    let responses = vec![
+        b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec(),
+        // The connection drops partway through the body
+        format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nAccept-Ranges: bytes\r\nConnection: close\r\n\r\n{}", body.len(), &body[..cut]).into_bytes(),
+        format!("HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n{}", body.len() - cut, cut, body.len() - 1, body.len(), &body[cut..]).into_bytes(),
+    ];
+    let (address, server) = serve_script(responses);
+    let url = format!("http://{}/people.csv", address);
+    let output = run_csvpretty_piped("", &[&url]).expect("Failed to run csvpretty");
+    let requests = server.join().expect("Server failed");
+
+    assert_eq!(requests.len(), 3);
+    assert!(!requests[1].iter().any(|line| line.to_ascii_lowercase().starts_with("range:")), "{:?}", requests[1]);
+    assert!(requests[2].iter().any(|line| line.eq_ignore_ascii_case(&format!("range: bytes={}-", cut))), "{:?}", requests[2]);
+    assert_eq!(output, run_csvpretty_piped(body, &[]).expect("Failed to run csvpretty"));
+}
+
+#[test]
+fn test_url_input_without_resume_support() {
+    // A server that answers the range request with the whole body again can't resume
+    let body = "name,age\nAlice,30\nBob,25\nCarol,41\n";
+    let responses = vec![
+        format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), &body[..20]).into_bytes(),
+        format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body).into_bytes(),
+    ];
+    let (address, server) = serve_script(responses);
+    let url = format!("http://{}/people.csv", address);
+    let output = run_csvpretty_piped("", &[&url]).expect("Failed to run csvpretty");
+    server.join().expect("Server failed");
+
+    assert!(output.contains(&format!("Error: {}", url)) && output.contains("can't resume the download"), "{}", output);
+}
+
+#[test]
+fn test_url_input() {
+    let (address, server) = serve_once("200 OK", "name,age\nAlice,30\nBob,25\n");
+    let url = format!("http://{}/people.csv", address);
+    let output = run_csvpretty_piped("", &[&url]).expect("Failed to run csvpretty");
+    let request = server.join().expect("Server failed");
+
+    assert!(request.iter().any(|line| line.to_ascii_lowercase().contains("accept-encoding") && line.contains("gzip")), "{:?}", request);
+    insta::assert_snapshot!("url_input", output);
+}
+
+#[test]
+fn test_url_input_sends_headers() {
+    let (address, server) = serve_once("200 OK", "name\nAlice\n");
+    let url = format!("http://{}/people.csv", address);
+    run_csvpretty_piped("", &["--header", "Authorization: Bearer secret", "--header", "X-Trace: 1", &url])
+        .expect("Failed to run csvpretty");
+    let request = server.join().expect("Server failed");
+
+    assert!(request.iter().any(|line| line == "authorization: Bearer secret" || line == "Authorization: Bearer secret"), "{:?}", request);
+    assert!(request.iter().any(|line| line.eq_ignore_ascii_case("x-trace: 1")), "{:?}", request);
+}
+
+#[test]
+fn test_url_format_from_extension_before_query() {
+    let (address, server) = serve_once("200 OK", "[{\"name\": \"Alice\"}]");
+    let url = format!("http://{}/people.json?token=abc", address);
+    let output = run_csvpretty_piped("", &[&url]).expect("Failed to run csvpretty");
+    server.join().expect("Server failed");
+
+    assert!(output.contains("Alice") && !output.contains('{'), "{}", output);
+}
+
+#[test]
+fn test_url_error_status() {
+    let (address, server) = serve_once("404 Not Found", "missing");
+    let url = format!("http://{}/missing.csv", address);
+    let output = run_csvpretty_piped("", &[&url]).expect("Failed to run csvpretty");
+    server.join().expect("Server failed");
+
+    assert!(output.contains(&format!("Error: {}", url)) && output.contains("404"), "{}", output);
+}
@@ -0,0 +1,58 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_hyperlinks() {
+    let csv_input = load_fixture("links.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--wrap", "none", "--hyperlinks"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("hyperlinks", output);
+}
+
+#[test]
+fn test_hyperlinks_host_label() {
+    // The url column narrows to the host names; the links still open the full URLs
+    let csv_input = load_fixture("links.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--wrap", "none", "--hyperlinks", "--hyperlink-label", "host"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("hyperlinks_host_label", output);
+}
+
+#[test]
+fn test_hyperlinks_in_one_column() {
+    let csv_input = "site,mirror\nhttps://example.com,https://mirror.example.net\n";
+    let output = run_csvpretty_colored(csv_input, &["--color", "always", "--wrap", "none", "--hyperlinks", "--hyperlink-column", "mirror"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("hyperlinks_in_one_column", output);
+}
+
+#[test]
+fn test_hyperlinks_without_colors() {
+    // Plain output has no escape sequences, so URLs stay as they are, even with the host label
+    let csv_input = load_fixture("links.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--hyperlinks", "--hyperlink-label", "host"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("hyperlinks_without_colors", output);
+}
+
+#[test]
+fn test_hyperlink_column_not_found() {
+    let csv_input = load_fixture("links.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--hyperlinks", "--hyperlink-column", "website"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--hyperlink-column") && output.contains("website"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_hyperlink_label_requires_hyperlinks() {
+    let output = run_csvpretty_piped("a\n1\n", &["--hyperlink-label", "host"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--hyperlinks"), "unexpected output: {}", output);
+}
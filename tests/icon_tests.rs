@@ -0,0 +1,41 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_icon_column() {
+    let csv_input = load_fixture("checks.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--icon-column", "status:ok=✅,warn=⚠️,error=❌"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("icon_column", output);
+}
+
+#[test]
+fn test_icon_only() {
+    let csv_input = load_fixture("checks.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--icon-column", "status:ok=✅,warn=⚠️,error=❌", "--icon-only"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("icon_only", output);
+}
+
+#[test]
+fn test_icon_column_wraps_emoji_whole() {
+    // The column is too narrow for "⚠️ warn", so the icon must not be split from its
+    // variation selector
+    let csv_input = load_fixture("checks.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--icon-column", "status:warn=⚠️⚠️⚠️", "--max-col-width", "3", "--wrap", "char"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("icon_column_wraps_emoji_whole", output);
+}
+
+#[test]
+fn test_icon_column_unknown_column() {
+    let csv_input = load_fixture("checks.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--icon-column", "state:ok=✅"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("icon_column_unknown_column", output);
+}
@@ -0,0 +1,291 @@
+mod helpers;
+
+use csvpretty::{display_width, escape_control_chars, set_width_conventions, structured, truncate_at, truncate_with_marker, Alignment, Cell, CollapsedRows, CsvOptions, Ellipsis, EmojiWidth, Error, GroupStart, MalformedRecord, ParseError, ParseErrorKind, ProgressiveRenderer, RenderOptions, Renderer, SortIndicator, Table, TableStyle, WidthConventions, WrapMode};
+use helpers::*;
+use std::borrow::Cow;
+
+fn render(table: &Table, options: &RenderOptions) -> String {
+    let mut out = Vec::new();
+    table.render_to(&mut out, options).expect("Failed to render table");
+    String::from_utf8(out).expect("Rendered table is not UTF-8")
+}
+
+#[test]
+fn test_render_default_options() {
+    let table = Table::from_reader(load_fixture("simple.csv").as_bytes()).expect("Failed to parse CSV");
+    let output = render(&table, &RenderOptions::default());
+
+    insta::assert_snapshot!("render_default_options", output);
+}
+
+#[test]
+fn test_render_custom_options() {
+    let table = Table::from_reader(load_fixture("simple.csv").as_bytes()).expect("Failed to parse CSV");
+    let options = RenderOptions {
+        show_line_numbers: true,
+        border: TableStyle::Ascii.border_style(),
+        alignments: vec![Alignment::Left, Alignment::Right],
+        terminal_width: 40,
+        ..RenderOptions::default()
+    };
+    let output = render(&table, &options);
+
+    insta::assert_snapshot!("render_custom_options", output);
+}
+
+#[test]
+fn test_renderer_header_and_rows() {
+    let table = Table::from_reader(load_fixture("simple.csv").as_bytes()).expect("Failed to parse CSV");
+    let options = RenderOptions::default();
+    let renderer = Renderer::new(&table, &options).expect("Failed to lay out table");
+
+    let mut header = Vec::new();
+    let mut rows = Vec::new();
+    renderer.render_header(&mut header).expect("Failed to render header");
+    renderer.render_rows(&mut rows).expect("Failed to render rows");
+
+    let header = String::from_utf8(header).expect("Rendered header is not UTF-8");
+    let rows = String::from_utf8(rows).expect("Rendered rows are not UTF-8");
+    assert_eq!(header + &rows, render(&table, &options));
+}
+
+#[test]
+fn test_truncate_cells() {
+    let mut table = Table::from_reader("id,blob\n1,ééééé\n".as_bytes()).expect("Failed to parse CSV");
+    // 5 bytes falls inside the third é, so the cut moves back to a char boundary
+    table.truncate_cells(5);
+
+    assert_eq!(table.records[0][1], "éé…");
+}
+
+#[test]
+fn test_truncate_with_marker() {
+    assert_eq!(truncate_with_marker("abcdefghijklmnopqrstuvwxyz0123456789", 16, "[+{chars}]"), "abcdefghijk[+25]");
+    // Once fewer than 10 characters are cut, the count takes one column less
+    assert_eq!(truncate_with_marker("abcdefghijkl", 10, "[+{chars}]"), "abcdef[+6]");
+    assert_eq!(truncate_with_marker("abcdefghij", 10, "[+{chars}]"), "abcdefghij");
+    // A marker that doesn't fit gives way to …
+    assert_eq!(truncate_with_marker("abcdefghij", 4, "(truncated)"), "abc…");
+}
+
+#[test]
+fn test_truncate_at() {
+    assert_eq!(truncate_at("/var/log/nginx/app.log", 14, "…", Ellipsis::Start), "…nginx/app.log");
+    // The start gets the odd column
+    assert_eq!(truncate_at("/var/log/nginx/app.log", 14, "…", Ellipsis::Middle), "/var/lo…pp.log");
+    assert_eq!(truncate_at("/var/log/nginx/app.log", 14, "…", Ellipsis::End), "/var/log/ngin…");
+    // Colors set before the cut carry on into the kept end
+    assert_eq!(truncate_at("\x1b[31mabcdefgh", 4, "…", Ellipsis::Start), "\x1b[31m…fgh");
+}
+
+#[test]
+fn test_from_reader_lenient() {
+    let (table, malformed) = Table::from_reader_lenient(load_fixture("malformed.csv").as_bytes(), &CsvOptions::default())
+        .expect("Failed to parse CSV");
+
+    assert_eq!(table.records.len(), 3);
+    assert_eq!(table.records[1], vec!["Bob", "25", ""]);
+    assert_eq!(malformed, vec![
+        MalformedRecord { record: 2, line: 3, fields: 2, expected: 3 },
+        MalformedRecord { record: 3, line: 4, fields: 4, expected: 3 },
+    ]);
+    assert!(!malformed[0].skipped());
+    assert!(malformed[1].skipped());
+}
+
+#[test]
+fn test_from_borrowed_str_borrows_cells() {
+    let input = String::from("name,note\nAlice,\"plain, quoted\"\r\nBob,\"say \"\"hi\"\"\"\nCarol\n");
+    let (table, malformed) = Table::from_borrowed_str(&input, &CsvOptions::default(), true).expect("Failed to parse CSV");
+    let copied = Table::from_reader_lenient(input.as_bytes(), &CsvOptions::default()).expect("Failed to parse CSV").0;
+
+    assert_eq!(table.records, copied.records);
+    assert_eq!(malformed.len(), 1);
+    let borrowed: Vec<Vec<bool>> = table.records.iter()
+        .map(|row| row.iter().map(|cell| matches!(cell, Cell::Borrowed(_))).collect())
+        .collect();
+    // Only the cell with escaped quotes differs from its text in the input
+    assert_eq!(borrowed, [[true, true], [true, false], [true, true]]);
+}
+
+#[test]
+fn test_parse_errors_have_a_place_and_kind() {
+    let e = Table::from_reader(load_fixture("malformed.csv").as_bytes()).expect_err("Parsed malformed CSV");
+    match e {
+        Error::Parse(ParseError { line, kind, .. }) => {
+            assert_eq!(line, Some(3));
+            assert_eq!(kind, ParseErrorKind::FieldCount { fields: 2, expected: 3 });
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let e = Table::from_reader(&b"name\nAl\xffce\n"[..]).expect_err("Parsed invalid UTF-8");
+    assert!(matches!(e, Error::Parse(ParseError { kind: ParseErrorKind::Encoding, line: Some(2), column: Some(1), .. })), "{:?}", e);
+
+    let e = structured::parse_ndjson("{\"a\": 1}\n{\"a\": }\n").expect_err("Parsed invalid JSON");
+    assert!(matches!(e, Error::Parse(ParseError { kind: ParseErrorKind::Syntax, line: Some(2), column: Some(7), .. })), "{:?}", e);
+    assert!(e.to_string().starts_with("invalid JSON on line 2"), "{}", e);
+}
+
+#[test]
+fn test_render_ragged_table_is_layout_error() {
+    let table = Table { headers: ["a", "b"].into_iter().collect(), records: vec![vec!["1".into()]] };
+    let e = table.render_to(&mut Vec::new(), &RenderOptions::default()).expect_err("Rendered a ragged table");
+
+    assert!(matches!(e, Error::Layout(_)), "{:?}", e);
+    assert_eq!(e.to_string(), "row 1 has 1 cell but the table has 2 columns");
+}
+
+#[test]
+fn test_renderer_short_row_is_layout_error() {
+    let table = Table { headers: ["a", "b"].into_iter().collect(), records: vec![vec!["1".into(), "2".into()], vec!["3".into()]] };
+    let options = RenderOptions { vertical: true, ..RenderOptions::default() };
+    let e = Renderer::new(&table, &options).err().expect("Laid out a table with a short row");
+
+    assert!(matches!(e, Error::Layout(_)), "{:?}", e);
+    assert_eq!(e.to_string(), "row 2 has 1 cell but the table has 2 columns");
+
+    let mut renderer = ProgressiveRenderer::new(&table.headers, &table.records[..1], &options).expect("Failed to lay out table");
+    let mut out = Vec::new();
+    renderer.render_row(&mut out, &table.records[0]).expect("Failed to render row");
+    let e = renderer.render_row(&mut out, &table.records[1]).expect_err("Rendered a short row");
+    assert_eq!(e.to_string(), "row 2 has 1 cell but the table has 2 columns");
+}
+
+#[test]
+fn test_select_columns() {
+    let table = Table::from_reader(load_fixture("simple.csv").as_bytes()).expect("Failed to parse CSV");
+    let options = RenderOptions {
+        alignments: vec![Alignment::Left, Alignment::Right, Alignment::Center],
+        ..RenderOptions::default()
+    };
+    let selected = table.select_columns(&[2, 0]);
+    let selected_options = options.select_columns(&[2, 0]);
+
+    assert_eq!(selected.headers.iter().collect::<Vec<_>>(), vec!["city", "name"]);
+    assert_eq!(selected.records[0], vec!["New York", "Alice"]);
+    assert_eq!(selected_options.alignments, vec![Alignment::Center, Alignment::Left]);
+    // The city column keeps the third palette color
+    assert_eq!(selected_options.palette_slots, vec![2, 0]);
+    insta::assert_snapshot!("select_columns", render(&selected, &selected_options));
+}
+
+#[test]
+fn test_row_groups() {
+    let table = Table::from_reader(load_fixture("simple.csv").as_bytes()).expect("Failed to parse CSV");
+    let options = RenderOptions {
+        groups: vec![
+            GroupStart { row: 0, label: Some("first".to_string()) },
+            GroupStart { row: 1, label: None },
+        ],
+        ..RenderOptions::default()
+    };
+
+    insta::assert_snapshot!("row_groups", render(&table, &options));
+}
+
+#[test]
+fn test_collapsed_rows() {
+    let table = Table::from_reader(load_fixture("simple.csv").as_bytes()).expect("Failed to parse CSV");
+    let options = RenderOptions {
+        collapsed_rows: vec![
+            CollapsedRows { row: 1, count: 4 },
+            CollapsedRows { row: table.records.len(), count: 1 },
+        ],
+        ..RenderOptions::default()
+    };
+
+    insta::assert_snapshot!("collapsed_rows", render(&table, &options));
+}
+
+#[test]
+fn test_sort_indicator_follows_selected_columns() {
+    let options = RenderOptions {
+        sort_indicator: Some(SortIndicator { column: 2, descending: true }),
+        ..RenderOptions::default()
+    };
+
+    assert_eq!(options.select_columns(&[2, 0]).sort_indicator, Some(SortIndicator { column: 0, descending: true }));
+    assert_eq!(options.select_columns(&[0, 1]).sort_indicator, None);
+}
+
+#[test]
+fn test_width_conventions() {
+    assert_eq!((display_width("🇳🇿"), display_width("±")), (2, 1));
+    // The conventions apply to the current thread only, so other tests are unaffected
+    set_width_conventions(WidthConventions { emoji_width: EmojiWidth::One, ambiguous_wide: true });
+    assert_eq!((display_width("🇳🇿"), display_width("±")), (1, 2));
+    set_width_conventions(WidthConventions::default());
+}
+
+#[test]
+fn test_expanded_rows_and_row_starts() {
+    let table = Table::from_reader(load_fixture("long_text.csv").as_bytes()).expect("Failed to parse CSV");
+    let options = RenderOptions {
+        max_row_height: Some(2),
+        terminal_width: 60,
+        ..RenderOptions::default()
+    };
+    let renderer = Renderer::new(&table, &options).expect("Failed to lay out table");
+    let mut rows = Vec::new();
+    let starts = renderer.render_rows_with_starts(&mut rows).expect("Failed to render rows");
+    let rows = String::from_utf8(rows).expect("Rendered rows are not UTF-8");
+    assert_eq!(starts.len(), table.records.len());
+    assert!(starts.windows(2).all(|pair| pair[1] - pair[0] == 2));
+    assert!(rows.contains("lines)"));
+
+    let expanded = RenderOptions { expanded_rows: vec![0], ..options.clone() };
+    let renderer = Renderer::new(&table, &expanded).expect("Failed to lay out table");
+    let mut expanded_rows = Vec::new();
+    let expanded_starts = renderer.render_rows_with_starts(&mut expanded_rows).expect("Failed to render rows");
+    assert_eq!(expanded_starts[0], 0);
+    assert!(expanded_starts[1] > 2);
+
+    insta::assert_snapshot!("expanded_rows", String::from_utf8(expanded_rows).expect("Rendered rows are not UTF-8"));
+}
+
+#[test]
+fn test_progressive_renderer_matches_renderer() {
+    let table = Table::from_reader(load_fixture("simple.csv").as_bytes()).expect("Failed to parse CSV");
+    let options = RenderOptions { wrap_mode: WrapMode::None, terminal_width: usize::MAX, ..RenderOptions::default() };
+
+    let mut renderer = ProgressiveRenderer::new(&table.headers, &table.records, &options).expect("Failed to lay out table");
+    let mut progressive = Vec::new();
+    renderer.render_header(&mut progressive).expect("Failed to render header");
+    for row in &table.records {
+        renderer.render_row(&mut progressive, row).expect("Failed to render row");
+    }
+    renderer.finish(&mut progressive).expect("Failed to render bottom");
+
+    assert_eq!(String::from_utf8(progressive).expect("Rendered table is not UTF-8"), render(&table, &options));
+}
+
+#[test]
+fn test_large_table_widths_follow_width_conventions() {
+    // Enough cells to be measured on several threads, with the widest cell in the last row
+    let mut records = vec![vec!["a".into(), "b".into()]; 40_000];
+    records.push(vec!["±±±±±±".into(), "b".into()]);
+    let table = Table { headers: ["x", "y"].into_iter().collect(), records };
+    let options = RenderOptions { wrap_mode: WrapMode::None, terminal_width: usize::MAX, ..RenderOptions::default() };
+    let header_width = |conventions| {
+        set_width_conventions(conventions);
+        let mut header = Vec::new();
+        Renderer::new(&table, &options).expect("Failed to lay out table").render_header(&mut header).expect("Failed to render header");
+        // Border characters are ambiguous-width too, so they're counted rather than measured
+        String::from_utf8(header).expect("Rendered header is not UTF-8").lines().next().unwrap().chars().count()
+    };
+
+    let narrow = header_width(WidthConventions::default());
+    let wide = header_width(WidthConventions { ambiguous_wide: true, ..WidthConventions::default() });
+    set_width_conventions(WidthConventions::default());
+    assert_eq!(wide, narrow + 6);
+}
+
+#[test]
+fn test_escape_control_chars() {
+    assert_eq!(escape_control_chars("\x1b[1mbold\x1b[0m\r\n\x07\x1b[2J\u{9b}"), "\x1b[1mbold\x1b[0m\\r\\n\\x07␛[2J\\u{9b}");
+    assert!(matches!(escape_control_chars("plain\x1b[0m"), Cow::Borrowed(_)));
+    // Control characters are measured as their escapes, so a cut leaves room for them
+    assert_eq!(display_width("a\tb"), 4);
+    assert_eq!(truncate_at("a\tbcdef", 5, "…", Ellipsis::End), "a\\tb…");
+}
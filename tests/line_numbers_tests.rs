@@ -73,3 +73,20 @@ fn test_no_line_numbers_many_rows() {
 
     insta::assert_snapshot!("no_line_numbers_many_rows", output);
 }
+
+#[test]
+fn test_gutter_and_continuation_chars() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_piped(&csv_input, &["-n", "--style", "ascii", "--gutter-char", ":", "--continuation-char", "+"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("gutter_and_continuation_chars", output);
+}
+
+#[test]
+fn test_gutter_char_must_be_one_column() {
+    let output = run_csvpretty_piped("a\n1\n", &["-n", "--gutter-char", "||"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("expected a single character one column wide"), "{}", output);
+}
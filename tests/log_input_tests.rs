@@ -0,0 +1,42 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_access_log() {
+    let log_input = load_fixture("access.log");
+    let output = run_csvpretty_in_pty(&log_input, 200, &["--input", "accesslog"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("access_log", output);
+}
+
+#[test]
+fn test_access_log_time_filter() {
+    let log_input = load_fixture("access.log");
+    let output = run_csvpretty_in_pty(
+        &log_input,
+        200,
+        &["--input", "accesslog", "--time-column", "time", "--since", "2000-10-10 20:56:00"],
+    )
+    .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("access_log_time_filter", output);
+}
+
+#[test]
+fn test_w3c_log() {
+    let log_input = load_fixture("w3c.log");
+    let output = run_csvpretty_piped(&log_input, &["--input", "w3c"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("w3c_log", output);
+}
+
+#[test]
+fn test_w3c_log_without_fields() {
+    let output = run_csvpretty_piped("2024-03-01 09:00:01 10.1.1.5 GET /index.html 200 15\n", &["--input", "w3c"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("w3c_log_without_fields", output);
+}
@@ -0,0 +1,47 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_lookup_replaces_codes() {
+    let csv_input = load_fixture("orders.csv");
+    let countries = fixture_path("countries.csv");
+    let lookup = format!("country={}:code:name", countries.display());
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--lookup", &lookup])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("lookup_replaces_codes", output);
+}
+
+#[test]
+fn test_lookup_default_columns() {
+    let csv_input = load_fixture("orders.csv");
+    let statuses = fixture_path("statuses.csv");
+    let lookup = format!("status={}", statuses.display());
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--lookup", &lookup])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("lookup_default_columns", output);
+}
+
+#[test]
+fn test_lookup_append() {
+    let csv_input = load_fixture("orders.csv");
+    let countries = fixture_path("countries.csv");
+    let lookup = format!("country={}:code:continent", countries.display());
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--lookup", &lookup, "--lookup-append"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("lookup_append", output);
+}
+
+#[test]
+fn test_lookup_missing_key_column() {
+    let csv_input = load_fixture("orders.csv");
+    let countries = fixture_path("countries.csv");
+    let lookup = format!("country={}:iso:name", countries.display());
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--lookup", &lookup])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("no column named 'iso'"), "unexpected output: {}", output);
+}
@@ -0,0 +1,39 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_max_row_height() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--max-row-height", "2"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("max_row_height", output);
+}
+
+#[test]
+fn test_max_lines_per_column() {
+    let csv_input = "name,notes\nalpha,one two three four five six seven eight nine ten eleven twelve\n";
+    let output = run_csvpretty_in_pty(csv_input, 40, &["--max-row-height", "1", "--max-lines", "notes=2"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("max_lines_per_column", output);
+}
+
+#[test]
+fn test_max_row_height_one_hidden_line() {
+    let csv_input = "notes\nfirst line\n\"one\ntwo\"\n";
+    let output = run_csvpretty_piped(csv_input, &["--max-row-height", "1", "--width", "40"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("one (+1 line)"), "{}", output);
+    assert!(!output.contains("two"), "{}", output);
+}
+
+#[test]
+fn test_max_lines_unknown_column() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--max-lines", "c=2"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unknown column 'c' in --max-lines"), "{}", output);
+}
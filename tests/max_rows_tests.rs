@@ -0,0 +1,54 @@
+mod helpers;
+
+use helpers::*;
+
+/// A table of `rows` numbered rows.
+fn numbered_rows(rows: usize) -> String {
+    let mut csv_input = String::from("n,square\n");
+    for n in 1..=rows {
+        csv_input += &format!("{},{}\n", n, n * n);
+    }
+    csv_input
+}
+
+#[test]
+fn test_max_rows_elides_the_middle() {
+    let output = run_csvpretty_piped(&numbered_rows(1500), &["--max-rows", "5", "--line-numbers"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("max_rows_elides_the_middle", output);
+}
+
+#[test]
+fn test_max_rows_within_limit() {
+    let csv_input = numbered_rows(5);
+    let limited = run_csvpretty_piped(&csv_input, &["--max-rows", "5"]).expect("Failed to run csvpretty");
+    let unlimited = run_csvpretty_piped(&csv_input, &[]).expect("Failed to run csvpretty");
+
+    assert_eq!(limited, unlimited);
+}
+
+#[test]
+fn test_max_rows_one_more_row() {
+    let output = run_csvpretty_piped(&numbered_rows(3), &["--max-rows", "2", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("⋯ 1 more row ⋯"), "{}", output);
+    assert!(output.contains(" 1 ") && output.contains(" 3 ") && !output.contains(" 2 "), "{}", output);
+}
+
+#[test]
+fn test_max_rows_auto_shows_every_row_when_piped() {
+    let csv_input = numbered_rows(200);
+    let auto = run_csvpretty_piped(&csv_input, &["--max-rows", "auto"]).expect("Failed to run csvpretty");
+    let unlimited = run_csvpretty_piped(&csv_input, &[]).expect("Failed to run csvpretty");
+
+    assert_eq!(auto, unlimited);
+}
+
+#[test]
+fn test_max_rows_must_be_positive() {
+    let output = run_csvpretty_piped(&numbered_rows(3), &["--max-rows", "0"]).expect("Failed to run csvpretty");
+
+    assert!(output.contains("invalid row count '0'"), "{}", output);
+}
@@ -0,0 +1,39 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_null_display_default() {
+    let csv_input = load_fixture("missing.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("null_display_default", output);
+}
+
+#[test]
+fn test_null_display_placeholder() {
+    let csv_input = load_fixture("missing.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--null-display", "∅"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("null_display_placeholder", output);
+}
+
+#[test]
+fn test_null_display_wider_than_column() {
+    let csv_input = load_fixture("missing.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--null-display", "(missing)", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("null_display_wider_than_column", output);
+}
+
+#[test]
+fn test_null_display_dimmed() {
+    let csv_input = load_fixture("missing.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--null-display", "∅"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("null_display_dimmed", output);
+}
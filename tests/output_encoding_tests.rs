@@ -0,0 +1,53 @@
+mod helpers;
+
+use helpers::*;
+use std::path::PathBuf;
+
+/// A path in the temporary directory for the output of the test called `name`.
+fn output_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("csvpretty-output-encoding-{}-{}.txt", std::process::id(), name))
+}
+
+/// Renders `csv_input` into a file with `args`, returning the file's bytes and what was printed.
+fn render_to_file(name: &str, csv_input: &str, args: &[&str]) -> (Vec<u8>, String) {
+    let path = output_path(name);
+    let mut full_args = vec!["-o", path.to_str().unwrap()];
+    full_args.extend_from_slice(args);
+    let printed = run_csvpretty_piped(csv_input, &full_args).expect("Failed to run csvpretty");
+    let written = std::fs::read(&path).expect("Failed to read output file");
+    std::fs::remove_file(&path).ok();
+    (written, printed)
+}
+
+#[test]
+fn test_utf8_by_default() {
+    let (written, _) = render_to_file("default", "name\ncafé\n", &[]);
+
+    assert!(!written.starts_with(b"\xef\xbb\xbf"));
+    assert!(String::from_utf8(written).expect("Output is not UTF-8").contains("café"));
+}
+
+#[test]
+fn test_utf8_bom() {
+    let (written, _) = render_to_file("bom", "name\ncafé\n", &["--output-encoding", "utf-8-bom"]);
+
+    let text = written.strip_prefix(b"\xef\xbb\xbf").expect("Output doesn't start with a BOM");
+    assert!(String::from_utf8(text.to_vec()).expect("Output is not UTF-8").contains("café"));
+}
+
+#[test]
+fn test_latin1() {
+    let (written, printed) = render_to_file("latin1", "name\ncafé\n", &["--output-encoding", "latin1", "--style", "ascii"]);
+
+    assert!(written.windows(4).any(|bytes| bytes == b"caf\xe9"), "{:?}", written);
+    assert!(printed.is_empty(), "{}", printed);
+    insta::assert_snapshot!("latin1", written.iter().map(|&byte| char::from(byte)).collect::<String>());
+}
+
+#[test]
+fn test_latin1_replaces_box_drawing() {
+    let (written, printed) = render_to_file("latin1-borders", "name\ncafé\n", &["--output-encoding", "latin-1"]);
+
+    assert!(written.contains(&b'?'), "{:?}", written);
+    assert!(printed.contains("written as ?; --style ascii"), "{}", printed);
+}
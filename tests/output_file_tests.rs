@@ -0,0 +1,46 @@
+mod helpers;
+
+use helpers::*;
+use std::path::PathBuf;
+
+/// A path in the temporary directory for the output of the test called `name`.
+fn output_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("csvpretty-output-{}-{}.txt", std::process::id(), name))
+}
+
+#[test]
+fn test_output_file_has_no_colors() {
+    let path = output_path("plain");
+    let input = fixture_path("simple.csv");
+    let output = run_csvpretty_in_pty_with_colors("", 80, &["-o", path.to_str().unwrap(), input.to_str().unwrap()], &[])
+        .expect("Failed to run csvpretty");
+    let written = std::fs::read_to_string(&path).expect("Failed to read output file");
+    std::fs::remove_file(&path).ok();
+
+    assert!(!output.contains("Alice"), "{}", output);
+    assert!(!written.contains('\x1b'), "{:?}", written);
+    insta::assert_snapshot!("output_file_has_no_colors", written);
+}
+
+#[test]
+fn test_output_file_with_color_always() {
+    let path = output_path("colored");
+    let input = fixture_path("simple.csv");
+    let output = run_csvpretty_in_pty_with_colors("", 80, &["--output", path.to_str().unwrap(), "--color", "always", "--verbose", input.to_str().unwrap()], &[])
+        .expect("Failed to run csvpretty");
+    let written = std::fs::read_to_string(&path).expect("Failed to read output file");
+    std::fs::remove_file(&path).ok();
+
+    assert!(written.contains('\x1b'), "{:?}", written);
+    assert!(output.contains("not asking the terminal"), "{}", output);
+    assert!(!output.contains("background color from the terminal"), "{}", output);
+}
+
+#[test]
+fn test_output_file_unwritable() {
+    let path = output_path("missing-dir").join("table.txt");
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["-o", path.to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error:") && output.contains("table.txt"), "{}", output);
+}
@@ -0,0 +1,50 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_page_height() {
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--page-height", "9"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("page_height", output);
+}
+
+#[test]
+fn test_page_height_keeps_rows_whole() {
+    // Wrapped rows take several lines and move to the next page whole
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--width", "50", "--page-height", "12"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("page_height_keeps_rows_whole", output);
+}
+
+#[test]
+fn test_page_height_every_page_fits() {
+    let csv_input = load_fixture("many_rows.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--page-height", "20"])
+        .expect("Failed to run csvpretty");
+
+    let mut page_lines = 0;
+    let mut pages = 0;
+    for line in output.lines() {
+        page_lines += 1;
+        if line.starts_with("Page ") {
+            assert!(page_lines <= 20, "page {} has {} lines: {}", pages + 1, page_lines, output);
+            page_lines = 0;
+            pages += 1;
+        }
+    }
+    assert!(pages > 1, "unexpected output: {}", output);
+}
+
+#[test]
+fn test_page_height_too_small() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--page-height", "5"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--page-height 5 leaves no room for rows; the header and page number take 5 lines"), "unexpected output: {}", output);
+}
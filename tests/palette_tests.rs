@@ -0,0 +1,108 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_default_palette() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("default_palette", output);
+}
+
+#[test]
+fn test_palette_flag() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--palette", "ff0000,#00ff00"], &[])
+        .expect("Failed to run csvpretty");
+
+    // Two colors cycle across three columns: red, green, red
+    insta::assert_snapshot!("palette_flag", output);
+}
+
+#[test]
+fn test_palette_env_var() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always"], &[("CSVPRETTY_COLORS", "0000ff")])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("palette_env_var", output);
+}
+
+#[test]
+fn test_palette_flag_overrides_env_var() {
+    let csv_input = load_fixture("simple.csv");
+    let from_flag = run_csvpretty_colored(&csv_input, &["--color", "always", "--palette", "ff0000"], &[("CSVPRETTY_COLORS", "0000ff")])
+        .expect("Failed to run csvpretty");
+    let flag_only = run_csvpretty_colored(&csv_input, &["--color", "always", "--palette", "ff0000"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(from_flag, flag_only);
+}
+
+#[test]
+fn test_theme_specific_palette() {
+    let csv_input = load_fixture("simple.csv");
+    // Theme detection can't succeed without a terminal, so the dark section applies
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--palette", "dark:112233;light:445566"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[38;2;17;34;51m"), "dark palette not used: {:?}", output);
+}
+
+#[test]
+fn test_invalid_palette() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--palette", "ff00zz"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("invalid color 'ff00zz'"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_color_cycle_source() {
+    // owner keeps the third palette color after tags is split into two columns
+    let csv_input = "id,tags,owner\n1,\"bug,ui\",ann\n";
+    let output = run_csvpretty_colored(csv_input, &["--color", "always", "--split-column", "tags"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("color_cycle_source", output);
+}
+
+#[test]
+fn test_color_cycle_display() {
+    let csv_input = "id,tags,owner\n1,\"bug,ui\",ann\n";
+    let output = run_csvpretty_colored(csv_input, &["--color", "always", "--split-column", "tags", "--color-cycle", "display"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("color_cycle_display", output);
+}
+
+#[test]
+fn test_column_color() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--palette", "ff0000,00ff00", "--column-color", "age=blue,city=#112233"], &[])
+        .expect("Failed to run csvpretty");
+
+    // name keeps the first palette color, and the header matches its column's values
+    insta::assert_snapshot!("column_color", output);
+}
+
+#[test]
+fn test_column_color_unknown_column() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--column-color", "country=red"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unknown column 'country' in --column-color"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_column_color_invalid_color() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--column-color", "age=teal"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("invalid color 'teal'"), "unexpected output: {}", output);
+}
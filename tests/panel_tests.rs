@@ -0,0 +1,49 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_split_columns_no_wrap() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--width", "60", "--split-columns"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("split_columns_no_wrap", output);
+}
+
+#[test]
+fn test_split_columns_with_key() {
+    // The title goes above the first panel only
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--width", "70", "--split-columns", "--panel-key", "first_name", "--title", "People"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("split_columns_with_key", output);
+}
+
+#[test]
+fn test_split_columns_when_everything_fits() {
+    // One panel is just the table, without line numbers added
+    let csv_input = load_fixture("simple.csv");
+    let split = run_csvpretty_piped(&csv_input, &["--split-columns"]).expect("Failed to run csvpretty");
+    let plain = run_csvpretty_piped(&csv_input, &[]).expect("Failed to run csvpretty");
+
+    assert_eq!(split, plain);
+}
+
+#[test]
+fn test_panel_key_not_found() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--split-columns", "--panel-key", "surname"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--panel-key") && output.contains("surname"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_panel_key_requires_split_columns() {
+    let output = run_csvpretty_piped("a\n1\n", &["--panel-key", "a"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--split-columns"), "unexpected output: {}", output);
+}
@@ -0,0 +1,89 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_lenient() {
+    let csv_input = load_fixture("malformed.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--lenient", "--footer"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("lenient", output);
+}
+
+#[test]
+fn test_lenient_without_headers() {
+    // The first record sets the number of columns
+    let csv_input = "1,2\n3\n4,5,6\n";
+    let output = run_csvpretty_piped(csv_input, &["--lenient", "--no-headers"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("lenient_without_headers", output);
+}
+
+#[test]
+fn test_strict_by_default() {
+    let csv_input = load_fixture("malformed.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: CSV error: record 2 (line: 3, byte: 32): found record with 2 fields"), "unexpected output: {}", output);
+    assert!(output.contains("(use --lenient to pad or skip such records)"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_strict() {
+    let csv_input = load_fixture("malformed.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--strict"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: CSV error: record 2"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_lenient_conflicts_with_strict() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--lenient", "--strict"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("cannot be used with"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_flexible() {
+    // The record with an extra field adds a column rather than being skipped
+    let csv_input = load_fixture("malformed.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--flexible"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("flexible", output);
+}
+
+#[test]
+fn test_flexible_conflicts_with_lenient() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--flexible", "--lenient"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("cannot be used with"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_quote_and_escape() {
+    let path = fixture_path("single_quoted.csv");
+    let args = ["--quote", "'", "--escape", "\\", "--no-double-quote", path.to_str().unwrap()];
+    let from_file = run_csvpretty_piped("", &args).expect("Failed to run csvpretty");
+    let piped = run_csvpretty_piped(&load_fixture("single_quoted.csv"), &args[..5])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(from_file, piped);
+    insta::assert_snapshot!("quote_and_escape", from_file);
+}
+
+#[test]
+fn test_doubled_quotes_read_as_written_without_double_quote() {
+    let output = run_csvpretty_piped("a,b\n1,\"x\"\"y\"\n", &["--no-double-quote"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("x\"y\""), "unexpected output: {}", output);
+}
@@ -0,0 +1,42 @@
+mod helpers;
+
+use helpers::*;
+use std::time::{Duration, Instant};
+
+/// Builds a CSV whose middle column holds a single cell of roughly `cell_bytes` bytes.
+fn pathological_csv(cell_bytes: usize) -> String {
+    let word = "lorem ipsum dolor sit amet ";
+    let blob = word.repeat(cell_bytes / word.len() + 1);
+    format!("id,blob,tail\n1,{},end\n2,short,end\n", blob.trim_end())
+}
+
+#[test]
+fn test_huge_cell_is_truncated_for_layout() {
+    let csv_input = pathological_csv(1024 * 1024);
+    let output = run_csvpretty_piped(&csv_input, &["--max-cell-bytes", "120"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("huge_cell_truncated", output);
+}
+
+#[test]
+fn test_huge_cell_default_limit_is_fast() {
+    let csv_input = pathological_csv(10 * 1024 * 1024);
+    let start = Instant::now();
+    let output = run_csvpretty_piped(&csv_input, &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(start.elapsed() < Duration::from_secs(20), "rendering took {:?}", start.elapsed());
+    // The default 64 KiB layout limit bounds the wrapped cell to about a thousand lines
+    assert!(output.lines().count() < 2000, "rendered {} lines", output.lines().count());
+    assert!(output.contains('…'));
+}
+
+#[test]
+fn test_huge_cell_in_no_wrap_mode() {
+    let csv_input = pathological_csv(10 * 1024 * 1024);
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--max-cell-bytes", "40"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("huge_cell_no_wrap", output);
+}
@@ -0,0 +1,59 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_pivot() {
+    let csv_input = load_fixture("monthly_sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--pivot", "rows=region,cols=month,values=sum(amount)"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("pivot", output);
+}
+
+#[test]
+fn test_pivot_counts_by_default() {
+    let csv_input = load_fixture("monthly_sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--pivot", "rows=region,cols=month"])
+        .expect("Failed to run csvpretty");
+
+    let north = output.lines().find(|line| line.contains("North")).unwrap_or_default();
+    assert_eq!(north.split_whitespace().collect::<Vec<_>>(), ["North", "│", "2", "│", "1", "│"], "unexpected output: {}", output);
+}
+
+#[test]
+fn test_pivot_several_row_columns() {
+    let csv_input = load_fixture("monthly_sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--pivot", "rows=month,region,cols=region,values=max(units)"])
+        .expect("Failed to run csvpretty");
+
+    let header = output.lines().nth(1).unwrap_or_default();
+    assert_eq!(header.split_whitespace().collect::<Vec<_>>(), ["month", "│", "region", "│", "North", "│", "South", "│", "East"], "unexpected output: {}", output);
+    assert!(output.lines().any(|line| line.split_whitespace().collect::<Vec<_>>() == ["Feb", "│", "South", "│", "│", "0", "│"]), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_pivot_keeps_value_type() {
+    let csv_input = load_fixture("monthly_sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--pivot", "rows=region,cols=month,values=sum(amount)", "--column-type", "amount=number(2)"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("1,450.50"), "unexpected output: {}", output);
+    assert!(output.contains("2,210.75"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_pivot_unknown_column() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--pivot", "rows=a,cols=c"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unknown column 'c' in --pivot"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_pivot_invalid_spec() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--pivot", "cols=b"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("expected rows=COLUMNS,cols=COLUMN,values=AGGREGATE"), "unexpected output: {}", output);
+}
@@ -0,0 +1,73 @@
+mod helpers;
+
+use helpers::*;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+const GROWING: &str = "id,name\n1,x\n2,y\n3,a much longer value\n4,z\n";
+
+#[test]
+fn test_progressive_widens_columns_for_later_rows() {
+    let output = run_csvpretty_piped(GROWING, &["--wrap", "none", "--progressive-rows", "2", "--line-numbers"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("progressive_widens_columns_for_later_rows", output);
+}
+
+#[test]
+fn test_progressive_wraps_later_rows() {
+    let output = run_csvpretty_piped(GROWING, &["--progressive", "--progressive-rows", "2", "--width", "20"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("progressive_wraps_later_rows", output);
+}
+
+#[test]
+fn test_progressive_matches_whole_table_when_every_row_is_measured() {
+    let csv_input = load_fixture("many_rows.csv");
+    let progressive = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--line-numbers"])
+        .expect("Failed to run csvpretty");
+    let whole = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--line-numbers", "--no-progressive"])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(progressive, whole);
+}
+
+#[test]
+fn test_progressive_prints_before_input_ends() {
+    let mut child = Command::new(get_binary_path())
+        .args(["--no-color", "--wrap", "none", "--progressive-rows", "1"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to run csvpretty");
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(b"id,name\n1,first\n").unwrap();
+    stdin.flush().unwrap();
+
+    // The header and the first row arrive while stdin is still open
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    let shown: Vec<String> = lines.by_ref().take(4).map(Result::unwrap).collect();
+    assert!(shown[3].contains("first"), "{:?}", shown);
+
+    drop(stdin);
+    let rest: Vec<String> = lines.map(Result::unwrap).collect();
+    assert_eq!(rest.len(), 1, "{:?}", rest);
+    child.wait().unwrap();
+}
+
+#[test]
+fn test_progressive_with_flag_needing_every_row() {
+    let output = run_csvpretty_piped(GROWING, &["--progressive", "--sort", "name"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: --progressive can't be used with --sort"), "{}", output);
+}
+
+#[test]
+fn test_wrap_none_with_flag_needing_every_row_reads_whole_input() {
+    let output = run_csvpretty_piped(GROWING, &["--wrap", "none", "--sort", "name", "--reverse"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.lines().nth(3).is_some_and(|line| line.contains('z')), "{}", output);
+}
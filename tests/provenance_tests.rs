@@ -0,0 +1,36 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_provenance_from_stdin() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "never", "--provenance"], &[("SOURCE_DATE_EPOCH", "1760000000")])
+        .expect("Failed to run csvpretty");
+
+    let expected = format!("stdin · 2025-10-09 08:53:20 UTC · csvpretty {}", env!("CARGO_PKG_VERSION"));
+    assert!(output.ends_with(&expected), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_provenance_lists_filters() {
+    let csv_input = load_fixture("events.csv");
+    let args = ["--color", "never", "--provenance", "--footer", "--time-column", "created_at", "--since", "2024-01-02", "--skip-lines", "0"];
+    let output = run_csvpretty_colored(&csv_input, &args, &[("SOURCE_DATE_EPOCH", "0")])
+        .expect("Failed to run csvpretty");
+
+    let expected = format!(
+        "stdin · 1970-01-01 00:00:00 UTC · csvpretty {} · --time-column created_at · --since '2024-01-02 00:00:00'",
+        env!("CARGO_PKG_VERSION"),
+    );
+    assert!(output.ends_with(&expected), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_provenance_dimmed() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--provenance"], &[("SOURCE_DATE_EPOCH", "0")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("␛[2mstdin · 1970-01-01 00:00:00 UTC"), "unexpected output: {}", output);
+}
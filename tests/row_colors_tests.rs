@@ -0,0 +1,51 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_row_colors_stripe() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--row-colors", "stripe"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("row_colors_stripe", output);
+}
+
+#[test]
+fn test_row_colors_both() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--row-colors", "both", "-n"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("row_colors_both", output);
+}
+
+#[test]
+fn test_row_colors_stripe_256() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--row-colors", "stripe", "--color-depth", "256"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("row_colors_stripe_256", output);
+}
+
+#[test]
+fn test_row_colors_stripe_without_colors() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--row-colors", "stripe"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("row_colors_stripe_without_colors", output);
+}
+
+#[test]
+fn test_row_colors_stripe_with_nulls() {
+    // Dimmed missing values must not end the stripe background partway through the row
+    let csv_input = load_fixture("missing.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--row-colors", "stripe", "--null-display", "∅"], &[])
+        .expect("Failed to run csvpretty");
+
+    let rows: Vec<&str> = output.lines().skip(3).collect();
+    assert!(rows.iter().all(|row| !row.contains("␛[0m")), "unexpected full reset in a data row: {:?}", rows);
+    insta::assert_snapshot!("row_colors_stripe_with_nulls", output);
+}
@@ -0,0 +1,56 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_rule_on_percent_column() {
+    let csv_input = load_fixture("conversions.csv");
+    let output = run_csvpretty_colored(
+        &csv_input,
+        &["--color", "always", "--column-type", "rate=percent", "--rule", "rate > 0.1 => red"],
+        &[],
+    )
+    .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("rule_on_percent_column", output);
+}
+
+#[test]
+fn test_rule_first_match_wins() {
+    let csv_input = load_fixture("conversions.csv");
+    let output = run_csvpretty_colored(
+        &csv_input,
+        &["--color", "always", "--rule", "visitors >= 10000 => green", "--rule", "visitors > 0 => ff0000", "--rule", "campaign == winter => blue"],
+        &[],
+    )
+    .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("rule_first_match_wins", output);
+}
+
+#[test]
+fn test_rule_without_colors() {
+    let csv_input = load_fixture("conversions.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--rule", "rate > 0.1 => red"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("rule_without_colors", output);
+}
+
+#[test]
+fn test_rule_unknown_column() {
+    let csv_input = load_fixture("conversions.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--rule", "ratio > 0.1 => red"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("rule_unknown_column", output);
+}
+
+#[test]
+fn test_rule_invalid() {
+    let csv_input = load_fixture("conversions.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--rule", "rate is high => red"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("rule_invalid", output);
+}
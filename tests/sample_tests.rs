@@ -0,0 +1,43 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_sample_with_seed() {
+    let csv_input = load_fixture("many_rows.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--sample", "4", "--seed", "7", "--footer"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("sample_with_seed", output);
+}
+
+#[test]
+fn test_sample_is_reproducible() {
+    let csv_input = load_fixture("many_rows.csv");
+    let first = run_csvpretty_piped(&csv_input, &["--sample", "5", "--seed", "42"])
+        .expect("Failed to run csvpretty");
+    let second = run_csvpretty_piped(&csv_input, &["--sample", "5", "--seed", "42"])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_sample_larger_than_input() {
+    let csv_input = load_fixture("simple.csv");
+    let sampled = run_csvpretty_piped(&csv_input, &["--sample", "10"])
+        .expect("Failed to run csvpretty");
+    let everything = run_csvpretty_piped(&csv_input, &[])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(sampled, everything);
+}
+
+#[test]
+fn test_sample_seed_in_provenance() {
+    let csv_input = load_fixture("many_rows.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "never", "--provenance", "--sample", "3", "--seed", "9"], &[("SOURCE_DATE_EPOCH", "0")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.ends_with(" · --sample 3 --seed 9"), "unexpected output: {}", output);
+}
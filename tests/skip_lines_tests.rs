@@ -0,0 +1,39 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_skip_lines_and_comment() {
+    let csv_input = load_fixture("export_with_preamble.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--skip-lines", "2", "--comment", "#"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("skip_lines_and_comment", output);
+}
+
+#[test]
+fn test_comment_before_header() {
+    let csv_input = "# exported by tool v2\nname,age\n# test row\nAlice,30\n";
+    let output = run_csvpretty_in_pty(csv_input, 80, &["--comment", "#"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("comment_before_header", output);
+}
+
+#[test]
+fn test_skip_all_lines() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--skip-lines", "10"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: No input provided"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_invalid_comment() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--comment", "//"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("expected a single ASCII character, got '//'"), "unexpected output: {}", output);
+}
@@ -0,0 +1,30 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_sort_indicator_ascending() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--sort", "name"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("sort_indicator_ascending", output);
+}
+
+#[test]
+fn test_sort_indicator_follows_bar_columns() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--sort", "score", "--reverse", "--bar", "age:5"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("score ▼"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_no_sort_indicator() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--sort", "name", "--no-sort-indicator"])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains('▲'), "unexpected output: {}", output);
+}
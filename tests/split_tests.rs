@@ -0,0 +1,39 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_split_column() {
+    let csv_input = load_fixture("tags.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--split-column", "tags"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("split_column", output);
+}
+
+#[test]
+fn test_explode() {
+    let csv_input = load_fixture("tags.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--explode", "tags:,"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("explode", output);
+}
+
+#[test]
+fn test_split_column_custom_delimiter() {
+    let csv_input = "id,path\n1,usr/local/bin\n2,etc\n";
+    let output = run_csvpretty_in_pty(csv_input, 80, &["--split-column", "path:/"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("split_column_custom_delimiter", output);
+}
+
+#[test]
+fn test_explode_unknown_column() {
+    let csv_input = load_fixture("tags.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--explode", "labels"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("explode_unknown_column", output);
+}
@@ -0,0 +1,65 @@
+mod helpers;
+
+use helpers::*;
+
+fn shop_path() -> String {
+    fixture_path("shop.sqlite").to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_sqlite_first_table() {
+    let output = run_csvpretty_piped("", &[&shop_path()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("sqlite_first_table", output);
+}
+
+#[test]
+fn test_sqlite_table_by_name() {
+    let output = run_csvpretty_piped("", &["--table", "orders", &shop_path()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("sqlite_table_by_name", output);
+}
+
+#[test]
+fn test_sqlite_query() {
+    let output = run_csvpretty_piped("", &["--query", "SELECT name, items FROM order_totals ORDER BY items DESC", &shop_path()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("sqlite_query", output);
+}
+
+#[test]
+fn test_sqlite_null_display() {
+    let output = run_csvpretty_piped("", &["--null-display", "NULL", "--query", "SELECT name, email FROM customers", &shop_path()])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("│ NULL"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_sqlite_unknown_table() {
+    let output = run_csvpretty_piped("", &["--table", "invoices", &shop_path()])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("no table 'invoices' in the database; its tables are customers, orders, order_totals"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_sqlite_query_is_read_only() {
+    let output = run_csvpretty_piped("", &["--query", "DELETE FROM orders", &shop_path()])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error:"), "unexpected output: {}", output);
+    let rows = run_csvpretty_piped("", &["--table", "orders", &shop_path()]).expect("Failed to run csvpretty");
+    assert!(rows.contains("Keyboard"));
+}
+
+#[test]
+fn test_table_needs_sqlite_input() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--table", "users"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--table and --query only apply to SQLite databases"), "unexpected output: {}", output);
+}
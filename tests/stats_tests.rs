@@ -0,0 +1,59 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_stats() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 120, &["--stats"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("stats", output);
+}
+
+#[test]
+fn test_stats_typed_column() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 120, &["--stats", "--column-type", "elapsed_ms=duration(ms)"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("stats_typed_column", output);
+}
+
+#[test]
+fn test_stats_sorted() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 120, &["--stats", "--sort", "distinct", "--reverse"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("stats_sorted", output);
+}
+
+#[test]
+fn test_stats_problems_first() {
+    let csv_input = load_fixture("missing.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--stats", "--stats-order", "problems"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("stats_problems_first", output);
+}
+
+#[test]
+fn test_stats_null_rate_colors() {
+    let csv_input = load_fixture("missing.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--stats", "--color", "always"], &[])
+        .expect("Failed to run csvpretty");
+
+    // The nulls cells go from green with no nulls to red as nulls take over
+    assert!(output.contains("␛[38;2;38;162;105m"), "unexpected output: {}", output);
+    assert!(output.contains("␛[38;2;225;115;24m"), "unexpected output: {}", output);
+    insta::assert_snapshot!("stats_null_rate_colors", output);
+}
+
+#[test]
+fn test_stats_order_requires_stats() {
+    let output = run_csvpretty_piped("a\n1\n", &["--stats-order", "problems"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--stats"), "unexpected output: {}", output);
+}
@@ -0,0 +1,68 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_json_input() {
+    let output = run_csvpretty_piped("", &[fixture_path("releases.json").to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("json_input", output);
+}
+
+#[test]
+fn test_ndjson_input() {
+    let output = run_csvpretty_piped("", &[fixture_path("events.ndjson").to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("ndjson_input", output);
+}
+
+#[test]
+fn test_yaml_input() {
+    let output = run_csvpretty_piped("", &[fixture_path("hosts.yaml").to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("yaml_input", output);
+}
+
+#[test]
+fn test_json_recognized_on_stdin() {
+    let json_input = load_fixture("releases.json");
+    let output = run_csvpretty_piped(&json_input, &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("downloads") && output.contains("v1.3.0-rc1"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_ndjson_recognized_on_stdin() {
+    let output = run_csvpretty_piped("{\"a\":1}\n{\"b\":2}\n", &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains(" a │ "), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_bracketed_csv_header_stays_csv() {
+    let output = run_csvpretty_piped("[id],[name]\n1,Alice\n", &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("[id] │ [name]"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_input_format_alias() {
+    let output = run_csvpretty_piped("- a: 1\n- a: 2\n", &["--input-format", "yaml"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.lines().nth(1).is_some_and(|line| line.trim() == "a"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_invalid_ndjson_line() {
+    let output = run_csvpretty_piped("{\"a\":1}\n{\"a\":\n", &["--input", "ndjson"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: invalid JSON on line 2"), "unexpected output: {}", output);
+}
@@ -0,0 +1,112 @@
+mod helpers;
+
+use helpers::*;
+
+// Non-unicode styles have no `─` rule for the PTY cleaner to find the table by,
+// so these run through plain pipes (which also fall back to an 80-column width).
+
+#[test]
+fn test_ascii_style() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--style", "ascii", "-n", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("ascii_style", output);
+}
+
+#[test]
+fn test_double_style() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--style", "double", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("double_style", output);
+}
+
+#[test]
+fn test_heavy_style() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--style", "heavy", "-n"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("heavy_style", output);
+}
+
+#[test]
+fn test_rounded_style() {
+    // Framed, with ├ ┤ where rules meet the sides, and closed at the bottom in wrap modes too
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--style", "rounded", "-n", "--width", "60"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("rounded_style", output);
+}
+
+#[test]
+fn test_none_style() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--style", "none", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("none_style", output);
+}
+
+#[test]
+fn test_unicode_style_matches_default() {
+    let csv_input = load_fixture("simple.csv");
+    let default_output = run_csvpretty_piped(&csv_input, &[]).expect("Failed to run csvpretty");
+    let unicode_output = run_csvpretty_piped(&csv_input, &["--style", "unicode"]).expect("Failed to run csvpretty");
+
+    assert_eq!(default_output, unicode_output);
+}
+
+#[test]
+fn test_junctions_with_line_numbers() {
+    // ┬ where separators start below the top border, ┼ across the header separator and ┴ at
+    // the bottom; the header leaves the gutter blank, so its junction only starts below it
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["-n", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("junctions_with_line_numbers", output);
+}
+
+#[test]
+fn test_padding_and_separator() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_piped(&csv_input, &["-n", "--padding", "2", "--separator", "┆"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("padding_and_separator", output);
+}
+
+#[test]
+fn test_padding_fits_terminal_width() {
+    // The wider padding comes out of the space left for wrapped text, not past the edge
+    let csv_input = load_fixture("long_text.csv");
+    for padding in ["0", "1", "3"] {
+        let output = run_csvpretty_piped(&csv_input, &["--padding", padding, "--width", "60"])
+            .expect("Failed to run csvpretty");
+
+        for line in output.lines() {
+            assert!(csvpretty::display_width(line) <= 60, "--padding {}: {:?}", padding, line);
+        }
+    }
+}
+
+#[test]
+fn test_padding_zero() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--padding", "0", "--separator", "|", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("padding_zero", output);
+}
+
+#[test]
+fn test_padding_without_wrapping() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--padding", "3", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("padding_without_wrapping", output);
+}
@@ -0,0 +1,107 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_columns_fallback() {
+    // Without a terminal to measure, COLUMNS sets the width
+    let csv_input = "id,description\n1,a description long enough to wrap at forty columns\n";
+    let output = run_csvpretty_colored(csv_input, &["--no-color"], &[("COLUMNS", "40")])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("columns_fallback", output);
+}
+
+#[test]
+fn test_width_overrides_columns() {
+    let output = run_csvpretty_colored("a\n1\n", &["--no-color", "--verbose", "--width", "30"], &[("COLUMNS", "100")])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains("Verbose: terminal width"), "unexpected output: {}", output);
+    assert!(output.lines().all(|line| line.starts_with("Verbose:") || line.chars().count() <= 30), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_zero_columns_ignored() {
+    let output = run_csvpretty_colored("a\n1\n", &["--no-color", "--verbose"], &[("COLUMNS", "0")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Verbose: no terminal width from COLUMNS"), "unexpected output: {}", output);
+    assert!(output.contains("Verbose: terminal width 80 by default"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_verbose_width_chain() {
+    let output = run_csvpretty_colored("a\n1\n", &["--no-color", "--verbose"], &[("COLUMNS", "100")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Verbose: no terminal width from the standard streams"), "unexpected output: {}", output);
+    assert!(output.contains("Verbose: terminal width 100 from COLUMNS"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_verbose_inside_screen() {
+    // Screen isn't asked for its background color, so COLORFGBG decides
+    let output = run_csvpretty_colored("a\n1\n", &["--color", "always", "--verbose"], &[("STY", "1234.pts-0.host"), ("COLORFGBG", "0;15")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Verbose: running inside GNU screen"), "unexpected output: {}", output);
+    assert!(output.contains("Verbose: not asking GNU screen for its background color"), "unexpected output: {}", output);
+    assert!(output.contains("Verbose: light theme from COLORFGBG"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_verbose_off_by_default() {
+    let output = run_csvpretty_colored("a\n1\n", &["--no-color"], &[("COLUMNS", "100")])
+        .expect("Failed to run csvpretty");
+
+    assert!(!output.contains("Verbose:"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_verbose_theme_not_asked_when_piped() {
+    let output = run_csvpretty_colored("a\n1\n", &["--color", "always", "--verbose"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Verbose: not asking for the background color, as stdout isn't a terminal"), "unexpected output: {}", output);
+    assert!(output.contains("Verbose: dark theme by default"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_theme_flag() {
+    let verbose = run_csvpretty_colored("a\n1\n", &["--color", "always", "--theme", "light", "--verbose"], &[("COLORFGBG", "15;0")])
+        .expect("Failed to run csvpretty");
+    let light = run_csvpretty_colored("a\n1\n", &["--color", "always", "--theme", "light"], &[("COLORFGBG", "15;0")])
+        .expect("Failed to run csvpretty");
+    let from_colorfgbg = run_csvpretty_colored("a\n1\n", &["--color", "always"], &[("COLORFGBG", "0;15")])
+        .expect("Failed to run csvpretty");
+
+    assert!(verbose.contains("Verbose: light theme as chosen; not asking the terminal"), "unexpected output: {}", verbose);
+    assert!(!verbose.contains("COLORFGBG"), "unexpected output: {}", verbose);
+    assert_eq!(light, from_colorfgbg);
+}
+
+#[test]
+fn test_theme_env_var() {
+    let from_env = run_csvpretty_colored("a\n1\n", &["--color", "always"], &[("CSVPRETTY_THEME", "light")])
+        .expect("Failed to run csvpretty");
+    let from_flag = run_csvpretty_colored("a\n1\n", &["--color", "always", "--theme", "light"], &[])
+        .expect("Failed to run csvpretty");
+    // The flag wins over the variable
+    let overridden = run_csvpretty_colored("a\n1\n", &["--color", "always", "--theme", "dark"], &[("CSVPRETTY_THEME", "light")])
+        .expect("Failed to run csvpretty");
+    let dark = run_csvpretty_colored("a\n1\n", &["--color", "always"], &[])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(from_env, from_flag);
+    assert_eq!(overridden, dark);
+    assert_ne!(from_env, dark);
+}
+
+#[test]
+fn test_invalid_theme_env_var() {
+    let output = run_csvpretty_colored("a\n1\n", &["--color", "always"], &[("CSVPRETTY_THEME", "solarized")])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Error: invalid CSVPRETTY_THEME: 'solarized' (expected auto, dark or light)"), "unexpected output: {}", output);
+}
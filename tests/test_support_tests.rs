@@ -0,0 +1,28 @@
+use csvpretty::test_support::{fixtures, render_to_string, snapshot_options};
+use csvpretty::{set_width_conventions, EmojiWidth, RenderOptions, WidthConventions, WrapMode};
+
+#[test]
+fn test_snapshot_fixtures() {
+    insta::assert_snapshot!("people", render_to_string(&fixtures::people(), &snapshot_options(40)));
+    insta::assert_snapshot!("grid", render_to_string(&fixtures::grid(3, 4), &snapshot_options(40)));
+    insta::assert_snapshot!("unicode", render_to_string(&fixtures::unicode(), &snapshot_options(40)));
+}
+
+#[test]
+fn test_long_text_wraps_to_width() {
+    let options = snapshot_options(30);
+    insta::assert_snapshot!("long_text_word", render_to_string(&fixtures::long_text(2, 8), &options));
+
+    let options = RenderOptions { wrap_mode: WrapMode::Truncate, ..options };
+    insta::assert_snapshot!("long_text_truncate", render_to_string(&fixtures::long_text(2, 8), &options));
+}
+
+#[test]
+fn test_render_ignores_thread_width_conventions() {
+    let expected = render_to_string(&fixtures::unicode(), &snapshot_options(40));
+    set_width_conventions(WidthConventions { emoji_width: EmojiWidth::One, ambiguous_wide: true });
+    let output = render_to_string(&fixtures::unicode(), &snapshot_options(40));
+    set_width_conventions(WidthConventions::default());
+
+    assert_eq!(output, expected);
+}
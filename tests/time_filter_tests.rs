@@ -0,0 +1,48 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_since_and_until() {
+    let csv_input = load_fixture("events.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--since", "2024-01-01", "--until", "2024-02-01", "--time-column", "created_at"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("since_and_until", output);
+}
+
+#[test]
+fn test_since_only() {
+    let csv_input = load_fixture("events.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--since", "2024-01-31T12:00:00", "--time-column", "created_at"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("since_only", output);
+}
+
+#[test]
+fn test_until_only() {
+    let csv_input = load_fixture("events.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--until", "2024-01-01", "--time-column", "created_at"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("until_only", output);
+}
+
+#[test]
+fn test_since_requires_time_column() {
+    let csv_input = load_fixture("events.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--since", "2024-01-01"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--time-column"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_invalid_since_date() {
+    let csv_input = load_fixture("events.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--since", "last tuesday", "--time-column", "created_at"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unrecognized date 'last tuesday'"), "unexpected output: {}", output);
+}
@@ -0,0 +1,84 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_title() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 60, &["--title", "Q3 Revenue"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("title", output);
+}
+
+#[test]
+fn test_title_center() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 60, &["--title", "Q3 Revenue", "--title-align", "center", "--style", "double"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("title_center", output);
+}
+
+#[test]
+fn test_title_right_with_line_numbers() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 60, &["--title", "Q3 Revenue", "--title-align", "right", "-n"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("title_right_with_line_numbers", output);
+}
+
+#[test]
+fn test_title_too_long() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--title", "Quarterly revenue by region and product line", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("title_too_long", output);
+}
+
+#[test]
+fn test_title_without_rules() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 60, &["--title", "Q3 Revenue", "--style", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("title_without_rules", output);
+}
+
+#[test]
+fn test_title_vertical() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 60, &["--title", "People", "--vertical"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("title_vertical", output);
+}
+
+#[test]
+fn test_caption() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 60, &["--title", "Q3 Revenue", "--caption", "Source: finance export, 30 September"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("caption", output);
+}
+
+#[test]
+fn test_caption_center_wraps_under_bottom_border() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--caption", "Figures are unaudited and may change before the annual report", "--title-align", "center", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("caption_center_wraps_under_bottom_border", output);
+}
+
+#[test]
+fn test_caption_vertical() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--caption", "3 people", "--vertical", "--width", "60"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("caption_vertical", output);
+}
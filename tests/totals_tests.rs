@@ -0,0 +1,47 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_totals() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--totals", "--line-numbers", "--column-type", "elapsed_ms=duration(ms)", "--null-display", "∅"])
+        .expect("Failed to run csvpretty");
+
+    // Typed columns are added up by their quantity; the totals row gets no placeholders
+    assert!(output.contains("348.25"), "unexpected output: {}", output);
+    insta::assert_snapshot!("totals", output);
+}
+
+#[test]
+fn test_totals_functions() {
+    let csv_input = load_fixture("sales.csv");
+    let mean = run_csvpretty_piped(&csv_input, &["--totals", "mean"]).expect("Failed to run csvpretty");
+    let min = run_csvpretty_piped(&csv_input, &["--totals", "min"]).expect("Failed to run csvpretty");
+    let max = run_csvpretty_piped(&csv_input, &["--totals", "max"]).expect("Failed to run csvpretty");
+
+    // Missing values are left out of the mean; minimums and maximums keep their decimals
+    let last_line = |output: &str| output.lines().last().unwrap_or_default().split_whitespace().collect::<Vec<_>>().join(" ");
+    assert_eq!(last_line(&mean), "│ 1224.12 │ 30", "unexpected output: {}", mean);
+    assert_eq!(last_line(&min), "│ 455.25 │ 0", "unexpected output: {}", min);
+    assert_eq!(last_line(&max), "│ 2210.75 │ 61", "unexpected output: {}", max);
+}
+
+#[test]
+fn test_totals_cover_elided_rows() {
+    let csv_input = load_fixture("sales.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--totals", "--max-rows", "2"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("3 more rows"), "unexpected output: {}", output);
+    assert!(output.contains("4896.50"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_totals_bold_with_colors() {
+    let output = run_csvpretty_colored("item,qty\napple,2\npear,3\n", &["--color", "always", "--totals"], &[])
+        .expect("Failed to run csvpretty");
+
+    let totals = output.lines().last().unwrap_or_default();
+    assert!(totals.contains("␛[1m") && totals.contains('5'), "expected a bold total: {:?}", output);
+}
@@ -0,0 +1,76 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_truncate_wrap_mode() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--wrap", "truncate"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("truncate_wrap_mode", output);
+}
+
+#[test]
+fn test_truncate_narrow_terminal() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 60, &["--wrap", "truncate", "-n"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("truncate_narrow_terminal", output);
+}
+
+#[test]
+fn test_max_col_width_word_wrap() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--max-col-width", "20"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("max_col_width_word_wrap", output);
+}
+
+#[test]
+fn test_max_col_width_no_wrap() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 80, &["--max-col-width", "8", "--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("max_col_width_no_wrap", output);
+}
+
+#[test]
+fn test_truncation_marker_with_count() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "truncate", "--truncation-marker", " [+{chars}]"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("truncation_marker_with_count", output);
+}
+
+#[test]
+fn test_truncation_marker_no_wrap() {
+    let csv_input = load_fixture("wide.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--max-col-width", "14", "--wrap", "none", "--truncation-marker", "(truncated)"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("truncation_marker_no_wrap", output);
+}
+
+#[test]
+fn test_ellipsis_middle() {
+    // Paths and IDs keep their distinguishing ends
+    let csv_input = load_fixture("paths.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "truncate", "--max-col-width", "20", "--ellipsis", "middle"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("ellipsis_middle", output);
+}
+
+#[test]
+fn test_ellipsis_start_with_count() {
+    let csv_input = load_fixture("paths.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none", "--max-col-width", "22", "--ellipsis", "start", "--truncation-marker", "[+{chars}]"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("ellipsis_start_with_count", output);
+}
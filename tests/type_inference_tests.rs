@@ -0,0 +1,39 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_color_by_type() {
+    let csv_input = load_fixture("inventory.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--color", "always", "--color-by", "type"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("color_by_type", output);
+}
+
+#[test]
+fn test_sort_by_inferred_date() {
+    let csv_input = "when,event\n05/03/2024,c\n2023-12-31,a\n2024/01/15,b\n";
+    let output = run_csvpretty_piped(csv_input, &["--sort", "when"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("sort_by_inferred_date", output);
+}
+
+#[test]
+fn test_sort_by_inferred_bool() {
+    let csv_input = load_fixture("inventory.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--sort", "in_stock", "--reverse"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("sort_by_inferred_bool", output);
+}
+
+#[test]
+fn test_inferred_types_in_stats() {
+    let csv_input = load_fixture("inventory.csv");
+    let output = run_csvpretty_in_pty(&csv_input, 120, &["--stats"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("inferred_types_in_stats", output);
+}
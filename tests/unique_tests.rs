@@ -0,0 +1,39 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_unique() {
+    let csv_input = load_fixture("duplicates.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--unique", "--footer"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("unique", output);
+}
+
+#[test]
+fn test_unique_by() {
+    let csv_input = load_fixture("duplicates.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--unique-by", "name,email", "--footer"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("unique_by", output);
+}
+
+#[test]
+fn test_unique_by_unknown_column() {
+    let csv_input = load_fixture("duplicates.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--unique-by", "phone"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unknown column 'phone' in --unique-by"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_unique_conflicts_with_unique_by() {
+    let csv_input = load_fixture("duplicates.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--unique", "--unique-by", "id"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("cannot be used with"), "unexpected output: {}", output);
+}
@@ -0,0 +1,49 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_vertical() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--vertical"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("vertical", output);
+}
+
+#[test]
+fn test_vertical_wraps_long_values() {
+    let csv_input = load_fixture("long_text.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--vertical", "--width", "50"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("vertical_wraps_long_values", output);
+}
+
+#[test]
+fn test_vertical_ascii_style() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--vertical", "--style", "ascii"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("vertical_ascii_style", output);
+}
+
+#[test]
+fn test_vertical_colored() {
+    let csv_input = load_fixture("simple.csv");
+    let output = run_csvpretty_colored(&csv_input, &["--vertical", "--color", "always"], &[])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("vertical_colored", output);
+}
+
+#[test]
+fn test_vertical_rule_junctions() {
+    // Field names wider than the record label leave the junctions visible
+    let csv_input = "customer_reference,order_total\nAC-1001,12.50\nAC-1002,7.25\n";
+    let output = run_csvpretty_piped(csv_input, &["--vertical"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("vertical_rule_junctions", output);
+}
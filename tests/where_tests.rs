@@ -0,0 +1,62 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_where() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--where", "team=red"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("where", output);
+}
+
+#[test]
+fn test_where_conditions_all_match() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--where", "team=red", "--where", "age=35", "--footer"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("where_conditions_all_match", output);
+}
+
+#[test]
+fn test_where_any() {
+    let csv_input = load_fixture("people.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--where", "team=green", "--where", "name=Bob", "--where-any"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("where_any", output);
+}
+
+#[test]
+fn test_where_empty_value() {
+    let output = run_csvpretty_piped("name,score\nAlice,3\nBob,\n", &["--where", "score="])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("Bob") && !output.contains("Alice"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_where_unknown_column() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--where", "c=1"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("unknown column 'c' in --where"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_where_without_equals_sign() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--where", "a"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("expected COLUMN=VALUE, got 'a'"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_where_any_requires_where() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--where-any"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("required arguments were not provided"), "unexpected output: {}", output);
+}
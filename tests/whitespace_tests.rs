@@ -0,0 +1,43 @@
+mod helpers;
+
+use helpers::*;
+
+#[test]
+fn test_show_whitespace() {
+    let csv_input = load_fixture("whitespace.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--show-whitespace"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("show_whitespace", output);
+}
+
+#[test]
+fn test_trim_all() {
+    let csv_input = load_fixture("whitespace.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--trim", "all", "--show-whitespace"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("trim_all", output);
+}
+
+#[test]
+fn test_trim_headers_only() {
+    // The fields keep their spaces
+    let csv_input = load_fixture("whitespace.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--trim", "headers", "--show-whitespace"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("trim_headers_only", output);
+}
+
+#[test]
+fn test_trim_fields_of_a_file() {
+    let path = fixture_path("whitespace.csv");
+    let from_file = run_csvpretty_piped("", &["--trim", "fields", path.to_str().unwrap()])
+        .expect("Failed to run csvpretty");
+    let piped = run_csvpretty_piped(&load_fixture("whitespace.csv"), &["--trim", "fields"])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(from_file, piped);
+    assert!(from_file.contains("│ Widget │"), "unexpected output: {}", from_file);
+}
@@ -64,3 +64,30 @@ fn test_none_wrap_simple_data() {
 
     insta::assert_snapshot!("none_wrap_simple_data", output);
 }
+
+#[test]
+fn test_embedded_newlines_word_wrap() {
+    let csv_input = "id,note\n1,\"Shipping address:\r\n12 Long Street\r\nSpringfield\"\n2,\"Ends with a newline\n\"\n";
+    let output = run_csvpretty_piped(csv_input, &["--max-col-width", "12"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("embedded_newlines_word_wrap", output);
+}
+
+#[test]
+fn test_embedded_newlines_none_wrap() {
+    let csv_input = load_fixture("multiline.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "none"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("embedded_newlines_none_wrap", output);
+}
+
+#[test]
+fn test_embedded_newlines_truncate() {
+    let csv_input = load_fixture("multiline.csv");
+    let output = run_csvpretty_piped(&csv_input, &["--wrap", "truncate", "--max-col-width", "8"])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("embedded_newlines_truncate", output);
+}
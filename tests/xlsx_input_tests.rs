@@ -0,0 +1,94 @@
+mod helpers;
+
+use helpers::*;
+
+fn orders_path() -> String {
+    fixture_path("orders.xlsx").to_str().unwrap().to_string()
+}
+
+/// Runs csvpretty with the workbook on stdin, which the string-based helpers can't carry.
+fn run_with_orders_on_stdin(args: &[&str]) -> String {
+    let stdin = std::fs::File::open(fixture_path("orders.xlsx")).expect("Failed to open fixture");
+    let output = std::process::Command::new(get_binary_path())
+        .arg("--no-color")
+        .args(args)
+        .stdin(stdin)
+        .output()
+        .expect("Failed to run csvpretty");
+    String::from_utf8_lossy(&output.stdout).into_owned() + &String::from_utf8_lossy(&output.stderr)
+}
+
+#[test]
+fn test_xlsx_input() {
+    let output = run_csvpretty_piped("", &[&orders_path()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("xlsx_input", output);
+}
+
+#[test]
+fn test_xlsx_sheet_by_name_with_skipped_title() {
+    let output = run_csvpretty_piped("", &["--sheet", "summary", "--skip-lines", "2", &orders_path()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("xlsx_sheet_by_name", output);
+}
+
+#[test]
+fn test_xlsx_sheet_by_index() {
+    let by_index = run_csvpretty_piped("", &["--sheet", "2", &orders_path()])
+        .expect("Failed to run csvpretty");
+    let by_name = run_csvpretty_piped("", &["--sheet", "Summary", &orders_path()])
+        .expect("Failed to run csvpretty");
+
+    assert_eq!(by_index, by_name);
+    assert!(by_index.contains("Monthly summary"), "unexpected output: {}", by_index);
+}
+
+#[test]
+fn test_xlsx_cells_shown_as_excel_shows_them() {
+    let output = run_csvpretty_piped("", &[&orders_path()])
+        .expect("Failed to run csvpretty");
+
+    for cell in ["2024-03-01 ", "2024-03-02 09:30:00", " 80 ", " 0.3 ", " TRUE ", " FALSE "] {
+        assert!(output.contains(cell), "missing {:?} in output: {}", cell, output);
+    }
+}
+
+#[test]
+fn test_xlsx_on_stdin() {
+    let output = run_with_orders_on_stdin(&["--input", "xlsx"]);
+
+    assert!(output.contains("Chloé"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_xlsx_on_stdin_without_input_format() {
+    let output = run_with_orders_on_stdin(&[]);
+
+    assert!(output.contains("read a workbook with --input xlsx"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_xlsx_unknown_sheet() {
+    let output = run_csvpretty_piped("", &["--sheet", "Returns", &orders_path()])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("no sheet 'Returns' in the workbook; its sheets are Orders, Summary"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_sheet_requires_workbook() {
+    let output = run_csvpretty_piped("a,b\n1,2\n", &["--sheet", "1"])
+        .expect("Failed to run csvpretty");
+
+    assert!(output.contains("--sheet only applies to Excel workbooks"), "unexpected output: {}", output);
+}
+
+#[test]
+fn test_xlsx_with_other_flags() {
+    let output = run_csvpretty_piped("", &["--sort", "amount", "--reverse", &orders_path()])
+        .expect("Failed to run csvpretty");
+
+    insta::assert_snapshot!("xlsx_with_other_flags", output);
+}